@@ -0,0 +1,55 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+// Errors that callers might want to react to. Frontends show them with their message
+#[derive(Debug)]
+pub enum BrainError {
+    // The id or the name of the note
+    NoteNotFound(String),
+    Io { path: PathBuf, source: io::Error },
+    // The note file doesn't have the expected format
+    Parse { note_id: String, message: String },
+    EditorFailed { editor: String, source: io::Error },
+    Other(String),
+}
+
+impl fmt::Display for BrainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BrainError::NoteNotFound(note) => write!(f, "the note '{}' does not exist!", note),
+            BrainError::Io { path, source } => {
+                write!(
+                    f,
+                    "couldn't access '{}': {}",
+                    path.to_string_lossy(),
+                    source
+                )
+            }
+            BrainError::Parse { note_id, message } => {
+                write!(f, "couldn't read the note '{}': {}", note_id, message)
+            }
+            BrainError::EditorFailed { editor, source } => {
+                write!(f, "couldn't start the editor '{}': {}", editor, source)
+            }
+            BrainError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for BrainError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BrainError::Io { source, .. } | BrainError::EditorFailed { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// Helper functions of the frontends still return their errors as messages
+impl From<BrainError> for String {
+    fn from(error: BrainError) -> String {
+        return error.to_string();
+    }
+}
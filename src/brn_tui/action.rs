@@ -0,0 +1,383 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Next,
+    Previous,
+    Open,
+    OpenMarked,
+    GitCommit,
+    FollowLink,
+    InsertLink,
+    Outline,
+    Finder,
+    Back,
+    Forward,
+    Home,
+    First,
+    Last,
+    HalfPageDown,
+    HalfPageUp,
+    ScrollDown,
+    ScrollUp,
+    NextMatch,
+    PreviousMatch,
+    PageDown,
+    PageUp,
+    LineNumbers,
+    ShrinkList,
+    GrowList,
+    History,
+    Random,
+    RandomNote,
+    Orphans,
+    Archived,
+    Notebooks,
+    MoveToNotebook,
+    Filter,
+    DefaultList,
+    Add,
+    AddChild,
+    AddSibling,
+    Capture,
+    Remove,
+    Merge,
+    Archive,
+    Rename,
+    Undo,
+    Mark,
+    Pin,
+    Label,
+    LabeledNotes,
+    TagMarked,
+    Sort,
+    CopyLink,
+    CopyPath,
+    CopyId,
+    SelectText,
+    Backlinks,
+    Tags,
+    TogglePreview,
+    ToggleBacklinks,
+    ToggleLayout,
+    Messages,
+    CommandPalette,
+    Help,
+}
+
+impl Action {
+    pub fn all() -> Vec<Action> {
+        return vec![
+            Action::Quit,
+            Action::Next,
+            Action::Previous,
+            Action::Open,
+            Action::OpenMarked,
+            Action::GitCommit,
+            Action::FollowLink,
+            Action::InsertLink,
+            Action::Outline,
+            Action::Finder,
+            Action::Back,
+            Action::Forward,
+            Action::Home,
+            Action::First,
+            Action::Last,
+            Action::HalfPageDown,
+            Action::HalfPageUp,
+            Action::ScrollDown,
+            Action::ScrollUp,
+            Action::NextMatch,
+            Action::PreviousMatch,
+            Action::PageDown,
+            Action::PageUp,
+            Action::LineNumbers,
+            Action::ShrinkList,
+            Action::GrowList,
+            Action::History,
+            Action::Random,
+            Action::RandomNote,
+            Action::Orphans,
+            Action::Archived,
+            Action::Notebooks,
+            Action::MoveToNotebook,
+            Action::Filter,
+            Action::DefaultList,
+            Action::Add,
+            Action::AddChild,
+            Action::AddSibling,
+            Action::Capture,
+            Action::Remove,
+            Action::Merge,
+            Action::Archive,
+            Action::Rename,
+            Action::Undo,
+            Action::Mark,
+            Action::Pin,
+            Action::Label,
+            Action::LabeledNotes,
+            Action::TagMarked,
+            Action::Sort,
+            Action::CopyLink,
+            Action::CopyPath,
+            Action::CopyId,
+            Action::SelectText,
+            Action::Backlinks,
+            Action::Tags,
+            Action::TogglePreview,
+            Action::ToggleBacklinks,
+            Action::ToggleLayout,
+            Action::Messages,
+            Action::CommandPalette,
+            Action::Help,
+        ];
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        return Action::all().into_iter().find(|m| m.to_name() == name);
+    }
+
+    pub fn to_name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Next => "next",
+            Action::Previous => "previous",
+            Action::Open => "open",
+            Action::OpenMarked => "open-marked",
+            Action::GitCommit => "git-commit",
+            Action::FollowLink => "follow-link",
+            Action::InsertLink => "insert-link",
+            Action::Outline => "outline",
+            Action::Finder => "finder",
+            Action::Back => "back",
+            Action::Forward => "forward",
+            Action::Home => "home",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::HalfPageDown => "half-page-down",
+            Action::HalfPageUp => "half-page-up",
+            Action::ScrollDown => "scroll-down",
+            Action::ScrollUp => "scroll-up",
+            Action::NextMatch => "next-match",
+            Action::PreviousMatch => "previous-match",
+            Action::PageDown => "page-down",
+            Action::PageUp => "page-up",
+            Action::LineNumbers => "line-numbers",
+            Action::ShrinkList => "shrink-list",
+            Action::GrowList => "grow-list",
+            Action::History => "history",
+            Action::Random => "random",
+            Action::RandomNote => "random-note",
+            Action::Orphans => "orphans",
+            Action::Archived => "archived",
+            Action::Notebooks => "notebooks",
+            Action::MoveToNotebook => "move-to-notebook",
+            Action::Filter => "filter",
+            Action::DefaultList => "default-list",
+            Action::Add => "add",
+            Action::AddChild => "add-child",
+            Action::AddSibling => "add-sibling",
+            Action::Capture => "capture",
+            Action::Remove => "remove",
+            Action::Merge => "merge",
+            Action::Archive => "archive",
+            Action::Rename => "rename",
+            Action::Undo => "undo",
+            Action::Mark => "mark",
+            Action::Pin => "pin",
+            Action::Label => "label",
+            Action::LabeledNotes => "labeled-notes",
+            Action::TagMarked => "tag-marked",
+            Action::Sort => "sort",
+            Action::CopyLink => "copy-link",
+            Action::CopyPath => "copy-path",
+            Action::CopyId => "copy-id",
+            Action::SelectText => "select-text",
+            Action::Backlinks => "backlinks",
+            Action::Tags => "tags",
+            Action::TogglePreview => "toggle-preview",
+            Action::ToggleBacklinks => "toggle-backlinks",
+            Action::ToggleLayout => "toggle-layout",
+            Action::Messages => "messages",
+            Action::CommandPalette => "command-palette",
+            Action::Help => "help",
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit the program",
+            Action::Next => "select next note",
+            Action::Previous => "select previous note",
+            Action::Open => "open selected note",
+            Action::OpenMarked => "open all marked notes in the editor at once",
+            Action::GitCommit => "commit all changes of the notes directory with git",
+            Action::FollowLink => "go to a note linked in the preview",
+            Action::InsertLink => "add a link to another note to the selected note",
+            Action::Outline => "jump to a heading of the previewed note",
+            Action::Finder => "find a note by its name or content and open it",
+            Action::Back => "go back to the previously visited note",
+            Action::Forward => "go forward again after going back",
+            Action::Home => "go to the home note",
+            Action::First => "go to the top of the note list",
+            Action::Last => "go to the bottom of the note list",
+            Action::HalfPageDown => {
+                "move down half a page in the note list (in the preview while selecting lines)"
+            }
+            Action::HalfPageUp => {
+                "move up half a page in the note list (in the preview while selecting lines)"
+            }
+            Action::ScrollDown => "scroll the preview down half a page",
+            Action::ScrollUp => "scroll the preview up half a page",
+            Action::NextMatch => "scroll the preview to the next match of the content search",
+            Action::PreviousMatch => {
+                "scroll the preview to the previous match of the content search"
+            }
+            Action::PageDown => "scroll the preview down a page",
+            Action::PageUp => "scroll the preview up a page",
+            Action::LineNumbers => "show / hide line numbers in the preview",
+            Action::ShrinkList => "make the note list narrower",
+            Action::GrowList => "make the note list wider",
+            Action::History => "show history of last visited notes",
+            Action::Random => "show a list of random notes",
+            Action::RandomNote => "select a random note",
+            Action::Orphans => "show notes that aren't linked by other notes",
+            Action::Archived => "show the archived notes",
+            Action::Notebooks => "switch to another notebook",
+            Action::MoveToNotebook => "move selected note into another notebook",
+            Action::Filter => "filter the note list",
+            Action::DefaultList => "show list of all notes",
+            Action::Add => "add new note",
+            Action::AddChild => "add new note as a child of the selected note",
+            Action::AddSibling => "add new note with the same parent as the selected note",
+            Action::Capture => "append an entry to the inbox note",
+            Action::Remove => "remove selected note",
+            Action::Merge => "merge the marked notes into the selected note",
+            Action::Archive => "archive or restore selected note",
+            Action::Rename => "rename selected note",
+            Action::Undo => "undo the last removal, rename or tag change",
+            Action::Mark => "mark / unmark selected note",
+            Action::Pin => "pin / unpin selected note to the top of the list",
+            Action::Label => "set or remove the label of the selected note",
+            Action::LabeledNotes => "show the notes with a label",
+            Action::TagMarked => "add a tag to the marked notes",
+            Action::Sort => "change the sort order of the note list",
+            Action::CopyLink => "copy link to selected note",
+            Action::CopyPath => "copy file path of selected note",
+            Action::CopyId => "copy id of selected note",
+            Action::SelectText => "select lines of the preview to copy them",
+            Action::Backlinks => "focus the backlinks",
+            Action::Tags => "show the tag sidebar",
+            Action::TogglePreview => "show / hide the preview",
+            Action::ToggleBacklinks => "show / hide the backlinks",
+            Action::ToggleLayout => "switch between a horizontal and a vertical layout",
+            Action::Messages => "show the history of messages",
+            Action::CommandPalette => "run an action by its name",
+            Action::Help => "show / hide this help",
+        }
+    }
+
+    pub fn get_default_keys(&self) -> Vec<&'static str> {
+        match self {
+            Action::Quit => vec!["q"],
+            Action::Next => vec!["j", "Down"],
+            Action::Previous => vec!["k", "Up"],
+            Action::Open => vec!["l", "Right", "Enter"],
+            Action::OpenMarked => vec!["E"],
+            Action::GitCommit => vec!["C"],
+            Action::FollowLink => vec!["f"],
+            Action::InsertLink => vec!["L"],
+            Action::Outline => vec!["o"],
+            Action::Finder => vec!["Ctrl-p"],
+            Action::Back => vec!["Backspace", "Ctrl-o"],
+            Action::Forward => vec!["Tab"],
+            Action::Home => vec!["H"],
+            Action::First => vec!["g g", "Home"],
+            Action::Last => vec!["G", "End"],
+            Action::HalfPageDown => vec!["Ctrl-d"],
+            Action::HalfPageUp => vec!["Ctrl-u"],
+            Action::ScrollDown => vec!["J"],
+            Action::ScrollUp => vec!["K"],
+            Action::NextMatch => vec!["]"],
+            Action::PreviousMatch => vec!["["],
+            Action::PageDown => vec!["PageDown"],
+            Action::PageUp => vec!["PageUp"],
+            Action::LineNumbers => vec!["Ctrl-l"],
+            Action::ShrinkList => vec!["<"],
+            Action::GrowList => vec![">"],
+            Action::History => vec!["h"],
+            Action::Random => vec!["R"],
+            Action::RandomNote => vec!["x"],
+            Action::Orphans => vec!["O"],
+            Action::Archived => vec!["Ctrl-a"],
+            Action::Notebooks => vec!["N"],
+            Action::MoveToNotebook => vec!["M"],
+            Action::Filter => vec!["/"],
+            Action::DefaultList => vec!["Esc"],
+            Action::Add => vec!["n", "a"],
+            Action::AddChild => vec!["c"],
+            Action::AddSibling => vec!["S"],
+            Action::Capture => vec!["i"],
+            Action::Remove => vec!["d"],
+            Action::Merge => vec!["U"],
+            Action::Archive => vec!["A"],
+            Action::Rename => vec!["r"],
+            Action::Undo => vec!["Ctrl-z"],
+            Action::Mark => vec!["Space"],
+            Action::Pin => vec!["p"],
+            Action::Label => vec!["F"],
+            Action::LabeledNotes => vec!["Ctrl-f"],
+            Action::TagMarked => vec!["T"],
+            Action::Sort => vec!["s"],
+            Action::CopyLink => vec!["Ctrl-y"],
+            Action::CopyPath => vec!["y"],
+            Action::CopyId => vec!["Y"],
+            Action::SelectText => vec!["v"],
+            Action::Backlinks => vec!["b"],
+            Action::Tags => vec!["t"],
+            Action::TogglePreview => vec!["P"],
+            Action::ToggleBacklinks => vec!["B"],
+            Action::ToggleLayout => vec!["V"],
+            Action::Messages => vec!["m"],
+            Action::CommandPalette => vec![":"],
+            Action::Help => vec!["?"],
+        }
+    }
+
+    // These actions do nothing if the note list is empty
+    pub fn needs_selected_note(&self) -> bool {
+        return matches!(
+            self,
+            Action::Next
+                | Action::Previous
+                | Action::First
+                | Action::Last
+                | Action::HalfPageDown
+                | Action::HalfPageUp
+                | Action::PageDown
+                | Action::PageUp
+                | Action::ScrollDown
+                | Action::ScrollUp
+                | Action::NextMatch
+                | Action::PreviousMatch
+                | Action::Open
+                | Action::FollowLink
+                | Action::InsertLink
+                | Action::Outline
+                | Action::AddChild
+                | Action::AddSibling
+                | Action::Remove
+                | Action::Merge
+                | Action::Archive
+                | Action::MoveToNotebook
+                | Action::Rename
+                | Action::Mark
+                | Action::Pin
+                | Action::Label
+                | Action::CopyLink
+                | Action::CopyPath
+                | Action::CopyId
+                | Action::SelectText
+                | Action::Backlinks
+        );
+    }
+}
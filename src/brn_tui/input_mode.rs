@@ -1,6 +1,24 @@
 pub enum InputMode {
     Normal,
     Add,
+    Template,
+    Notebooks,
+    Labels,
+    Links,
+    Outline,
+    Finder,
+    CommandPalette,
+    PreviewSelection,
     Remove,
+    Merge,
+    MoveToNotebook,
+    Rename,
+    Capture,
+    AddTag,
+    InsertLink,
     Search,
+    Backlinks,
+    Tags,
+    Messages,
+    Help,
 }
@@ -0,0 +1,230 @@
+use crate::brn_tui::action::Action;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use yaml_rust::Yaml;
+
+pub struct Keybindings {
+    key_map: HashMap<Vec<KeyEvent>, Action>,
+    action_keys: Vec<(Action, Vec<String>)>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Keybindings {
+        let mut keybindings = Keybindings {
+            key_map: HashMap::new(),
+            action_keys: Vec::new(),
+        };
+        for action in Action::all() {
+            let default_keys = action.get_default_keys();
+            keybindings.bind(action, default_keys.iter().map(|m| m.to_string()).collect());
+        }
+        return keybindings;
+    }
+}
+
+impl Keybindings {
+    // Configured actions replace their default keys, all other actions keep them.
+    // Returns a warning for every problem found in the config
+    pub fn from_config(config: &Yaml) -> (Keybindings, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut configured_keys: Vec<(Action, Vec<String>)> = Vec::new();
+
+        if let Some(config_hash) = config.as_hash() {
+            for (action_name, keys) in config_hash {
+                let action_name = match action_name.as_str() {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let action = match Action::from_name(action_name) {
+                    Some(value) => value,
+                    None => {
+                        warnings.push(format!("unknown keybinding action '{}'", action_name));
+                        continue;
+                    }
+                };
+
+                let keys: Vec<String> = match keys {
+                    Yaml::String(key) => vec![key.clone()],
+                    Yaml::Array(key_list) => key_list
+                        .iter()
+                        .filter_map(|m| m.as_str().map(|v| v.to_string()))
+                        .collect(),
+                    _ => {
+                        warnings.push(format!(
+                            "the keys of the action '{}' have to be a string or a list of strings",
+                            action_name
+                        ));
+                        continue;
+                    }
+                };
+                configured_keys.push((action, keys));
+            }
+        } else if !config.is_badvalue() {
+            warnings.push("'keybindings' has to be a mapping of actions to keys".to_string());
+        }
+
+        let mut keybindings = Keybindings {
+            key_map: HashMap::new(),
+            action_keys: Vec::new(),
+        };
+        for action in Action::all() {
+            if configured_keys.iter().any(|m| m.0 == action) {
+                continue;
+            }
+            let default_keys = action.get_default_keys();
+            keybindings.bind(action, default_keys.iter().map(|m| m.to_string()).collect());
+        }
+
+        // Configured keys are bound last so that they take precedence over the defaults
+        for (action, keys) in configured_keys {
+            for key in &keys {
+                let key_sequence = match Keybindings::parse_key_sequence(key) {
+                    Some(value) => value,
+                    None => {
+                        warnings.push(format!(
+                            "invalid key '{}' for the action '{}'",
+                            key,
+                            action.to_name()
+                        ));
+                        continue;
+                    }
+                };
+                if let Some(bound_action) = keybindings.key_map.get(&key_sequence) {
+                    if *bound_action != action {
+                        warnings.push(format!(
+                            "the key '{}' is bound to '{}' and '{}', using '{}'",
+                            key,
+                            bound_action.to_name(),
+                            action.to_name(),
+                            action.to_name()
+                        ));
+                    }
+                }
+            }
+            keybindings.bind(action, keys);
+        }
+
+        return (keybindings, warnings);
+    }
+
+    pub fn get_action(&self, keys: &[KeyEvent]) -> Option<Action> {
+        let keys: Vec<KeyEvent> = keys.iter().map(|m| Keybindings::normalize(*m)).collect();
+        return self.key_map.get(&keys).copied();
+    }
+
+    // Checks if the keys are the start of a longer key sequence like 'g g'
+    pub fn is_start_of_key_sequence(&self, keys: &[KeyEvent]) -> bool {
+        let keys: Vec<KeyEvent> = keys.iter().map(|m| Keybindings::normalize(*m)).collect();
+        return self
+            .key_map
+            .keys()
+            .any(|m| m.len() > keys.len() && m.starts_with(&keys));
+    }
+
+    // Every action with the keys that are still bound to it
+    pub fn get_help_entries(&self) -> Vec<(String, &'static str)> {
+        return self
+            .action_keys
+            .iter()
+            .map(|(action, _)| (self.get_keys_of(*action), action.get_description()))
+            .filter(|m| !m.0.is_empty())
+            .collect();
+    }
+
+    // Keys that were bound to another action later aren't active anymore
+    pub fn get_keys_of(&self, action: Action) -> String {
+        let keys = match self.action_keys.iter().find(|m| m.0 == action) {
+            Some(value) => &value.1,
+            None => return String::new(),
+        };
+        return keys
+            .iter()
+            .filter(|m| match Keybindings::parse_key_sequence(m) {
+                Some(key_sequence) => self.key_map.get(&key_sequence) == Some(&action),
+                None => false,
+            })
+            .map(|m| m.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+    }
+
+    fn bind(&mut self, action: Action, keys: Vec<String>) {
+        for key in &keys {
+            if let Some(key_sequence) = Keybindings::parse_key_sequence(key) {
+                self.key_map.insert(key_sequence, action);
+            }
+        }
+        self.action_keys.retain(|m| m.0 != action);
+        self.action_keys.push((action, keys));
+        self.action_keys.sort_by_key(|m| {
+            Action::all()
+                .iter()
+                .position(|v| *v == m.0)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    // Key sequences like 'g g' are separated by spaces
+    fn parse_key_sequence(keys: &str) -> Option<Vec<KeyEvent>> {
+        let key_sequence: Option<Vec<KeyEvent>> = keys
+            .split_whitespace()
+            .map(Keybindings::parse_key)
+            .collect();
+        match key_sequence {
+            Some(value) if !value.is_empty() => Some(value),
+            _ => None,
+        }
+    }
+
+    // Parses keys like 'j', 'G', 'Ctrl-n', 'Alt-x', 'Enter' or 'PageDown'
+    fn parse_key(key: &str) -> Option<KeyEvent> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key_name = key;
+        loop {
+            if let Some(rest) = key_name.strip_prefix("Ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                key_name = rest;
+            } else if let Some(rest) = key_name.strip_prefix("Alt-") {
+                modifiers |= KeyModifiers::ALT;
+                key_name = rest;
+            } else {
+                break;
+            }
+        }
+
+        let key_code = match key_name {
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            "Tab" => KeyCode::Tab,
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Space" => KeyCode::Char(' '),
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            _ => {
+                let mut chars = key_name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        };
+
+        return Some(Keybindings::normalize(KeyEvent::new(key_code, modifiers)));
+    }
+
+    // Shift is already part of the char (e.g. 'G' or '?')
+    fn normalize(key: KeyEvent) -> KeyEvent {
+        match key.code {
+            KeyCode::Char(_) => KeyEvent::new(key.code, key.modifiers - KeyModifiers::SHIFT),
+            _ => key,
+        }
+    }
+}
@@ -1,53 +1,99 @@
+use crate::brain_error::BrainError;
+use crate::brn_tui::action::Action;
 use crate::brn_tui::input_mode::InputMode;
+use crate::brn_tui::markdown_renderer::MarkdownRenderer;
+use crate::brn_tui::message_log::{MessageLevel, MessageLog};
+use crate::brn_tui::navigation_history::NavigationHistory;
+use crate::brn_tui::org_renderer::OrgRenderer;
+use crate::brn_tui::panel_layout::{Panel, PanelLayout};
+use crate::brn_tui::preview_cache::PreviewCache;
+use crate::brn_tui::stateful_list::StatefulList;
+use crate::brn_tui::terminal_guard::TerminalGuard;
+use crate::brn_tui::theme::Theme;
 use crate::brn_tui::tui_data::TuiData;
+use crate::brn_tui::undo_action::UndoAction;
 use crate::database::Database;
+use crate::destructive_action::DestructiveAction;
+use crate::file_utility::FileUtility;
+use crate::fuzzy_matcher::FuzzyMatcher;
+use crate::git_repository::GitRepository;
+use crate::message::Message;
+use crate::name_overflow::NameOverflow;
+use crate::note::Note;
+use crate::note_label::NoteLabel;
 use crate::note_property::NoteProperty;
+use crate::note_statistics::NoteStatistics;
+use crate::note_tasks::NoteTasks;
 use crate::note_type::NoteType;
 use crate::note_utility::NoteUtility;
-use crate::settings::Settings;
+use crate::render_mode::RenderMode;
+use crate::search_query::SearchQuery;
+use crate::settings::{Settings, DEFAULT_LIST_FORMAT};
+use crate::tag_order::TagOrder;
 
 use clipboard::{ClipboardContext, ClipboardProvider};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
-use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
 use std::io;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tui::{
     backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, Wrap},
     Frame, Terminal,
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// Only a window of the default note list is loaded at once, as loading all notes
+// takes too long for zettelkastens with many notes
+const NOTE_LIST_WINDOW_SIZE: usize = 300;
+const NOTE_LIST_WINDOW_MARGIN: usize = 50;
+
+const LIST_WIDTH_STEP: i64 = 5;
+
+const MAX_TAG_SUGGESTION_COUNT: usize = 8;
+
+const PREVIEW_LOADING_DELAY: Duration = Duration::from_millis(20);
+const PREVIEW_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Long names of selected notes scroll by one character per interval
+const NAME_SCROLL_DELAY: Duration = Duration::from_millis(1000);
+const NAME_SCROLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Commits made outside of brn are noticed after this time
+const GIT_STATUS_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct BrnTui;
 
 impl BrnTui {
-    pub fn init(settings: &mut Settings) {
-        enable_raw_mode().unwrap();
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture).unwrap();
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend).unwrap();
+    // The terminal is restored by the guard, even if the TUI fails
+    pub fn init(settings: &mut Settings) -> io::Result<()> {
+        let terminal_guard = TerminalGuard::new()?;
+        let backend = CrosstermBackend::new(io::stdout());
+        let mut terminal = Terminal::new(backend)?;
 
         let mut tui_data = TuiData::default();
         let result = BrnTui::run_app(&mut terminal, &mut tui_data, settings);
 
-        disable_raw_mode().unwrap();
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )
-        .unwrap();
-        terminal.show_cursor().unwrap();
-
-        if let Err(error) = result {
-            println!("{:?}", error);
+        // The terminal is restored first, so that warnings can be printed
+        drop(terminal_guard);
+        if settings.restore_session_enabled {
+            BrnTui::save_session(&mut tui_data, settings);
         }
+        return result;
     }
 
     fn run_app<B: Backend + Write>(
@@ -55,160 +101,961 @@ impl BrnTui {
         tui_data: &mut TuiData,
         settings: &mut Settings,
     ) -> io::Result<()> {
+        tui_data.list_width = settings.list_width;
+        tui_data.split_direction = settings.split_direction;
+        tui_data.visible_panels = settings.visible_panels.clone();
+        tui_data.preview_cache = PreviewCache::new(settings.preview_cache_size);
+        tui_data.message = MessageLog::new(settings.message_log_size);
+        BrnTui::show_default_note_list(tui_data, settings);
+        if settings.restore_session_enabled {
+            BrnTui::restore_session(tui_data, settings);
+        } else {
+            BrnTui::select_home_note(tui_data, settings);
+        }
         BrnTui::show_note_content_preview(tui_data, settings);
+        if tui_data.visible_panels.contains(&Panel::Tags) {
+            BrnTui::update_tag_list(tui_data);
+        }
+        if let Err(error) = tui_data
+            .note_watcher
+            .watch(PathBuf::from(&settings.notes_dir))
+        {
+            tui_data.message.set(format!(
+                "WARNING: notes changed outside of the TUI won't be reloaded: {}",
+                error
+            ));
+        }
         loop {
-            terminal.draw(|f| BrnTui::render_ui(f, tui_data)).unwrap();
+            // Most notes load instantly, so wait a moment to not flash the loading text
+            if tui_data.is_preview_loading {
+                BrnTui::receive_loaded_preview(tui_data, settings, PREVIEW_LOADING_DELAY);
+            }
+            if tui_data.is_finder_preview_loading {
+                BrnTui::receive_finder_preview(tui_data, settings, PREVIEW_LOADING_DELAY);
+            }
+            let database_errors = Database::take_errors();
+            if !database_errors.is_empty() {
+                tui_data
+                    .message
+                    .set(format!("ERROR: {}", database_errors.join("; ")));
+            }
+            terminal.draw(|f| BrnTui::render_ui(f, tui_data, settings))?;
+
+            // The preview has to be shown once it's loaded, even if no key is pressed
+            while !event::poll(PREVIEW_POLL_INTERVAL)? {
+                if tui_data.is_preview_loading
+                    && BrnTui::receive_loaded_preview(tui_data, settings, Duration::ZERO)
+                {
+                    break;
+                }
+                if tui_data.is_finder_preview_loading
+                    && BrnTui::receive_finder_preview(tui_data, settings, Duration::ZERO)
+                {
+                    break;
+                }
+
+                // Changes are only applied in normal mode, so that no popup loses its state
+                if let InputMode::Normal = tui_data.input_mode {
+                    if let Some(changed_file_names) = tui_data.note_watcher.receive() {
+                        BrnTui::reload_changed_notes(&changed_file_names, tui_data, settings);
+                        BrnTui::update_git_status(tui_data, settings);
+                        break;
+                    }
+                }
+                if tui_data.is_name_scrolling
+                    && BrnTui::get_name_scroll_offset(tui_data) != tui_data.name_scroll_offset
+                {
+                    break;
+                }
+                let is_git_status_outdated = tui_data
+                    .git_status_update_time
+                    .is_none_or(|m| m.elapsed() >= GIT_STATUS_INTERVAL);
+                if is_git_status_outdated && BrnTui::update_git_status(tui_data, settings) {
+                    break;
+                }
+            }
+            if !event::poll(Duration::ZERO)? {
+                continue;
+            }
+
+            let key = match event::read() {
+                Ok(Event::Key(key)) => key,
+                Ok(Event::Mouse(mouse_event)) => {
+                    if let InputMode::Normal = tui_data.input_mode {
+                        BrnTui::handle_mouse_event(mouse_event, terminal, tui_data, settings);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
 
             // Detect keydown events
-            if let Ok(Event::Key(key)) = event::read() {
-                match tui_data.input_mode {
-                    InputMode::Normal => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            BrnTui::increment_selected_value(tui_data, settings)
-                        }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            BrnTui::decrement_selected_value(tui_data, settings)
-                        }
-                        KeyCode::Char('g') => BrnTui::select_first_value(tui_data, settings),
-                        KeyCode::Char('G') => BrnTui::select_last_value(tui_data, settings),
-                        KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
-                            BrnTui::open_selected_note(terminal, tui_data, settings)
-                        }
-                        KeyCode::Char('y') => BrnTui::copy_selected_note_as_link(tui_data),
-                        KeyCode::Char('a') => {
-                            tui_data.edit_text.set_pre_text("Name: ");
-                            tui_data.input_mode = InputMode::Add;
-                        }
-                        KeyCode::Esc => {
-                            let note_list = NoteUtility::get(100);
-                            tui_data.note_list.replace_items_with(note_list);
-                            tui_data.note_list.select(Some(0));
-                            tui_data.note_list_title = String::from("List");
+            match tui_data.input_mode {
+                InputMode::Normal => {
+                    if let Some(action) = BrnTui::get_action_of_key(key, tui_data, settings) {
+                        if BrnTui::execute_action(action, terminal, tui_data, settings) {
+                            return Ok(());
                         }
-                        KeyCode::Char('h') => {
-                            let note_history = NoteUtility::get_note_history(settings);
-                            tui_data.note_list.replace_items_with(
-                                note_history.iter().map(|m| m.note_name.clone()).collect(),
-                            );
-                            tui_data.note_list.select(Some(0));
-                            tui_data.note_list_title = String::from("History");
-                        }
-                        KeyCode::Char('r') => {
-                            let note_id_list = Database::get_random_note_ids(10);
-                            let mut note_list = Vec::new();
-                            for note_id in note_id_list {
-                                if let Some(note) = Database::get_note_where_id(&note_id) {
-                                    note_list.push(note);
-                                };
+                    }
+                }
+                InputMode::Help => match key.code {
+                    KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    _ => (),
+                },
+                InputMode::Tags => match key.code {
+                    KeyCode::Esc | KeyCode::Char('t') => {
+                        tui_data.input_mode = InputMode::Normal;
+                        BrnTui::hide_tag_sidebar(tui_data, settings);
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => tui_data.tag_list.next(),
+                    KeyCode::Char('k') | KeyCode::Up => tui_data.tag_list.previous(),
+                    KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                        BrnTui::filter_by_selected_tag(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Char('s') => {
+                        tui_data.tag_order = tui_data.tag_order.toggled();
+                        BrnTui::update_tag_list(tui_data);
+                    }
+                    KeyCode::Char('r') => {
+                        tui_data.tag_counts_rolled_up = !tui_data.tag_counts_rolled_up;
+                        BrnTui::update_tag_list(tui_data);
+                    }
+                    _ => (),
+                },
+                InputMode::Backlinks => match key.code {
+                    KeyCode::Esc | KeyCode::Char('b') => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => tui_data.backlink_list.next(),
+                    KeyCode::Char('k') | KeyCode::Up => tui_data.backlink_list.previous(),
+                    KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                        BrnTui::jump_to_selected_backlink(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    _ => (),
+                },
+                InputMode::Add => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.input_mode = InputMode::Normal;
+                        tui_data.note_name_cache.clear();
+                    }
+                    KeyCode::Enter => {
+                        if tui_data.note_name_cache.is_empty() {
+                            let note_name = tui_data.edit_text.get_content_text();
+                            if let Err(error) = NoteUtility::validate_new_note_name(&note_name) {
+                                tui_data.message.set(format!("ERROR: {}", error));
+                                tui_data.input_mode = InputMode::Normal;
+                            } else {
+                                tui_data.note_name_cache = note_name;
+                                tui_data.edit_text.set_pre_text("Type (T|q|j): ");
                             }
+                        } else {
+                            tui_data.note_type_cache =
+                                match tui_data.edit_text.get_content_text().as_str() {
+                                    "Q" | "q" => NoteType::Quote,
+                                    "J" | "j" => NoteType::Journal,
+                                    _ => NoteType::Topic,
+                                };
+                            tui_data.edit_text.clear();
 
-                            tui_data.note_list.replace_items_with(
-                                note_list.iter().map(|m| m.note_name.clone()).collect(),
-                            );
-                            tui_data.note_list.select(Some(0));
-                            tui_data.note_list_title = String::from("Random notes");
-                        }
-                        KeyCode::Char('x') => {
-                            tui_data
-                                .edit_text
-                                .set_pre_text("Remove currently selected note [y|N]: ");
-                            tui_data.input_mode = InputMode::Remove;
-                        }
-                        KeyCode::Char('/') => tui_data.input_mode = InputMode::Search,
-                        _ => (),
-                    },
-                    InputMode::Add => match key.code {
-                        KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
-                        KeyCode::Enter => {
-                            if tui_data.note_name_cache.is_empty() {
-                                tui_data.note_name_cache = tui_data.edit_text.get_content_text();
-                                tui_data.edit_text.set_pre_text("Type (T|q|j): ");
-                            } else {
-                                BrnTui::add_note(terminal, tui_data, settings);
+                            let template_names = NoteUtility::get_template_names(settings);
+                            if template_names.is_empty() {
+                                BrnTui::add_note(terminal, tui_data, settings, None);
                                 tui_data.input_mode = InputMode::Normal;
-                                tui_data.edit_text.clear();
                                 tui_data.note_name_cache.clear();
+                            } else {
+                                BrnTui::show_template_picker(tui_data, template_names);
                             }
                         }
-                        KeyCode::Backspace | KeyCode::Delete => {
+                    }
+                    KeyCode::Backspace | KeyCode::Delete => {
+                        tui_data.edit_text.pop();
+                    }
+                    KeyCode::Char('h') => {
+                        // Workaround to recognize `^H` control char as backspace
+                        if key.modifiers == KeyModifiers::CONTROL {
                             tui_data.edit_text.pop();
+                        } else {
+                            tui_data.edit_text.push('h');
                         }
-                        KeyCode::Char('h') => {
-                            // Workaround to recognize `^H` control char as backspace
-                            if key.modifiers == KeyModifiers::CONTROL {
-                                tui_data.edit_text.pop();
-                            } else {
-                                tui_data.edit_text.push('h');
+                    }
+                    KeyCode::Char(c) => {
+                        tui_data.edit_text.push(c);
+                    }
+                    _ => (),
+                },
+                InputMode::Template => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.input_mode = InputMode::Normal;
+                        tui_data.note_name_cache.clear();
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => tui_data.template_list.next(),
+                    KeyCode::Char('k') | KeyCode::Up => tui_data.template_list.previous(),
+                    KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                        // The first entry stands for the default template
+                        let template_name = match tui_data.template_list.selected() {
+                            Some(0) | None => None,
+                            Some(_) => tui_data.template_list.selected_item().cloned(),
+                        };
+                        BrnTui::add_note(terminal, tui_data, settings, template_name.as_deref());
+                        tui_data.input_mode = InputMode::Normal;
+                        tui_data.note_name_cache.clear();
+                    }
+                    _ => (),
+                },
+                InputMode::Notebooks => match key.code {
+                    KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
+                    KeyCode::Char('j') | KeyCode::Down => tui_data.notebook_list.next(),
+                    KeyCode::Char('k') | KeyCode::Up => tui_data.notebook_list.previous(),
+                    KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                        match tui_data.is_notebook_move {
+                            true if settings.needs_confirmation(DestructiveAction::MoveNote) => {
+                                BrnTui::confirm_move_to_notebook(tui_data)
                             }
+                            true => BrnTui::move_selected_note_to_notebook(tui_data, settings),
+                            false => BrnTui::switch_to_selected_notebook(tui_data, settings),
                         }
-                        KeyCode::Char(c) => {
-                            tui_data.edit_text.push(c);
-                        }
-                        _ => (),
-                    },
-                    InputMode::Remove => match key.code {
-                        KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
-                        KeyCode::Enter => {
-                            let confirmation_validator = Regex::new("^[Yy]$").unwrap();
-                            if confirmation_validator
-                                .is_match(&tui_data.edit_text.get_content_text())
-                            {
-                                if let Some(selected_note) = tui_data.note_list.selected_item() {
-                                    if let Err(error) =
-                                        NoteUtility::remove(selected_note, &settings.notes_dir)
-                                    {
-                                        tui_data.message = error;
-                                    }
-                                }
-                            }
-                            tui_data.input_mode = InputMode::Normal;
+                    }
+                    _ => (),
+                },
+                InputMode::Labels => match key.code {
+                    KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
+                    KeyCode::Char('j') | KeyCode::Down => tui_data.label_list.next(),
+                    KeyCode::Char('k') | KeyCode::Up => tui_data.label_list.previous(),
+                    KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                        BrnTui::apply_selected_label(tui_data, settings);
+                    }
+                    _ => (),
+                },
+                InputMode::Links => match key.code {
+                    KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
+                    KeyCode::Char('j') | KeyCode::Down => tui_data.link_list.next(),
+                    KeyCode::Char('k') | KeyCode::Up => tui_data.link_list.previous(),
+                    KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                        if let Some(index) = tui_data.link_list.selected() {
+                            BrnTui::follow_link_with_index(index, terminal, tui_data, settings);
                         }
-                        KeyCode::Backspace | KeyCode::Delete => {
-                            tui_data.edit_text.pop();
+                    }
+                    _ => (),
+                },
+                InputMode::Outline => match key.code {
+                    KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
+                    KeyCode::Char('j') | KeyCode::Down => tui_data.outline_list.next(),
+                    KeyCode::Char('k') | KeyCode::Up => tui_data.outline_list.previous(),
+                    KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                        BrnTui::scroll_preview_to_selected_heading(tui_data);
+                    }
+                    _ => (),
+                },
+                InputMode::Finder => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.finder_preview_loader.cancel();
+                        tui_data.is_finder_preview_loading = false;
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                        BrnTui::open_selected_finder_note(terminal, tui_data, settings);
+                    }
+                    KeyCode::Tab => {
+                        tui_data.finder_content_search_enabled =
+                            !tui_data.finder_content_search_enabled;
+                        BrnTui::update_finder_candidates(tui_data, settings);
+                    }
+                    KeyCode::Down => BrnTui::select_finder_candidate(tui_data, settings, true),
+                    KeyCode::Up => BrnTui::select_finder_candidate(tui_data, settings, false),
+                    KeyCode::Char('n') if key.modifiers == KeyModifiers::CONTROL => {
+                        BrnTui::select_finder_candidate(tui_data, settings, true)
+                    }
+                    KeyCode::Char('p') if key.modifiers == KeyModifiers::CONTROL => {
+                        BrnTui::select_finder_candidate(tui_data, settings, false)
+                    }
+                    KeyCode::Backspace | KeyCode::Delete => {
+                        tui_data.finder_query.pop();
+                        BrnTui::update_finder_candidates(tui_data, settings);
+                    }
+                    KeyCode::Char('h') => {
+                        // Workaround to recognize `^H` control char as backspace
+                        if key.modifiers == KeyModifiers::CONTROL {
+                            tui_data.finder_query.pop();
+                        } else {
+                            tui_data.finder_query.push('h');
                         }
-                        KeyCode::Char('h') => {
-                            // Workaround to recognize `^H` control char as backspace
-                            if key.modifiers == KeyModifiers::CONTROL {
-                                tui_data.edit_text.pop();
-                            } else {
-                                tui_data.edit_text.push('h');
+                        BrnTui::update_finder_candidates(tui_data, settings);
+                    }
+                    KeyCode::Char(c) => {
+                        tui_data.finder_query.push(c);
+                        BrnTui::update_finder_candidates(tui_data, settings);
+                    }
+                    _ => (),
+                },
+                InputMode::CommandPalette => match key.code {
+                    KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
+                    KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                        let selected_action = tui_data.command_list.selected_item().copied();
+                        if let Some(action) = selected_action {
+                            if BrnTui::execute_action(action, terminal, tui_data, settings) {
+                                return Ok(());
                             }
                         }
-                        KeyCode::Char(c) => {
-                            tui_data.edit_text.push(c);
+                    }
+                    KeyCode::Down => tui_data.command_list.next(),
+                    KeyCode::Up => tui_data.command_list.previous(),
+                    KeyCode::Char('n') if key.modifiers == KeyModifiers::CONTROL => {
+                        tui_data.command_list.next()
+                    }
+                    KeyCode::Char('p') if key.modifiers == KeyModifiers::CONTROL => {
+                        tui_data.command_list.previous()
+                    }
+                    KeyCode::Backspace | KeyCode::Delete => {
+                        tui_data.command_query.pop();
+                        BrnTui::update_command_candidates(tui_data);
+                    }
+                    KeyCode::Char('h') => {
+                        // Workaround to recognize `^H` control char as backspace
+                        if key.modifiers == KeyModifiers::CONTROL {
+                            tui_data.command_query.pop();
+                        } else {
+                            tui_data.command_query.push('h');
+                        }
+                        BrnTui::update_command_candidates(tui_data);
+                    }
+                    KeyCode::Char(c) => {
+                        tui_data.command_query.push(c);
+                        BrnTui::update_command_candidates(tui_data);
+                    }
+                    _ => (),
+                },
+                InputMode::PreviewSelection => match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => tui_data.input_mode = InputMode::Normal,
+                    KeyCode::Char('j') | KeyCode::Down => BrnTui::move_preview_cursor(tui_data, 1),
+                    KeyCode::Char('k') | KeyCode::Up => BrnTui::move_preview_cursor(tui_data, -1),
+                    // The half-page jumps move in the focused pane, which is the preview here
+                    _ if settings.keybindings.get_action(&[key]) == Some(Action::HalfPageDown) => {
+                        BrnTui::move_preview_cursor(tui_data, tui_data.preview_height as isize / 2)
+                    }
+                    _ if settings.keybindings.get_action(&[key]) == Some(Action::HalfPageUp) => {
+                        BrnTui::move_preview_cursor(
+                            tui_data,
+                            -(tui_data.preview_height as isize / 2),
+                        )
+                    }
+                    KeyCode::Char('g') | KeyCode::Home => {
+                        BrnTui::move_preview_cursor(tui_data, isize::MIN)
+                    }
+                    KeyCode::Char('G') | KeyCode::End => {
+                        BrnTui::move_preview_cursor(tui_data, isize::MAX)
+                    }
+                    KeyCode::Char('v') | KeyCode::Char(' ') => {
+                        tui_data.preview_selection_start = match tui_data.preview_selection_start {
+                            Some(_) => None,
+                            None => Some(tui_data.preview_cursor),
+                        };
+                    }
+                    KeyCode::Char('x') => BrnTui::toggle_task_at_preview_cursor(tui_data, settings),
+                    KeyCode::Char('y') => {
+                        BrnTui::copy_selected_preview_lines(tui_data);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    // Enter on a heading folds its section, anywhere else it copies the lines
+                    KeyCode::Enter
+                        if tui_data.preview_selection_start.is_some()
+                            || !BrnTui::toggle_fold_at_preview_cursor(tui_data) =>
+                    {
+                        BrnTui::copy_selected_preview_lines(tui_data);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    _ => (),
+                },
+                InputMode::Messages => match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => {
+                        tui_data.input_mode = InputMode::Normal
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        tui_data.message_log_scroll = tui_data.message_log_scroll.saturating_add(1)
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        tui_data.message_log_scroll = tui_data.message_log_scroll.saturating_sub(1)
+                    }
+                    KeyCode::Char('g') | KeyCode::Home => tui_data.message_log_scroll = 0,
+                    KeyCode::Char('G') | KeyCode::End => tui_data.message_log_scroll = u16::MAX,
+                    _ => (),
+                },
+                InputMode::InsertLink => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.link_source_note_id = None;
+                        tui_data.message.clear();
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Enter => {
+                        BrnTui::insert_link_to_selected_note(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    // The target note is selected in the note list
+                    _ => match BrnTui::get_action_of_key(key, tui_data, settings) {
+                        Some(Action::Next) => BrnTui::increment_selected_value(tui_data, settings),
+                        Some(Action::Previous) => {
+                            BrnTui::decrement_selected_value(tui_data, settings)
                         }
+                        Some(Action::First) => BrnTui::select_first_value(tui_data, settings),
+                        Some(Action::Last) => BrnTui::select_last_value(tui_data, settings),
                         _ => (),
                     },
-                    InputMode::Search => match key.code {
-                        KeyCode::Esc => tui_data.input_mode = InputMode::Normal,
-                        KeyCode::Enter => {
-                            tui_data.input_mode = InputMode::Normal;
-                            BrnTui::execute_search(tui_data, settings);
-                            tui_data.note_list_title = tui_data.search_text.get_displayed_text();
+                },
+                InputMode::Remove => match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        BrnTui::remove_selected_note(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    _ => (),
+                },
+                InputMode::Merge => match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        BrnTui::merge_marked_notes_into_selected_note(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    _ => (),
+                },
+                InputMode::MoveToNotebook => match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        BrnTui::move_selected_note_to_notebook(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    _ => (),
+                },
+                InputMode::Rename => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Enter => {
+                        BrnTui::rename_selected_note(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Backspace | KeyCode::Delete => {
+                        tui_data.edit_text.pop();
+                    }
+                    KeyCode::Char('h') => {
+                        // Workaround to recognize `^H` control char as backspace
+                        if key.modifiers == KeyModifiers::CONTROL {
+                            tui_data.edit_text.pop();
+                        } else {
+                            tui_data.edit_text.push('h');
                         }
-                        KeyCode::Backspace | KeyCode::Delete => {
-                            tui_data.search_text.pop();
+                    }
+                    KeyCode::Char(c) => {
+                        tui_data.edit_text.push(c);
+                    }
+                    _ => (),
+                },
+                InputMode::Capture => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Enter => {
+                        BrnTui::capture_edit_text(tui_data, settings);
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Backspace | KeyCode::Delete => {
+                        tui_data.edit_text.pop();
+                    }
+                    KeyCode::Char('h') => {
+                        // Workaround to recognize `^H` control char as backspace
+                        if key.modifiers == KeyModifiers::CONTROL {
+                            tui_data.edit_text.pop();
+                        } else {
+                            tui_data.edit_text.push('h');
                         }
-                        KeyCode::Char('h') => {
-                            // Workaround to recognize `^H` control char as backspace
-                            if key.modifiers == KeyModifiers::CONTROL {
-                                tui_data.search_text.pop();
-                            } else {
-                                tui_data.search_text.push('h');
+                    }
+                    KeyCode::Char(c) => {
+                        tui_data.edit_text.push(c);
+                    }
+                    _ => (),
+                },
+                InputMode::AddTag => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Enter => BrnTui::confirm_tag_of_marked_notes(tui_data, settings),
+                    KeyCode::Down | KeyCode::Tab => tui_data.tag_suggestion_list.next(),
+                    KeyCode::Up | KeyCode::BackTab => {
+                        // The typed tag is used again if no suggestion is selected
+                        if tui_data.tag_suggestion_list.selected() == Some(0) {
+                            tui_data.tag_suggestion_list.select(None);
+                        } else {
+                            tui_data.tag_suggestion_list.previous();
+                        }
+                    }
+                    KeyCode::Backspace | KeyCode::Delete => {
+                        tui_data.edit_text.pop();
+                        BrnTui::update_tag_suggestions(tui_data);
+                    }
+                    KeyCode::Char('h') => {
+                        // Workaround to recognize `^H` control char as backspace
+                        if key.modifiers == KeyModifiers::CONTROL {
+                            tui_data.edit_text.pop();
+                        } else {
+                            tui_data.edit_text.push('h');
+                        }
+                        BrnTui::update_tag_suggestions(tui_data);
+                    }
+                    KeyCode::Char(c) => {
+                        tui_data.edit_text.push(c);
+                        BrnTui::update_tag_suggestions(tui_data);
+                    }
+                    _ => (),
+                },
+                InputMode::Search => match key.code {
+                    KeyCode::Esc => {
+                        tui_data.input_mode = InputMode::Normal;
+                        tui_data.search_query.clear();
+                        BrnTui::apply_search_filter(tui_data, settings);
+                    }
+                    KeyCode::Tab => BrnTui::toggle_content_search(tui_data, settings),
+                    KeyCode::Enter => {
+                        tui_data.input_mode = InputMode::Normal;
+                        let search_query = tui_data.search_query.get_content_text();
+                        if tui_data.content_search_enabled {
+                            if let Err(error) = SearchQuery::parse(&search_query) {
+                                tui_data
+                                    .message
+                                    .set(format!("ERROR: invalid search: {}", error));
                             }
                         }
-                        KeyCode::Char(c) => {
-                            tui_data.search_text.push(c);
+                        if !tui_data.content_search_enabled
+                            && BrnTui::uses_search_operators(&search_query)
+                        {
+                            BrnTui::execute_search(tui_data, settings);
                         }
-                        _ => (),
-                    },
+                        if !search_query.is_empty() {
+                            tui_data.note_list_title = tui_data.search_query.get_displayed_text();
+                        }
+                    }
+                    KeyCode::Backspace | KeyCode::Delete => {
+                        tui_data.search_query.pop();
+                        BrnTui::apply_search_filter(tui_data, settings);
+                    }
+                    KeyCode::Char('h') => {
+                        // Workaround to recognize `^H` control char as backspace
+                        if key.modifiers == KeyModifiers::CONTROL {
+                            tui_data.search_query.pop();
+                        } else {
+                            tui_data.search_query.push('h');
+                        }
+                        BrnTui::apply_search_filter(tui_data, settings);
+                    }
+                    KeyCode::Char(c) => {
+                        tui_data.search_query.push(c);
+                        BrnTui::apply_search_filter(tui_data, settings);
+                    }
+                    _ => (),
+                },
+            }
+        }
+    }
+
+    fn handle_mouse_event<B: Backend + Write>(
+        mouse_event: MouseEvent,
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => BrnTui::increment_selected_value(tui_data, settings),
+            MouseEventKind::ScrollUp => BrnTui::decrement_selected_value(tui_data, settings),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let clicked_index = match BrnTui::get_note_list_index_at(
+                    mouse_event.column,
+                    mouse_event.row,
+                    tui_data,
+                ) {
+                    Some(value) => value,
+                    None => return,
+                };
+
+                let is_double_click = match tui_data.last_click {
+                    Some((click_time, index)) => {
+                        index == clicked_index && click_time.elapsed() < Duration::from_millis(500)
+                    }
+                    None => false,
+                };
+                tui_data.note_list.select(Some(clicked_index));
+                BrnTui::update_note_list_window(tui_data, settings);
+                BrnTui::show_note_content_preview(tui_data, settings);
+
+                if is_double_click {
+                    tui_data.last_click = None;
+                    BrnTui::open_selected_note(terminal, tui_data, settings);
+                } else {
+                    tui_data.last_click = Some((Instant::now(), clicked_index));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn get_note_list_index_at(column: u16, row: u16, tui_data: &TuiData) -> Option<usize> {
+        // The borders of the list can't be clicked
+        let list_area = tui_data.note_list_area.inner(&Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        let is_inside_list = column >= list_area.x
+            && column < list_area.x + list_area.width
+            && row >= list_area.y
+            && row < list_area.y + list_area.height;
+        if !is_inside_list {
+            return None;
+        }
+
+        return tui_data
+            .note_list
+            .get_index_at_row((row - list_area.y) as usize);
+    }
+
+    // Used by the keybindings and the command palette. Returns true if the TUI should quit
+    fn execute_action<B: Backend + Write>(
+        action: Action,
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) -> bool {
+        if action.needs_selected_note() && tui_data.note_list.get_items().is_empty() {
+            tui_data.message.set(BrnTui::get_empty_list_hint(settings));
+            return false;
+        }
+
+        match action {
+            Action::Quit => return true,
+            Action::Next => BrnTui::increment_selected_value(tui_data, settings),
+            Action::Previous => BrnTui::decrement_selected_value(tui_data, settings),
+            Action::First => BrnTui::select_first_value(tui_data, settings),
+            Action::Last => BrnTui::select_last_value(tui_data, settings),
+            Action::Open => BrnTui::open_selected_note(terminal, tui_data, settings),
+            Action::OpenMarked => BrnTui::open_marked_notes(terminal, tui_data, settings),
+            Action::GitCommit => BrnTui::commit_changes(tui_data, settings),
+            Action::FollowLink => BrnTui::follow_link(terminal, tui_data, settings),
+            Action::InsertLink => BrnTui::start_inserting_link(tui_data),
+            Action::Outline => BrnTui::show_outline(tui_data),
+            Action::Finder => BrnTui::show_finder(tui_data, settings),
+            Action::Back => BrnTui::go_back(tui_data, settings),
+            Action::Forward => BrnTui::go_forward(tui_data, settings),
+            Action::Home => BrnTui::go_home(tui_data, settings),
+            Action::CopyLink => BrnTui::copy_selected_note_as_link(tui_data),
+            Action::CopyPath => BrnTui::copy_selected_note_path(tui_data, settings),
+            Action::CopyId => BrnTui::copy_selected_note_id(tui_data),
+            Action::HalfPageDown => BrnTui::move_selection_down(
+                tui_data,
+                settings,
+                tui_data.note_list_area.height.saturating_sub(2) as usize / 2,
+            ),
+            Action::HalfPageUp => BrnTui::move_selection_up(
+                tui_data,
+                settings,
+                tui_data.note_list_area.height.saturating_sub(2) as usize / 2,
+            ),
+            Action::ScrollDown => {
+                BrnTui::scroll_preview_down(tui_data, tui_data.preview_height / 2)
+            }
+            Action::ScrollUp => BrnTui::scroll_preview_up(tui_data, tui_data.preview_height / 2),
+            Action::NextMatch => BrnTui::scroll_preview_to_match(tui_data, true),
+            Action::PreviousMatch => BrnTui::scroll_preview_to_match(tui_data, false),
+            Action::PageDown => BrnTui::scroll_preview_down(tui_data, tui_data.preview_height),
+            Action::PageUp => BrnTui::scroll_preview_up(tui_data, tui_data.preview_height),
+            Action::LineNumbers => BrnTui::toggle_line_numbers(tui_data, settings),
+            Action::ShrinkList => BrnTui::change_list_width(tui_data, settings, -LIST_WIDTH_STEP),
+            Action::GrowList => BrnTui::change_list_width(tui_data, settings, LIST_WIDTH_STEP),
+            Action::Add => {
+                tui_data.edit_text.set_pre_text("Name: ");
+                tui_data.note_name_cache.clear();
+                tui_data.parent_note_id = None;
+                tui_data.broken_link_cache = None;
+                tui_data.input_mode = InputMode::Add;
+            }
+            Action::Capture => {
+                tui_data.edit_text.set_pre_text("Capture: ");
+                tui_data.input_mode = InputMode::Capture;
+            }
+            Action::AddChild => {
+                if let Some(parent_note_id) = BrnTui::get_selected_note_id(tui_data) {
+                    tui_data.edit_text.set_pre_text("Name of child note: ");
+                    tui_data.note_name_cache.clear();
+                    tui_data.parent_note_id = Some(parent_note_id);
+                    tui_data.broken_link_cache = None;
+                    tui_data.input_mode = InputMode::Add;
+                }
+            }
+            // Siblings of notes without a parent are created without a parent too
+            Action::AddSibling => {
+                if let Some(selected_note_id) = BrnTui::get_selected_note_id(tui_data) {
+                    tui_data.edit_text.set_pre_text("Name of sibling note: ");
+                    tui_data.note_name_cache.clear();
+                    tui_data.parent_note_id =
+                        NoteUtility::get_parent_note_id(&selected_note_id, settings);
+                    tui_data.broken_link_cache = None;
+                    tui_data.input_mode = InputMode::Add;
+                }
+            }
+            Action::DefaultList => {
+                BrnTui::show_default_note_list(tui_data, settings);
+                BrnTui::show_note_content_preview(tui_data, settings);
+            }
+            Action::History => {
+                let note_history = NoteUtility::get_note_history(settings);
+                BrnTui::replace_note_list_items(
+                    tui_data,
+                    note_history.iter().map(|m| m.note_name.clone()).collect(),
+                );
+                tui_data.note_list_total = None;
+                tui_data.note_list.select(Some(0));
+                tui_data.note_list_title = String::from("History");
+                tui_data.search_query.clear();
+            }
+            Action::Sort => BrnTui::change_sort_order(tui_data, settings),
+            Action::Mark => BrnTui::toggle_mark_of_selected_note(tui_data, settings),
+            Action::Pin => BrnTui::toggle_pin_of_selected_note(tui_data, settings),
+            Action::TagMarked => {
+                if tui_data.marked_notes.is_empty() {
+                    tui_data
+                        .message
+                        .set("no notes are marked (notes are marked with Space)".to_string());
+                } else {
+                    tui_data.edit_text.set_pre_text("Tag: ");
+                    tui_data.new_tag_to_confirm = None;
+                    BrnTui::update_tag_suggestions(tui_data);
+                    tui_data.input_mode = InputMode::AddTag;
+                }
+            }
+            Action::Rename => {
+                if let Some(selected_note) = tui_data.note_list.selected_item() {
+                    let selected_note = selected_note.clone();
+                    tui_data.edit_text.set_pre_text("Rename to: ");
+                    for c in selected_note.chars() {
+                        tui_data.edit_text.push(c);
+                    }
+                    tui_data.input_mode = InputMode::Rename;
+                }
+            }
+            Action::Random => {
+                let note_id_list =
+                    NoteUtility::get_random_note_ids(10, None, &mut tui_data.random_generator);
+                let mut note_list = Vec::new();
+                for note_id in note_id_list {
+                    if let Some(note) = Database::get_note_where_id(&note_id) {
+                        note_list.push(note);
+                    };
+                }
+
+                BrnTui::replace_note_list_items(
+                    tui_data,
+                    note_list.iter().map(|m| m.note_name.clone()).collect(),
+                );
+                tui_data.note_list_total = None;
+                tui_data.note_list.select(Some(0));
+                tui_data.note_list_title = String::from("Random notes");
+                tui_data.search_query.clear();
+            }
+            Action::Archived => {
+                let mut note_list = Database::get_archived_notes()
+                    .into_iter()
+                    .map(|m| m.note_name)
+                    .collect();
+                NoteUtility::sort_note_names(&mut note_list, settings.sort_order, settings);
+                BrnTui::replace_note_list_items(tui_data, note_list);
+                tui_data.note_list_total = None;
+                tui_data.note_list.select(Some(0));
+                tui_data.note_list.clamp_selection();
+                tui_data.note_list_title = String::from("Archived");
+                tui_data.search_query.clear();
+                BrnTui::show_note_content_preview(tui_data, settings);
+            }
+            Action::Archive => BrnTui::toggle_archive_of_selected_note(tui_data, settings),
+            Action::RandomNote => BrnTui::select_random_note(tui_data, settings),
+            Action::Orphans => {
+                let mut note_list = Database::get_orphan_notes(false)
+                    .into_iter()
+                    .map(|m| m.note_name)
+                    .collect();
+                NoteUtility::sort_note_names(&mut note_list, settings.sort_order, settings);
+                BrnTui::replace_note_list_items(tui_data, note_list);
+                tui_data.note_list_total = None;
+                tui_data.note_list.select(Some(0));
+                tui_data.note_list.clamp_selection();
+                tui_data.note_list_title = String::from("Orphans");
+                tui_data.search_query.clear();
+                BrnTui::show_note_content_preview(tui_data, settings);
+            }
+            Action::Remove => {
+                if !settings.needs_confirmation(DestructiveAction::Delete) {
+                    BrnTui::remove_selected_note(tui_data, settings);
+                } else if let Some(selected_note) = tui_data.note_list.selected_item() {
+                    let confirmation_text = format!("Delete {}? (y/n): ", selected_note);
+                    tui_data.edit_text.set_pre_text(&confirmation_text);
+                    tui_data.input_mode = InputMode::Remove;
+                }
+            }
+            Action::Merge => {
+                let selected_note_name = tui_data.note_list.selected_item().cloned();
+                let merged_note_count = tui_data
+                    .marked_notes
+                    .iter()
+                    .filter(|m| Some(*m) != selected_note_name.as_ref())
+                    .count();
+                if merged_note_count == 0 {
+                    tui_data
+                        .message
+                        .set("no notes are marked (notes are marked with Space)".to_string());
+                } else if !settings.needs_confirmation(DestructiveAction::NoteMerge) {
+                    BrnTui::merge_marked_notes_into_selected_note(tui_data, settings);
+                } else if let Some(selected_note_name) = selected_note_name {
+                    let confirmation_text = format!(
+                        "Merge {} marked notes into {}? (y/n): ",
+                        merged_note_count, selected_note_name
+                    );
+                    tui_data.edit_text.set_pre_text(&confirmation_text);
+                    tui_data.input_mode = InputMode::Merge;
+                }
+            }
+            Action::Undo => BrnTui::undo_last_action(tui_data, settings),
+            Action::Notebooks => BrnTui::show_notebook_picker(tui_data, settings, false),
+            Action::MoveToNotebook => BrnTui::show_notebook_picker(tui_data, settings, true),
+            Action::Label => BrnTui::show_label_picker(tui_data, false),
+            Action::LabeledNotes => BrnTui::show_label_picker(tui_data, true),
+            Action::Filter => BrnTui::enter_search_mode(tui_data),
+            Action::Tags => BrnTui::show_tag_sidebar(tui_data),
+            Action::TogglePreview => BrnTui::toggle_panel(tui_data, Panel::Preview),
+            Action::ToggleBacklinks => BrnTui::toggle_panel(tui_data, Panel::Backlinks),
+            Action::ToggleLayout => {
+                tui_data.split_direction = tui_data.split_direction.toggled();
+            }
+            Action::Help => tui_data.input_mode = InputMode::Help,
+            Action::CommandPalette => BrnTui::show_command_palette(tui_data),
+            Action::Messages => {
+                if tui_data.message.get_entries().is_empty() {
+                    tui_data.message.set("no messages yet".to_string());
+                } else {
+                    tui_data.message_log_scroll = 0;
+                    tui_data.input_mode = InputMode::Messages;
                 }
             }
+            Action::SelectText => BrnTui::enter_preview_selection_mode(tui_data),
+            Action::Backlinks => {
+                if !tui_data.visible_panels.contains(&Panel::Backlinks) {
+                    tui_data.message.set("the backlinks are hidden".to_string());
+                } else if !tui_data.backlink_list.get_items().is_empty() {
+                    tui_data.backlink_list.select(Some(0));
+                    tui_data.input_mode = InputMode::Backlinks;
+                }
+            }
+        }
+        return false;
+    }
+
+    fn get_action_of_key(
+        key: KeyEvent,
+        tui_data: &mut TuiData,
+        settings: &Settings,
+    ) -> Option<Action> {
+        tui_data.pending_keys.push(key);
+        if let Some(action) = settings.keybindings.get_action(&tui_data.pending_keys) {
+            tui_data.pending_keys.clear();
+            return Some(action);
+        }
+        if settings
+            .keybindings
+            .is_start_of_key_sequence(&tui_data.pending_keys)
+        {
+            return None;
+        }
+
+        // The key doesn't continue the pending key sequence, so it's used on its own
+        tui_data.pending_keys.clear();
+        if let Some(action) = settings.keybindings.get_action(&[key]) {
+            return Some(action);
+        }
+        if settings.keybindings.is_start_of_key_sequence(&[key]) {
+            tui_data.pending_keys.push(key);
+        }
+        return None;
+    }
+
+    // The width is remembered for the next start of the TUI
+    // The top of the list is selected if the last selected note doesn't exist anymore
+    fn restore_session(tui_data: &mut TuiData, settings: &mut Settings) {
+        if !settings.last_search_query.is_empty() {
+            BrnTui::enter_search_mode(tui_data);
+            for c in settings.last_search_query.chars() {
+                tui_data.search_query.push(c);
+            }
+            BrnTui::apply_search_filter(tui_data, settings);
+            if BrnTui::uses_search_operators(&settings.last_search_query) {
+                BrnTui::execute_search(tui_data, settings);
+            }
+            tui_data.note_list_title = tui_data.search_query.get_displayed_text();
+            tui_data.input_mode = InputMode::Normal;
+        }
+
+        if let Some(note) = settings
+            .last_note_id
+            .as_ref()
+            .and_then(|m| Database::get_note_where_id(m))
+        {
+            if !BrnTui::select_note_with_name(tui_data, settings, &note.note_name) {
+                tui_data.note_list.select(Some(0));
+                tui_data.note_list.clamp_selection();
+            }
+        }
+    }
+
+    // Only the filter of the note list is remembered, as the content search could take long
+    fn save_session(tui_data: &mut TuiData, settings: &mut Settings) {
+        settings.last_note_id = BrnTui::get_selected_note_id(tui_data);
+        settings.last_search_query = if tui_data.content_search_enabled {
+            String::new()
+        } else {
+            tui_data.search_query.get_content_text()
+        };
+        if let Err(error) = settings.save_state() {
+            Message::warning(&format!("saving session: {}", error));
+        }
+    }
+
+    fn change_list_width(tui_data: &mut TuiData, settings: &mut Settings, change: i64) {
+        tui_data.list_width = Settings::clamp_list_width(tui_data.list_width as i64 + change);
+        if tui_data.list_width == settings.list_width {
+            return;
+        }
+
+        settings.list_width = tui_data.list_width;
+        if let Err(error) = settings.save_state() {
+            tui_data.message.set(format!("WARNING: {}", error));
+        }
+    }
+
+    fn toggle_line_numbers(tui_data: &mut TuiData, settings: &mut Settings) {
+        settings.line_numbers_enabled = !settings.line_numbers_enabled;
+        if let Err(error) = settings.save_state() {
+            tui_data.message.set(format!("WARNING: {}", error));
         }
     }
 
-    fn render_ui<B: Backend>(f: &mut Frame<B>, tui_data: &mut TuiData) {
+    fn render_ui<B: Backend>(f: &mut Frame<B>, tui_data: &mut TuiData, settings: &Settings) {
         let vertical_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
@@ -217,14 +1064,25 @@ impl BrnTui {
                 horizontal: 1,
             }));
 
-        let horizontal_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .margin(0)
-            .constraints([Constraint::Percentage(35), Constraint::Min(0)].as_ref())
-            .split(vertical_chunks[0]);
-
-        BrnTui::render_note_list(f, horizontal_chunks[0], tui_data);
-        BrnTui::render_note_preview(f, horizontal_chunks[1], tui_data);
+        let panel_areas = PanelLayout::split(
+            vertical_chunks[0],
+            tui_data.split_direction,
+            &tui_data.visible_panels,
+            tui_data.list_width,
+        );
+        if let Some(tag_sidebar_area) = panel_areas.tags {
+            BrnTui::render_tag_list(f, tag_sidebar_area, tui_data, &settings.theme);
+        }
+        match panel_areas.list {
+            Some(note_list_area) => BrnTui::render_note_list(f, note_list_area, tui_data, settings),
+            None => tui_data.note_list_area = Rect::default(),
+        }
+        if let Some(backlink_area) = panel_areas.backlinks {
+            BrnTui::render_backlink_list(f, backlink_area, tui_data, &settings.theme);
+        }
+        if let Some(preview_area) = panel_areas.preview {
+            BrnTui::render_note_preview(f, preview_area, tui_data, settings);
+        }
         BrnTui::render_message_block(
             f,
             vertical_chunks[1].inner(&Margin {
@@ -232,177 +1090,3480 @@ impl BrnTui {
                 horizontal: 1,
             }),
             tui_data,
+            settings,
         );
+
+        match tui_data.input_mode {
+            InputMode::Template => BrnTui::render_list_popup(
+                f,
+                "Template",
+                &mut tui_data.template_list,
+                &settings.theme,
+            ),
+            InputMode::Links => {
+                BrnTui::render_list_popup(f, "Links", &mut tui_data.link_list, &settings.theme)
+            }
+            InputMode::Outline => {
+                BrnTui::render_list_popup(f, "Outline", &mut tui_data.outline_list, &settings.theme)
+            }
+            InputMode::Notebooks => BrnTui::render_list_popup(
+                f,
+                match tui_data.is_notebook_move {
+                    true => "Move to notebook",
+                    false => "Notebooks",
+                },
+                &mut tui_data.notebook_list,
+                &settings.theme,
+            ),
+            InputMode::Labels => {
+                BrnTui::render_list_popup(f, "Label", &mut tui_data.label_list, &settings.theme)
+            }
+            InputMode::AddTag => {
+                BrnTui::render_tag_suggestions(f, vertical_chunks[1], tui_data, &settings.theme)
+            }
+            InputMode::Finder => BrnTui::render_finder(f, tui_data, settings),
+            InputMode::CommandPalette => BrnTui::render_command_palette(f, tui_data, settings),
+            InputMode::Messages => BrnTui::render_message_log(f, tui_data, settings),
+            _ => (),
+        }
+        if let InputMode::Help = tui_data.input_mode {
+            BrnTui::render_help_overlay(f, settings);
+        }
     }
 
-    fn render_note_list<B: Backend>(f: &mut Frame<B>, area: Rect, tui_data: &mut TuiData) {
-        let selected_style = Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD);
-        let normal_style = Style::default().fg(Color::White);
+    fn render_help_overlay<B: Backend>(f: &mut Frame<B>, settings: &Settings) {
+        let theme = &settings.theme;
+        let help_entries = settings.keybindings.get_help_entries();
+        let keys_column_width = help_entries
+            .iter()
+            .map(|m| m.0.chars().count())
+            .max()
+            .unwrap_or(0) as u16;
+        let description_column_width = help_entries
+            .iter()
+            .map(|m| m.1.chars().count())
+            .max()
+            .unwrap_or(0) as u16;
 
-        // Get notes to show
-        let items: Vec<ListItem> = tui_data
-            .note_list
-            .get_items()
+        // 2 for the borders and 1 for the space between the columns
+        let overlay_area = BrnTui::get_centered_rect(
+            keys_column_width + description_column_width + 3,
+            help_entries.len() as u16 + 2,
+            f.size(),
+        );
+
+        let rows: Vec<Row> = help_entries
             .iter()
-            .map(|m| ListItem::new(m.to_string()))
+            .map(|m| {
+                Row::new(vec![
+                    Cell::from(m.0.as_str()).style(Style::default().fg(theme.selected)),
+                    Cell::from(m.1),
+                ])
+            })
             .collect();
+        let column_widths = [Constraint::Length(keys_column_width), Constraint::Min(0)];
+        let help_table = Table::new(rows)
+            .block(BrnTui::get_block(
+                "Keybindings (? or Esc to close)".to_string(),
+                theme,
+            ))
+            .widths(&column_widths);
 
-        // Render note list
-        let list = List::new(items)
+        f.render_widget(Clear, overlay_area);
+        f.render_widget(help_table, overlay_area);
+    }
+
+    fn render_command_palette<B: Backend>(
+        f: &mut Frame<B>,
+        tui_data: &mut TuiData,
+        settings: &Settings,
+    ) {
+        let theme = &settings.theme;
+        let entries: Vec<(&str, String, &str)> = Action::all()
+            .into_iter()
+            .map(|m| {
+                (
+                    m.to_name(),
+                    settings.keybindings.get_keys_of(m),
+                    m.get_description(),
+                )
+            })
+            .collect();
+        let name_column_width = entries.iter().map(|m| m.0.len()).max().unwrap_or(0);
+        let keys_column_width = entries
+            .iter()
+            .map(|m| m.1.chars().count())
+            .max()
+            .unwrap_or(0);
+        let description_column_width = entries
+            .iter()
+            .map(|m| m.2.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        // The size is based on all actions, so that the palette doesn't change its size while typing.
+        // 2 for the borders, 2 for the highlight symbol and 4 for the space between the columns
+        let palette_area = BrnTui::get_centered_rect(
+            (name_column_width + keys_column_width + description_column_width + 8) as u16,
+            entries.len() as u16 + 5,
+            f.size(),
+        );
+        let vertical_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(palette_area);
+
+        let query_paragraph = Paragraph::new(tui_data.command_query.get_displayed_text()).block(
+            BrnTui::get_block("Command (Esc to close)".to_string(), theme),
+        );
+
+        let items: Vec<ListItem> = tui_data
+            .command_list
+            .get_items()
+            .iter()
+            .map(|m| {
+                ListItem::new(Spans::from(vec![
+                    Span::raw(format!(
+                        "{:width$}  ",
+                        m.to_name(),
+                        width = name_column_width
+                    )),
+                    Span::styled(
+                        format!(
+                            "{:width$}  ",
+                            settings.keybindings.get_keys_of(*m),
+                            width = keys_column_width
+                        ),
+                        Style::default().fg(theme.status),
+                    ),
+                    Span::raw(m.get_description()),
+                ]))
+            })
+            .collect();
+        let list_title = format!("Actions ({})", items.len());
+        let list = List::new(items)
+            .style(Style::default().fg(theme.normal))
+            .highlight_style(
+                Style::default()
+                    .fg(theme.selected)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ")
+            .block(BrnTui::get_block(list_title, theme));
+
+        f.render_widget(Clear, palette_area);
+        f.render_widget(query_paragraph, vertical_chunks[0]);
+        f.render_stateful_widget(list, vertical_chunks[1], tui_data.command_list.get_state());
+    }
+
+    // The most recent message is shown first
+    fn render_message_log<B: Backend>(
+        f: &mut Frame<B>,
+        tui_data: &mut TuiData,
+        settings: &Settings,
+    ) {
+        let theme = &settings.theme;
+        let log_area = f.size().inner(&Margin {
+            vertical: 2,
+            horizontal: 4,
+        });
+        let lines: Vec<Spans> = tui_data
+            .message
+            .get_entries()
+            .iter()
+            .rev()
+            .map(|m| {
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{} ", m.time.format("%H:%M:%S")),
+                        Style::default().fg(theme.status),
+                    ),
+                    Span::styled(
+                        m.text.clone(),
+                        Style::default().fg(BrnTui::get_color_of_message_level(m.level, theme)),
+                    ),
+                ])
+            })
+            .collect();
+
+        // 2 for the borders
+        let max_scroll = (lines.len() as u16).saturating_sub(log_area.height.saturating_sub(2));
+        tui_data.message_log_scroll = tui_data.message_log_scroll.min(max_scroll);
+
+        let title = format!("Messages ({})", lines.len());
+        let log_paragraph = Paragraph::new(lines)
+            .block(BrnTui::get_block(title, theme))
+            .scroll((tui_data.message_log_scroll, 0));
+        f.render_widget(Clear, log_area);
+        f.render_widget(log_paragraph, log_area);
+    }
+
+    fn get_color_of_message_level(level: MessageLevel, theme: &Theme) -> Color {
+        match level {
+            MessageLevel::Info => theme.message,
+            MessageLevel::Warning => theme.warning,
+            MessageLevel::Error => theme.error,
+        }
+    }
+
+    fn render_list_popup<B: Backend>(
+        f: &mut Frame<B>,
+        title: &str,
+        list: &mut StatefulList<String>,
+        theme: &Theme,
+    ) {
+        let width = list
+            .get_items()
+            .iter()
+            .map(|m| m.width())
+            .chain(std::iter::once(title.len()))
+            .max()
+            .unwrap_or(0) as u16;
+
+        // 2 for the borders and 2 for the highlight symbol
+        let overlay_area =
+            BrnTui::get_centered_rect(width + 4, list.get_items().len() as u16 + 2, f.size());
+
+        let items: Vec<ListItem> = list
+            .get_items()
+            .iter()
+            .map(|m| ListItem::new(m.to_string()))
+            .collect();
+        let list_widget = List::new(items)
+            .style(Style::default().fg(theme.normal))
+            .highlight_style(
+                Style::default()
+                    .fg(theme.selected)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ")
+            .block(BrnTui::get_block(title.to_string(), theme));
+
+        f.render_widget(Clear, overlay_area);
+        f.render_stateful_widget(list_widget, overlay_area, list.get_state());
+    }
+
+    // Covers the whole TUI, so that the list and the preview underneath stay unchanged
+    fn render_finder<B: Backend>(f: &mut Frame<B>, tui_data: &mut TuiData, settings: &Settings) {
+        let theme = &settings.theme;
+        let finder_area = f.size().inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let vertical_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(finder_area);
+        let horizontal_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Min(0)].as_ref())
+            .split(vertical_chunks[1]);
+        f.render_widget(Clear, finder_area);
+
+        let title = if tui_data.finder_content_search_enabled {
+            "Find note by content (Tab: search names)"
+        } else {
+            "Find note (Tab: search content)"
+        };
+        let query_paragraph = Paragraph::new(tui_data.finder_query.get_displayed_text())
+            .block(BrnTui::get_block(title.to_string(), theme));
+        f.render_widget(query_paragraph, vertical_chunks[0]);
+
+        let search_query = tui_data.finder_query.get_content_text();
+        let content_search_enabled = tui_data.finder_content_search_enabled;
+        let items: Vec<ListItem> = tui_data
+            .finder_list
+            .get_items()
+            .iter()
+            .map(|m| {
+                if content_search_enabled {
+                    ListItem::new(m.to_string())
+                } else {
+                    ListItem::new(BrnTui::get_fuzzy_match_spans(&search_query, m))
+                }
+            })
+            .collect();
+        let list_title = format!("Notes ({})", tui_data.finder_list.get_items().len());
+        let list = List::new(items)
+            .style(Style::default().fg(theme.normal))
+            .highlight_style(
+                Style::default()
+                    .fg(theme.selected)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ")
+            .block(BrnTui::get_block(list_title, theme));
+        f.render_stateful_widget(list, horizontal_chunks[0], tui_data.finder_list.get_state());
+
+        let preview_lines = if tui_data.is_finder_preview_loading {
+            vec![Spans::from("loading...")]
+        } else {
+            BrnTui::render_preview_content(
+                &tui_data.finder_preview,
+                tui_data.finder_preview_render_mode,
+                &tui_data.finder_preview_broken_links,
+                settings,
+            )
+        };
+        let preview_title = match tui_data.finder_list.selected_item() {
+            Some(value) => value.clone(),
+            None => "Note preview".to_string(),
+        };
+        f.render_widget(
+            BrnTui::get_block(preview_title, theme),
+            horizontal_chunks[1],
+        );
+        let mut preview_paragraph = Paragraph::new(Text::from(preview_lines));
+        if settings.preview_wrap_enabled || tui_data.finder_preview_render_mode == RenderMode::Plain
+        {
+            preview_paragraph = preview_paragraph.wrap(Wrap { trim: false });
+        }
+        f.render_widget(
+            preview_paragraph,
+            horizontal_chunks[1].inner(&Margin {
+                vertical: 1,
+                horizontal: 2,
+            }),
+        );
+    }
+
+    // Shown like a dropdown above the tag prompt
+    fn render_tag_suggestions<B: Backend>(
+        f: &mut Frame<B>,
+        message_area: Rect,
+        tui_data: &mut TuiData,
+        theme: &Theme,
+    ) {
+        let title = if tui_data.new_tag_to_confirm.is_some() {
+            "New tag: press Enter again to add it"
+        } else {
+            "Tags"
+        };
+        let items: Vec<String> = tui_data
+            .tag_suggestion_list
+            .get_items()
+            .iter()
+            .map(|(tag_name, note_count)| format!("#{} ({})", tag_name, note_count))
+            .collect();
+        if items.is_empty() && tui_data.new_tag_to_confirm.is_none() {
+            return;
+        }
+
+        // 2 for the borders and 2 for the highlight symbol
+        let width = items
+            .iter()
+            .map(|m| m.width())
+            .chain(std::iter::once(title.len()))
+            .max()
+            .unwrap_or(0) as u16
+            + 4;
+        let height = (items.len().min(MAX_TAG_SUGGESTION_COUNT) as u16 + 2).min(message_area.y);
+        let dropdown_area = Rect::new(
+            message_area.x,
+            message_area.y - height,
+            width.min(message_area.width),
+            height,
+        );
+
+        let list_widget = List::new(
+            items
+                .into_iter()
+                .map(ListItem::new)
+                .collect::<Vec<ListItem>>(),
+        )
+        .style(Style::default().fg(theme.normal))
+        .highlight_style(
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ")
+        .block(BrnTui::get_block(title.to_string(), theme));
+
+        f.render_widget(Clear, dropdown_area);
+        f.render_stateful_widget(
+            list_widget,
+            dropdown_area,
+            tui_data.tag_suggestion_list.get_state(),
+        );
+    }
+
+    fn get_block(title: String, theme: &Theme) -> Block<'static> {
+        return Block::default()
+            .title(Span::styled(title, Style::default().fg(theme.title)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border));
+    }
+
+    fn get_centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+        let width = width.min(area.width);
+        let height = height.min(area.height);
+        return Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+    }
+
+    // The placeholders of the list format are replaced with the data of the note. Data that
+    // doesn't exist (e.g. the tags of a note without tags) is left empty
+    fn get_formatted_list_item(
+        settings: &Settings,
+        note_name: &str,
+        note_details: Option<&(Note, Vec<String>)>,
+        note_name_spans: Spans<'static>,
+        link_count_text: Option<&str>,
+    ) -> Spans<'static> {
+        let list_format = settings.list_format.as_str();
+        let replace_placeholders_in = |text: &str| -> String {
+            let mut text = text.to_string();
+            if text.contains("{id}") {
+                let note_id = note_details.map(|(note, _)| note.note_id.clone());
+                text = text.replace("{id}", &note_id.unwrap_or_default());
+            }
+            if text.contains("{tags}") {
+                let tags = note_details.map(|(_, tags)| tags.join(", "));
+                text = text.replace("{tags}", &tags.unwrap_or_default());
+            }
+            if text.contains("{date}") {
+                let date = note_details
+                    .and_then(|(note, _)| note.creation_date_time)
+                    .map(|m| m.format(&settings.date_format).to_string());
+                text = text.replace("{date}", &date.unwrap_or_default());
+            }
+            return text.replace("{links}", link_count_text.unwrap_or_default());
+        };
+
+        // The note name keeps the highlighting of the fuzzy matches
+        let (before_name, after_name) = match list_format.split_once("{name}") {
+            Some((before_name, after_name)) => (before_name, Some(after_name)),
+            None => (list_format, None),
+        };
+        let mut spans = Vec::new();
+        let text_before_name = replace_placeholders_in(before_name);
+        if !text_before_name.is_empty() {
+            spans.push(Span::raw(text_before_name));
+        }
+        if let Some(after_name) = after_name {
+            spans.extend(note_name_spans.0);
+            let text_after_name = replace_placeholders_in(&after_name.replace("{name}", note_name));
+            if !text_after_name.is_empty() {
+                spans.push(Span::raw(text_after_name));
+            }
+        }
+        return Spans::from(spans);
+    }
+
+    fn render_note_list<B: Backend>(
+        f: &mut Frame<B>,
+        area: Rect,
+        tui_data: &mut TuiData,
+        settings: &Settings,
+    ) {
+        let theme = &settings.theme;
+        let selected_style = Style::default()
+            .fg(theme.selected)
+            .add_modifier(Modifier::BOLD);
+        let normal_style = Style::default().fg(theme.normal);
+        let marked_style = Style::default()
+            .fg(theme.selected)
+            .add_modifier(Modifier::BOLD);
+        // The previewed note differs from the selected one while its preview is loading
+        let previewed_style = Style::default()
+            .fg(theme.selected)
+            .add_modifier(Modifier::ITALIC);
+
+        // Get notes to show
+        let search_query = tui_data.search_query.get_content_text();
+        let emphasize_matches = settings.fuzzy_matching_enabled
+            && !tui_data.content_search_enabled
+            && !search_query.is_empty();
+        // 2 for the borders
+        let visible_height = area.height.saturating_sub(2) as usize;
+        // 2 for the borders and 2 for the highlight symbol
+        let row_width = area.width.saturating_sub(4) as usize;
+
+        // Names can be wrapped onto several rows, so the rows of the notes around the selected
+        // one are built first. The shown notes are always within one visible height of it
+        let item_count = tui_data.note_list.get_items().len();
+        let selected_position = tui_data.note_list.selected().unwrap_or(0);
+        let candidate_positions = selected_position.saturating_sub(visible_height)
+            ..(selected_position + visible_height + 1).min(item_count);
+        let candidate_note_names: Vec<String> = tui_data
+            .note_list
+            .get_items()
+            .iter()
+            .skip(candidate_positions.start)
+            .take(candidate_positions.len())
+            .cloned()
+            .collect();
+
+        // Link counts and labels of all candidates are loaded with a single query
+        let link_counts =
+            if settings.link_counts_enabled || settings.list_format.contains("{links}") {
+                Database::get_link_counts_of_note_names(&candidate_note_names)
+            } else {
+                HashMap::new()
+            };
+        let labels = Database::get_labels_of_note_names(&candidate_note_names);
+        if settings.list_format != DEFAULT_LIST_FORMAT || settings.note_id_column_enabled {
+            BrnTui::load_note_list_details_of(tui_data, &candidate_note_names);
+        }
+
+        // The last pinned note is underlined to separate the pinned notes from the others.
+        // The names of the pinned notes are resolved again after the pins or the list changed
+        if tui_data.pinned_note_names.is_none() {
+            tui_data.pinned_note_names = Some(
+                settings
+                    .pinned_note_ids
+                    .iter()
+                    .filter_map(|m| Database::get_note_where_id(m))
+                    .map(|m| m.note_name)
+                    .collect(),
+            );
+        }
+        let pinned_note_names = tui_data.pinned_note_names.as_ref();
+        let last_pinned_index = tui_data
+            .note_list
+            .get_items()
+            .iter()
+            .take_while(|m| pinned_note_names.is_some_and(|pinned| pinned.contains(*m)))
+            .count()
+            .checked_sub(1);
+
+        // The name of the selected note starts to scroll after a moment
+        let selected_index = tui_data.note_list.selected();
+        if tui_data.name_scroll_start.0 != selected_index {
+            tui_data.name_scroll_start = (selected_index, Instant::now());
+        }
+        let name_scroll_offset = BrnTui::get_name_scroll_offset(tui_data);
+        tui_data.name_scroll_offset = name_scroll_offset;
+        let mut is_selected_name_too_long = false;
+
+        let marked_notes = &tui_data.marked_notes;
+        let note_list_details = &tui_data.note_list_details;
+        let previewed_note_name = tui_data.previewed_note_name.as_ref();
+        let candidate_rows: Vec<(Vec<Spans>, Style)> = candidate_note_names
+            .iter()
+            .zip(candidate_positions.clone())
+            .map(|(m, i)| {
+                let mut note_spans = if emphasize_matches {
+                    BrnTui::get_fuzzy_match_spans(&search_query, m)
+                } else {
+                    Spans::from(m.to_string())
+                };
+
+                let note_details = note_list_details.get(m).and_then(|m| m.as_ref());
+                let link_count_text = link_counts
+                    .get(m)
+                    .map(|(incoming, outgoing)| format!("↑{} ↓{}", incoming, outgoing));
+                if settings.list_format != DEFAULT_LIST_FORMAT {
+                    note_spans = BrnTui::get_formatted_list_item(
+                        settings,
+                        m,
+                        note_details,
+                        note_spans,
+                        link_count_text.as_deref(),
+                    );
+                }
+                if let Some(label) = labels.get(m) {
+                    let label_style = Style::default().fg(BrnTui::get_color_of_label(*label));
+                    note_spans.0.insert(0, Span::styled("● ", label_style));
+                }
+                if marked_notes.contains(m) {
+                    note_spans.0.insert(0, Span::styled("* ", marked_style));
+                }
+
+                let mut column_text = Vec::new();
+                if settings.link_counts_enabled {
+                    if let Some(link_count_text) = link_count_text {
+                        column_text.push(link_count_text);
+                    }
+                }
+                if settings.note_id_column_enabled {
+                    if let Some((note, _)) = note_details {
+                        column_text.push(note.note_id.clone());
+                    }
+                }
+                let column_text = column_text.join(" ");
+                let max_name_width = if column_text.is_empty() {
+                    row_width
+                } else {
+                    row_width.saturating_sub(column_text.width() + 1)
+                };
+                let is_selected = selected_index == Some(i);
+                if is_selected && note_spans.width() > max_name_width {
+                    is_selected_name_too_long = true;
+                }
+                let mut row_lines = BrnTui::fit_spans_into_width(
+                    note_spans,
+                    max_name_width,
+                    settings.name_overflow,
+                    if is_selected {
+                        name_scroll_offset
+                    } else {
+                        None
+                    },
+                );
+                if !column_text.is_empty() {
+                    row_lines[0] = BrnTui::get_spans_with_column(
+                        row_lines[0].clone(),
+                        &column_text,
+                        row_width,
+                        Style::default().fg(theme.status),
+                    );
+                }
+                let item_style = if previewed_note_name == Some(m) && !is_selected {
+                    previewed_style
+                } else {
+                    Style::default()
+                };
+                if last_pinned_index == Some(i) {
+                    if let Some(last_line) = row_lines.last_mut() {
+                        let padding = row_width.saturating_sub(last_line.width());
+                        last_line.0.push(Span::raw(" ".repeat(padding)));
+                    }
+                    return (row_lines, item_style.add_modifier(Modifier::UNDERLINED));
+                }
+                (row_lines, item_style)
+            })
+            .collect();
+        let shown_positions = tui_data
+            .note_list
+            .update_offset(visible_height, |position| {
+                position
+                    .checked_sub(candidate_positions.start)
+                    .and_then(|m| candidate_rows.get(m))
+                    .map_or(1, |m| m.0.len())
+            });
+        let items: Vec<ListItem> = candidate_rows
+            .into_iter()
+            .skip(shown_positions.start - candidate_positions.start)
+            .take(shown_positions.len())
+            .map(|(row_lines, item_style)| ListItem::new(Text::from(row_lines)).style(item_style))
+            .collect();
+        tui_data.is_name_scrolling =
+            settings.name_overflow == NameOverflow::Scroll && is_selected_name_too_long;
+
+        // Show the position of the selected note, e.g. 'List (12/340)'
+        let (note_offset, note_count) = match tui_data.note_list_total {
+            Some(value) => (tui_data.note_list_offset, value),
+            None => (0, item_count),
+        };
+        let title = match tui_data.note_list.selected() {
+            Some(selected_index) if note_count > 0 => format!(
+                "{} ({}/{})",
+                tui_data.note_list_title,
+                note_offset + selected_index + 1,
+                note_count
+            ),
+            _ => format!("{} (0)", tui_data.note_list_title),
+        };
+
+        // Render note list
+        let list = List::new(items)
             .style(normal_style)
             .highlight_style(selected_style)
             .highlight_symbol("> ")
-            .block(
-                Block::default()
-                    .title(tui_data.note_list_title.clone())
-                    .borders(Borders::ALL),
-            );
-        f.render_stateful_widget(list, area, tui_data.note_list.get_state());
+            .block(BrnTui::get_block(title, theme));
+        tui_data.note_list_area = area;
+        f.render_stateful_widget(
+            list,
+            area,
+            &mut tui_data.note_list.get_state_of_shown_items(),
+        );
+    }
+
+    // Only the notes that haven't been shown since the note list was loaded are queried
+    fn load_note_list_details_of(tui_data: &mut TuiData, note_names: &[String]) {
+        let missing_note_names: Vec<String> = note_names
+            .iter()
+            .filter(|m| !tui_data.note_list_details.contains_key(*m))
+            .cloned()
+            .collect();
+        if missing_note_names.is_empty() {
+            return;
+        }
+
+        let mut notes = Database::get_notes_with_names(&missing_note_names);
+        let mut tags = Database::get_tags_of_note_names(&missing_note_names);
+        for note_name in missing_note_names {
+            let note_details = notes
+                .remove(&note_name)
+                .map(|note| (note, tags.remove(&note_name).unwrap_or_default()));
+            tui_data.note_list_details.insert(note_name, note_details);
+        }
+    }
+
+    fn render_tag_list<B: Backend>(
+        f: &mut Frame<B>,
+        area: Rect,
+        tui_data: &mut TuiData,
+        theme: &Theme,
+    ) {
+        let selected_style = Style::default()
+            .fg(theme.selected)
+            .add_modifier(Modifier::BOLD);
+        let normal_style = Style::default().fg(theme.normal);
+
+        let highlight_symbol = match tui_data.input_mode {
+            InputMode::Tags => "> ",
+            _ => "",
+        };
+
+        let items: Vec<ListItem> = tui_data
+            .tag_list
+            .get_items()
+            .iter()
+            .map(|(tag_name, note_count)| ListItem::new(format!("#{} ({})", tag_name, note_count)))
+            .collect();
+
+        let mut title = match tui_data.tag_order {
+            TagOrder::Name => "Tags".to_string(),
+            TagOrder::Count => "Tags by count".to_string(),
+        };
+        if tui_data.tag_counts_rolled_up {
+            title.push_str(" (rolled up)");
+        }
+
+        let list = List::new(items)
+            .style(normal_style)
+            .highlight_style(selected_style)
+            .highlight_symbol(highlight_symbol)
+            .block(BrnTui::get_block(title, theme));
+        f.render_stateful_widget(list, area, tui_data.tag_list.get_state());
+    }
+
+    fn render_backlink_list<B: Backend>(
+        f: &mut Frame<B>,
+        area: Rect,
+        tui_data: &mut TuiData,
+        theme: &Theme,
+    ) {
+        let normal_style = Style::default().fg(theme.normal);
+
+        // Only show the selection if the backlink list is focused
+        let selected_style;
+        let highlight_symbol;
+        if let InputMode::Backlinks = tui_data.input_mode {
+            selected_style = Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD);
+            highlight_symbol = "> ";
+        } else {
+            selected_style = normal_style;
+            highlight_symbol = "";
+        }
+
+        let items: Vec<ListItem> = tui_data
+            .backlink_list
+            .get_items()
+            .iter()
+            .map(|m| ListItem::new(m.to_string()))
+            .collect();
+        let title = format!("Backlinks ({})", items.len());
+
+        let list = List::new(items)
+            .style(normal_style)
+            .highlight_style(selected_style)
+            .highlight_symbol(highlight_symbol)
+            .block(BrnTui::get_block(title, theme));
+        f.render_stateful_widget(list, area, tui_data.backlink_list.get_state());
+    }
+
+    // The column text (e.g. the note id) is right-aligned.
+    // The name is shortened if it doesn't fit next to it
+    fn get_name_scroll_offset(tui_data: &TuiData) -> Option<usize> {
+        return tui_data
+            .name_scroll_start
+            .1
+            .elapsed()
+            .checked_sub(NAME_SCROLL_DELAY)
+            .map(|m| (m.as_millis() / NAME_SCROLL_INTERVAL.as_millis()) as usize);
+    }
+
+    // Returns the lines of the row. Names are only scrolled if the scroll offset is given
+    fn fit_spans_into_width(
+        spans: Spans<'static>,
+        max_width: usize,
+        name_overflow: NameOverflow,
+        scroll_offset: Option<usize>,
+    ) -> Vec<Spans<'static>> {
+        if spans.width() <= max_width || max_width == 0 {
+            return vec![spans];
+        }
+        let styled_chars: Vec<(char, Style)> = spans
+            .0
+            .iter()
+            .flat_map(|m| m.content.chars().map(move |c| (c, m.style)))
+            .collect();
+
+        match (name_overflow, scroll_offset) {
+            (NameOverflow::Wrap, _) => {
+                let mut lines = Vec::new();
+                let mut remaining_chars = &styled_chars[..];
+                while !remaining_chars.is_empty() {
+                    let line_chars = BrnTui::get_chars_within_width(remaining_chars, max_width);
+                    // A character that is wider than the row is shown anyway
+                    let char_count = line_chars.len().max(1);
+                    lines.push(BrnTui::get_spans_of_styled_chars(
+                        &remaining_chars[..char_count],
+                    ));
+                    remaining_chars = &remaining_chars[char_count..];
+                }
+                return lines;
+            }
+            (NameOverflow::Scroll, Some(scroll_offset)) => {
+                // The end of the name is followed by its start again
+                let mut looped_chars = styled_chars;
+                looped_chars.extend("   ".chars().map(|c| (c, Style::default())));
+                let scroll_offset = scroll_offset % looped_chars.len();
+                looped_chars.rotate_left(scroll_offset);
+                let mut shown_chars = BrnTui::get_chars_within_width(&looped_chars, max_width);
+                BrnTui::pad_chars_to_width(&mut shown_chars, max_width);
+                return vec![BrnTui::get_spans_of_styled_chars(&shown_chars)];
+            }
+            _ => {
+                let mut shown_chars = BrnTui::get_chars_within_width(&styled_chars, max_width - 1);
+                BrnTui::pad_chars_to_width(&mut shown_chars, max_width - 1);
+                shown_chars.push(('…', Style::default()));
+                return vec![BrnTui::get_spans_of_styled_chars(&shown_chars)];
+            }
+        }
+    }
+
+    // Wide characters (e.g. CJK characters) take two columns, so they are never split
+    // and are left out entirely if they don't fit anymore
+    fn get_chars_within_width(
+        styled_chars: &[(char, Style)],
+        max_width: usize,
+    ) -> Vec<(char, Style)> {
+        let mut width = 0;
+        return styled_chars
+            .iter()
+            .take_while(|m| {
+                width += m.0.width().unwrap_or(0);
+                width <= max_width
+            })
+            .cloned()
+            .collect();
+    }
+
+    // Fills the column that is left free when a wide character doesn't fit anymore
+    fn pad_chars_to_width(styled_chars: &mut Vec<(char, Style)>, width: usize) {
+        let chars_width: usize = styled_chars.iter().map(|m| m.0.width().unwrap_or(0)).sum();
+        for _ in chars_width..width {
+            styled_chars.push((' ', Style::default()));
+        }
+    }
+
+    // Neighboring characters with the same style are put into the same span
+    fn get_spans_of_styled_chars(styled_chars: &[(char, Style)]) -> Spans<'static> {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (c, style) in styled_chars {
+            match spans.last_mut() {
+                Some(span) if span.style == *style => span.content.to_mut().push(*c),
+                _ => spans.push(Span::styled(c.to_string(), *style)),
+            }
+        }
+        return Spans::from(spans);
+    }
+
+    fn get_spans_with_column(
+        note_spans: Spans<'static>,
+        column_text: &str,
+        row_width: usize,
+        column_style: Style,
+    ) -> Spans<'static> {
+        let column_width = column_text.width();
+        let max_name_width = row_width.saturating_sub(column_width + 1);
+        let mut name_width = note_spans.width();
+        let mut spans = note_spans.0;
+
+        if name_width > max_name_width {
+            let mut remaining_width = max_name_width.saturating_sub(1);
+            let mut shortened_spans = Vec::new();
+            for span in spans {
+                let content: String = span
+                    .content
+                    .chars()
+                    .take_while(|c| match c.width().unwrap_or(0) {
+                        char_width if char_width <= remaining_width => {
+                            remaining_width -= char_width;
+                            true
+                        }
+                        _ => {
+                            remaining_width = 0;
+                            false
+                        }
+                    })
+                    .collect();
+                if !content.is_empty() {
+                    shortened_spans.push(Span::styled(content, span.style));
+                }
+            }
+            if max_name_width > 0 {
+                shortened_spans.push(Span::raw("…"));
+            }
+            name_width = Spans::from(shortened_spans.clone()).width();
+            spans = shortened_spans;
+        }
+
+        let padding = row_width.saturating_sub(name_width + column_width).max(1);
+        spans.push(Span::raw(" ".repeat(padding)));
+        spans.push(Span::styled(column_text.to_string(), column_style));
+        return Spans::from(spans);
+    }
+
+    fn get_fuzzy_match_spans(search_query: &str, note_name: &str) -> Spans<'static> {
+        let matched_indices = match FuzzyMatcher::fuzzy_match(search_query, note_name) {
+            Some(fuzzy_match) => fuzzy_match.matched_indices,
+            None => Vec::new(),
+        };
+
+        let matched_style = Style::default().add_modifier(Modifier::UNDERLINED);
+        let spans: Vec<Span> = note_name
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if matched_indices.contains(&i) {
+                    Span::styled(c.to_string(), matched_style)
+                } else {
+                    Span::raw(c.to_string())
+                }
+            })
+            .collect();
+        return Spans::from(spans);
+    }
+
+    // Shown instead of the preview as long as the zettelkasten has no notes
+    fn render_empty_zettelkasten_screen<B: Backend>(
+        f: &mut Frame<B>,
+        area: Rect,
+        settings: &Settings,
+    ) {
+        let theme = &settings.theme;
+        let key_style = Style::default()
+            .fg(theme.selected)
+            .add_modifier(Modifier::BOLD);
+        let lines = vec![
+            Spans::from(Span::styled(
+                "Welcome to Brain!",
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Spans::from(""),
+            Spans::from("There are no notes yet."),
+            Spans::from(vec![
+                Span::raw("Press "),
+                Span::styled(BrnTui::get_first_key_of(Action::Add, settings), key_style),
+                Span::raw(" to create your first note"),
+            ]),
+            Spans::from(vec![
+                Span::raw("or "),
+                Span::styled(BrnTui::get_first_key_of(Action::Help, settings), key_style),
+                Span::raw(" to see all keybindings."),
+            ]),
+        ];
+
+        let text_height = lines.len() as u16;
+        let inner_area = area.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let text_area = Rect::new(
+            inner_area.x,
+            inner_area.y + inner_area.height.saturating_sub(text_height) / 2,
+            inner_area.width,
+            text_height.min(inner_area.height),
+        );
+        let paragraph = Paragraph::new(Text::from(lines))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.normal));
+        f.render_widget(paragraph, text_area);
+    }
+
+    // Actions without a key can still be run from the command palette
+    fn get_first_key_of(action: Action, settings: &Settings) -> String {
+        let keys = settings.keybindings.get_keys_of(action);
+        return match keys.split(", ").next() {
+            Some(key) if !key.is_empty() => key.to_string(),
+            _ => format!("':' and run '{}'", action.to_name()),
+        };
+    }
+
+    fn get_empty_list_hint(settings: &Settings) -> String {
+        if Database::get_total_note_count() == 0 {
+            return format!(
+                "there are no notes yet, press {} to create the first one",
+                BrnTui::get_first_key_of(Action::Add, settings)
+            );
+        }
+        return format!(
+            "the note list is empty, press {} to show all notes",
+            BrnTui::get_first_key_of(Action::DefaultList, settings)
+        );
+    }
+
+    fn render_note_preview<B: Backend>(
+        f: &mut Frame<B>,
+        area: Rect,
+        tui_data: &mut TuiData,
+        settings: &Settings,
+    ) {
+        let title = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => "Note preview".to_string(),
+        };
+
+        // Render note preview
+        let outer_note_block = BrnTui::get_block(title, &settings.theme);
+        f.render_widget(outer_note_block, area);
+        if tui_data.note_list.get_items().is_empty() && Database::get_total_note_count() == 0 {
+            BrnTui::render_empty_zettelkasten_screen(f, area, settings);
+            return;
+        }
+
+        // The preview is rendered once when it's loaded (see `set_preview_content`)
+        let mut note_content_lines = if tui_data.is_preview_loading {
+            vec![Spans::from("loading...")]
+        } else {
+            tui_data.preview_lines.clone()
+        };
+        tui_data.preview_match_lines = BrnTui::highlight_search_words_in(
+            &mut note_content_lines,
+            &tui_data.preview_search_words,
+        );
+        if settings.line_numbers_enabled && !tui_data.is_preview_loading {
+            BrnTui::add_line_numbers_to(
+                &mut note_content_lines,
+                tui_data.preview_first_line_number,
+                &settings.theme,
+            );
+        }
+        if matches!(tui_data.input_mode, InputMode::PreviewSelection) {
+            let (first_line, last_line) = BrnTui::get_selected_preview_lines(tui_data);
+            for line in note_content_lines
+                .iter_mut()
+                .take(last_line + 1)
+                .skip(first_line)
+            {
+                for span in line.0.iter_mut() {
+                    span.style = span.style.add_modifier(Modifier::REVERSED);
+                }
+            }
+        }
+        let inner_note_area = area.inner(&Margin {
+            vertical: 2,
+            horizontal: 2,
+        });
+        tui_data.preview_height = inner_note_area.height;
+
+        // Plain text isn't formatted, so it's always wrapped to keep it readable
+        let is_preview_wrapped =
+            settings.preview_wrap_enabled || tui_data.preview_render_mode == RenderMode::Plain;
+
+        // Scrolling is based on the lines that are actually shown. The lines of folded
+        // sections aren't shown, so they start at the same line as the line after them
+        let folded_line_ranges = if tui_data.is_preview_loading {
+            Vec::new()
+        } else {
+            BrnTui::get_folded_line_ranges(tui_data)
+        };
+        let mut shown_lines = Vec::with_capacity(note_content_lines.len());
+        tui_data.preview_line_starts.clear();
+        tui_data.preview_line_count = 0;
+        for (line_index, line) in note_content_lines.into_iter().enumerate() {
+            tui_data
+                .preview_line_starts
+                .push(tui_data.preview_line_count);
+            if folded_line_ranges
+                .iter()
+                .any(|m| line_index > m.0 && line_index < m.1)
+            {
+                continue;
+            }
+            tui_data.preview_line_count += if is_preview_wrapped {
+                BrnTui::get_wrapped_line_count(&line, inner_note_area.width)
+            } else {
+                1
+            };
+            shown_lines.push(line);
+            if folded_line_ranges.iter().any(|m| m.0 == line_index) {
+                shown_lines.push(Spans::from(Span::styled(
+                    "...",
+                    Style::default().fg(settings.theme.status),
+                )));
+                tui_data.preview_line_count += 1;
+            }
+        }
+
+        // The preview height could have changed since the last scroll
+        tui_data.preview_scroll = tui_data
+            .preview_scroll
+            .min(BrnTui::get_max_preview_scroll(tui_data));
+
+        let mut inner_note_paragraph = Paragraph::new(Text::from(shown_lines))
+            .alignment(Alignment::Left)
+            .scroll((tui_data.preview_scroll, 0));
+        if is_preview_wrapped {
+            inner_note_paragraph = inner_note_paragraph.wrap(Wrap { trim: false });
+        }
+        f.render_widget(inner_note_paragraph, inner_note_area);
+    }
+
+    // Shows the matches in reversed colors and returns the indices of the lines that contain one
+    fn highlight_search_words_in(
+        lines: &mut [Spans<'static>],
+        search_words: &[String],
+    ) -> Vec<usize> {
+        let mut match_lines = Vec::new();
+        if search_words.is_empty() {
+            return match_lines;
+        }
+
+        let search_words: Vec<Vec<char>> =
+            search_words.iter().map(|m| m.chars().collect()).collect();
+        for (line_index, line) in lines.iter_mut().enumerate() {
+            // Characters are compared one by one, as lower case text can have another length
+            let line_chars: Vec<char> = line
+                .0
+                .iter()
+                .flat_map(|m| m.content.chars())
+                .map(|m| m.to_lowercase().next().unwrap_or(m))
+                .collect();
+            let mut is_highlighted = vec![false; line_chars.len()];
+            for search_word in search_words.iter().filter(|m| m.len() <= line_chars.len()) {
+                for start in 0..=(line_chars.len() - search_word.len()) {
+                    if line_chars[start..start + search_word.len()] == search_word[..] {
+                        is_highlighted[start..start + search_word.len()].fill(true);
+                    }
+                }
+            }
+            if !is_highlighted.contains(&true) {
+                continue;
+            }
+            match_lines.push(line_index);
+
+            let mut highlighted_spans: Vec<Span<'static>> = Vec::new();
+            let mut char_index = 0;
+            for span in line.0.iter() {
+                for c in span.content.chars() {
+                    let style = if is_highlighted[char_index] {
+                        span.style.add_modifier(Modifier::REVERSED)
+                    } else {
+                        span.style
+                    };
+                    match highlighted_spans.last_mut() {
+                        Some(last_span) if last_span.style == style => {
+                            last_span.content.to_mut().push(c)
+                        }
+                        _ => highlighted_spans.push(Span::styled(c.to_string(), style)),
+                    }
+                    char_index += 1;
+                }
+            }
+            *line = Spans::from(highlighted_spans);
+        }
+        return match_lines;
+    }
+
+    fn scroll_preview_to_match(tui_data: &mut TuiData, next: bool) {
+        // Wrapped lines take up more than one line of the preview
+        let match_scrolls: Vec<usize> = tui_data
+            .preview_match_lines
+            .iter()
+            .map(|m| tui_data.preview_line_starts.get(*m).copied().unwrap_or(*m))
+            .collect();
+        let preview_scroll = tui_data.preview_scroll as usize;
+        let match_index = if next {
+            match_scrolls.iter().position(|m| *m > preview_scroll)
+        } else {
+            match_scrolls.iter().rposition(|m| *m < preview_scroll)
+        };
+
+        match match_index {
+            Some(index) => {
+                tui_data.preview_scroll = (match_scrolls[index].min(u16::MAX as usize) as u16)
+                    .min(BrnTui::get_max_preview_scroll(tui_data));
+                tui_data
+                    .message
+                    .set(format!("match {}/{}", index + 1, match_scrolls.len()));
+            }
+            None if match_scrolls.is_empty() => tui_data
+                .message
+                .set("the preview doesn't contain any matches".to_string()),
+            None => tui_data.message.set("no more matches".to_string()),
+        }
+    }
+
+    // Replicates the word wrapping of tui, as the number of wrapped lines isn't exposed
+    fn get_wrapped_line_count(line: &Spans, width: u16) -> usize {
+        let width = width as usize;
+        if width == 0 {
+            return 1;
+        }
+
+        let line_text: String = line.0.iter().map(|m| m.content.as_ref()).collect();
+        let mut line_count = 1;
+        let mut current_width = 0;
+        for word in line_text.split_inclusive(' ') {
+            let word_text = word.trim_end_matches(' ');
+            let word_width = word_text.width();
+            if current_width > 0 && current_width + word_width > width {
+                line_count += 1;
+                current_width = 0;
+            }
+
+            // Words that are longer than a line are split between two characters. A wide
+            // character that doesn't fit at the end of a line is moved to the next one
+            if word_width > width {
+                for c in word_text.chars() {
+                    let char_width = c.width().unwrap_or(0);
+                    if current_width > 0 && current_width + char_width > width {
+                        line_count += 1;
+                        current_width = 0;
+                    }
+                    current_width += char_width;
+                }
+            } else {
+                current_width += word_width;
+            }
+            current_width += word.len() - word_text.len();
+        }
+        return line_count;
+    }
+
+    // Every line of the preview is a line of the note, so the line numbers stay correct
+    // when the preview is scrolled
+    fn add_line_numbers_to(
+        lines: &mut Vec<Spans<'static>>,
+        first_line_number: usize,
+        theme: &Theme,
+    ) {
+        let last_line_number = first_line_number + lines.len().saturating_sub(1);
+        let gutter_width = last_line_number.to_string().len();
+        for (index, line) in lines.iter_mut().enumerate() {
+            let line_number = Span::styled(
+                format!(
+                    "{:>width$} ",
+                    first_line_number + index,
+                    width = gutter_width
+                ),
+                Style::default().fg(theme.status),
+            );
+            line.0.insert(0, line_number);
+        }
+    }
+
+    fn render_message_block<B: Backend>(
+        f: &mut Frame<B>,
+        area: Rect,
+        tui_data: &mut TuiData,
+        settings: &Settings,
+    ) {
+        // The status of the previewed note is shown on the right side
+        let mut status_text = format!("{} words", tui_data.preview_word_count);
+        if tui_data.preview_attachment_count > 0 {
+            status_text = format!(
+                "{} attachments | {}",
+                tui_data.preview_attachment_count, status_text
+            );
+        }
+        if tui_data.preview_task_count > 0 {
+            status_text = format!(
+                "{}/{} tasks ({}%) | {}",
+                tui_data.preview_done_task_count,
+                tui_data.preview_task_count,
+                tui_data.preview_done_task_count * 100 / tui_data.preview_task_count,
+                status_text
+            );
+        }
+        if let Some(creation_date) = tui_data.preview_creation_date {
+            status_text = format!(
+                "{} | {}",
+                creation_date.format(&settings.date_format),
+                status_text
+            );
+        }
+        if settings.notebooks.len() > 1 {
+            status_text = format!("{} | {}", settings.notebook_name, status_text);
+        }
+        match tui_data.git_changed_file_count {
+            Some(0) => status_text = format!("git: clean | {}", status_text),
+            Some(changed_file_count) => {
+                status_text = format!("git: {} changed | {}", changed_file_count, status_text)
+            }
+            None => (),
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Min(0),
+                    Constraint::Length(status_text.width() as u16),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        let status_paragraph = Paragraph::new(status_text)
+            .alignment(Alignment::Right)
+            .style(Style::default().fg(settings.theme.status));
+        f.render_widget(status_paragraph, chunks[1]);
+        let area = chunks[0];
+
+        let message_paragraph;
+        match tui_data.input_mode {
+            InputMode::Normal
+            | InputMode::Backlinks
+            | InputMode::Tags
+            | InputMode::Template
+            | InputMode::Notebooks
+            | InputMode::Labels
+            | InputMode::Links
+            | InputMode::Outline
+            | InputMode::Finder
+            | InputMode::CommandPalette
+            | InputMode::PreviewSelection
+            | InputMode::InsertLink
+            | InputMode::Messages
+            | InputMode::Help => {
+                let message_color = BrnTui::get_color_of_message_level(
+                    tui_data.message.get_current_level(),
+                    &settings.theme,
+                );
+                message_paragraph = Paragraph::new(tui_data.message.get_current())
+                    .alignment(Alignment::Left)
+                    .style(Style::default().fg(message_color));
+            }
+            InputMode::Add
+            | InputMode::Remove
+            | InputMode::Merge
+            | InputMode::MoveToNotebook
+            | InputMode::Rename
+            | InputMode::Capture
+            | InputMode::AddTag => {
+                message_paragraph = Paragraph::new(tui_data.edit_text.get_displayed_text())
+                    .alignment(Alignment::Left)
+                    .style(Style::default());
+            }
+            InputMode::Search => {
+                message_paragraph = Paragraph::new(tui_data.search_query.get_displayed_text())
+                    .alignment(Alignment::Left)
+                    .style(Style::default());
+            }
+        }
+        f.render_widget(message_paragraph, area);
+    }
+
+    fn increment_selected_value(tui_data: &mut TuiData, settings: &mut Settings) {
+        tui_data.note_list.next();
+        BrnTui::update_note_list_window(tui_data, settings);
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn decrement_selected_value(tui_data: &mut TuiData, settings: &mut Settings) {
+        tui_data.note_list.previous();
+        BrnTui::update_note_list_window(tui_data, settings);
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn select_first_value(tui_data: &mut TuiData, settings: &mut Settings) {
+        if tui_data.note_list_offset > 0 {
+            BrnTui::load_note_list_window(tui_data, settings, 0);
+        }
+        tui_data.note_list.select(Some(0));
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn select_last_value(tui_data: &mut TuiData, settings: &mut Settings) {
+        if let Some(note_count) = tui_data.note_list_total {
+            BrnTui::load_note_list_window(tui_data, settings, note_count.saturating_sub(1));
+        }
+        let note_count = tui_data.note_list.get_items().len();
+        if note_count == 0 {
+            return;
+        }
+        tui_data.note_list.select(Some(note_count - 1));
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn move_selection_down(tui_data: &mut TuiData, settings: &mut Settings, amount: usize) {
+        tui_data.note_list.next_by(amount.max(1));
+        BrnTui::update_note_list_window(tui_data, settings);
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn move_selection_up(tui_data: &mut TuiData, settings: &mut Settings, amount: usize) {
+        tui_data.note_list.previous_by(amount.max(1));
+        BrnTui::update_note_list_window(tui_data, settings);
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn scroll_preview_down(tui_data: &mut TuiData, amount: u16) {
+        let new_preview_scroll = tui_data.preview_scroll.saturating_add(amount.max(1));
+        tui_data.preview_scroll = new_preview_scroll.min(BrnTui::get_max_preview_scroll(tui_data));
+    }
+
+    fn scroll_preview_up(tui_data: &mut TuiData, amount: u16) {
+        tui_data.preview_scroll = tui_data.preview_scroll.saturating_sub(amount.max(1));
+    }
+
+    fn get_max_preview_scroll(tui_data: &TuiData) -> u16 {
+        let line_count = tui_data.preview_line_count.min(u16::MAX as usize) as u16;
+        return line_count.saturating_sub(tui_data.preview_height);
+    }
+
+    fn show_note_content_preview(tui_data: &mut TuiData, settings: &mut Settings) {
+        tui_data.preview_scroll = 0;
+
+        // The matches of the content search are highlighted until the search is cleared
+        let search_query = tui_data.search_query.get_content_text();
+        tui_data.preview_search_words = if tui_data.content_search_enabled {
+            NoteUtility::get_search_words_of(&search_query)
+        } else {
+            Vec::new()
+        };
+        if let Some(selected_note_name) = &tui_data.note_list.selected_item() {
+            if let Some(note_id) =
+                Database::get_note_id_where(NoteProperty::NoteName, selected_note_name)
+            {
+                // The content is shown as soon as it's loaded (see `receive_loaded_preview`)
+                if let Some(note) = Database::get_note_where_id(&note_id) {
+                    let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+                    tui_data.preview_render_mode = RenderMode::of_file(&note.file_name, settings);
+                    tui_data.preview_creation_date = note.creation_date_time;
+                    tui_data.preview_links.clear();
+                    tui_data.preview_file_links.clear();
+                    tui_data.preview_modification_time = fs::metadata(&note_file_path)
+                        .and_then(|m| m.modified())
+                        .ok();
+
+                    let cached_preview = tui_data
+                        .preview_modification_time
+                        .and_then(|m| tui_data.preview_cache.get(&note_id, m))
+                        .filter(|m| {
+                            m.broken_link_texts
+                                == NoteUtility::get_broken_link_texts(&m.note_content)
+                        })
+                        .map(|m| (m.note_content.clone(), m.rendered_lines.clone()));
+                    if let Some((note_content, rendered_lines)) = cached_preview {
+                        tui_data.preview_loader.cancel();
+                        tui_data.is_preview_loading = false;
+                        tui_data.previewed_note_name = Some(note.note_name.clone());
+                        BrnTui::set_preview_content(
+                            tui_data,
+                            settings,
+                            &note_content,
+                            Some(rendered_lines),
+                        );
+                    } else {
+                        tui_data.preview_loader.load(&note_id, note_file_path);
+                        tui_data.is_preview_loading = true;
+                    }
+                }
+                BrnTui::update_backlink_list(tui_data, &note_id);
+            }
+        } else {
+            tui_data.preview_loader.cancel();
+            tui_data.is_preview_loading = false;
+            tui_data.previewed_note_name = None;
+            tui_data.note_content_preview.clear();
+            tui_data.preview_lines.clear();
+            tui_data.preview_word_count = 0;
+            tui_data.preview_attachment_count = 0;
+            tui_data.preview_task_count = 0;
+            tui_data.preview_done_task_count = 0;
+            tui_data.preview_creation_date = None;
+            tui_data.preview_links.clear();
+            tui_data.preview_broken_links.clear();
+            tui_data.preview_file_links.clear();
+            tui_data.preview_headings.clear();
+            tui_data.backlink_list.replace_items_with(Vec::new());
+        }
+    }
+
+    // Returns true if the loaded preview was received in the given time
+    fn receive_loaded_preview(
+        tui_data: &mut TuiData,
+        settings: &Settings,
+        timeout: Duration,
+    ) -> bool {
+        let loaded_preview = match tui_data.preview_loader.receive(timeout) {
+            Some(value) => value,
+            None => return false,
+        };
+
+        tui_data.is_preview_loading = false;
+        tui_data.previewed_note_name =
+            Database::get_note_where_id(&loaded_preview.note_id).map(|m| m.note_name);
+        match loaded_preview.content {
+            Ok(note_content) => {
+                BrnTui::set_preview_content(tui_data, settings, &note_content, None);
+                if let Some(modification_time) = tui_data.preview_modification_time {
+                    tui_data.preview_cache.insert(
+                        &loaded_preview.note_id,
+                        modification_time,
+                        note_content,
+                        tui_data.preview_lines.clone(),
+                        tui_data.preview_broken_links.clone(),
+                    );
+                }
+            }
+            Err(error) => {
+                tui_data.note_content_preview.clear();
+                tui_data.preview_lines.clear();
+                tui_data.preview_headings.clear();
+                tui_data.message.set(format!(
+                    "ERROR: couldn't load note '{}': {}",
+                    loaded_preview.note_id, error
+                ));
+            }
+        }
+        return true;
+    }
+
+    // The rendered lines are only given if they are cached
+    fn set_preview_content(
+        tui_data: &mut TuiData,
+        settings: &Settings,
+        note_content: &str,
+        rendered_lines: Option<Vec<Spans<'static>>>,
+    ) {
+        // The folds are kept while the same note is shown, e.g. when it's changed outside of brn
+        if tui_data.preview_folds_note_name != tui_data.previewed_note_name {
+            tui_data.preview_folded_headings.clear();
+            tui_data.preview_folds_note_name = tui_data.previewed_note_name.clone();
+        }
+        let render_mode = tui_data.preview_render_mode;
+        tui_data.preview_word_count = NoteStatistics::from_content(note_content).word_count;
+        tui_data.preview_attachment_count = if render_mode == RenderMode::Markdown {
+            MarkdownRenderer::get_image_count(note_content)
+        } else {
+            0
+        };
+        let note_tasks = NoteTasks::from_content(note_content);
+        tui_data.preview_task_count = note_tasks.task_count;
+        tui_data.preview_done_task_count = note_tasks.done_task_count;
+        tui_data.preview_links = NoteUtility::get_link_texts(note_content);
+        tui_data.preview_broken_links = NoteUtility::get_broken_link_texts(note_content);
+        tui_data.preview_file_links = if render_mode == RenderMode::Markdown {
+            NoteUtility::get_file_link_targets(note_content)
+        } else {
+            Vec::new()
+        };
+        tui_data.note_content_preview = if settings.frontmatter_preview_enabled {
+            note_content.to_string()
+        } else {
+            NoteUtility::remove_yaml_header(note_content)
+        };
+        tui_data.preview_headings = match render_mode {
+            RenderMode::Markdown => MarkdownRenderer::get_headings(&tui_data.note_content_preview),
+            RenderMode::Org => OrgRenderer::get_headings(&tui_data.note_content_preview),
+            RenderMode::Plain => Vec::new(),
+        };
+
+        // The line numbers are the ones of the note file, even if the yaml header is hidden
+        let hidden_text_length = note_content.len() - tui_data.note_content_preview.len();
+        tui_data.preview_first_line_number =
+            note_content[..hidden_text_length].matches('\n').count() + 1;
+
+        tui_data.preview_lines = match rendered_lines {
+            Some(value) => value,
+            None => BrnTui::render_preview_content(
+                &tui_data.note_content_preview,
+                render_mode,
+                &tui_data.preview_broken_links,
+                settings,
+            ),
+        };
+    }
+
+    // Without the markdown preview every note is shown as plain text
+    fn render_preview_content(
+        content: &str,
+        render_mode: RenderMode,
+        broken_link_texts: &[String],
+        settings: &Settings,
+    ) -> Vec<Spans<'static>> {
+        if !settings.markdown_preview_enabled {
+            return content
+                .lines()
+                .map(|m| Spans::from(m.to_string()))
+                .collect();
+        }
+        match render_mode {
+            RenderMode::Markdown => MarkdownRenderer::render(
+                content,
+                Path::new(&settings.notes_dir),
+                broken_link_texts,
+                settings.syntax_highlighting_enabled,
+            ),
+            RenderMode::Org => OrgRenderer::render(
+                content,
+                settings.syntax_highlighting_enabled,
+                broken_link_texts,
+            ),
+            RenderMode::Plain => content
+                .lines()
+                .map(|m| Spans::from(m.to_string()))
+                .collect(),
+        }
+    }
+
+    // The default note list is shown if the random note isn't part of the current list
+    fn select_random_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let note_id =
+            match NoteUtility::get_random_note_ids(1, None, &mut tui_data.random_generator).pop() {
+                Some(value) => value,
+                None => {
+                    tui_data.message.set("there are no notes".to_string());
+                    return;
+                }
+            };
+        let note = match Database::get_note_where_id(&note_id) {
+            Some(value) => value,
+            None => return,
+        };
+
+        BrnTui::remember_selected_note(tui_data);
+        if !BrnTui::select_note_with_name(tui_data, settings, &note.note_name) {
+            BrnTui::show_default_note_list(tui_data, settings);
+            BrnTui::select_note_with_name(tui_data, settings, &note.note_name);
+        }
+        tui_data
+            .message
+            .set(format!("Random note: {}", note.note_name));
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn start_inserting_link(tui_data: &mut TuiData) {
+        let selected_note_name = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        tui_data.link_source_note_id = BrnTui::get_selected_note_id(tui_data);
+        if tui_data.link_source_note_id.is_some() {
+            tui_data.message.set(format!(
+                "Link '{}' to: select a note and press Enter (Esc cancels)",
+                selected_note_name
+            ));
+            tui_data.input_mode = InputMode::InsertLink;
+        }
+    }
+
+    fn insert_link_to_selected_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let source_note_id = match tui_data.link_source_note_id.take() {
+            Some(value) => value,
+            None => return,
+        };
+        let target_note_id = match BrnTui::get_selected_note_id(tui_data) {
+            Some(value) => value,
+            None => return,
+        };
+
+        let source_note_name = match Database::get_note_where_id(&source_note_id) {
+            Some(value) => value.note_name,
+            None => source_note_id.clone(),
+        };
+        let link_text = format!("[[{}]]", target_note_id);
+        match NoteUtility::add_link(&source_note_id, &target_note_id, settings) {
+            Ok(Some(warnings)) if warnings.is_empty() => tui_data
+                .message
+                .set(format!("Added {} to '{}'", link_text, source_note_name)),
+            Ok(Some(warnings)) => tui_data.message.set(format!(
+                "WARNING: added {} to '{}', but {}",
+                link_text,
+                source_note_name,
+                warnings.join("; ")
+            )),
+            Ok(None) => tui_data.message.set(format!(
+                "'{}' already links to {}",
+                source_note_name, link_text
+            )),
+            Err(error) => tui_data.message.set(format!("ERROR: {}", error)),
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn follow_link<B: Backend + Write>(
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        match tui_data.preview_links.len() + tui_data.preview_file_links.len() {
+            0 => tui_data
+                .message
+                .set("the note doesn't contain any links".to_string()),
+            1 => BrnTui::follow_link_with_index(0, terminal, tui_data, settings),
+            _ => {
+                let notes_dir = PathBuf::from(&settings.notes_dir);
+                let mut link_entries: Vec<String> = tui_data
+                    .preview_links
+                    .iter()
+                    .map(|m| match NoteUtility::get_linked_note(m) {
+                        Ok(Some(_)) => m.clone(),
+                        Ok(None) => format!("{} (broken)", m),
+                        Err(_) => format!("{} (ambiguous)", m),
+                    })
+                    .collect();
+                link_entries.extend(tui_data.preview_file_links.iter().map(|m| {
+                    if let Some(note) = NoteUtility::get_note_of_file_link(m, &notes_dir) {
+                        return format!("{} ({})", m, note.note_name);
+                    }
+                    if NoteUtility::get_path_of_file_link(m, &notes_dir).exists() {
+                        return format!("{} (file)", m);
+                    }
+                    return format!("{} (missing file)", m);
+                }));
+                tui_data.link_list.replace_items_with(link_entries);
+                tui_data.link_list.select(Some(0));
+                tui_data.input_mode = InputMode::Links;
+            }
+        }
+    }
+
+    // The links of brn come before the links to files
+    fn follow_link_with_index<B: Backend + Write>(
+        index: usize,
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        if let Some(link_text) = tui_data.preview_links.get(index).cloned() {
+            BrnTui::go_to_linked_note(&link_text, tui_data, settings);
+            return;
+        }
+        let link_target = match tui_data
+            .preview_file_links
+            .get(index - tui_data.preview_links.len())
+        {
+            Some(value) => value.clone(),
+            None => return,
+        };
+
+        let notes_dir = PathBuf::from(&settings.notes_dir);
+        if let Some(note) = NoteUtility::get_note_of_file_link(&link_target, &notes_dir) {
+            BrnTui::go_to_linked_note(&note.note_id, tui_data, settings);
+            return;
+        }
+        let file_path = NoteUtility::get_path_of_file_link(&link_target, &notes_dir);
+        if !file_path.exists() {
+            tui_data.message.set(format!(
+                "ERROR: the linked file '{}' doesn't exist",
+                link_target
+            ));
+            return;
+        }
+
+        if !BrnTui::leave_tui(terminal, tui_data) {
+            return;
+        }
+        if let Err(error) = NoteUtility::open_file(&file_path, settings) {
+            tui_data.message.set(format!("ERROR: {}", error));
+        }
+        BrnTui::return_to_tui(terminal, tui_data);
+    }
+
+    // The finder only searches the notes that aren't archived, like the default note list
+    fn show_finder(tui_data: &mut TuiData, settings: &mut Settings) {
+        tui_data.finder_note_names = Database::get_unarchived_notes()
+            .into_iter()
+            .map(|m| m.note_name)
+            .collect();
+        NoteUtility::sort_note_names(
+            &mut tui_data.finder_note_names,
+            settings.sort_order,
+            settings,
+        );
+        tui_data.finder_query.clear();
+        tui_data.finder_content_search_enabled = false;
+        tui_data.input_mode = InputMode::Finder;
+        BrnTui::update_finder_candidates(tui_data, settings);
+    }
+
+    fn update_finder_candidates(tui_data: &mut TuiData, settings: &mut Settings) {
+        let search_query = tui_data.finder_query.get_content_text();
+        let candidates = if search_query.is_empty() {
+            tui_data.finder_note_names.clone()
+        } else if tui_data.finder_content_search_enabled {
+            match NoteUtility::search_content(&search_query) {
+                Ok(notes) => notes
+                    .into_iter()
+                    .map(|m| m.note_name)
+                    .filter(|m| tui_data.finder_note_names.contains(m))
+                    .collect(),
+                Err(error) => {
+                    tui_data
+                        .message
+                        .set(format!("ERROR: invalid search: {}", error));
+                    return;
+                }
+            }
+        } else {
+            FuzzyMatcher::sort_by_score(&search_query, &tui_data.finder_note_names)
+        };
+
+        // The best match is always on top
+        tui_data.finder_list.replace_items_with(candidates);
+        tui_data.finder_list.select(Some(0));
+        tui_data.finder_list.clamp_selection();
+        BrnTui::show_finder_preview(tui_data, settings);
+    }
+
+    fn select_finder_candidate(tui_data: &mut TuiData, settings: &mut Settings, next: bool) {
+        if next {
+            tui_data.finder_list.next();
+        } else {
+            tui_data.finder_list.previous();
+        }
+        BrnTui::show_finder_preview(tui_data, settings);
+    }
+
+    fn show_finder_preview(tui_data: &mut TuiData, settings: &mut Settings) {
+        let note = tui_data
+            .finder_list
+            .selected_item()
+            .and_then(|m| Database::get_note_id_where(NoteProperty::NoteName, m))
+            .and_then(|m| Database::get_note_where_id(&m));
+        match note {
+            Some(note) => {
+                let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+                tui_data.finder_preview_render_mode =
+                    RenderMode::of_file(&note.file_name, settings);
+                tui_data
+                    .finder_preview_loader
+                    .load(&note.note_id, note_file_path);
+                tui_data.is_finder_preview_loading = true;
+            }
+            None => {
+                tui_data.finder_preview_loader.cancel();
+                tui_data.is_finder_preview_loading = false;
+                tui_data.finder_preview.clear();
+            }
+        }
+    }
+
+    // Returns true if the loaded preview was received in the given time
+    fn receive_finder_preview(
+        tui_data: &mut TuiData,
+        settings: &Settings,
+        timeout: Duration,
+    ) -> bool {
+        let loaded_preview = match tui_data.finder_preview_loader.receive(timeout) {
+            Some(value) => value,
+            None => return false,
+        };
+
+        tui_data.is_finder_preview_loading = false;
+        tui_data.finder_preview = match loaded_preview.content {
+            Ok(note_content) if settings.frontmatter_preview_enabled => note_content,
+            Ok(note_content) => NoteUtility::remove_yaml_header(&note_content),
+            Err(error) => format!("couldn't load note '{}': {}", loaded_preview.note_id, error),
+        };
+        // The finder preview is rendered with every frame, so the links are only resolved here
+        tui_data.finder_preview_broken_links =
+            NoteUtility::get_broken_link_texts(&tui_data.finder_preview);
+        return true;
+    }
+
+    // The selection of the note list isn't changed
+    fn open_selected_finder_note<B: Backend + Write>(
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        tui_data.finder_preview_loader.cancel();
+        tui_data.is_finder_preview_loading = false;
+        let note_id = match tui_data
+            .finder_list
+            .selected_item()
+            .and_then(|m| Database::get_note_id_where(NoteProperty::NoteName, m))
+        {
+            Some(value) => value,
+            None => return,
+        };
+
+        // Jump to the first match of the content search
+        let search_query = tui_data.finder_query.get_content_text();
+        let line_number = if tui_data.finder_content_search_enabled && !search_query.is_empty() {
+            NoteUtility::get_line_of_first_match(&note_id, &search_query, settings)
+        } else {
+            None
+        };
+        BrnTui::open_note_at_line(&note_id, line_number, terminal, tui_data, settings);
+    }
+
+    fn show_command_palette(tui_data: &mut TuiData) {
+        tui_data.command_query.clear();
+        BrnTui::update_command_candidates(tui_data);
+        tui_data.input_mode = InputMode::CommandPalette;
+    }
+
+    // Actions are matched by their name and their description, the better match counts
+    fn update_command_candidates(tui_data: &mut TuiData) {
+        let search_query = tui_data.command_query.get_content_text();
+        let mut candidates: Vec<(i64, Action)> = Action::all()
+            .into_iter()
+            .filter(|m| *m != Action::CommandPalette)
+            .filter_map(|m| {
+                if search_query.is_empty() {
+                    return Some((0, m));
+                }
+                let name_match = FuzzyMatcher::fuzzy_match(&search_query, m.to_name());
+                let description_match =
+                    FuzzyMatcher::fuzzy_match(&search_query, m.get_description());
+                return name_match
+                    .into_iter()
+                    .chain(description_match)
+                    .map(|v| v.score)
+                    .max()
+                    .map(|v| (v, m));
+            })
+            .collect();
+        candidates.sort_by_key(|m| Reverse(m.0));
+
+        tui_data
+            .command_list
+            .replace_items_with(candidates.into_iter().map(|m| m.1).collect());
+        tui_data.command_list.select(Some(0));
+        tui_data.command_list.clamp_selection();
+    }
+
+    fn show_outline(tui_data: &mut TuiData) {
+        if tui_data.is_preview_loading || tui_data.preview_headings.is_empty() {
+            tui_data
+                .message
+                .set("the note doesn't contain any headings".to_string());
+            return;
+        }
+
+        // Nested headings are indented by their level
+        let outline_entries = tui_data
+            .preview_headings
+            .iter()
+            .map(|m| format!("{}{}", "  ".repeat(m.level - 1), m.text))
+            .collect();
+        tui_data.outline_list.replace_items_with(outline_entries);
+        tui_data.outline_list.select(Some(0));
+        tui_data.input_mode = InputMode::Outline;
+    }
+
+    fn scroll_preview_to_selected_heading(tui_data: &mut TuiData) {
+        let line_index = match tui_data
+            .outline_list
+            .selected()
+            .and_then(|m| tui_data.preview_headings.get(m))
+        {
+            Some(heading) => heading.line_index,
+            None => return,
+        };
+
+        // Wrapped lines take up more than one line of the preview
+        let scroll = tui_data
+            .preview_line_starts
+            .get(line_index)
+            .copied()
+            .unwrap_or(line_index);
+        tui_data.preview_scroll =
+            (scroll.min(u16::MAX as usize) as u16).min(BrnTui::get_max_preview_scroll(tui_data));
+    }
+
+    fn go_to_linked_note(link_text: &str, tui_data: &mut TuiData, settings: &mut Settings) {
+        let linked_note = match NoteUtility::get_linked_note(link_text) {
+            Ok(Some(value)) => value,
+            Ok(None) => {
+                BrnTui::offer_note_for_broken_link(link_text, tui_data);
+                return;
+            }
+            Err(error) => {
+                tui_data.message.set(format!("ERROR: {}", error));
+                return;
+            }
+        };
+        BrnTui::remember_selected_note(tui_data);
+
+        if !BrnTui::select_note_with_name(tui_data, settings, &linked_note.note_name) {
+            // The linked note isn't part of the current list, so show all links instead
+            let selected_note_name = match tui_data.note_list.selected_item() {
+                Some(value) => value.clone(),
+                None => String::new(),
+            };
+            let notes_dir = PathBuf::from(&settings.notes_dir);
+            let linked_note_names: Vec<String> = tui_data
+                .preview_links
+                .iter()
+                .filter_map(|m| NoteUtility::get_linked_note(m).ok().flatten())
+                .chain(
+                    tui_data
+                        .preview_file_links
+                        .iter()
+                        .filter_map(|m| NoteUtility::get_note_of_file_link(m, &notes_dir)),
+                )
+                .map(|m| m.note_name)
+                .collect();
+            let index = linked_note_names
+                .iter()
+                .position(|m| *m == linked_note.note_name);
+
+            BrnTui::replace_note_list_items(tui_data, linked_note_names);
+            tui_data.note_list_total = None;
+            tui_data.note_list.select(index);
+            tui_data.note_list_title = format!("Links of {}", selected_note_name);
+            tui_data.search_query.clear();
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn get_selected_note_id(tui_data: &mut TuiData) -> Option<String> {
+        let selected_note_name = tui_data.note_list.selected_item()?;
+        return Database::get_note_id_where(NoteProperty::NoteName, selected_note_name);
+    }
+
+    // Has to be called before jumping to another note, so that it's possible to go back
+    fn remember_selected_note(tui_data: &mut TuiData) {
+        if let Some(note_id) = BrnTui::get_selected_note_id(tui_data) {
+            tui_data.navigation_history.push(&note_id);
+        }
+    }
+
+    fn go_back(tui_data: &mut TuiData, settings: &mut Settings) {
+        let current_note_id = BrnTui::get_selected_note_id(tui_data);
+        match tui_data
+            .navigation_history
+            .go_back(current_note_id.as_deref())
+        {
+            Some(note_id) => BrnTui::show_note_with_id(&note_id, tui_data, settings),
+            None => tui_data
+                .message
+                .set("there is no previous note".to_string()),
+        }
+    }
+
+    fn go_forward(tui_data: &mut TuiData, settings: &mut Settings) {
+        let current_note_id = BrnTui::get_selected_note_id(tui_data);
+        match tui_data
+            .navigation_history
+            .go_forward(current_note_id.as_deref())
+        {
+            Some(note_id) => BrnTui::show_note_with_id(&note_id, tui_data, settings),
+            None => tui_data.message.set("there is no next note".to_string()),
+        }
+    }
+
+    // The top of the list stays selected if no home note is set or it doesn't exist
+    fn select_home_note(tui_data: &mut TuiData, settings: &Settings) {
+        if let Some(note) = settings
+            .home_note_id
+            .as_ref()
+            .and_then(|m| Database::get_note_where_id(m))
+        {
+            BrnTui::select_note_with_name(tui_data, settings, &note.note_name);
+        }
+    }
+
+    fn go_home(tui_data: &mut TuiData, settings: &mut Settings) {
+        let home_note_id = match &settings.home_note_id {
+            Some(value) => value.clone(),
+            None => {
+                tui_data.message.set(
+                    "WARNING: no home note is set (see 'home-note' in config.yml)".to_string(),
+                );
+                return;
+            }
+        };
+        BrnTui::remember_selected_note(tui_data);
+        BrnTui::show_note_with_id(&home_note_id, tui_data, settings);
+    }
+
+    // Selects the note in the note list. If it isn't part of the list, the default list is shown
+    // instead
+    fn show_note_with_id(note_id: &str, tui_data: &mut TuiData, settings: &mut Settings) {
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => {
+                tui_data.message.set(format!(
+                    "ERROR: the note '{}' doesn't exist anymore",
+                    note_id
+                ));
+                return;
+            }
+        };
+
+        if !BrnTui::select_note_with_name(tui_data, settings, &note.note_name) {
+            BrnTui::show_default_note_list(tui_data, settings);
+            BrnTui::select_note_with_name(tui_data, settings, &note.note_name);
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn update_backlink_list(tui_data: &mut TuiData, note_id: &str) {
+        let backlinks = Database::get_incoming_links(note_id)
+            .iter()
+            .filter_map(|m| Database::get_note_where_id(m))
+            .map(|m| m.note_name)
+            .collect();
+        tui_data.backlink_list.replace_items_with(backlinks);
+    }
+
+    fn show_tag_sidebar(tui_data: &mut TuiData) {
+        // Keep the selection if the sidebar is already shown
+        if !tui_data.visible_panels.contains(&Panel::Tags) {
+            tui_data.tag_list.select(None);
+            tui_data.visible_panels.push(Panel::Tags);
+        }
+        BrnTui::update_tag_list(tui_data);
+        tui_data.input_mode = InputMode::Tags;
+    }
+
+    // The tag sidebar stays visible if it's one of the configured panels
+    fn hide_tag_sidebar(tui_data: &mut TuiData, settings: &Settings) {
+        if !settings.visible_panels.contains(&Panel::Tags) {
+            tui_data.visible_panels.retain(|m| *m != Panel::Tags);
+        }
+    }
+
+    // The last visible panel can't be hidden
+    fn toggle_panel(tui_data: &mut TuiData, panel: Panel) {
+        if !tui_data.visible_panels.contains(&panel) {
+            tui_data.visible_panels.push(panel);
+        } else if tui_data.visible_panels.len() == 1 {
+            tui_data
+                .message
+                .set(format!("the {} is the only visible panel", panel.to_name()));
+        } else {
+            tui_data.visible_panels.retain(|m| *m != panel);
+        }
+    }
+
+    // The selected tag stays selected if it's still in the list
+    fn update_tag_list(tui_data: &mut TuiData) {
+        let selected_tag_name = tui_data.tag_list.selected_item().map(|m| m.0.clone());
+        let tag_counts =
+            NoteUtility::get_tag_counts(tui_data.tag_order, tui_data.tag_counts_rolled_up);
+        let selected_index = selected_tag_name
+            .and_then(|m| tag_counts.iter().position(|n| n.0 == m))
+            .unwrap_or(0);
+        tui_data.tag_list.replace_items_with(tag_counts);
+        tui_data.tag_list.select(Some(selected_index));
+        tui_data.tag_list.clamp_selection();
+    }
+
+    fn filter_by_selected_tag(tui_data: &mut TuiData, settings: &mut Settings) {
+        let tag_name = match tui_data.tag_list.selected_item() {
+            Some((tag_name, _)) => tag_name.clone(),
+            None => return,
+        };
+
+        let mut note_list = Database::get_notes_with_tag(&tag_name)
+            .into_iter()
+            .map(|m| m.note_name)
+            .collect();
+        NoteUtility::sort_note_names(&mut note_list, settings.sort_order, settings);
+        BrnTui::replace_note_list_items(tui_data, note_list);
+        tui_data.note_list_total = None;
+        tui_data.note_list.select(Some(0));
+        tui_data.note_list.clamp_selection();
+        tui_data.note_list_title = format!("#{}", tag_name);
+        tui_data.search_query.clear();
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn jump_to_selected_backlink(tui_data: &mut TuiData, settings: &mut Settings) {
+        let backlink_note_name = match tui_data.backlink_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        BrnTui::remember_selected_note(tui_data);
+
+        if !BrnTui::select_note_with_name(tui_data, settings, &backlink_note_name) {
+            // The linking note isn't part of the current list, so show all backlinks instead
+            let selected_note_name = match tui_data.note_list.selected_item() {
+                Some(value) => value.clone(),
+                None => String::new(),
+            };
+            let backlinks = tui_data.backlink_list.get_items().clone();
+            let index = backlinks.iter().position(|m| *m == backlink_note_name);
+
+            BrnTui::replace_note_list_items(tui_data, backlinks);
+            tui_data.note_list_total = None;
+            tui_data.note_list.select(index);
+            tui_data.note_list_title = format!("Backlinks of {}", selected_note_name);
+            tui_data.search_query.clear();
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn open_selected_note<B: Backend + Write>(
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        if let Some(selected_note_name) = tui_data.note_list.selected_item() {
+            if let Some(note_id) =
+                Database::get_note_id_where(NoteProperty::NoteName, selected_note_name)
+            {
+                // Jump to the first match of the content search
+                let search_query = tui_data.search_query.get_content_text();
+                let line_number = if tui_data.content_search_enabled && !search_query.is_empty() {
+                    NoteUtility::get_line_of_first_match(&note_id, &search_query, settings)
+                } else {
+                    None
+                };
+                BrnTui::open_note_at_line(&note_id, line_number, terminal, tui_data, settings);
+            };
+        };
+    }
+
+    fn copy_selected_note_as_link(tui_data: &mut TuiData) {
+        if let Some(note_id) = BrnTui::get_selected_note_id(tui_data) {
+            BrnTui::copy_to_clipboard(tui_data, &format!("[[{}]] ", note_id));
+        }
+    }
+
+    fn copy_selected_note_path(tui_data: &mut TuiData, settings: &mut Settings) {
+        if let Some(note_id) = BrnTui::get_selected_note_id(tui_data) {
+            match NoteUtility::get_absolute_path_of_note(&note_id, settings) {
+                Ok(note_path) => {
+                    BrnTui::copy_to_clipboard(tui_data, &note_path.to_string_lossy());
+                }
+                Err(error) => tui_data.message.set(format!("ERROR: {}", error)),
+            }
+        }
+    }
+
+    fn copy_selected_note_id(tui_data: &mut TuiData) {
+        if let Some(note_id) = BrnTui::get_selected_note_id(tui_data) {
+            BrnTui::copy_to_clipboard(tui_data, &note_id);
+        }
+    }
+
+    // Headless systems don't have a clipboard, so the text is shown in the message block instead.
+    // Returns false in that case
+    fn copy_to_clipboard(tui_data: &mut TuiData, text: &str) -> bool {
+        let copy_result = ClipboardProvider::new()
+            .and_then(|mut m: ClipboardContext| m.set_contents(text.to_string()));
+        match copy_result {
+            Ok(()) => {
+                tui_data.message.set(format!("copied {}", text.trim()));
+                return true;
+            }
+            Err(error) => {
+                tui_data.message.set(format!(
+                    "WARNING: clipboard not available ({}): {}",
+                    error,
+                    text.trim()
+                ));
+                return false;
+            }
+        }
+    }
+
+    // The selection starts at the first line that is shown in the preview
+    fn enter_preview_selection_mode(tui_data: &mut TuiData) {
+        if tui_data.is_preview_loading || tui_data.preview_lines.is_empty() {
+            return;
+        }
+        let preview_scroll = tui_data.preview_scroll as usize;
+        tui_data.preview_cursor = tui_data
+            .preview_line_starts
+            .iter()
+            .position(|m| *m >= preview_scroll)
+            .unwrap_or(0)
+            .min(tui_data.preview_lines.len() - 1);
+        tui_data.preview_selection_start = None;
+        tui_data.message.set(
+            "v: select a range, y: copy the selected lines, Enter: fold a heading, x: toggle a task, Esc: cancel"
+                .to_string(),
+        );
+        tui_data.input_mode = InputMode::PreviewSelection;
+    }
+
+    // The preview is scrolled so that the cursor stays visible
+    fn move_preview_cursor(tui_data: &mut TuiData, amount: isize) {
+        let last_line = tui_data.preview_lines.len().saturating_sub(1);
+        tui_data.preview_cursor = if amount < 0 {
+            tui_data
+                .preview_cursor
+                .saturating_sub(amount.unsigned_abs())
+        } else {
+            tui_data
+                .preview_cursor
+                .saturating_add(amount as usize)
+                .min(last_line)
+        };
+
+        // The cursor skips the lines of folded sections
+        let folded_line_ranges = BrnTui::get_folded_line_ranges(tui_data);
+        let surrounding_folds = folded_line_ranges
+            .iter()
+            .filter(|m| tui_data.preview_cursor > m.0 && tui_data.preview_cursor < m.1);
+        if amount < 0 {
+            if let Some(fold_start) = surrounding_folds.map(|m| m.0).min() {
+                tui_data.preview_cursor = fold_start;
+            }
+        } else if let Some(fold_end) = surrounding_folds.map(|m| m.1).max() {
+            tui_data.preview_cursor = if fold_end <= last_line {
+                fold_end
+            } else {
+                folded_line_ranges
+                    .iter()
+                    .filter(|m| m.1 == fold_end)
+                    .map(|m| m.0)
+                    .min()
+                    .unwrap_or(last_line)
+            };
+        }
+
+        let cursor_line_start = match tui_data.preview_line_starts.get(tui_data.preview_cursor) {
+            Some(value) => *value,
+            None => return,
+        };
+        let cursor_line_end = tui_data
+            .preview_line_starts
+            .get(tui_data.preview_cursor + 1)
+            .copied()
+            .unwrap_or(tui_data.preview_line_count);
+        let preview_scroll = tui_data.preview_scroll as usize;
+        let preview_height = tui_data.preview_height as usize;
+        if cursor_line_start < preview_scroll {
+            tui_data.preview_scroll = cursor_line_start.min(u16::MAX as usize) as u16;
+        } else if cursor_line_end > preview_scroll + preview_height {
+            let new_preview_scroll = cursor_line_end.saturating_sub(preview_height);
+            tui_data.preview_scroll = (new_preview_scroll.min(u16::MAX as usize) as u16)
+                .min(BrnTui::get_max_preview_scroll(tui_data));
+        }
+    }
+
+    // Returns false if the cursor isn't on a heading
+    fn toggle_fold_at_preview_cursor(tui_data: &mut TuiData) -> bool {
+        let heading_index = match tui_data
+            .preview_headings
+            .iter()
+            .position(|m| m.line_index == tui_data.preview_cursor)
+        {
+            Some(value) => value,
+            None => return false,
+        };
+        if !tui_data.preview_folded_headings.remove(&heading_index) {
+            tui_data.preview_folded_headings.insert(heading_index);
+        }
+        return true;
+    }
+
+    // The task is changed in the note file, so the preview is reloaded at the same position
+    fn toggle_task_at_preview_cursor(tui_data: &mut TuiData, settings: &mut Settings) {
+        let note_id = match tui_data
+            .previewed_note_name
+            .as_ref()
+            .and_then(|m| Database::get_note_id_where(NoteProperty::NoteName, m))
+        {
+            Some(value) => value,
+            None => return,
+        };
+        let line_index = tui_data.preview_first_line_number - 1 + tui_data.preview_cursor;
+        match NoteUtility::toggle_task(&note_id, line_index, settings) {
+            Ok(true) => tui_data.message.set("task done".to_string()),
+            Ok(false) => tui_data.message.set("task reopened".to_string()),
+            Err(error) => {
+                tui_data.message.set(format!("ERROR: {}", error));
+                return;
+            }
+        }
+
+        let preview_scroll = tui_data.preview_scroll;
+        BrnTui::show_note_content_preview(tui_data, settings);
+        tui_data.preview_scroll = preview_scroll;
+    }
+
+    // A section ends at the next heading of the same or a higher level. Returns the line of
+    // the heading and the end (exclusive) of every folded section
+    fn get_folded_line_ranges(tui_data: &TuiData) -> Vec<(usize, usize)> {
+        let headings = &tui_data.preview_headings;
+        let mut folded_line_ranges: Vec<(usize, usize)> = tui_data
+            .preview_folded_headings
+            .iter()
+            .filter_map(|m| {
+                let heading = headings.get(*m)?;
+                let section_end = headings[m + 1..]
+                    .iter()
+                    .find(|n| n.level <= heading.level)
+                    .map_or(tui_data.preview_lines.len(), |n| n.line_index);
+                Some((heading.line_index, section_end))
+            })
+            .collect();
+        folded_line_ranges.sort_unstable();
+        return folded_line_ranges;
+    }
+
+    fn get_selected_preview_lines(tui_data: &TuiData) -> (usize, usize) {
+        let selection_start = tui_data
+            .preview_selection_start
+            .unwrap_or(tui_data.preview_cursor);
+        return (
+            selection_start.min(tui_data.preview_cursor),
+            selection_start.max(tui_data.preview_cursor),
+        );
+    }
+
+    // The lines of the note file are copied instead of the rendered ones, so that the
+    // formatting is kept when quoting the note elsewhere
+    fn copy_selected_preview_lines(tui_data: &mut TuiData) {
+        let (first_line, last_line) = BrnTui::get_selected_preview_lines(tui_data);
+        let selected_text = tui_data
+            .note_content_preview
+            .lines()
+            .skip(first_line)
+            .take(last_line - first_line + 1)
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let line_count = last_line - first_line + 1;
+        if BrnTui::copy_to_clipboard(tui_data, &selected_text) && line_count > 1 {
+            tui_data.message.set(format!("copied {} lines", line_count));
+        }
+    }
+
+    fn enter_search_mode(tui_data: &mut TuiData) {
+        // Only take a new snapshot if no filter is active, as the current list
+        // would already be filtered otherwise
+        if tui_data.search_query.get_content_text().is_empty() {
+            // Only a window of the default note list is loaded, so it's searched in the database
+            tui_data.search_in_database = tui_data.note_list_total.is_some();
+            tui_data.unfiltered_note_list = if tui_data.search_in_database {
+                Vec::new()
+            } else {
+                tui_data.note_list.get_items().clone()
+            };
+
+            // A new search always starts as name search
+            tui_data.content_search_enabled = false;
+            tui_data.search_query.set_pre_text("/");
+        }
+        tui_data.input_mode = InputMode::Search;
+    }
+
+    fn toggle_content_search(tui_data: &mut TuiData, settings: &mut Settings) {
+        tui_data.content_search_enabled = !tui_data.content_search_enabled;
+
+        let search_query = tui_data.search_query.get_content_text();
+        if tui_data.content_search_enabled {
+            tui_data.search_query.set_pre_text("content/");
+        } else {
+            tui_data.search_query.set_pre_text("/");
+        }
+        for c in search_query.chars() {
+            tui_data.search_query.push(c);
+        }
+        BrnTui::apply_search_filter(tui_data, settings);
+    }
+
+    fn apply_search_filter(tui_data: &mut TuiData, settings: &mut Settings) {
+        let search_query = tui_data.search_query.get_content_text().to_lowercase();
+        let filtered_note_list: Vec<String>;
+        let new_selected_index;
+
+        if tui_data.search_in_database && search_query.is_empty() {
+            let selected_note_name = tui_data.note_list.selected_item().cloned();
+            BrnTui::show_default_note_list(tui_data, settings);
+            if let Some(note_name) = selected_note_name {
+                BrnTui::select_note_with_name(tui_data, settings, &note_name);
+            }
+            BrnTui::show_note_content_preview(tui_data, settings);
+            return;
+        }
+        let unfiltered_note_list = if tui_data.search_in_database {
+            let like_pattern =
+                BrnTui::get_like_pattern_of(&search_query, settings.fuzzy_matching_enabled);
+            Database::get_note_names_like(
+                &like_pattern,
+                settings.sort_order,
+                &settings.pinned_note_ids,
+            )
+        } else {
+            tui_data.unfiltered_note_list.clone()
+        };
+
+        if tui_data.content_search_enabled && !search_query.is_empty() {
+            // The results of the content search are ranked by relevance. Tag names are
+            // case-sensitive, so the search query isn't converted to lower case. While
+            // a query is typed it is often incomplete (e.g. a phrase without its closing
+            // quote), so the last results are kept until it is valid again
+            filtered_note_list =
+                match NoteUtility::search_content(&tui_data.search_query.get_content_text()) {
+                    Ok(notes) => notes.into_iter().map(|m| m.note_name).collect(),
+                    Err(_) => return,
+                };
+            new_selected_index = Some(0);
+        } else if tui_data.content_search_enabled {
+            filtered_note_list = unfiltered_note_list;
+            new_selected_index = Some(0);
+        } else if settings.fuzzy_matching_enabled {
+            filtered_note_list = FuzzyMatcher::sort_by_score(&search_query, &unfiltered_note_list);
+
+            // The best match is always on top
+            new_selected_index = Some(0);
+        } else {
+            filtered_note_list = unfiltered_note_list
+                .iter()
+                .filter(|m| m.to_lowercase().contains(&search_query))
+                .cloned()
+                .collect();
+
+            // Keep the selected note if it is still part of the filtered list
+            let selected_note_name = tui_data.note_list.selected_item().cloned();
+            let selected_index = tui_data.note_list.selected();
+            new_selected_index = match selected_note_name {
+                Some(note_name) => filtered_note_list
+                    .iter()
+                    .position(|m| *m == note_name)
+                    .or(selected_index),
+                None => selected_index,
+            };
+        }
+
+        BrnTui::replace_note_list_items(tui_data, filtered_note_list);
+        tui_data.note_list_total = None;
+        tui_data.note_list.select(new_selected_index);
+        tui_data.note_list.clamp_selection();
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    // Fuzzy matching needs all characters of the query in the same order, but not next to each other
+    fn get_like_pattern_of(search_query: &str, fuzzy_matching_enabled: bool) -> String {
+        let mut like_pattern = String::from("%");
+        for c in search_query.chars() {
+            if c == '%' || c == '_' || c == '\\' {
+                like_pattern.push('\\');
+            }
+            like_pattern.push(c);
+            if fuzzy_matching_enabled {
+                like_pattern.push('%');
+            }
+        }
+        if !fuzzy_matching_enabled {
+            like_pattern.push('%');
+        }
+        return like_pattern;
+    }
+
+    fn uses_search_operators(search_query: &str) -> bool {
+        return search_query.contains("&&")
+            || search_query.starts_with('#')
+            || search_query.starts_with('!');
+    }
+
+    fn execute_search(tui_data: &mut TuiData, settings: &mut Settings) {
+        let mut search_results = NoteUtility::search(&tui_data.search_query.get_content_text())
+            .iter()
+            .map(|m| match Database::get_note_where_id(&m.note_id) {
+                Some(note) => note.note_name,
+                None => String::new(),
+            })
+            .collect();
+        NoteUtility::sort_note_names(&mut search_results, settings.sort_order, settings);
+        BrnTui::replace_note_list_items(tui_data, search_results);
+        tui_data.note_list_total = None;
+        tui_data.note_list.select(Some(0));
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn show_template_picker(tui_data: &mut TuiData, template_names: Vec<String>) {
+        let mut template_list = vec!["(default)".to_string()];
+        template_list.extend(template_names);
+        tui_data.template_list.replace_items_with(template_list);
+        tui_data.template_list.select(Some(0));
+        tui_data.input_mode = InputMode::Template;
+    }
+
+    // A note can only be moved into the other notebooks
+    fn show_notebook_picker(tui_data: &mut TuiData, settings: &Settings, is_notebook_move: bool) {
+        if settings.notebooks.len() < 2 {
+            tui_data
+                .message
+                .set("there are no other notebooks".to_string());
+            return;
+        }
+
+        let notebook_names: Vec<String> = settings
+            .notebooks
+            .iter()
+            .map(|m| m.name.clone())
+            .filter(|m| !is_notebook_move || *m != settings.notebook_name)
+            .collect();
+        let active_index = notebook_names
+            .iter()
+            .position(|m| *m == settings.notebook_name);
+        tui_data.notebook_list.replace_items_with(notebook_names);
+        tui_data.notebook_list.select(active_index.or(Some(0)));
+        tui_data.is_notebook_move = is_notebook_move;
+        tui_data.input_mode = InputMode::Notebooks;
+    }
+
+    // The label picker for the selected note has an additional entry to remove its label
+    fn show_label_picker(tui_data: &mut TuiData, is_label_filter: bool) {
+        let labels = NoteLabel::all();
+        let mut label_names: Vec<String> = labels.iter().map(|m| m.to_name()).collect();
+        let mut selected_index = 0;
+        if !is_label_filter {
+            let note_id = match BrnTui::get_selected_note_id(tui_data) {
+                Some(value) => value,
+                None => return,
+            };
+            label_names.push("(none)".to_string());
+            if let Some(label) = Database::get_label_of(&note_id) {
+                selected_index = labels.iter().position(|m| *m == label).unwrap_or(0);
+            }
+        }
+
+        tui_data.label_list.replace_items_with(label_names);
+        tui_data.label_list.select(Some(selected_index));
+        tui_data.is_label_filter = is_label_filter;
+        tui_data.input_mode = InputMode::Labels;
+    }
+
+    fn apply_selected_label(tui_data: &mut TuiData, settings: &mut Settings) {
+        let label = tui_data
+            .label_list
+            .selected_item()
+            .and_then(|m| NoteLabel::from_name(m));
+
+        if tui_data.is_label_filter {
+            let label = match label {
+                Some(value) => value,
+                None => return,
+            };
+            let mut note_list = Database::get_notes_with_label(label)
+                .into_iter()
+                .map(|m| m.note_name)
+                .collect();
+            NoteUtility::sort_note_names(&mut note_list, settings.sort_order, settings);
+            BrnTui::replace_note_list_items(tui_data, note_list);
+            tui_data.note_list_total = None;
+            tui_data.note_list.select(Some(0));
+            tui_data.note_list.clamp_selection();
+            tui_data.note_list_title = format!("Label: {}", label.to_name());
+            tui_data.search_query.clear();
+            BrnTui::show_note_content_preview(tui_data, settings);
+            return;
+        }
+
+        if let Some(note_id) = BrnTui::get_selected_note_id(tui_data) {
+            Database::set_label_of(&note_id, label);
+        }
+    }
+
+    fn get_color_of_label(label: NoteLabel) -> Color {
+        return match label {
+            NoteLabel::Important => Color::Red,
+            NoteLabel::ToRead => Color::Yellow,
+            NoteLabel::Stub => Color::Blue,
+        };
+    }
+
+    fn switch_to_selected_notebook(tui_data: &mut TuiData, settings: &mut Settings) {
+        let notebook_name = match tui_data.notebook_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        if notebook_name == settings.notebook_name {
+            return;
+        }
+
+        let warnings = match NoteUtility::open_notebook(&notebook_name, settings) {
+            Ok(value) => value,
+            Err(error) => {
+                tui_data.message.set(format!("ERROR: {}", error));
+                return;
+            }
+        };
+
+        // The notes of the previous notebook can't be visited anymore
+        tui_data.navigation_history = NavigationHistory::default();
+        tui_data.preview_cache = PreviewCache::new(settings.preview_cache_size);
+        BrnTui::show_default_note_list(tui_data, settings);
+        BrnTui::show_note_content_preview(tui_data, settings);
+        if warnings.is_empty() {
+            tui_data
+                .message
+                .set(format!("Switched to notebook '{}'", notebook_name));
+        } else {
+            tui_data.message.set(format!(
+                "WARNING: switched to notebook '{}', but {}",
+                notebook_name,
+                warnings.join("; ")
+            ));
+        }
+        if let Err(error) = tui_data
+            .note_watcher
+            .watch(PathBuf::from(&settings.notes_dir))
+        {
+            tui_data.message.set(format!(
+                "WARNING: notes changed outside of the TUI won't be reloaded: {}",
+                error
+            ));
+        }
+    }
+
+    // The notebook stays selected in the notebook picker while the move is confirmed
+    fn confirm_move_to_notebook(tui_data: &mut TuiData) {
+        if let (Some(selected_note_name), Some(notebook_name)) = (
+            tui_data.note_list.selected_item(),
+            tui_data.notebook_list.selected_item(),
+        ) {
+            let confirmation_text = format!(
+                "Move {} into notebook {}? (y/n): ",
+                selected_note_name, notebook_name
+            );
+            tui_data.edit_text.set_pre_text(&confirmation_text);
+            tui_data.input_mode = InputMode::MoveToNotebook;
+        }
+    }
+
+    // The broken links aren't replaced, so that they can still be fixed by hand
+    fn move_selected_note_to_notebook(tui_data: &mut TuiData, settings: &mut Settings) {
+        let notebook_name = match tui_data.notebook_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        let selected_note_name = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        let note_id = match Database::get_note_id_where(NoteProperty::NoteName, &selected_note_name)
+        {
+            Some(value) => value,
+            None => return,
+        };
+
+        let moved_note =
+            match NoteUtility::move_to_notebook(&note_id, &notebook_name, false, settings) {
+                Ok(value) => value,
+                Err(error) => {
+                    tui_data.message.set(format!("ERROR: {}", error));
+                    return;
+                }
+            };
+        let mut warnings = moved_note.warnings;
+        if !moved_note.broken_links.is_empty() {
+            warnings.insert(
+                0,
+                format!(
+                    "{} links are broken now (see 'brn check')",
+                    moved_note.broken_links.len()
+                ),
+            );
+        }
+        if warnings.is_empty() {
+            tui_data.message.set(format!(
+                "Moved '{}' into notebook '{}'",
+                selected_note_name, notebook_name
+            ));
+        } else {
+            tui_data.message.set(format!(
+                "WARNING: moved '{}' into notebook '{}', {}",
+                selected_note_name,
+                notebook_name,
+                warnings.join("; ")
+            ));
+        }
+
+        tui_data.marked_notes.remove(&selected_note_name);
+        BrnTui::remove_note_from_note_list(tui_data, &selected_note_name);
+        BrnTui::show_note_content_preview(tui_data, settings);
     }
 
-    fn render_note_preview<B: Backend>(f: &mut Frame<B>, area: Rect, tui_data: &mut TuiData) {
-        let title = match tui_data.note_list.selected_item() {
+    // Note files that were changed outside of the TUI are shown without restarting it
+    fn reload_changed_notes(
+        changed_file_names: &[String],
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        let warnings = NoteUtility::sync_changed_files(changed_file_names, settings);
+        // The tags and names of the changed notes might have changed as well
+        tui_data.note_list_details.clear();
+        tui_data.pinned_note_names = None;
+        let selected_note_name = tui_data.note_list.selected_item().cloned();
+        let selected_index = tui_data.note_list.selected().unwrap_or(0);
+
+        if tui_data.note_list_total.is_some() {
+            tui_data.note_list_total = Some(Database::get_note_count());
+            let position = selected_note_name
+                .as_ref()
+                .and_then(|m| {
+                    Database::get_note_position(m, settings.sort_order, &settings.pinned_note_ids)
+                })
+                .unwrap_or(tui_data.note_list_offset + selected_index);
+            BrnTui::load_note_list_window(tui_data, settings, position);
+        } else {
+            // Other note lists only lose the notes that don't exist anymore
+            let removed_note_names: Vec<String> = tui_data
+                .note_list
+                .get_items()
+                .iter()
+                .filter(|m| Database::get_note_id_where(NoteProperty::NoteName, m).is_none())
+                .cloned()
+                .collect();
+            for note_name in removed_note_names.iter() {
+                BrnTui::remove_note_from_note_list(tui_data, note_name);
+            }
+        }
+
+        // The preview stays at the same position if the selected note was only changed
+        let preview_scroll = tui_data.preview_scroll;
+        BrnTui::show_note_content_preview(tui_data, settings);
+        if tui_data.note_list.selected_item() == selected_note_name.as_ref() {
+            tui_data.preview_scroll = preview_scroll;
+        }
+
+        if !warnings.is_empty() {
+            tui_data
+                .message
+                .set(format!("WARNING: {}", warnings.join("; ")));
+        }
+    }
+
+    // The name of the new note is prefilled with the link text, so that the link is valid
+    // right away. If the name is changed, the link is changed to the new name
+    fn offer_note_for_broken_link(link_text: &str, tui_data: &mut TuiData) {
+        let selected_note_id = match BrnTui::get_selected_note_id(tui_data) {
             Some(value) => value,
-            None => "Note preview",
+            None => return,
         };
+        tui_data.edit_text.set_pre_text(&format!(
+            "Broken link [[{}]], name of new note: ",
+            link_text
+        ));
+        link_text.chars().for_each(|c| tui_data.edit_text.push(c));
+        tui_data.note_name_cache.clear();
+        tui_data.parent_note_id = None;
+        tui_data.broken_link_cache = Some((selected_note_id, link_text.to_string()));
+        tui_data.input_mode = InputMode::Add;
+    }
 
-        // Render note preview
-        let outer_note_block = Block::default().title(title).borders(Borders::ALL);
-        f.render_widget(outer_note_block, area);
+    fn link_note_to_broken_link(note_id: &str, tui_data: &mut TuiData, settings: &Settings) {
+        let (source_note_id, link_text) = match tui_data.broken_link_cache.take() {
+            Some(value) => value,
+            None => return,
+        };
+        let (source_note, note) = match (
+            Database::get_note_where_id(&source_note_id),
+            Database::get_note_where_id(note_id),
+        ) {
+            (Some(source_note), Some(note)) => (source_note, note),
+            _ => return,
+        };
 
-        let inner_note_paragraph =
-            Paragraph::new(tui_data.note_content_preview.as_str()).alignment(Alignment::Left);
-        f.render_widget(
-            inner_note_paragraph,
-            area.inner(&Margin {
-                vertical: 2,
-                horizontal: 2,
-            }),
+        let is_link_valid = matches!(
+            NoteUtility::get_linked_note(&link_text),
+            Ok(Some(linked_note)) if linked_note.note_id == note.note_id
         );
+        let new_link_text = if is_link_valid {
+            link_text.clone()
+        } else {
+            note.note_name.clone()
+        };
+        match NoteUtility::relink(&source_note, &link_text, &new_link_text, settings) {
+            Ok(warnings) if !warnings.is_empty() => tui_data
+                .message
+                .set(format!("WARNING: {}", warnings.join("; "))),
+            Ok(_) if is_link_valid => (),
+            Ok(_) => tui_data.message.set(format!(
+                "Changed [[{}]] in '{}' to [[{}]]",
+                link_text, source_note.note_name, new_link_text
+            )),
+            Err(error) => tui_data.message.set(format!("ERROR: {}", error)),
+        }
     }
 
-    fn render_message_block<B: Backend>(f: &mut Frame<B>, area: Rect, tui_data: &mut TuiData) {
-        let message_paragraph;
-        match tui_data.input_mode {
-            InputMode::Normal => {
-                message_paragraph = Paragraph::new(tui_data.message.as_str())
-                    .alignment(Alignment::Left)
-                    .style(Style::default().fg(Color::LightRed));
+    fn add_note<B: Backend + Write>(
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+        template_name: Option<&str>,
+    ) {
+        match NoteUtility::add(
+            tui_data.note_name_cache.as_str(),
+            tui_data.note_type_cache,
+            template_name,
+            tui_data.parent_note_id.as_deref(),
+            settings,
+        ) {
+            Ok(note_id) => {
+                BrnTui::remember_selected_note(tui_data);
+                if settings.open_new_notes_in_editor {
+                    BrnTui::open_note(&note_id, terminal, tui_data, settings);
+                }
+                BrnTui::link_note_to_broken_link(&note_id, tui_data, settings);
+
+                // The note name could have been changed in the editor
+                BrnTui::show_default_note_list(tui_data, settings);
+                if let Some(note) = Database::get_note_where_id(&note_id) {
+                    BrnTui::select_note_with_name(tui_data, settings, &note.note_name);
+                }
+                BrnTui::show_note_content_preview(tui_data, settings);
             }
-            InputMode::Add | InputMode::Remove => {
-                message_paragraph = Paragraph::new(tui_data.edit_text.get_displayed_text())
-                    .alignment(Alignment::Left)
-                    .style(Style::default());
+            Err(error) => tui_data.message.set(format!("ERROR: {}", error)),
+        };
+    }
+
+    // Pins are stored in the state file, so that they survive rebuilding the database
+    fn toggle_pin_of_selected_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let selected_note_id = match BrnTui::get_selected_note_id(tui_data) {
+            Some(value) => value,
+            None => return,
+        };
+        let note_name = tui_data
+            .note_list
+            .selected_item()
+            .cloned()
+            .unwrap_or_default();
+        match settings
+            .pinned_note_ids
+            .iter()
+            .position(|m| *m == selected_note_id)
+        {
+            Some(index) => {
+                settings.pinned_note_ids.remove(index);
+                tui_data.message.set(format!("Unpinned '{}'", note_name));
             }
-            InputMode::Search => {
-                message_paragraph = Paragraph::new(tui_data.search_text.get_displayed_text())
-                    .alignment(Alignment::Left)
-                    .style(Style::default());
+            None => {
+                settings.pinned_note_ids.push(selected_note_id);
+                tui_data.message.set(format!("Pinned '{}'", note_name));
             }
         }
-        f.render_widget(message_paragraph, area);
+        if let Err(error) = settings.save_state() {
+            tui_data.message.set(format!("ERROR: {}", error));
+        }
+        tui_data.pinned_note_names = None;
+        BrnTui::sort_note_list(tui_data, settings);
     }
 
-    fn increment_selected_value(tui_data: &mut TuiData, settings: &mut Settings) {
-        tui_data.note_list.next();
-        BrnTui::show_note_content_preview(tui_data, settings);
+    fn toggle_mark_of_selected_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let selected_note_name = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        if !tui_data.marked_notes.remove(&selected_note_name) {
+            tui_data.marked_notes.insert(selected_note_name);
+        }
+        BrnTui::increment_selected_value(tui_data, settings);
     }
 
-    fn decrement_selected_value(tui_data: &mut TuiData, settings: &mut Settings) {
-        tui_data.note_list.previous();
-        BrnTui::show_note_content_preview(tui_data, settings);
+    fn update_tag_suggestions(tui_data: &mut TuiData) {
+        let tag_suggestions =
+            NoteUtility::get_tag_suggestions(&tui_data.edit_text.get_content_text());
+        tui_data
+            .tag_suggestion_list
+            .replace_items_with(tag_suggestions);
+        tui_data.new_tag_to_confirm = None;
     }
 
-    fn select_first_value(tui_data: &mut TuiData, settings: &mut Settings) {
-        tui_data.note_list.select(Some(0));
-        BrnTui::show_note_content_preview(tui_data, settings);
+    // New tags have to be confirmed by pressing Enter again, as they are often typos of existing tags
+    fn confirm_tag_of_marked_notes(tui_data: &mut TuiData, settings: &mut Settings) {
+        let tag_name = match tui_data.tag_suggestion_list.selected_item() {
+            Some((tag_name, _)) => tag_name.clone(),
+            None => tui_data
+                .edit_text
+                .get_content_text()
+                .trim()
+                .trim_start_matches('#')
+                .to_string(),
+        };
+        if tag_name.is_empty() {
+            return;
+        }
+        if !NoteUtility::is_existing_tag(&tag_name)
+            && tui_data.new_tag_to_confirm.as_ref() != Some(&tag_name)
+        {
+            tui_data.new_tag_to_confirm = Some(tag_name);
+            return;
+        }
+
+        BrnTui::add_tag_to_marked_notes(&tag_name, tui_data, settings);
+        tui_data.input_mode = InputMode::Normal;
     }
 
-    fn select_last_value(tui_data: &mut TuiData, settings: &mut Settings) {
-        let last_index = tui_data.note_list.get_items().len() - 1;
-        tui_data.note_list.select(Some(last_index));
+    // The previous contents of the tagged notes are kept to be able to undo the tag change
+    fn add_tag_to_marked_notes(tag_name: &str, tui_data: &mut TuiData, settings: &mut Settings) {
+        let mut previous_contents = Vec::new();
+        let mut tag_result = Ok(());
+        for note_name in tui_data.marked_notes.iter() {
+            let note = match Database::get_note_id_where(NoteProperty::NoteName, note_name)
+                .and_then(|m| Database::get_note_where_id(&m))
+            {
+                Some(value) => value,
+                None => continue,
+            };
+            let note_file_path = Path::new(&settings.notes_dir).join(&note.file_name);
+            let note_content = match fs::read_to_string(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    tag_result = Err(BrainError::Io {
+                        path: note_file_path,
+                        source: error,
+                    });
+                    break;
+                }
+            };
+            tag_result = NoteUtility::add_tag(&note.note_id, tag_name, settings);
+            if tag_result.is_err() {
+                break;
+            }
+            previous_contents.push((note.file_name, note_content));
+        }
+
+        let tagged_note_count = previous_contents.len();
+        if !previous_contents.is_empty() {
+            tui_data.last_undo_action = Some(UndoAction::AddTag {
+                tag_name: tag_name.trim().trim_start_matches('#').to_string(),
+                previous_contents,
+            });
+        }
+        if let Err(error) = tag_result {
+            tui_data.message.set(format!("ERROR: {}", error));
+            return;
+        }
+
+        tui_data.marked_notes.clear();
+        tui_data.message.set(format!(
+            "Added #{} to {} notes",
+            tag_name.trim().trim_start_matches('#'),
+            tagged_note_count
+        ));
         BrnTui::show_note_content_preview(tui_data, settings);
     }
 
-    fn show_note_content_preview(tui_data: &mut TuiData, settings: &mut Settings) {
-        if let Some(selected_note_name) = &tui_data.note_list.selected_item() {
-            if let Some(note_id) =
-                Database::get_note_id_where(NoteProperty::NoteName, selected_note_name)
-            {
-                match NoteUtility::get_content_of_note(&note_id, settings) {
-                    Ok(note_content) => {
-                        tui_data.note_content_preview = note_content;
-                    }
-                    Err(error) => {
-                        tui_data.message = error;
-                    }
+    fn remove_selected_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let selected_note_name = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+
+        let file_name = Database::get_note_id_where(NoteProperty::NoteName, &selected_note_name)
+            .and_then(|m| Database::get_note_where_id(&m))
+            .map(|m| m.file_name);
+
+        match NoteUtility::remove(&selected_note_name, settings) {
+            Ok(removed_note) => {
+                let mut warnings = removed_note.warnings;
+                if !removed_note.linking_note_ids.is_empty() {
+                    warnings.push(format!(
+                        "the removed note '{}' is still linked in the following notes: {}",
+                        selected_note_name,
+                        removed_note.linking_note_ids.join(", ")
+                    ));
                 }
+                if !warnings.is_empty() {
+                    tui_data
+                        .message
+                        .set(format!("WARNING: {}", warnings.join("; ")));
+                }
+            }
+            Err(error) => {
+                tui_data.message.set(format!("ERROR: {}", error));
+                return;
             }
         }
+        if let Some(file_name) = file_name {
+            tui_data.last_undo_action = Some(UndoAction::Remove {
+                note_name: selected_note_name.clone(),
+                file_name,
+            });
+        }
+
+        BrnTui::remove_note_from_note_list(tui_data, &selected_note_name);
+        BrnTui::show_note_content_preview(tui_data, settings);
     }
 
-    fn open_selected_note<B: Backend + Write>(
-        terminal: &mut Terminal<B>,
-        tui_data: &mut TuiData,
-        settings: &mut Settings,
-    ) {
-        if let Some(selected_note_name) = tui_data.note_list.selected_item() {
-            if let Some(note_id) =
-                Database::get_note_id_where(NoteProperty::NoteName, selected_note_name)
+    // The marked notes are merged in the order of their names. They can't be restored with undo
+    fn merge_marked_notes_into_selected_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let selected_note_name = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        let into_note_id =
+            match Database::get_note_id_where(NoteProperty::NoteName, &selected_note_name) {
+                Some(value) => value,
+                None => return,
+            };
+        let mut marked_note_names: Vec<String> = tui_data
+            .marked_notes
+            .iter()
+            .filter(|m| **m != selected_note_name)
+            .cloned()
+            .collect();
+        marked_note_names.sort();
+
+        let mut merged_note_count = 0;
+        let mut merge_error = None;
+        let mut warnings = Vec::new();
+        for note_name in &marked_note_names {
+            let from_note_id = match Database::get_note_id_where(NoteProperty::NoteName, note_name)
             {
-                BrnTui::open_note(&note_id, terminal, tui_data, settings);
+                Some(value) => value,
+                None => continue,
             };
+            match NoteUtility::merge(&from_note_id, &into_note_id, settings) {
+                Ok((_, merge_warnings)) => warnings.extend(merge_warnings),
+                Err(error) => {
+                    merge_error = Some(error);
+                    break;
+                }
+            }
+            BrnTui::remove_note_from_note_list(tui_data, note_name);
+            tui_data.marked_notes.remove(note_name);
+            merged_note_count += 1;
+        }
+
+        // The removed notes can change the index of the selected note
+        let selected_index = tui_data
+            .note_list
+            .get_items()
+            .iter()
+            .position(|m| *m == selected_note_name);
+        if selected_index.is_some() {
+            tui_data.note_list.select(selected_index);
+        }
+        match merge_error {
+            Some(error) => tui_data.message.set(format!("ERROR: {}", error)),
+            None if warnings.is_empty() => tui_data.message.set(format!(
+                "Merged {} notes into '{}'",
+                merged_note_count, selected_note_name
+            )),
+            None => tui_data.message.set(format!(
+                "WARNING: merged {} notes into '{}', but {}",
+                merged_note_count,
+                selected_note_name,
+                warnings.join("; ")
+            )),
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    // The following note takes the place of the removed note
+    fn remove_note_from_note_list(tui_data: &mut TuiData, note_name: &str) {
+        let selected_index = tui_data.note_list.selected();
+        tui_data.note_list.get_items().retain(|m| *m != note_name);
+        tui_data.unfiltered_note_list.retain(|m| *m != note_name);
+        if let Some(note_count) = tui_data.note_list_total.as_mut() {
+            *note_count = note_count.saturating_sub(1);
+        }
+        tui_data.note_list.select(selected_index);
+        tui_data.note_list.clamp_selection();
+    }
+
+    // Archived notes aren't part of the default note list, so they are removed from it
+    fn toggle_archive_of_selected_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let selected_note_name = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        let note_id = match Database::get_note_id_where(NoteProperty::NoteName, &selected_note_name)
+        {
+            Some(value) => value,
+            None => return,
         };
+
+        let archived = !Database::is_archived(&note_id);
+        if let Err(error) = NoteUtility::set_archived(&note_id, archived, settings) {
+            tui_data.message.set(format!("ERROR: {}", error));
+            return;
+        }
+
+        if archived {
+            tui_data
+                .message
+                .set(format!("Archived '{}'", selected_note_name));
+            if tui_data.note_list_total.is_some() {
+                BrnTui::remove_note_from_note_list(tui_data, &selected_note_name);
+                tui_data.marked_notes.remove(&selected_note_name);
+            }
+        } else {
+            tui_data
+                .message
+                .set(format!("Restored '{}'", selected_note_name));
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
     }
 
-    fn copy_selected_note_as_link(tui_data: &mut TuiData) {
-        let mut clipboard_context: ClipboardContext = ClipboardProvider::new().unwrap();
-        if let Some(selected_note_name) = tui_data.note_list.selected_item() {
-            if let Some(note_id) =
-                Database::get_note_id_where(NoteProperty::NoteName, selected_note_name)
-            {
-                if let Err(boxed_error) =
-                    clipboard_context.set_contents(format!("[[{}]] ", note_id))
-                {
-                    tui_data.message = (*boxed_error).to_string();
-                }
-            };
+    fn rename_selected_note(tui_data: &mut TuiData, settings: &mut Settings) {
+        let selected_note_name = match tui_data.note_list.selected_item() {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        let note_id = match Database::get_note_id_where(NoteProperty::NoteName, &selected_note_name)
+        {
+            Some(value) => value,
+            None => return,
         };
+        let new_note_name = tui_data.edit_text.get_content_text().trim().to_string();
+
+        let rename_result = NoteUtility::rename(&note_id, &new_note_name, settings);
+        if let Err(error) = &rename_result {
+            tui_data.message.set(format!("ERROR: {}", error));
+        }
+
+        // The note could have been renamed even if some links couldn't be updated
+        if let Some(note) = Database::get_note_where_id(&note_id) {
+            BrnTui::replace_note_name_in_note_list(tui_data, &selected_note_name, &note.note_name);
+            if note.note_name != selected_note_name {
+                if rename_result.is_ok() {
+                    tui_data.message.set(format!(
+                        "Renamed '{}' to '{}'",
+                        selected_note_name, note.note_name
+                    ));
+                }
+                tui_data.last_undo_action = Some(UndoAction::Rename {
+                    note_id,
+                    old_note_name: selected_note_name,
+                    new_note_name: note.note_name,
+                });
+            }
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
     }
 
-    fn execute_search(tui_data: &mut TuiData, settings: &mut Settings) {
-        let search_results = NoteUtility::search(&tui_data.search_text.get_content_text())
-            .iter()
-            .map(|m| match Database::get_note_where_id(&m.note_id) {
-                Some(note) => note.note_name,
-                None => String::new(),
-            })
-            .collect();
-        tui_data.note_list.replace_items_with(search_results);
-        tui_data.note_list.select(Some(0));
+    // The note list is updated by the note watcher if the inbox note was created
+    fn capture_edit_text(tui_data: &mut TuiData, settings: &mut Settings) {
+        let text = tui_data.edit_text.get_content_text();
+        match NoteUtility::capture(&text, settings) {
+            Ok((note_id, warnings)) => {
+                let note_name = Database::get_note_where_id(&note_id)
+                    .map(|m| m.note_name)
+                    .unwrap_or(note_id);
+                if warnings.is_empty() {
+                    tui_data
+                        .message
+                        .set(format!("Captured into '{}'", note_name));
+                } else {
+                    tui_data.message.set(format!(
+                        "WARNING: captured into '{}', but {}",
+                        note_name,
+                        warnings.join("; ")
+                    ));
+                }
+            }
+            Err(error) => tui_data.message.set(format!("ERROR: {}", error)),
+        }
+        // The inbox note could be the previewed note
         BrnTui::show_note_content_preview(tui_data, settings);
     }
 
-    fn add_note<B: Backend + Write>(
-        terminal: &mut Terminal<B>,
+    fn replace_note_name_in_note_list(
         tui_data: &mut TuiData,
-        settings: &mut Settings,
+        old_note_name: &str,
+        new_note_name: &str,
     ) {
-        let note_type = match tui_data.edit_text.get_content_text().as_str() {
-            "Q" | "q" => NoteType::Quote,
-            "J" | "j" => NoteType::Journal,
-            "T" | "t" | _ => NoteType::Topic,
+        for note_name in tui_data
+            .note_list
+            .get_items()
+            .iter_mut()
+            .chain(tui_data.unfiltered_note_list.iter_mut())
+        {
+            if *note_name == old_note_name {
+                *note_name = new_note_name.to_string();
+            }
+        }
+        tui_data.pinned_note_names = None;
+    }
+
+    // Only the last destructive action can be undone, so it's forgotten afterwards
+    fn undo_last_action(tui_data: &mut TuiData, settings: &mut Settings) {
+        let undo_action = match tui_data.last_undo_action.take() {
+            Some(value) => value,
+            None => {
+                tui_data.message.set("nothing to undo".to_string());
+                return;
+            }
         };
 
-        match NoteUtility::add(tui_data.note_name_cache.as_str(), note_type, settings) {
-            Ok(None) => (),
-            Ok(Some(note_id)) => {
-                BrnTui::open_note(&note_id, terminal, tui_data, settings);
+        match undo_action {
+            UndoAction::Remove {
+                note_name,
+                file_name,
+            } => {
+                if let Err(error) = NoteUtility::restore_from_trash(&file_name, settings) {
+                    tui_data.message.set(format!("ERROR: {}", error));
+                    return;
+                }
+                BrnTui::reload_changed_notes(&[file_name], tui_data, settings);
+                if tui_data.note_list_total.is_none() {
+                    tui_data.note_list.get_items().push(note_name.clone());
+                }
+                BrnTui::select_note_with_name(tui_data, settings, &note_name);
+                tui_data.message.set(format!("Restored '{}'", note_name));
+            }
+            UndoAction::Rename {
+                note_id,
+                old_note_name,
+                new_note_name,
+            } => {
+                if let Err(error) = NoteUtility::rename(&note_id, &old_note_name, settings) {
+                    tui_data.message.set(format!("ERROR: {}", error));
+                    return;
+                }
+                BrnTui::replace_note_name_in_note_list(tui_data, &new_note_name, &old_note_name);
+                tui_data.message.set(format!(
+                    "Renamed '{}' back to '{}'",
+                    new_note_name, old_note_name
+                ));
+            }
+            UndoAction::AddTag {
+                tag_name,
+                previous_contents,
+            } => {
+                let mut file_names = Vec::new();
+                let mut restore_error = None;
+                for (file_name, note_content) in previous_contents {
+                    let note_file_path = Path::new(&settings.notes_dir).join(&file_name);
+                    if let Err(error) = FileUtility::write_atomically(&note_file_path, note_content)
+                    {
+                        restore_error = Some(format!(
+                            "ERROR: couldn't restore note file '{}': {}",
+                            file_name, error
+                        ));
+                        break;
+                    }
+                    file_names.push(file_name);
+                }
+                tui_data.message.set(format!(
+                    "Removed #{} from {} notes",
+                    tag_name,
+                    file_names.len()
+                ));
+                BrnTui::reload_changed_notes(&file_names, tui_data, settings);
+                if let Some(error) = restore_error {
+                    tui_data.message.set(error);
+                }
+                return;
+            }
+        }
+        BrnTui::show_note_content_preview(tui_data, settings);
+    }
+
+    fn show_default_note_list(tui_data: &mut TuiData, settings: &Settings) {
+        tui_data.note_list_total = Some(Database::get_note_count());
+        BrnTui::load_note_list_window(tui_data, settings, 0);
+        tui_data.note_list_title = String::from("List");
+        tui_data.search_query.clear();
+        BrnTui::hide_tag_sidebar(tui_data, settings);
+    }
+
+    fn change_sort_order(tui_data: &mut TuiData, settings: &mut Settings) {
+        settings.sort_order = settings.sort_order.next();
+        BrnTui::sort_note_list(tui_data, settings);
+        tui_data.message.set(format!(
+            "Sorted by {}",
+            settings.sort_order.to_display_string()
+        ));
+    }
+
+    // The selected note stays selected after sorting
+    fn sort_note_list(tui_data: &mut TuiData, settings: &Settings) {
+        let selected_note_name = tui_data.note_list.selected_item().cloned();
+        // The pages of the default note list are already sorted by the database
+        if tui_data.note_list_total.is_some() {
+            BrnTui::load_note_list_window(tui_data, settings, 0);
+        } else {
+            NoteUtility::sort_note_names(
+                tui_data.note_list.get_items(),
+                settings.sort_order,
+                settings,
+            );
+        }
+        NoteUtility::sort_note_names(
+            &mut tui_data.unfiltered_note_list,
+            settings.sort_order,
+            settings,
+        );
+        if let Some(note_name) = selected_note_name {
+            BrnTui::select_note_with_name(tui_data, settings, &note_name);
+        }
+    }
+
+    // Returns false if the note isn't part of the note list
+    fn select_note_with_name(tui_data: &mut TuiData, settings: &Settings, note_name: &str) -> bool {
+        if let Some(index) = tui_data
+            .note_list
+            .get_items()
+            .iter()
+            .position(|m| m == note_name)
+        {
+            tui_data.note_list.select(Some(index));
+            return true;
+        }
+
+        // The note could be part of a window of the default note list that isn't loaded yet
+        if tui_data.note_list_total.is_some() {
+            if let Some(position) = Database::get_note_position(
+                note_name,
+                settings.sort_order,
+                &settings.pinned_note_ids,
+            ) {
+                BrnTui::load_note_list_window(tui_data, settings, position);
+                return true;
             }
-            Err(error) => tui_data.message = "ERROR: ".to_string() + &error,
+        }
+        return false;
+    }
+
+    // The details of the previous notes are dropped, so that they are loaded again once
+    // the new notes are shown
+    fn replace_note_list_items(tui_data: &mut TuiData, note_names: Vec<String>) {
+        tui_data.note_list.replace_items_with(note_names);
+        tui_data.note_list_details.clear();
+        tui_data.pinned_note_names = None;
+    }
+
+    // Loads the window of the default note list around the given position and selects it
+    fn load_note_list_window(tui_data: &mut TuiData, settings: &Settings, position: usize) {
+        let note_count = match tui_data.note_list_total {
+            Some(value) => value,
+            None => return,
+        };
+
+        let offset = position
+            .saturating_sub(NOTE_LIST_WINDOW_SIZE / 2)
+            .min(note_count.saturating_sub(NOTE_LIST_WINDOW_SIZE));
+        tui_data.note_list_offset = offset;
+        let note_list = Database::get_notes_page(
+            offset,
+            NOTE_LIST_WINDOW_SIZE,
+            settings.sort_order,
+            &settings.pinned_note_ids,
+        )
+        .into_iter()
+        .map(|m| m.note_name)
+        .collect();
+
+        BrnTui::replace_note_list_items(tui_data, note_list);
+        tui_data
+            .note_list
+            .select(Some(position.saturating_sub(tui_data.note_list_offset)));
+        tui_data.note_list.clamp_selection();
+    }
+
+    // Has to be called after moving the selection, so that the next window of the
+    // default note list is loaded before reaching the end of the current one
+    fn update_note_list_window(tui_data: &mut TuiData, settings: &Settings) {
+        let note_count = match tui_data.note_list_total {
+            Some(value) => value,
+            None => return,
+        };
+        let selected_index = match tui_data.note_list.selected() {
+            Some(value) => value,
+            None => return,
         };
+
+        let loaded_note_count = tui_data.note_list.get_items().len();
+        let is_near_start =
+            tui_data.note_list_offset > 0 && selected_index < NOTE_LIST_WINDOW_MARGIN;
+        let is_near_end = tui_data.note_list_offset + loaded_note_count < note_count
+            && selected_index + NOTE_LIST_WINDOW_MARGIN >= loaded_note_count;
+        if is_near_start || is_near_end {
+            let position = tui_data.note_list_offset + selected_index;
+            BrnTui::load_note_list_window(tui_data, settings, position);
+        }
     }
 
     fn open_note<B: Backend + Write>(
@@ -411,27 +4572,139 @@ impl BrnTui {
         tui_data: &mut TuiData,
         settings: &mut Settings,
     ) {
-        execute!(
+        BrnTui::open_note_at_line(note_id, None, terminal, tui_data, settings);
+    }
+
+    fn open_note_at_line<B: Backend + Write>(
+        note_id: &str,
+        line_number: Option<usize>,
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        if !BrnTui::leave_tui(terminal, tui_data) {
+            return;
+        }
+        match NoteUtility::open_at_line(&note_id, line_number, settings) {
+            Ok(warnings) if warnings.is_empty() => (),
+            Ok(warnings) => tui_data
+                .message
+                .set(format!("INFO: {}", warnings.join("; "))),
+            Err(message) => tui_data.message.set(format!("ERROR: {}", message)),
+        }
+        BrnTui::return_to_tui(terminal, tui_data);
+    }
+
+    // The marked notes are opened in the order of the note list
+    fn open_marked_notes<B: Backend + Write>(
+        terminal: &mut Terminal<B>,
+        tui_data: &mut TuiData,
+        settings: &mut Settings,
+    ) {
+        if tui_data.marked_notes.is_empty() {
+            tui_data
+                .message
+                .set("no notes are marked (notes are marked with Space)".to_string());
+            return;
+        }
+        let mut marked_note_names: Vec<String> = tui_data.marked_notes.iter().cloned().collect();
+        marked_note_names.sort_by_key(|m| {
+            tui_data
+                .note_list
+                .get_items()
+                .iter()
+                .position(|n| n == m)
+                .unwrap_or(usize::MAX)
+        });
+        let note_ids: Vec<String> = marked_note_names
+            .iter()
+            .filter_map(|m| Database::get_note_id_where(NoteProperty::NoteName, m))
+            .collect();
+
+        if !BrnTui::leave_tui(terminal, tui_data) {
+            return;
+        }
+        match NoteUtility::open_many(&note_ids, settings) {
+            Ok(messages) if messages.is_empty() => tui_data
+                .message
+                .set(format!("Opened {} marked notes", note_ids.len())),
+            Ok(messages) => tui_data
+                .message
+                .set(format!("INFO: {}", messages.join("; "))),
+            Err(message) => tui_data.message.set(format!("ERROR: {}", message)),
+        }
+        BrnTui::return_to_tui(terminal, tui_data);
+    }
+
+    // Returns true if the shown git status changed
+    fn update_git_status(tui_data: &mut TuiData, settings: &Settings) -> bool {
+        if !settings.git_integration_enabled {
+            return false;
+        }
+        tui_data.git_status_update_time = Some(Instant::now());
+
+        let notes_dir = PathBuf::from(&settings.notes_dir);
+        let changed_file_count = match GitRepository::get_changed_files(&notes_dir) {
+            Ok(changed_files) => changed_files.map(|m| m.len()),
+            Err(_) => None,
+        };
+        if changed_file_count == tui_data.git_changed_file_count {
+            return false;
+        }
+        tui_data.git_changed_file_count = changed_file_count;
+        return true;
+    }
+
+    fn commit_changes(tui_data: &mut TuiData, settings: &Settings) {
+        if !settings.git_integration_enabled {
+            tui_data.message.set(
+                "the git integration is disabled (see 'git-integration' in the config)".to_string(),
+            );
+            return;
+        }
+        match GitRepository::commit_all(Path::new(&settings.notes_dir)) {
+            Ok(0) => tui_data
+                .message
+                .set("there are no changes to commit".to_string()),
+            Ok(1) => tui_data
+                .message
+                .set("INFO: committed 1 changed file".to_string()),
+            Ok(file_count) => tui_data
+                .message
+                .set(format!("INFO: committed {} changed files", file_count)),
+            Err(error) => tui_data.message.set(format!("ERROR: {}", error)),
+        }
+        BrnTui::update_git_status(tui_data, settings);
+    }
+
+    // Returns false if the TUI couldn't be left
+    fn leave_tui<B: Backend + Write>(terminal: &mut Terminal<B>, tui_data: &mut TuiData) -> bool {
+        if let Err(error) = execute!(
             terminal.backend_mut(),
             LeaveAlternateScreen,
             DisableMouseCapture
-        )
-        .unwrap();
-
-        match NoteUtility::open(&note_id, settings) {
-            Ok(None) => (),
-            Ok(Some(message)) => tui_data.message = "INFO: ".to_string() + &message,
-            Err(message) => tui_data.message = "ERROR: ".to_string() + &message,
+        ) {
+            tui_data
+                .message
+                .set(format!("ERROR: couldn't leave the TUI: {}", error));
+            return false;
         }
+        return true;
+    }
 
+    fn return_to_tui<B: Backend + Write>(terminal: &mut Terminal<B>, tui_data: &mut TuiData) {
         // Force full redraw in the terminal
-        terminal.clear().unwrap();
-
-        execute!(
-            terminal.backend_mut(),
-            EnterAlternateScreen,
-            EnableMouseCapture
-        )
-        .unwrap();
+        let result = terminal.clear().and_then(|_| {
+            execute!(
+                terminal.backend_mut(),
+                EnterAlternateScreen,
+                EnableMouseCapture
+            )
+        });
+        if let Err(error) = result {
+            tui_data
+                .message
+                .set(format!("ERROR: couldn't redraw the terminal: {}", error));
+        }
     }
 }
@@ -0,0 +1,753 @@
+use crate::brn_tui::syntax_highlighter::SyntaxHighlighter;
+use crate::note_tasks::NoteTasks;
+use crate::note_utility::NoteUtility;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use unicode_width::UnicodeWidthStr;
+
+lazy_static! {
+    static ref HEADING_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        ^
+        (\#{1,6})           # $1 = heading level
+        \s+
+        (.*?)               # $2 = heading text
+        \s*
+        $
+    "
+    )
+    .unwrap();
+    static ref LIST_ITEM_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        ^
+        ([ \t]*)            # $1 = indentation
+        ([-*+]|\d+[.)])     # $2 = list marker
+        \s+
+        (.*)                # $3 = list item text
+        $
+    "
+    )
+    .unwrap();
+    static ref BLOCKQUOTE_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        ^
+        \s*
+        >\s?
+        (.*)                # $1 = quoted text
+        $
+    "
+    )
+    .unwrap();
+    static ref CODE_FENCE_VALIDATOR: Regex = Regex::new(r"^\s*(```|~~~)").unwrap();
+    static ref HORIZONTAL_RULE_VALIDATOR: Regex =
+        Regex::new(r"^\s*(\*\s*\*\s*\*[\s*]*|-\s*-\s*-[\s-]*|_\s*_\s*_[\s_]*)$").unwrap();
+    static ref YAML_HEADER_DELIMITER_VALIDATOR: Regex = Regex::new(r"^---[ \t]*$").unwrap();
+    // The row below the header of a table, e.g. '| --- | :-: |'. It has to contain a '|',
+    // otherwise it's a horizontal rule
+    static ref TABLE_DELIMITER_VALIDATOR: Regex =
+        Regex::new(r"^\s*\|?\s*:?-+:?\s*(\|\s*:?-+:?\s*)*\|?\s*$").unwrap();
+    static ref INLINE_FORMAT_VALIDATOR: Regex = Regex::new(
+        r#"(?x)
+        \[\[(?P<note_link>[^\[\]\n]+?)\]\]
+        | !\[(?P<image_text>[^\]]*)\]\((?P<image>[^)\s]+)(?:\s+"[^"]*")?\)
+        | \[(?P<link_text>[^\]\n]*)\]\((?P<link_target>[^)\s]+)(?:\s+"[^"]*")?\)
+        | \*\*(?P<bold>[^*]+)\*\*
+        | __(?P<bold_underscore>[^_]+)__
+        | \*(?P<italic>[^*\s][^*]*)\*
+        | _(?P<italic_underscore>[^_\s][^_]*)_
+        | `(?P<code>[^`]+)`
+    "#
+    )
+    .unwrap();
+}
+
+pub struct Heading {
+    pub level: usize,
+    pub text: String,
+    pub line_index: usize,
+}
+
+// A line of a markdown note. The preview and the html export are rendered from these lines,
+// so that both show the notes the same way
+pub enum MarkdownLine<'a> {
+    // The yaml header is only recognized at the top of the note. Its delimiters are included
+    YamlHeader(&'a str),
+    // The language is only set for the fence that starts a code block
+    CodeFence {
+        line: &'a str,
+        language: Option<String>,
+    },
+    Code(&'a str),
+    Heading {
+        level: usize,
+        text: &'a str,
+    },
+    HorizontalRule,
+    ListItem {
+        indentation: &'a str,
+        marker: &'a str,
+        text: &'a str,
+    },
+    Blockquote(&'a str),
+    // The header and the body rows of a table. Every row of a table has the same number of cells
+    TableRow(Vec<&'a str>),
+    // The row between the header and the body of a table
+    TableDelimiter(Vec<ColumnAlignment>),
+    Text(&'a str),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColumnAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+// The markdown text of links is kept to show links that aren't rendered as it is
+pub enum InlineElement<'a> {
+    Text(&'a str),
+    Image {
+        text: &'a str,
+        path: &'a str,
+    },
+    // Links to websites or to files, e.g. other notes
+    Link {
+        text: &'a str,
+        target: &'a str,
+        markdown: &'a str,
+    },
+    // Links of brn like '[[note name]]'
+    NoteLink {
+        link_text: &'a str,
+        markdown: &'a str,
+    },
+    Bold(&'a str),
+    Italic(&'a str),
+    Code(&'a str),
+}
+
+// The columns of a table in the preview are as wide as their widest cell
+struct TableLayout {
+    column_widths: Vec<usize>,
+    alignments: Vec<ColumnAlignment>,
+}
+
+pub struct MarkdownRenderer;
+impl MarkdownRenderer {
+    pub fn parse_lines(content: &str) -> Vec<MarkdownLine<'_>> {
+        let mut lines = Vec::new();
+        let mut is_before_first_line = true;
+        let mut is_in_yaml_header = false;
+        let mut is_in_code_block = false;
+
+        let content_lines: Vec<&str> = content.lines().collect();
+        let mut line_index = 0;
+        while line_index < content_lines.len() {
+            let line = content_lines[line_index];
+            line_index += 1;
+
+            if is_before_first_line && !line.trim().is_empty() {
+                is_before_first_line = false;
+                if YAML_HEADER_DELIMITER_VALIDATOR.is_match(line) {
+                    is_in_yaml_header = true;
+                    lines.push(MarkdownLine::YamlHeader(line));
+                    continue;
+                }
+            }
+
+            if is_in_yaml_header {
+                if YAML_HEADER_DELIMITER_VALIDATOR.is_match(line) {
+                    is_in_yaml_header = false;
+                }
+                lines.push(MarkdownLine::YamlHeader(line));
+                continue;
+            }
+
+            if CODE_FENCE_VALIDATOR.is_match(line) {
+                is_in_code_block = !is_in_code_block;
+                let language = if is_in_code_block {
+                    Some(SyntaxHighlighter::get_language_of_code_fence(line))
+                } else {
+                    None
+                };
+                lines.push(MarkdownLine::CodeFence { line, language });
+                continue;
+            }
+            if is_in_code_block {
+                lines.push(MarkdownLine::Code(line));
+                continue;
+            }
+
+            if let Some(mut table_lines) =
+                MarkdownRenderer::parse_table(&content_lines[line_index - 1..])
+            {
+                line_index += table_lines.len() - 1;
+                lines.append(&mut table_lines);
+                continue;
+            }
+
+            lines.push(MarkdownRenderer::parse_line(line));
+        }
+        return lines;
+    }
+
+    // A table starts with a header row that is followed by a delimiter row and ends at the
+    // first line that isn't a table row. Tables whose rows have different numbers of cells
+    // are shown as they are
+    fn parse_table<'a>(lines: &[&'a str]) -> Option<Vec<MarkdownLine<'a>>> {
+        let header_cells = MarkdownRenderer::split_table_row(lines[0])?;
+        let delimiter_line = lines.get(1)?;
+        if !TABLE_DELIMITER_VALIDATOR.is_match(delimiter_line) {
+            return None;
+        }
+        let alignments: Vec<ColumnAlignment> = MarkdownRenderer::split_table_row(delimiter_line)?
+            .iter()
+            .map(|m| match (m.starts_with(':'), m.ends_with(':')) {
+                (true, true) => ColumnAlignment::Center,
+                (false, true) => ColumnAlignment::Right,
+                _ => ColumnAlignment::Left,
+            })
+            .collect();
+        if alignments.len() != header_cells.len() {
+            return None;
+        }
+
+        let column_count = header_cells.len();
+        let mut table_lines = vec![
+            MarkdownLine::TableRow(header_cells),
+            MarkdownLine::TableDelimiter(alignments),
+        ];
+        for line in &lines[2..] {
+            let cells = match MarkdownRenderer::split_table_row(line) {
+                Some(value) => value,
+                None => break,
+            };
+            if cells.len() != column_count {
+                return None;
+            }
+            table_lines.push(MarkdownLine::TableRow(cells));
+        }
+        return Some(table_lines);
+    }
+
+    // The pipes at the start and the end of the row are optional. Escaped pipes ('\|') are
+    // part of the cell text
+    fn split_table_row(line: &str) -> Option<Vec<&str>> {
+        if !line.contains('|') {
+            return None;
+        }
+        let mut row = line.trim();
+        row = row.strip_prefix('|').unwrap_or(row);
+        if row.ends_with('|') && !row.ends_with("\\|") {
+            row = &row[..row.len() - 1];
+        }
+
+        let mut cells = Vec::new();
+        let mut cell_start = 0;
+        let mut is_escaped = false;
+        for (char_index, c) in row.char_indices() {
+            if c == '|' && !is_escaped {
+                cells.push(row[cell_start..char_index].trim());
+                cell_start = char_index + 1;
+            }
+            is_escaped = c == '\\' && !is_escaped;
+        }
+        cells.push(row[cell_start..].trim());
+        return Some(cells);
+    }
+
+    fn parse_line(line: &str) -> MarkdownLine<'_> {
+        if let Some(heading_match) = HEADING_VALIDATOR.captures(line) {
+            return MarkdownLine::Heading {
+                level: heading_match.get(1).unwrap().as_str().len(),
+                text: heading_match.get(2).unwrap().as_str(),
+            };
+        }
+        if HORIZONTAL_RULE_VALIDATOR.is_match(line) {
+            return MarkdownLine::HorizontalRule;
+        }
+        if let Some(list_item_match) = LIST_ITEM_VALIDATOR.captures(line) {
+            return MarkdownLine::ListItem {
+                indentation: list_item_match.get(1).unwrap().as_str(),
+                marker: list_item_match.get(2).unwrap().as_str(),
+                text: list_item_match.get(3).unwrap().as_str(),
+            };
+        }
+        if let Some(blockquote_match) = BLOCKQUOTE_VALIDATOR.captures(line) {
+            return MarkdownLine::Blockquote(blockquote_match.get(1).unwrap().as_str());
+        }
+        return MarkdownLine::Text(line);
+    }
+
+    pub fn parse_inline(text: &str) -> Vec<InlineElement<'_>> {
+        let mut elements = Vec::new();
+        let mut unformatted_text_start = 0;
+
+        for format_match in INLINE_FORMAT_VALIDATOR.captures_iter(text) {
+            let whole_match = format_match.get(0).unwrap();
+
+            let element;
+            if let Some(link_text) = format_match.name("note_link") {
+                element = InlineElement::NoteLink {
+                    link_text: link_text.as_str().trim(),
+                    markdown: whole_match.as_str(),
+                };
+            } else if let Some(image_path) = format_match.name("image") {
+                element = InlineElement::Image {
+                    text: format_match.name("image_text").unwrap().as_str(),
+                    path: image_path.as_str(),
+                };
+            } else if let Some(link_target) = format_match.name("link_target") {
+                element = InlineElement::Link {
+                    text: format_match.name("link_text").unwrap().as_str(),
+                    target: link_target.as_str(),
+                    markdown: whole_match.as_str(),
+                };
+            } else if let Some(bold_text) = format_match
+                .name("bold")
+                .or_else(|| format_match.name("bold_underscore"))
+            {
+                element = InlineElement::Bold(bold_text.as_str());
+            } else if let Some(italic_text) = format_match.name("italic") {
+                element = InlineElement::Italic(italic_text.as_str());
+            } else if let Some(italic_text) = format_match.name("italic_underscore") {
+                // Underscores inside of words (e.g. snake_case) don't mark italic text
+                let is_inside_word = is_alphanumeric_before(text, whole_match.start())
+                    || is_alphanumeric_after(text, whole_match.end());
+                if is_inside_word {
+                    continue;
+                }
+                element = InlineElement::Italic(italic_text.as_str());
+            } else if let Some(code_text) = format_match.name("code") {
+                element = InlineElement::Code(code_text.as_str());
+            } else {
+                continue;
+            }
+
+            if whole_match.start() > unformatted_text_start {
+                elements.push(InlineElement::Text(
+                    &text[unformatted_text_start..whole_match.start()],
+                ));
+            }
+            elements.push(element);
+            unformatted_text_start = whole_match.end();
+        }
+
+        if unformatted_text_start < text.len() {
+            elements.push(InlineElement::Text(&text[unformatted_text_start..]));
+        }
+        return elements;
+
+        fn is_alphanumeric_before(text: &str, index: usize) -> bool {
+            match text[..index].chars().last() {
+                Some(c) => c.is_alphanumeric(),
+                None => false,
+            }
+        }
+
+        fn is_alphanumeric_after(text: &str, index: usize) -> bool {
+            match text[index..].chars().next() {
+                Some(c) => c.is_alphanumeric(),
+                None => false,
+            }
+        }
+    }
+
+    // Relative paths of images and linked files are relative to the notes directory.
+    // The broken links are determined by the caller, so that they are only resolved once
+    pub fn render(
+        content: &str,
+        notes_dir: &Path,
+        broken_link_texts: &[String],
+        syntax_highlighting_enabled: bool,
+    ) -> Vec<Spans<'static>> {
+        let mut rendered_lines = Vec::new();
+        let mut code_block_language = String::new();
+
+        // The column widths depend on all rows of a table, so they are determined first
+        let lines = MarkdownRenderer::parse_lines(content);
+        let mut table_layouts =
+            MarkdownRenderer::get_table_layouts(&lines, notes_dir, broken_link_texts).into_iter();
+        let mut table_layout = None;
+        let mut table_row_index = 0;
+
+        for line in lines {
+            if !matches!(
+                line,
+                MarkdownLine::TableRow(_) | MarkdownLine::TableDelimiter(_)
+            ) {
+                table_layout = None;
+            } else if table_layout.is_none() {
+                table_layout = table_layouts.next();
+                table_row_index = 0;
+            }
+
+            let rendered_line = match (line, &table_layout) {
+                (MarkdownLine::TableRow(cells), Some(table_layout)) => {
+                    table_row_index += 1;
+                    MarkdownRenderer::render_table_row(
+                        &cells,
+                        table_layout,
+                        table_row_index == 1,
+                        notes_dir,
+                        broken_link_texts,
+                    )
+                }
+                (MarkdownLine::TableDelimiter(_), Some(table_layout)) => {
+                    table_row_index += 1;
+                    MarkdownRenderer::render_table_delimiter(table_layout)
+                }
+                (line, _) => MarkdownRenderer::render_line(
+                    line,
+                    &mut code_block_language,
+                    syntax_highlighting_enabled,
+                    notes_dir,
+                    broken_link_texts,
+                ),
+            };
+            rendered_lines.push(rendered_line);
+        }
+
+        return rendered_lines;
+    }
+
+    fn render_line(
+        line: MarkdownLine,
+        code_block_language: &mut String,
+        syntax_highlighting_enabled: bool,
+        notes_dir: &Path,
+        broken_link_texts: &[String],
+    ) -> Spans<'static> {
+        let dimmed_style = Style::default().fg(Color::DarkGray);
+        return match line {
+            MarkdownLine::YamlHeader(line) => {
+                Spans::from(Span::styled(line.to_string(), dimmed_style))
+            }
+            MarkdownLine::CodeFence { line, language } => {
+                if let Some(language) = language {
+                    *code_block_language = language;
+                }
+                Spans::from(Span::styled(line.to_string(), dimmed_style))
+            }
+            MarkdownLine::Code(line) => {
+                // Code of unknown languages is shown without highlighting
+                let highlighted_line = if syntax_highlighting_enabled {
+                    SyntaxHighlighter::highlight_line(line, code_block_language)
+                } else {
+                    None
+                };
+                highlighted_line.unwrap_or_else(|| {
+                    Spans::from(Span::styled(
+                        line.to_string(),
+                        Style::default().fg(Color::Green),
+                    ))
+                })
+            }
+            MarkdownLine::Heading { level, text } => {
+                let mut heading_style = Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD);
+                if level == 1 {
+                    heading_style = heading_style.add_modifier(Modifier::UNDERLINED);
+                }
+                Spans::from(Span::styled(text.to_string(), heading_style))
+            }
+            MarkdownLine::HorizontalRule => Spans::from(Span::styled("─".repeat(20), dimmed_style)),
+            MarkdownLine::ListItem {
+                indentation,
+                marker,
+                text,
+            } => {
+                let displayed_marker = match marker {
+                    "-" | "*" | "+" => "•",
+                    _ => marker,
+                };
+                let mut spans = vec![
+                    Span::raw(format!("  {}", indentation.replace('\t', "    "))),
+                    Span::styled(
+                        format!("{} ", displayed_marker),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                ];
+                // The checkbox of a task replaces its brackets. Done tasks are dimmed
+                let (text, text_style) = match NoteTasks::split_checkbox(text) {
+                    Some((false, task_text)) => {
+                        spans.push(Span::styled("☐ ", Style::default().fg(Color::Yellow)));
+                        (task_text, Style::default())
+                    }
+                    Some((true, task_text)) => {
+                        spans.push(Span::styled("☑ ", Style::default().fg(Color::Green)));
+                        (task_text, dimmed_style.add_modifier(Modifier::CROSSED_OUT))
+                    }
+                    None => (text, Style::default()),
+                };
+                spans.append(&mut MarkdownRenderer::render_inline(
+                    text,
+                    text_style,
+                    notes_dir,
+                    broken_link_texts,
+                ));
+                Spans::from(spans)
+            }
+            MarkdownLine::Blockquote(text) => {
+                let mut spans = vec![Span::styled("│ ".to_string(), dimmed_style)];
+                spans.append(&mut MarkdownRenderer::render_inline(
+                    text,
+                    Style::default().add_modifier(Modifier::ITALIC),
+                    notes_dir,
+                    broken_link_texts,
+                ));
+                Spans::from(spans)
+            }
+            // Rows of tables are always rendered with their layout
+            MarkdownLine::TableRow(_) | MarkdownLine::TableDelimiter(_) => Spans::default(),
+            MarkdownLine::Text(text) => Spans::from(MarkdownRenderer::render_inline(
+                text,
+                Style::default(),
+                notes_dir,
+                broken_link_texts,
+            )),
+        };
+    }
+
+    fn get_table_layouts(
+        lines: &[MarkdownLine],
+        notes_dir: &Path,
+        broken_link_texts: &[String],
+    ) -> Vec<TableLayout> {
+        let mut table_layouts = Vec::new();
+        let mut current_table_layout: Option<TableLayout> = None;
+        for line in lines {
+            match line {
+                MarkdownLine::TableRow(cells) => {
+                    let table_layout = current_table_layout.get_or_insert_with(|| TableLayout {
+                        column_widths: vec![0; cells.len()],
+                        alignments: vec![ColumnAlignment::Left; cells.len()],
+                    });
+                    for (column_index, cell) in cells.iter().enumerate() {
+                        let cell_width =
+                            MarkdownRenderer::get_width_of(&MarkdownRenderer::render_table_cell(
+                                cell,
+                                Style::default(),
+                                notes_dir,
+                                broken_link_texts,
+                            ));
+                        let column_width = &mut table_layout.column_widths[column_index];
+                        *column_width = (*column_width).max(cell_width);
+                    }
+                }
+                MarkdownLine::TableDelimiter(alignments) => {
+                    if let Some(table_layout) = &mut current_table_layout {
+                        table_layout.alignments = alignments.clone();
+                    }
+                }
+                _ => table_layouts.extend(current_table_layout.take()),
+            }
+        }
+        table_layouts.extend(current_table_layout);
+        return table_layouts;
+    }
+
+    // The cells of the header are bold
+    fn render_table_row(
+        cells: &[&str],
+        table_layout: &TableLayout,
+        is_header: bool,
+        notes_dir: &Path,
+        broken_link_texts: &[String],
+    ) -> Spans<'static> {
+        let border_style = Style::default().fg(Color::DarkGray);
+        let cell_style = match is_header {
+            true => Style::default().add_modifier(Modifier::BOLD),
+            false => Style::default(),
+        };
+
+        let mut spans = vec![Span::styled("│ ", border_style)];
+        for (column_index, cell) in cells.iter().enumerate() {
+            let mut cell_spans =
+                MarkdownRenderer::render_table_cell(cell, cell_style, notes_dir, broken_link_texts);
+            let padding = table_layout.column_widths[column_index]
+                .saturating_sub(MarkdownRenderer::get_width_of(&cell_spans));
+            let (left_padding, right_padding) = match table_layout.alignments[column_index] {
+                ColumnAlignment::Left => (0, padding),
+                ColumnAlignment::Center => (padding / 2, padding - padding / 2),
+                ColumnAlignment::Right => (padding, 0),
+            };
+
+            spans.push(Span::raw(" ".repeat(left_padding)));
+            spans.append(&mut cell_spans);
+            spans.push(Span::raw(" ".repeat(right_padding)));
+            if column_index + 1 < cells.len() {
+                spans.push(Span::styled(" │ ", border_style));
+            } else {
+                spans.push(Span::styled(" │", border_style));
+            }
+        }
+        return Spans::from(spans);
+    }
+
+    fn render_table_delimiter(table_layout: &TableLayout) -> Spans<'static> {
+        let columns: Vec<String> = table_layout
+            .column_widths
+            .iter()
+            .map(|m| "─".repeat(*m))
+            .collect();
+        return Spans::from(Span::styled(
+            format!("├─{}─┤", columns.join("─┼─")),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    fn render_table_cell(
+        cell: &str,
+        base_style: Style,
+        notes_dir: &Path,
+        broken_link_texts: &[String],
+    ) -> Vec<Span<'static>> {
+        return MarkdownRenderer::render_inline(
+            &cell.replace("\\|", "|"),
+            base_style,
+            notes_dir,
+            broken_link_texts,
+        );
+    }
+
+    fn get_width_of(spans: &[Span]) -> usize {
+        return spans.iter().map(|m| m.content.width()).sum();
+    }
+
+    fn render_inline(
+        text: &str,
+        base_style: Style,
+        notes_dir: &Path,
+        broken_link_texts: &[String],
+    ) -> Vec<Span<'static>> {
+        return MarkdownRenderer::parse_inline(text)
+            .into_iter()
+            .map(|element| match element {
+                InlineElement::Text(text) => Span::styled(text.to_string(), base_style),
+                InlineElement::Image { path, .. } => {
+                    if MarkdownRenderer::is_missing_file(path, notes_dir) {
+                        Span::styled(
+                            format!("[missing image: {}]", path),
+                            base_style.fg(Color::Red),
+                        )
+                    } else {
+                        Span::styled(format!("[image: {}]", path), base_style.fg(Color::Magenta))
+                    }
+                }
+                InlineElement::Link {
+                    text,
+                    target,
+                    markdown,
+                } => {
+                    if NoteUtility::is_relative_file_link(target) {
+                        MarkdownRenderer::get_file_link_span(text, target, base_style, notes_dir)
+                    } else {
+                        Span::styled(markdown.to_string(), base_style)
+                    }
+                }
+                // Broken links are shown in red, so that they are noticed without 'brn check'
+                InlineElement::NoteLink {
+                    link_text,
+                    markdown,
+                } => {
+                    let link_color = match broken_link_texts.iter().any(|m| m == link_text) {
+                        true => Color::Red,
+                        false => Color::Blue,
+                    };
+                    Span::styled(
+                        markdown.to_string(),
+                        base_style.fg(link_color).add_modifier(Modifier::UNDERLINED),
+                    )
+                }
+                InlineElement::Bold(text) => {
+                    Span::styled(text.to_string(), base_style.add_modifier(Modifier::BOLD))
+                }
+                InlineElement::Italic(text) => {
+                    Span::styled(text.to_string(), base_style.add_modifier(Modifier::ITALIC))
+                }
+                InlineElement::Code(text) => {
+                    Span::styled(text.to_string(), base_style.fg(Color::Green))
+                }
+            })
+            .collect();
+    }
+
+    // Links to notes are shown with the name of the note, links to other files with their path
+    fn get_file_link_span(
+        link_text: &str,
+        link_target: &str,
+        base_style: Style,
+        notes_dir: &Path,
+    ) -> Span<'static> {
+        if let Some(note) = NoteUtility::get_note_of_file_link(link_target, notes_dir) {
+            return Span::styled(
+                note.note_name,
+                base_style
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::UNDERLINED),
+            );
+        }
+        if !NoteUtility::get_path_of_file_link(link_target, notes_dir).exists() {
+            return Span::styled(
+                format!("{} [missing file: {}]", link_text, link_target),
+                base_style.fg(Color::Red),
+            );
+        }
+        return Span::styled(
+            format!("{} [file: {}]", link_text, link_target),
+            base_style.fg(Color::Magenta),
+        );
+    }
+
+    // Images that aren't shown in the preview are still counted as attachments
+    pub fn get_image_count(content: &str) -> usize {
+        let mut image_count = 0;
+        for line in MarkdownRenderer::parse_lines(content) {
+            let texts = match line {
+                MarkdownLine::Heading { text, .. }
+                | MarkdownLine::ListItem { text, .. }
+                | MarkdownLine::Blockquote(text)
+                | MarkdownLine::Text(text) => vec![text],
+                MarkdownLine::TableRow(cells) => cells,
+                _ => continue,
+            };
+            for text in texts {
+                image_count += MarkdownRenderer::parse_inline(text)
+                    .iter()
+                    .filter(|m| matches!(m, InlineElement::Image { .. }))
+                    .count();
+            }
+        }
+        return image_count;
+    }
+
+    // The line indices are the ones of the rendered lines, so they can be used for scrolling
+    pub fn get_headings(content: &str) -> Vec<Heading> {
+        return MarkdownRenderer::parse_lines(content)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(line_index, line)| match line {
+                MarkdownLine::Heading { level, text } => Some(Heading {
+                    level,
+                    text: text.to_string(),
+                    line_index,
+                }),
+                _ => None,
+            })
+            .collect();
+    }
+
+    // Remote images can't be checked
+    fn is_missing_file(file_path: &str, notes_dir: &Path) -> bool {
+        if file_path.contains("://") {
+            return false;
+        }
+        return !notes_dir.join(file_path).exists();
+    }
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl MessageLevel {
+    // The level is part of the message text, e.g. 'ERROR: the note id ... doesn't exist'
+    fn of_text(text: &str) -> MessageLevel {
+        if text.starts_with("ERROR: ") {
+            return MessageLevel::Error;
+        }
+        if text.starts_with("WARNING: ") {
+            return MessageLevel::Warning;
+        }
+        return MessageLevel::Info;
+    }
+}
+
+pub struct LoggedMessage {
+    pub text: String,
+    pub level: MessageLevel,
+    pub time: DateTime<Local>,
+}
+
+// The message shown at the bottom of the TUI together with the messages that were shown before,
+// so that messages that are replaced quickly can still be read. The oldest message is removed first
+pub struct MessageLog {
+    current: String,
+    // The most recent message is at the back
+    entries: VecDeque<LoggedMessage>,
+    capacity: usize,
+}
+
+impl Default for MessageLog {
+    fn default() -> MessageLog {
+        return MessageLog::new(0);
+    }
+}
+
+impl MessageLog {
+    // Only the current message is kept with a capacity of 0
+    pub fn new(capacity: usize) -> MessageLog {
+        return MessageLog {
+            current: String::new(),
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        };
+    }
+
+    // Setting the message that is already shown doesn't log it again
+    pub fn set(&mut self, text: String) {
+        if text.is_empty() || text == self.current {
+            self.current = text;
+            return;
+        }
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(LoggedMessage {
+                text: text.clone(),
+                level: MessageLevel::of_text(&text),
+                time: Local::now(),
+            });
+        }
+        self.current = text;
+    }
+
+    // Only hides the current message, the log is kept
+    pub fn clear(&mut self) {
+        self.current.clear();
+    }
+
+    pub fn get_current(&self) -> &str {
+        return &self.current;
+    }
+
+    pub fn get_current_level(&self) -> MessageLevel {
+        return MessageLevel::of_text(&self.current);
+    }
+
+    pub fn get_entries(&self) -> &VecDeque<LoggedMessage> {
+        return &self.entries;
+    }
+}
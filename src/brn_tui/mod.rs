@@ -1,5 +1,19 @@
+pub mod action;
 pub mod input_mode;
 pub mod input_string;
+pub mod keybindings;
 pub mod main;
+pub mod markdown_renderer;
+pub mod message_log;
+pub mod navigation_history;
+pub mod note_watcher;
+pub mod org_renderer;
+pub mod panel_layout;
+pub mod preview_cache;
+pub mod preview_loader;
 pub mod stateful_list;
+pub mod syntax_highlighter;
+pub mod terminal_guard;
+pub mod theme;
 pub mod tui_data;
+pub mod undo_action;
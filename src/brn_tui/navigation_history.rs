@@ -0,0 +1,38 @@
+// Only the last jumps are remembered
+const MAX_HISTORY_SIZE: usize = 100;
+
+// Works like the back and forward buttons of a browser
+#[derive(Default)]
+pub struct NavigationHistory {
+    back_stack: Vec<String>,
+    forward_stack: Vec<String>,
+}
+
+impl NavigationHistory {
+    // Has to be called with the note that is left before jumping to another note
+    pub fn push(&mut self, note_id: &str) {
+        if self.back_stack.last().map(|m| m.as_str()) != Some(note_id) {
+            self.back_stack.push(note_id.to_string());
+            if self.back_stack.len() > MAX_HISTORY_SIZE {
+                self.back_stack.remove(0);
+            }
+        }
+        self.forward_stack.clear();
+    }
+
+    pub fn go_back(&mut self, current_note_id: Option<&str>) -> Option<String> {
+        let note_id = self.back_stack.pop()?;
+        if let Some(current_note_id) = current_note_id {
+            self.forward_stack.push(current_note_id.to_string());
+        }
+        return Some(note_id);
+    }
+
+    pub fn go_forward(&mut self, current_note_id: Option<&str>) -> Option<String> {
+        let note_id = self.forward_stack.pop()?;
+        if let Some(current_note_id) = current_note_id {
+            self.back_stack.push(current_note_id.to_string());
+        }
+        return Some(note_id);
+    }
+}
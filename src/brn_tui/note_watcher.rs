@@ -0,0 +1,97 @@
+use notify::{RawEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+// Editors often write a file in several steps, so the notes directory has to stay
+// unchanged for this long before the changes are reported
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(300);
+
+// Looks for note files that were created, changed or deleted outside of the TUI.
+// The file system events are collected in a separate thread, so that the TUI isn't blocked
+pub struct NoteWatcher {
+    receiver: Receiver<Vec<String>>,
+    // The thread stops as soon as the watcher is dropped
+    watcher: Option<RecommendedWatcher>,
+}
+
+impl Default for NoteWatcher {
+    fn default() -> NoteWatcher {
+        let (_, receiver) = mpsc::channel();
+        return NoteWatcher {
+            receiver,
+            watcher: None,
+        };
+    }
+}
+
+impl NoteWatcher {
+    // Stops watching the previous notes directory
+    pub fn watch(&mut self, notes_dir: PathBuf) -> Result<(), String> {
+        self.watcher = None;
+
+        let (event_sender, event_receiver) = mpsc::channel();
+        let mut watcher = notify::raw_watcher(event_sender)
+            .map_err(|error| format!("watch: couldn't create the file watcher: {}", error))?;
+        watcher
+            .watch(&notes_dir, RecursiveMode::NonRecursive)
+            .map_err(|error| {
+                format!(
+                    "watch: couldn't watch the directory '{}': {}",
+                    notes_dir.display(),
+                    error
+                )
+            })?;
+
+        let (sender, receiver) = mpsc::channel();
+        self.receiver = receiver;
+        self.watcher = Some(watcher);
+
+        thread::spawn(move || {
+            while let Ok(event) = event_receiver.recv() {
+                let mut changed_file_names = HashSet::new();
+                NoteWatcher::add_file_name_of(&event, &mut changed_file_names);
+                while let Ok(event) = event_receiver.recv_timeout(DEBOUNCE_INTERVAL) {
+                    NoteWatcher::add_file_name_of(&event, &mut changed_file_names);
+                }
+
+                if changed_file_names.is_empty() {
+                    continue;
+                }
+                if sender
+                    .send(changed_file_names.into_iter().collect())
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        });
+        return Ok(());
+    }
+
+    // Returns the names of all note files that changed since the last call
+    pub fn receive(&mut self) -> Option<Vec<String>> {
+        let mut changed_file_names: Vec<String> = self.receiver.try_iter().flatten().collect();
+        if changed_file_names.is_empty() {
+            return None;
+        }
+        changed_file_names.sort();
+        changed_file_names.dedup();
+        return Some(changed_file_names);
+    }
+
+    fn add_file_name_of(event: &RawEvent, changed_file_names: &mut HashSet<String>) {
+        let path = match &event.path {
+            Some(value) => value,
+            None => return,
+        };
+        if path.extension().and_then(|m| m.to_str()) != Some("md") {
+            return;
+        }
+        if let Some(file_name) = Path::new(path).file_name().and_then(|m| m.to_str()) {
+            changed_file_names.insert(file_name.to_string());
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use crate::brn_tui::markdown_renderer::Heading;
+use crate::brn_tui::syntax_highlighter::SyntaxHighlighter;
+use crate::note_tasks::NoteTasks;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+
+lazy_static! {
+    static ref HEADING_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        ^
+        (\*+)               # $1 = heading level
+        \s+
+        (.*?)               # $2 = heading text
+        \s*
+        $
+    "
+    )
+    .unwrap();
+    static ref LIST_ITEM_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        ^
+        ([ \t]*)            # $1 = indentation
+        ([-+]|\d+[.)])      # $2 = list marker
+        \s+
+        (.*)                # $3 = list item text
+        $
+    "
+    )
+    .unwrap();
+    static ref BLOCK_BEGIN_VALIDATOR: Regex =
+        Regex::new(r"(?i)^\s*\#\+begin_(src|example)\b\s*(\S*)").unwrap();
+    static ref BLOCK_END_VALIDATOR: Regex = Regex::new(r"(?i)^\s*\#\+end_(src|example)\b").unwrap();
+    // Keywords like '#+TITLE: ...' and comments
+    static ref META_LINE_VALIDATOR: Regex = Regex::new(r"^\s*\#(\+|\s|$)").unwrap();
+    static ref YAML_HEADER_DELIMITER_VALIDATOR: Regex = Regex::new(r"^---[ \t]*$").unwrap();
+    static ref INLINE_FORMAT_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        \[\[(?P<link_target>[^\]\n]+)\](?:\[(?P<link_text>[^\]\n]+)\])?\]
+        | \*(?P<bold>[^*\s][^*]*)\*
+        | /(?P<italic>[^/\s][^/]*)/
+        | _(?P<underline>[^_\s][^_]*)_
+        | [=~](?P<code>[^=~\s][^=~]*)[=~]
+    "
+    )
+    .unwrap();
+}
+
+// Renders the most common elements of org-mode files: headings, lists, source blocks
+// and inline markup. Everything else is shown as it is
+pub struct OrgRenderer;
+impl OrgRenderer {
+    // The broken links are determined by the caller, so that they are only resolved once
+    pub fn render(
+        content: &str,
+        syntax_highlighting_enabled: bool,
+        broken_link_texts: &[String],
+    ) -> Vec<Spans<'static>> {
+        let mut rendered_lines = Vec::new();
+        let mut is_before_first_line = true;
+        let mut is_in_yaml_header = false;
+        let mut code_block_language: Option<String> = None;
+
+        for line in content.lines() {
+            // The yaml header of brn is only recognized at the top of the note
+            if is_before_first_line && !line.trim().is_empty() {
+                is_before_first_line = false;
+                is_in_yaml_header = YAML_HEADER_DELIMITER_VALIDATOR.is_match(line);
+                if is_in_yaml_header {
+                    rendered_lines.push(OrgRenderer::get_dimmed_line(line));
+                    continue;
+                }
+            }
+            if is_in_yaml_header {
+                is_in_yaml_header = !YAML_HEADER_DELIMITER_VALIDATOR.is_match(line);
+                rendered_lines.push(OrgRenderer::get_dimmed_line(line));
+                continue;
+            }
+
+            if let Some(language) = &code_block_language {
+                if BLOCK_END_VALIDATOR.is_match(line) {
+                    code_block_language = None;
+                    rendered_lines.push(OrgRenderer::get_dimmed_line(line));
+                    continue;
+                }
+                if syntax_highlighting_enabled {
+                    if let Some(highlighted_line) =
+                        SyntaxHighlighter::highlight_line(line, language)
+                    {
+                        rendered_lines.push(highlighted_line);
+                        continue;
+                    }
+                }
+                rendered_lines.push(Spans::from(Span::styled(
+                    line.to_string(),
+                    Style::default().fg(Color::Green),
+                )));
+                continue;
+            }
+            if let Some(block_match) = BLOCK_BEGIN_VALIDATOR.captures(line) {
+                code_block_language = Some(block_match[2].to_lowercase());
+                rendered_lines.push(OrgRenderer::get_dimmed_line(line));
+                continue;
+            }
+
+            rendered_lines.push(OrgRenderer::render_line(line, broken_link_texts));
+        }
+        return rendered_lines;
+    }
+
+    fn render_line(line: &str, broken_link_texts: &[String]) -> Spans<'static> {
+        if let Some(heading_match) = HEADING_VALIDATOR.captures(line) {
+            let mut heading_style = Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD);
+            if heading_match[1].len() == 1 {
+                heading_style = heading_style.add_modifier(Modifier::UNDERLINED);
+            }
+            return Spans::from(Span::styled(heading_match[2].to_string(), heading_style));
+        }
+
+        if META_LINE_VALIDATOR.is_match(line) {
+            return OrgRenderer::get_dimmed_line(line);
+        }
+
+        if let Some(list_item_match) = LIST_ITEM_VALIDATOR.captures(line) {
+            let indentation = list_item_match[1].replace('\t', "    ");
+            let displayed_marker = match &list_item_match[2] {
+                "-" | "+" => "•",
+                list_marker => list_marker,
+            };
+
+            let mut spans = vec![
+                Span::raw(format!("  {}", indentation)),
+                Span::styled(
+                    format!("{} ", displayed_marker),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ];
+            // The checkbox of a task replaces its brackets. Done tasks are dimmed
+            match NoteTasks::split_checkbox(&list_item_match[3]) {
+                Some((false, task_text)) => {
+                    spans.push(Span::styled("☐ ", Style::default().fg(Color::Yellow)));
+                    spans.append(&mut OrgRenderer::render_inline(
+                        task_text,
+                        broken_link_texts,
+                    ));
+                }
+                Some((true, task_text)) => {
+                    spans.push(Span::styled("☑ ", Style::default().fg(Color::Green)));
+                    let done_style = Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::CROSSED_OUT);
+                    spans.extend(
+                        OrgRenderer::render_inline(task_text, broken_link_texts)
+                            .into_iter()
+                            .map(|m| Span::styled(m.content, m.style.patch(done_style))),
+                    );
+                }
+                None => spans.append(&mut OrgRenderer::render_inline(
+                    &list_item_match[3],
+                    broken_link_texts,
+                )),
+            }
+            return Spans::from(spans);
+        }
+
+        return Spans::from(OrgRenderer::render_inline(line, broken_link_texts));
+    }
+
+    fn render_inline(text: &str, broken_link_texts: &[String]) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+        let mut unformatted_text_start = 0;
+
+        for format_match in INLINE_FORMAT_VALIDATOR.captures_iter(text) {
+            let whole_match = format_match.get(0).unwrap();
+
+            // Markup characters inside of words (e.g. snake_case or a/b) don't format the text
+            let is_inside_word = text[..whole_match.start()]
+                .chars()
+                .last()
+                .is_some_and(|c| c.is_alphanumeric())
+                || text[whole_match.end()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphanumeric());
+
+            let formatted_span;
+            if let Some(link_target) = format_match.name("link_target") {
+                // Links without a description are links of brn, which are shown as they are.
+                // Broken links are shown in red
+                match format_match.name("link_text") {
+                    Some(link_text) => {
+                        formatted_span = Span::styled(
+                            link_text.as_str().to_string(),
+                            Style::default()
+                                .fg(Color::Blue)
+                                .add_modifier(Modifier::UNDERLINED),
+                        );
+                    }
+                    None => {
+                        let link_text = link_target.as_str().trim();
+                        let link_color = match broken_link_texts.iter().any(|m| m == link_text) {
+                            true => Color::Red,
+                            false => Color::Blue,
+                        };
+                        formatted_span = Span::styled(
+                            format!("[[{}]]", link_target.as_str()),
+                            Style::default()
+                                .fg(link_color)
+                                .add_modifier(Modifier::UNDERLINED),
+                        );
+                    }
+                }
+            } else if is_inside_word {
+                continue;
+            } else if let Some(bold_text) = format_match.name("bold") {
+                formatted_span = Span::styled(
+                    bold_text.as_str().to_string(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                );
+            } else if let Some(italic_text) = format_match.name("italic") {
+                formatted_span = Span::styled(
+                    italic_text.as_str().to_string(),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                );
+            } else if let Some(underlined_text) = format_match.name("underline") {
+                formatted_span = Span::styled(
+                    underlined_text.as_str().to_string(),
+                    Style::default().add_modifier(Modifier::UNDERLINED),
+                );
+            } else if let Some(code_text) = format_match.name("code") {
+                formatted_span = Span::styled(
+                    code_text.as_str().to_string(),
+                    Style::default().fg(Color::Green),
+                );
+            } else {
+                continue;
+            }
+
+            if whole_match.start() > unformatted_text_start {
+                spans.push(Span::raw(
+                    text[unformatted_text_start..whole_match.start()].to_string(),
+                ));
+            }
+            spans.push(formatted_span);
+            unformatted_text_start = whole_match.end();
+        }
+
+        if unformatted_text_start < text.len() {
+            spans.push(Span::raw(text[unformatted_text_start..].to_string()));
+        }
+        return spans;
+    }
+
+    fn get_dimmed_line(line: &str) -> Spans<'static> {
+        return Spans::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    // The line indices are the ones of the rendered lines, so they can be used for scrolling
+    pub fn get_headings(content: &str) -> Vec<Heading> {
+        let mut headings = Vec::new();
+        let mut is_in_code_block = false;
+        for (line_index, line) in content.lines().enumerate() {
+            if is_in_code_block {
+                is_in_code_block = !BLOCK_END_VALIDATOR.is_match(line);
+                continue;
+            }
+            if BLOCK_BEGIN_VALIDATOR.is_match(line) {
+                is_in_code_block = true;
+                continue;
+            }
+
+            if let Some(heading_match) = HEADING_VALIDATOR.captures(line) {
+                headings.push(Heading {
+                    level: heading_match[1].len(),
+                    text: heading_match[2].to_string(),
+                    line_index,
+                });
+            }
+        }
+        return headings;
+    }
+}
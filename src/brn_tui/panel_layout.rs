@@ -0,0 +1,140 @@
+use tui::layout::{Constraint, Direction, Layout, Rect};
+
+// The panels of the TUI that can be shown or hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    List,
+    Preview,
+    Backlinks,
+    Tags,
+}
+
+impl Panel {
+    pub fn from_config_string(value: &str) -> Option<Panel> {
+        match value {
+            "list" => Some(Panel::List),
+            "preview" => Some(Panel::Preview),
+            "backlinks" => Some(Panel::Backlinks),
+            "tags" => Some(Panel::Tags),
+            _ => None,
+        }
+    }
+
+    pub fn to_name(self) -> &'static str {
+        match self {
+            Panel::List => "list",
+            Panel::Preview => "preview",
+            Panel::Backlinks => "backlinks",
+            Panel::Tags => "tags",
+        }
+    }
+}
+
+// Whether the note list and the preview are next to each other (horizontal)
+// or above each other (vertical)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+impl SplitDirection {
+    pub fn from_config_string(value: &str) -> Option<SplitDirection> {
+        match value {
+            "horizontal" => Some(SplitDirection::Horizontal),
+            "vertical" => Some(SplitDirection::Vertical),
+            _ => None,
+        }
+    }
+
+    pub fn toggled(&self) -> SplitDirection {
+        match self {
+            SplitDirection::Horizontal => SplitDirection::Vertical,
+            SplitDirection::Vertical => SplitDirection::Horizontal,
+        }
+    }
+}
+
+// The areas of the visible panels. Hidden panels don't get an area
+#[derive(Default)]
+pub struct PanelAreas {
+    pub list: Option<Rect>,
+    pub preview: Option<Rect>,
+    pub backlinks: Option<Rect>,
+    pub tags: Option<Rect>,
+}
+
+pub struct PanelLayout;
+impl PanelLayout {
+    // The list, the backlinks and the tags are grouped together and take list_width percent
+    // of the width (horizontal) or the height (vertical). The preview gets the rest
+    pub fn split(
+        area: Rect,
+        direction: SplitDirection,
+        visible_panels: &[Panel],
+        list_width: u16,
+    ) -> PanelAreas {
+        let mut panel_areas = PanelAreas::default();
+        let is_list_visible = visible_panels.contains(&Panel::List);
+        let is_backlinks_visible = visible_panels.contains(&Panel::Backlinks);
+        let is_tags_visible = visible_panels.contains(&Panel::Tags);
+        let is_list_group_visible = is_list_visible || is_backlinks_visible || is_tags_visible;
+
+        // The tag sidebar takes additional space next to the list
+        let list_group_width = match (direction, is_tags_visible) {
+            (SplitDirection::Horizontal, true) => list_width + 10,
+            _ => list_width,
+        };
+        let list_group_area;
+        if !visible_panels.contains(&Panel::Preview) {
+            list_group_area = area;
+        } else if !is_list_group_visible {
+            panel_areas.preview = Some(area);
+            return panel_areas;
+        } else {
+            let chunks = Layout::default()
+                .direction(match direction {
+                    SplitDirection::Horizontal => Direction::Horizontal,
+                    SplitDirection::Vertical => Direction::Vertical,
+                })
+                .constraints([Constraint::Percentage(list_group_width), Constraint::Min(0)])
+                .split(area);
+            list_group_area = chunks[0];
+            panel_areas.preview = Some(chunks[1]);
+        }
+
+        // The tags are left of the list. The backlinks are below the list (horizontal)
+        // or right of it (vertical)
+        let mut list_column_area = list_group_area;
+        if is_tags_visible {
+            let tag_width = match (is_list_visible || is_backlinks_visible, direction) {
+                (false, _) => 100,
+                (true, SplitDirection::Horizontal) => 15 * 100 / list_group_width,
+                (true, SplitDirection::Vertical) => 15,
+            };
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(tag_width), Constraint::Min(0)])
+                .split(list_group_area);
+            panel_areas.tags = Some(chunks[0]);
+            list_column_area = chunks[1];
+        }
+        match (is_list_visible, is_backlinks_visible) {
+            (true, true) => {
+                let chunks = Layout::default()
+                    .direction(match direction {
+                        SplitDirection::Horizontal => Direction::Vertical,
+                        SplitDirection::Vertical => Direction::Horizontal,
+                    })
+                    .constraints([Constraint::Percentage(70), Constraint::Min(0)])
+                    .split(list_column_area);
+                panel_areas.list = Some(chunks[0]);
+                panel_areas.backlinks = Some(chunks[1]);
+            }
+            (true, false) => panel_areas.list = Some(list_column_area),
+            (false, true) => panel_areas.backlinks = Some(list_column_area),
+            (false, false) => (),
+        }
+        return panel_areas;
+    }
+}
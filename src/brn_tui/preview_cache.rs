@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+use std::time::SystemTime;
+use tui::text::Spans;
+
+pub struct CachedPreview {
+    pub note_content: String,
+    pub rendered_lines: Vec<Spans<'static>>,
+    // The links are shown differently when they are broken, so the preview is outdated as
+    // soon as other notes are created, renamed or removed
+    pub broken_link_texts: Vec<String>,
+    modification_time: SystemTime,
+}
+
+// Keeps the rendered previews of the last shown notes, so that rendering (especially
+// syntax highlighting) isn't repeated when going back to a note. The least recently
+// used preview is removed first and a preview is outdated as soon as its file changes
+pub struct PreviewCache {
+    // The most recently used preview is at the back
+    entries: VecDeque<(String, CachedPreview)>,
+    capacity: usize,
+}
+
+impl Default for PreviewCache {
+    fn default() -> PreviewCache {
+        return PreviewCache::new(0);
+    }
+}
+
+impl PreviewCache {
+    // Nothing is cached with a capacity of 0
+    pub fn new(capacity: usize) -> PreviewCache {
+        return PreviewCache {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        };
+    }
+
+    pub fn get(&mut self, note_id: &str, modification_time: SystemTime) -> Option<&CachedPreview> {
+        let index = self.entries.iter().position(|m| m.0 == note_id)?;
+        let entry = self.entries.remove(index)?;
+        if entry.1.modification_time != modification_time {
+            return None;
+        }
+        self.entries.push_back(entry);
+        return self.entries.back().map(|m| &m.1);
+    }
+
+    pub fn insert(
+        &mut self,
+        note_id: &str,
+        modification_time: SystemTime,
+        note_content: String,
+        rendered_lines: Vec<Spans<'static>>,
+        broken_link_texts: Vec<String>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.retain(|m| m.0 != note_id);
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((
+            note_id.to_string(),
+            CachedPreview {
+                note_content,
+                rendered_lines,
+                broken_link_texts,
+                modification_time,
+            },
+        ));
+    }
+}
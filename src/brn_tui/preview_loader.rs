@@ -0,0 +1,72 @@
+use crate::file_utility::FileUtility;
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct LoadedPreview {
+    pub note_id: String,
+    pub content: Result<String, String>,
+    request_id: u64,
+}
+
+// Reads the note files in a separate thread, so that slow file systems don't block the TUI
+pub struct PreviewLoader {
+    sender: Sender<LoadedPreview>,
+    receiver: Receiver<LoadedPreview>,
+    latest_request_id: u64,
+}
+
+impl Default for PreviewLoader {
+    fn default() -> PreviewLoader {
+        let (sender, receiver) = mpsc::channel();
+        return PreviewLoader {
+            sender,
+            receiver,
+            latest_request_id: 0,
+        };
+    }
+}
+
+impl PreviewLoader {
+    // Older requests that haven't finished yet are discarded
+    pub fn load(&mut self, note_id: &str, note_file_path: PathBuf) {
+        self.latest_request_id += 1;
+
+        let request_id = self.latest_request_id;
+        let note_id = note_id.to_string();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let content =
+                FileUtility::get_content_from_file(&note_file_path).map_err(|m| m.to_string());
+
+            // The receiver only stops existing when the TUI is closed
+            let _ = sender.send(LoadedPreview {
+                note_id,
+                content,
+                request_id,
+            });
+        });
+    }
+
+    pub fn cancel(&mut self) {
+        self.latest_request_id += 1;
+    }
+
+    // Waits up to the given time for the result of the latest request
+    pub fn receive(&mut self, timeout: Duration) -> Option<LoadedPreview> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining_time = deadline.saturating_duration_since(Instant::now());
+            match self.receiver.recv_timeout(remaining_time) {
+                Ok(loaded_preview) if loaded_preview.request_id == self.latest_request_id => {
+                    return Some(loaded_preview);
+                }
+                // The selection changed while the note was loaded
+                Ok(_) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
@@ -1,8 +1,12 @@
+use std::ops::Range;
 use tui::widgets::ListState;
 
 pub struct StatefulList<T> {
     state: ListState,
     items: Vec<T>,
+    offset: usize,
+    // The number of rows of every shown item, starting at the offset
+    row_heights: Vec<usize>,
 }
 
 impl<T> Default for StatefulList<T> {
@@ -16,12 +20,16 @@ impl<T> StatefulList<T> {
         StatefulList {
             state: ListState::default(),
             items,
+            offset: 0,
+            row_heights: Vec::new(),
         }
     }
 
     pub fn replace_items_with(&mut self, items: Vec<T>) {
         self.items = items;
         self.state = ListState::default();
+        self.offset = 0;
+        self.row_heights.clear();
     }
 
     pub fn select(&mut self, index: Option<usize>) {
@@ -44,6 +52,19 @@ impl<T> StatefulList<T> {
         }
     }
 
+    pub fn clamp_selection(&mut self) {
+        if self.items.is_empty() {
+            self.state.select(None);
+            return;
+        }
+
+        match self.selected() {
+            Some(i) if i >= self.items.len() => self.state.select(Some(self.items.len() - 1)),
+            Some(_) => (),
+            None => self.state.select(Some(0)),
+        }
+    }
+
     pub fn next(&mut self) {
         if self.items.len() == 0 {
             return;
@@ -80,6 +101,95 @@ impl<T> StatefulList<T> {
         self.state.select(Some(prev_index));
     }
 
+    pub fn next_by(&mut self, amount: usize) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let next_index = match self.selected() {
+            Some(i) => i.saturating_add(amount).min(self.items.len() - 1),
+            None => 0,
+        };
+        self.state.select(Some(next_index));
+    }
+
+    pub fn previous_by(&mut self, amount: usize) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let prev_index = match self.selected() {
+            Some(i) => i.saturating_sub(amount),
+            None => 0,
+        };
+        self.state.select(Some(prev_index));
+    }
+
+    // Items can span several rows (e.g. wrapped names), so the list is scrolled here instead
+    // of by tui and only the shown items are rendered (see `get_state_of_shown_items`).
+    // This way it's known which item is shown in which row. Like in tui, the selected item
+    // stays at the bottom when scrolling down. The height is only requested for the items
+    // within one visible height around the selected item. Returns the shown items
+    pub fn update_offset<F: FnMut(usize) -> usize>(
+        &mut self,
+        visible_height: usize,
+        mut get_height_of: F,
+    ) -> Range<usize> {
+        self.row_heights.clear();
+        if self.items.is_empty() || visible_height == 0 {
+            self.offset = 0;
+            return 0..0;
+        }
+
+        let selected_index = self.selected().unwrap_or(0).min(self.items.len() - 1);
+        self.offset = self.offset.min(selected_index);
+        let mut start = selected_index;
+        let mut height = get_height_of(selected_index);
+        while start > self.offset {
+            let item_height = get_height_of(start - 1);
+            if height + item_height > visible_height {
+                break;
+            }
+            height += item_height;
+            start -= 1;
+        }
+        self.offset = start;
+
+        // Items that don't fit completely aren't shown
+        let mut height = 0;
+        for index in self.offset..self.items.len() {
+            let item_height = get_height_of(index);
+            if height + item_height > visible_height && index > selected_index {
+                break;
+            }
+            height += item_height;
+            self.row_heights.push(item_height);
+        }
+        return self.offset..self.offset + self.row_heights.len();
+    }
+
+    // The selection within the shown items
+    pub fn get_state_of_shown_items(&mut self) -> ListState {
+        let mut state = ListState::default();
+        state.select(
+            self.selected()
+                .and_then(|m| m.checked_sub(self.offset))
+                .filter(|m| *m < self.row_heights.len()),
+        );
+        return state;
+    }
+
+    pub fn get_index_at_row(&self, row: usize) -> Option<usize> {
+        let mut row_end = 0;
+        for (index, row_height) in self.row_heights.iter().enumerate() {
+            row_end += row_height;
+            if row < row_end {
+                return Some(self.offset + index);
+            }
+        }
+        return None;
+    }
+
     pub fn get_items(&mut self) -> &mut Vec<T> {
         &mut self.items
     }
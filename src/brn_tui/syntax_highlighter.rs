@@ -0,0 +1,320 @@
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+
+struct LanguageSyntax {
+    keywords: &'static [&'static str],
+    line_comment_starts: &'static [&'static str],
+    string_delimiters: &'static [char],
+    ignore_case: bool,
+}
+
+// Lifetimes look like the start of a character literal, so only double quotes start strings
+const RUST_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+    line_comment_starts: &["//"],
+    string_delimiters: &['"'],
+    ignore_case: false,
+};
+
+const PYTHON_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+        "try", "while", "with", "yield",
+    ],
+    line_comment_starts: &["#"],
+    string_delimiters: &['"', '\''],
+    ignore_case: false,
+};
+
+const JAVASCRIPT_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &[
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "of",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    line_comment_starts: &["//"],
+    string_delimiters: &['"', '\'', '`'],
+    ignore_case: false,
+};
+
+const SHELL_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "exit", "export", "fi", "for", "function",
+        "if", "in", "local", "return", "then", "until", "while",
+    ],
+    line_comment_starts: &["#"],
+    string_delimiters: &['"', '\''],
+    ignore_case: false,
+};
+
+const GO_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "false",
+        "fallthrough",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "nil",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "true",
+        "type",
+        "var",
+    ],
+    line_comment_starts: &["//"],
+    string_delimiters: &['"', '\'', '`'],
+    ignore_case: false,
+};
+
+const C_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &[
+        "auto",
+        "bool",
+        "break",
+        "case",
+        "catch",
+        "char",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "extern",
+        "false",
+        "final",
+        "float",
+        "for",
+        "if",
+        "implements",
+        "import",
+        "include",
+        "int",
+        "long",
+        "namespace",
+        "new",
+        "nullptr",
+        "null",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "short",
+        "signed",
+        "sizeof",
+        "static",
+        "struct",
+        "switch",
+        "template",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typedef",
+        "union",
+        "unsigned",
+        "using",
+        "virtual",
+        "void",
+        "volatile",
+        "while",
+    ],
+    line_comment_starts: &["//"],
+    string_delimiters: &['"', '\''],
+    ignore_case: false,
+};
+
+const DATA_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &["false", "null", "true", "yes", "no"],
+    line_comment_starts: &["#"],
+    string_delimiters: &['"', '\''],
+    ignore_case: false,
+};
+
+const SQL_SYNTAX: LanguageSyntax = LanguageSyntax {
+    keywords: &[
+        "and", "as", "by", "create", "delete", "desc", "distinct", "drop", "from", "group",
+        "having", "in", "index", "insert", "into", "is", "join", "left", "like", "limit", "not",
+        "null", "on", "or", "order", "primary", "key", "select", "set", "table", "union", "update",
+        "values", "where",
+    ],
+    line_comment_starts: &["--"],
+    string_delimiters: &['\''],
+    ignore_case: true,
+};
+
+// Highlights code blocks line by line. Only the most important parts of a language
+// (keywords, strings, numbers and comments) are highlighted, which is fast enough
+// to be done whenever the preview is drawn
+pub struct SyntaxHighlighter;
+impl SyntaxHighlighter {
+    // Returns None if the language isn't known, so that the line can be shown as plain code
+    pub fn highlight_line(line: &str, language: &str) -> Option<Spans<'static>> {
+        let syntax = SyntaxHighlighter::get_syntax_of(language)?;
+        let mut spans = Vec::new();
+        let mut plain_text = String::new();
+        let mut rest = line;
+
+        while let Some(c) = rest.chars().next() {
+            let token_length;
+            let token_style;
+            if syntax
+                .line_comment_starts
+                .iter()
+                .any(|m| rest.starts_with(m))
+            {
+                token_length = rest.len();
+                token_style = Style::default().fg(Color::DarkGray);
+            } else if syntax.string_delimiters.contains(&c) {
+                token_length = SyntaxHighlighter::get_string_length(rest, c);
+                token_style = Style::default().fg(Color::Yellow);
+            } else if c.is_alphanumeric() || c == '_' {
+                token_length = rest
+                    .find(|m: char| !(m.is_alphanumeric() || m == '_'))
+                    .unwrap_or(rest.len());
+                let word = &rest[..token_length];
+                if c.is_ascii_digit() {
+                    token_style = Style::default().fg(Color::LightCyan);
+                } else if SyntaxHighlighter::is_keyword(word, &syntax) {
+                    token_style = Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD);
+                } else {
+                    plain_text.push_str(word);
+                    rest = &rest[token_length..];
+                    continue;
+                }
+            } else {
+                plain_text.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+
+            if !plain_text.is_empty() {
+                spans.push(Span::raw(std::mem::take(&mut plain_text)));
+            }
+            spans.push(Span::styled(rest[..token_length].to_string(), token_style));
+            rest = &rest[token_length..];
+        }
+        if !plain_text.is_empty() {
+            spans.push(Span::raw(plain_text));
+        }
+
+        return Some(Spans::from(spans));
+    }
+
+    // The language is the first word after the opening code fence
+    pub fn get_language_of_code_fence(code_fence_line: &str) -> String {
+        return code_fence_line
+            .trim()
+            .trim_start_matches(['`', '~'])
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+    }
+
+    fn get_syntax_of(language: &str) -> Option<LanguageSyntax> {
+        match language {
+            "rust" | "rs" => Some(RUST_SYNTAX),
+            "python" | "py" => Some(PYTHON_SYNTAX),
+            "javascript" | "js" | "jsx" | "typescript" | "ts" | "tsx" => Some(JAVASCRIPT_SYNTAX),
+            "sh" | "bash" | "shell" | "zsh" => Some(SHELL_SYNTAX),
+            "go" => Some(GO_SYNTAX),
+            "c" | "h" | "cpp" | "c++" | "java" | "cs" | "csharp" => Some(C_SYNTAX),
+            "json" | "yaml" | "yml" | "toml" => Some(DATA_SYNTAX),
+            "sql" => Some(SQL_SYNTAX),
+            _ => None,
+        }
+    }
+
+    fn is_keyword(word: &str, syntax: &LanguageSyntax) -> bool {
+        if syntax.ignore_case {
+            return syntax.keywords.contains(&word.to_lowercase().as_str());
+        }
+        return syntax.keywords.contains(&word);
+    }
+
+    // Strings end at the next delimiter that isn't escaped or at the end of the line
+    fn get_string_length(text: &str, delimiter: char) -> usize {
+        let mut is_escaped = false;
+        for (index, c) in text.char_indices().skip(1) {
+            if is_escaped {
+                is_escaped = false;
+            } else if c == '\\' {
+                is_escaped = true;
+            } else if c == delimiter {
+                return index + c.len_utf8();
+            }
+        }
+        return text.len();
+    }
+}
@@ -0,0 +1,48 @@
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io;
+use std::panic;
+use std::thread;
+
+// Puts the terminal into the state the TUI needs and restores it when dropped,
+// so that an error or a panic doesn't leave the terminal in raw mode
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn new() -> io::Result<TerminalGuard> {
+        enable_raw_mode()?;
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+
+        // The panic message would be printed to the alternate screen and lost otherwise
+        let default_panic_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |panic_info| {
+            TerminalGuard::restore();
+            default_panic_hook(panic_info);
+        }));
+
+        return Ok(guard);
+    }
+
+    // Errors are ignored because the terminal should be restored as far as possible
+    fn restore() {
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), crossterm::cursor::Show);
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        TerminalGuard::restore();
+
+        // The default panic hook is used again outside of the TUI.
+        // Changing the hook while panicking isn't allowed
+        if !thread::panicking() {
+            let _ = panic::take_hook();
+        }
+    }
+}
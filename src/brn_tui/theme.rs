@@ -0,0 +1,168 @@
+use tui::style::Color;
+use yaml_rust::Yaml;
+
+pub struct Theme {
+    pub selected: Color,
+    pub normal: Color,
+    pub border: Color,
+    pub title: Color,
+    pub message: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub status: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        return Theme {
+            selected: Color::Yellow,
+            normal: Color::White,
+            border: Color::Reset,
+            title: Color::Reset,
+            message: Color::LightRed,
+            warning: Color::Yellow,
+            error: Color::LightRed,
+            status: Color::DarkGray,
+        };
+    }
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name {
+            "default" => Some(Theme::default()),
+            "light" => Some(Theme {
+                selected: Color::Blue,
+                normal: Color::Black,
+                border: Color::DarkGray,
+                title: Color::Black,
+                message: Color::DarkGray,
+                warning: Color::Magenta,
+                error: Color::Red,
+                status: Color::Gray,
+            }),
+            "monochrome" => Some(Theme {
+                selected: Color::Reset,
+                normal: Color::Reset,
+                border: Color::Reset,
+                title: Color::Reset,
+                message: Color::Reset,
+                warning: Color::Reset,
+                error: Color::Reset,
+                status: Color::Reset,
+            }),
+            _ => None,
+        }
+    }
+
+    // The theme can either be the name of a built-in theme or a mapping of roles to colors.
+    // The mapping can be based on a built-in theme with the 'name' key.
+    // Returns a warning for every problem found in the config
+    pub fn from_config(config: &Yaml) -> (Theme, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        if let Some(theme_name) = config.as_str() {
+            return match Theme::from_name(theme_name) {
+                Some(theme) => (theme, warnings),
+                None => {
+                    warnings.push(format!("unknown theme '{}'", theme_name));
+                    (Theme::default(), warnings)
+                }
+            };
+        }
+
+        let config_hash = match config.as_hash() {
+            Some(value) => value,
+            None => {
+                warnings.push(
+                    "'theme' has to be a theme name or a mapping of roles to colors".to_string(),
+                );
+                return (Theme::default(), warnings);
+            }
+        };
+
+        let mut theme = match config["name"].as_str() {
+            Some(theme_name) => match Theme::from_name(theme_name) {
+                Some(value) => value,
+                None => {
+                    warnings.push(format!("unknown theme '{}'", theme_name));
+                    Theme::default()
+                }
+            },
+            None => Theme::default(),
+        };
+
+        for (role, color) in config_hash {
+            let role = match role.as_str() {
+                Some("name") | None => continue,
+                Some(value) => value,
+            };
+            let color = match Theme::parse_color(color) {
+                Some(value) => value,
+                None => {
+                    warnings.push(format!("invalid color for the theme role '{}'", role));
+                    continue;
+                }
+            };
+
+            match role {
+                "selected" => theme.selected = color,
+                "normal" => theme.normal = color,
+                "border" => theme.border = color,
+                "title" => theme.title = color,
+                "message" => theme.message = color,
+                "warning" => theme.warning = color,
+                "error" => theme.error = color,
+                "status" => theme.status = color,
+                _ => warnings.push(format!("unknown theme role '{}'", role)),
+            }
+        }
+
+        return (theme, warnings);
+    }
+
+    // Parses color names like 'light-red', ANSI indices like 208 and hex colors like '#ffaf00'
+    fn parse_color(color: &Yaml) -> Option<Color> {
+        if let Some(ansi_index) = color.as_i64() {
+            if (0..=255).contains(&ansi_index) {
+                return Some(Color::Indexed(ansi_index as u8));
+            }
+            return None;
+        }
+
+        let color_name = color.as_str()?.trim().to_lowercase();
+        if let Some(hex_color) = color_name.strip_prefix('#') {
+            if hex_color.len() != 6 {
+                return None;
+            }
+            let red = u8::from_str_radix(&hex_color[0..2], 16).ok()?;
+            let green = u8::from_str_radix(&hex_color[2..4], 16).ok()?;
+            let blue = u8::from_str_radix(&hex_color[4..6], 16).ok()?;
+            return Some(Color::Rgb(red, green, blue));
+        }
+        if let Ok(ansi_index) = color_name.parse::<u8>() {
+            return Some(Color::Indexed(ansi_index));
+        }
+
+        match color_name.replace('_', "-").as_str() {
+            "reset" | "default" => Some(Color::Reset),
+            "black" => Some(Color::Black),
+            "red" => Some(Color::Red),
+            "green" => Some(Color::Green),
+            "yellow" => Some(Color::Yellow),
+            "blue" => Some(Color::Blue),
+            "magenta" => Some(Color::Magenta),
+            "cyan" => Some(Color::Cyan),
+            "gray" | "grey" => Some(Color::Gray),
+            "dark-gray" | "dark-grey" => Some(Color::DarkGray),
+            "light-red" => Some(Color::LightRed),
+            "light-green" => Some(Color::LightGreen),
+            "light-yellow" => Some(Color::LightYellow),
+            "light-blue" => Some(Color::LightBlue),
+            "light-magenta" => Some(Color::LightMagenta),
+            "light-cyan" => Some(Color::LightCyan),
+            "white" => Some(Color::White),
+            _ => None,
+        }
+    }
+}
@@ -1,30 +1,220 @@
+use crate::brn_tui::action::Action;
 use crate::brn_tui::input_mode::InputMode;
 use crate::brn_tui::input_string::InputString;
+use crate::brn_tui::markdown_renderer::Heading;
+use crate::brn_tui::message_log::MessageLog;
+use crate::brn_tui::navigation_history::NavigationHistory;
+use crate::brn_tui::note_watcher::NoteWatcher;
+use crate::brn_tui::panel_layout::{Panel, SplitDirection};
+use crate::brn_tui::preview_cache::PreviewCache;
+use crate::brn_tui::preview_loader::PreviewLoader;
 use crate::brn_tui::stateful_list::StatefulList;
-use crate::note_utility::NoteUtility;
+use crate::brn_tui::undo_action::UndoAction;
+use crate::note::Note;
+use crate::note_type::NoteType;
+use crate::random_generator::RandomGenerator;
+use crate::render_mode::RenderMode;
+use crate::settings::DEFAULT_LIST_WIDTH;
+use crate::tag_order::TagOrder;
+
+use chrono::{DateTime, Local};
+use crossterm::event::KeyEvent;
+use std::collections::{HashMap, HashSet};
+use std::time::{Instant, SystemTime};
+use tui::layout::Rect;
+use tui::text::Spans;
 
 pub struct TuiData {
     pub note_list: StatefulList<String>,
+    pub note_list_offset: usize,
+    pub note_list_total: Option<usize>,
+    // The note and the tags of every shown note name, which are loaded once per note list
+    // instead of on every draw. None if the note doesn't exist in the database
+    pub note_list_details: HashMap<String, Option<(Note, Vec<String>)>>,
+    // None until the names of the pinned notes are resolved for the current note list
+    pub pinned_note_names: Option<HashSet<String>>,
+    pub marked_notes: HashSet<String>,
+    pub note_list_area: Rect,
+    // The selected index and the time it was selected at
+    pub name_scroll_start: (Option<usize>, Instant),
+    pub name_scroll_offset: Option<usize>,
+    pub is_name_scrolling: bool,
+    pub list_width: u16,
+    pub split_direction: SplitDirection,
+    pub visible_panels: Vec<Panel>,
+    pub last_click: Option<(Instant, usize)>,
+    pub backlink_list: StatefulList<String>,
+    pub tag_list: StatefulList<(String, usize)>,
+    pub tag_suggestion_list: StatefulList<(String, usize)>,
+    pub new_tag_to_confirm: Option<String>,
+    pub tag_order: TagOrder,
+    pub tag_counts_rolled_up: bool,
     pub note_content_preview: String,
-    pub message: String,
-    pub search_text: InputString,
+    pub previewed_note_name: Option<String>,
+    pub preview_lines: Vec<Spans<'static>>,
+    pub preview_render_mode: RenderMode,
+    pub preview_loader: PreviewLoader,
+    pub preview_cache: PreviewCache,
+    pub preview_modification_time: Option<SystemTime>,
+    pub is_preview_loading: bool,
+    pub note_watcher: NoteWatcher,
+    // None if the git integration is disabled or the notes aren't in a git repository
+    pub git_changed_file_count: Option<usize>,
+    pub git_status_update_time: Option<Instant>,
+    pub preview_word_count: usize,
+    pub preview_attachment_count: usize,
+    pub preview_task_count: usize,
+    pub preview_done_task_count: usize,
+    pub preview_creation_date: Option<DateTime<Local>>,
+    pub preview_first_line_number: usize,
+    pub preview_links: Vec<String>,
+    pub preview_broken_links: Vec<String>,
+    // Relative markdown links to files, which are followed after the links of brn
+    pub preview_file_links: Vec<String>,
+    pub preview_headings: Vec<Heading>,
+    pub navigation_history: NavigationHistory,
+    pub random_generator: RandomGenerator,
+    pub link_source_note_id: Option<String>,
+    pub preview_scroll: u16,
+    pub preview_height: u16,
+    pub preview_line_count: usize,
+    pub preview_line_starts: Vec<usize>,
+    pub preview_search_words: Vec<String>,
+    pub preview_match_lines: Vec<usize>,
+    pub preview_cursor: usize,
+    // The other end of the selected lines. Only the line of the cursor is selected without it
+    pub preview_selection_start: Option<usize>,
+    // Indices of the folded headings of `preview_headings`, which belong to the previewed note
+    pub preview_folded_headings: HashSet<usize>,
+    pub preview_folds_note_name: Option<String>,
+    pub message: MessageLog,
+    pub message_log_scroll: u16,
+    pub search_query: InputString,
+    pub content_search_enabled: bool,
+    pub search_in_database: bool,
+    pub unfiltered_note_list: Vec<String>,
     pub edit_text: InputString,
     pub input_mode: InputMode,
+    pub pending_keys: Vec<KeyEvent>,
     pub note_name_cache: String,
+    pub parent_note_id: Option<String>,
+    // The id of the note with the broken link and the link text, if the added note is created for it
+    pub broken_link_cache: Option<(String, String)>,
+    pub note_type_cache: NoteType,
+    pub template_list: StatefulList<String>,
+    pub notebook_list: StatefulList<String>,
+    // The notebook picker either switches to a notebook or moves the selected note into it
+    pub is_notebook_move: bool,
+    pub label_list: StatefulList<String>,
+    // The label picker either sets the label of the selected note or shows the notes with a label
+    pub is_label_filter: bool,
+    pub link_list: StatefulList<String>,
+    pub outline_list: StatefulList<String>,
+    pub finder_query: InputString,
+    pub finder_content_search_enabled: bool,
+    pub finder_note_names: Vec<String>,
+    pub finder_list: StatefulList<String>,
+    pub finder_preview: String,
+    pub finder_preview_broken_links: Vec<String>,
+    pub finder_preview_render_mode: RenderMode,
+    pub finder_preview_loader: PreviewLoader,
+    pub is_finder_preview_loading: bool,
+    pub command_query: InputString,
+    pub command_list: StatefulList<Action>,
     pub note_list_title: String,
+    pub last_undo_action: Option<UndoAction>,
 }
 
 impl Default for TuiData {
     fn default() -> TuiData {
         let mut tui_data = TuiData {
-            note_list: StatefulList::with_items(NoteUtility::get(100)),
+            note_list: StatefulList::default(),
+            note_list_offset: 0,
+            note_list_total: None,
+            note_list_details: HashMap::new(),
+            pinned_note_names: None,
+            marked_notes: HashSet::new(),
+            note_list_area: Rect::default(),
+            name_scroll_start: (None, Instant::now()),
+            name_scroll_offset: None,
+            is_name_scrolling: false,
+            list_width: DEFAULT_LIST_WIDTH,
+            split_direction: SplitDirection::Horizontal,
+            visible_panels: Vec::new(),
+            last_click: None,
+            backlink_list: StatefulList::default(),
+            tag_list: StatefulList::default(),
+            tag_suggestion_list: StatefulList::default(),
+            new_tag_to_confirm: None,
+            tag_order: TagOrder::Name,
+            tag_counts_rolled_up: false,
             note_content_preview: String::default(),
-            message: String::default(),
-            search_text: InputString::from("/"),
+            previewed_note_name: None,
+            preview_lines: Vec::new(),
+            preview_render_mode: RenderMode::Markdown,
+            preview_cache: PreviewCache::default(),
+            preview_modification_time: None,
+            preview_loader: PreviewLoader::default(),
+            is_preview_loading: false,
+            note_watcher: NoteWatcher::default(),
+            git_changed_file_count: None,
+            git_status_update_time: None,
+            preview_word_count: 0,
+            preview_attachment_count: 0,
+            preview_task_count: 0,
+            preview_done_task_count: 0,
+            preview_creation_date: None,
+            preview_first_line_number: 1,
+            preview_links: Vec::new(),
+            preview_broken_links: Vec::new(),
+            preview_file_links: Vec::new(),
+            preview_headings: Vec::new(),
+            navigation_history: NavigationHistory::default(),
+            random_generator: RandomGenerator::new(),
+            link_source_note_id: None,
+            preview_scroll: 0,
+            preview_height: 0,
+            preview_line_count: 0,
+            preview_line_starts: Vec::new(),
+            preview_search_words: Vec::new(),
+            preview_match_lines: Vec::new(),
+            preview_cursor: 0,
+            preview_selection_start: None,
+            preview_folded_headings: HashSet::new(),
+            preview_folds_note_name: None,
+            message: MessageLog::default(),
+            message_log_scroll: 0,
+            search_query: InputString::from("/"),
+            content_search_enabled: false,
+            search_in_database: false,
+            unfiltered_note_list: Vec::new(),
             edit_text: InputString::from("Name: "),
             input_mode: InputMode::Normal,
+            pending_keys: Vec::new(),
             note_name_cache: String::default(),
+            parent_note_id: None,
+            broken_link_cache: None,
+            note_type_cache: NoteType::Topic,
+            template_list: StatefulList::default(),
+            notebook_list: StatefulList::default(),
+            is_notebook_move: false,
+            label_list: StatefulList::default(),
+            is_label_filter: false,
+            link_list: StatefulList::default(),
+            outline_list: StatefulList::default(),
+            finder_query: InputString::from("> "),
+            finder_content_search_enabled: false,
+            finder_note_names: Vec::new(),
+            finder_list: StatefulList::default(),
+            finder_preview: String::default(),
+            finder_preview_broken_links: Vec::new(),
+            finder_preview_render_mode: RenderMode::Markdown,
+            finder_preview_loader: PreviewLoader::default(),
+            is_finder_preview_loading: false,
+            command_query: InputString::from(": "),
+            command_list: StatefulList::default(),
             note_list_title: String::from("List"),
+            last_undo_action: None,
         };
         tui_data.note_list.select(Some(0));
         return tui_data;
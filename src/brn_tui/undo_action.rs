@@ -0,0 +1,18 @@
+// The last destructive action of the session with everything that is needed to reverse it
+pub enum UndoAction {
+    // The note file was moved into the trash
+    Remove {
+        note_name: String,
+        file_name: String,
+    },
+    Rename {
+        note_id: String,
+        old_note_name: String,
+        new_note_name: String,
+    },
+    // The contents of the note files before the tag was added, as (file name, content)
+    AddTag {
+        tag_name: String,
+        previous_contents: Vec<(String, String)>,
+    },
+}
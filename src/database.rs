@@ -1,23 +1,73 @@
-use crate::message::Message;
+use crate::brain_error::BrainError;
 use crate::note::Note;
+use crate::note_activity::NoteActivity;
+use crate::note_label::NoteLabel;
 use crate::note_link::NoteLink;
 use crate::note_property::NoteProperty;
 use crate::note_tagging::NoteTagging;
+use crate::search_query::SearchQuery;
+use crate::sort_order::SortOrder;
 
 use chrono::prelude::*;
 use lazy_static::lazy_static;
-use rusqlite::{named_params, params, Connection, Error, Statement};
+use rusqlite::{named_params, params, params_from_iter, Connection, Error, Row, Statement, ToSql};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::path::Path;
+use std::rc::Rc;
 use std::sync::Mutex;
 
 lazy_static! {
     static ref DB_DIR_PATH: Mutex<OsString> = Mutex::default();
+    // The queries don't fail, so their errors are collected here until a frontend shows them
+    static ref DB_ERRORS: Mutex<Vec<String>> = Mutex::default();
+}
+
+// Notes that are opened often and were opened recently rank higher. The number of openings is
+// divided by the days since the last opening plus a week, so that a note that was opened a lot
+// stays on top for a while. Notes that were never opened count as opened once when they were
+// created, so that new notes show up on top
+const FRECENCY_SCORE: &str = "
+    COALESCE(
+        (SELECT open_count FROM note_usage WHERE note_usage.note_id = note.note_id), 1
+    ) / (
+        julianday('now', 'localtime') - julianday(COALESCE(
+            (SELECT last_opened FROM note_usage WHERE note_usage.note_id = note.note_id),
+            note.creation_date
+        )) + 7.0
+    )";
+
+thread_local! {
+    // Opening the database for every query is slow when navigating quickly in the TUI,
+    // so the connection is kept until the path of the database changes
+    static DB_CONNECTION: RefCell<Option<Rc<Connection>>> = const { RefCell::new(None) };
 }
 
 pub struct Database;
+
+// Returned by `Database::open_temporarily()`
+pub struct DatabaseGuard {
+    previous_db_file_path: OsString,
+}
+
+impl Drop for DatabaseGuard {
+    fn drop(&mut self) {
+        Database::set_db_path(&self.previous_db_file_path);
+    }
+}
+
 impl Database {
+    // Returns the errors of the queries since the last call
+    pub fn take_errors() -> Vec<String> {
+        return std::mem::take(&mut *DB_ERRORS.lock().unwrap());
+    }
+
+    fn report_error(message: &str) {
+        DB_ERRORS.lock().unwrap().push(message.to_string());
+    }
+
     pub fn init() {
         let conn = Database::get_connection();
 
@@ -30,6 +80,8 @@ impl Database {
                 note_name varchar(255) NOT NULL,
                 file_name varchar(50) NOT NULL,
                 creation_date datetime NOT NULL,
+                archived integer NOT NULL DEFAULT 0,
+                modification_date datetime,
                 PRIMARY KEY (note_id)
             );
 
@@ -58,13 +110,80 @@ impl Database {
                     REFERENCES note (note_id)
             );
 
+            CREATE TABLE IF NOT EXISTS note_property (
+                note_id varchar(20) NOT NULL,
+                property_key varchar(200) NOT NULL,
+                property_value text NOT NULL,
+                FOREIGN KEY (note_id)
+                    REFERENCES note (note_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS note_alias (
+                note_id varchar(20) NOT NULL,
+                alias_name varchar(255) NOT NULL,
+                PRIMARY KEY (note_id, alias_name),
+                FOREIGN KEY (note_id)
+                    REFERENCES note (note_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS note_label (
+                note_id varchar(20) NOT NULL PRIMARY KEY,
+                label_name varchar(255) NOT NULL,
+                FOREIGN KEY (note_id)
+                    REFERENCES note (note_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS note_usage (
+                note_id varchar(20) NOT NULL PRIMARY KEY,
+                open_count integer NOT NULL,
+                last_opened datetime NOT NULL,
+                FOREIGN KEY (note_id)
+                    REFERENCES note (note_id)
+            );
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS note_content USING fts5 (
+                note_id UNINDEXED,
+                content
+            );
+
             COMMIT TRANSACTION;
         ",
         )
         .unwrap();
+
+        // The note table of older databases doesn't have the archived column yet
+        let has_archived_column = conn.prepare("SELECT archived FROM note LIMIT 1;").is_ok();
+        if !has_archived_column {
+            conn.execute_batch("ALTER TABLE note ADD COLUMN archived integer NOT NULL DEFAULT 0;")
+                .unwrap();
+        }
+        let has_modification_date_column = conn
+            .prepare("SELECT modification_date FROM note LIMIT 1;")
+            .is_ok();
+        if !has_modification_date_column {
+            conn.execute_batch("ALTER TABLE note ADD COLUMN modification_date datetime;")
+                .unwrap();
+        }
+    }
+
+    pub fn get_schema_version() -> i32 {
+        let conn = Database::get_connection();
+
+        return conn
+            .query_row("PRAGMA user_version;", [], |row| row.get(0))
+            .unwrap_or(0);
+    }
+
+    pub fn set_schema_version(version: i32) {
+        let conn = Database::get_connection();
+
+        if let Err(error) = conn.execute_batch(&format!("PRAGMA user_version = {};", version)) {
+            Database::report_error(&format!("set-schema-version: {}", &error.to_string()));
+        }
     }
 
     pub fn clear() -> bool {
+        Database::close_connection();
         let db_dir = &*DB_DIR_PATH.lock().unwrap();
         if db_dir.is_empty() {
             return false;
@@ -79,9 +198,20 @@ impl Database {
     }
 
     pub fn set_db_path(db_file_path: &OsStr) {
+        Database::close_connection();
         *DB_DIR_PATH.lock().unwrap() = db_file_path.to_os_string();
     }
 
+    // Opens the database of another notebook until the returned guard is dropped, so that
+    // the previous database is opened again on every return path
+    pub fn open_temporarily(db_file_path: &OsStr) -> DatabaseGuard {
+        let previous_db_file_path = DB_DIR_PATH.lock().unwrap().clone();
+        Database::set_db_path(db_file_path);
+        return DatabaseGuard {
+            previous_db_file_path,
+        };
+    }
+
     pub fn insert_note(note: &Note) -> bool {
         if let Some(creation_date_time) = note.creation_date_time {
             let creation_timestamp = creation_date_time.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -114,7 +244,7 @@ impl Database {
         ) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
@@ -122,7 +252,7 @@ impl Database {
         let rows = match select_statement.query_map([], |row| row.get(0)) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
@@ -135,24 +265,149 @@ impl Database {
         return row_vector;
     }
 
-    pub fn get_random_note_ids(amount: usize) -> Vec<String> {
-        let conn = Database::get_connection();
+    pub fn get_all_notes() -> Vec<Note> {
+        return Database::get_notes_where_query(
+            "SELECT note_id, note_name, file_name, creation_date
+             FROM note",
+        );
+    }
 
-        let select_statement = match conn.prepare(
-            "SELECT note_id
+    pub fn get_unarchived_notes() -> Vec<Note> {
+        return Database::get_notes_where_query(
+            "SELECT note_id, note_name, file_name, creation_date
              FROM note
-             ORDER BY RANDOM()
-             LIMIT ?",
-        ) {
+             WHERE archived = 0",
+        );
+    }
+
+    pub fn get_archived_notes() -> Vec<Note> {
+        return Database::get_notes_where_query(
+            "SELECT note_id, note_name, file_name, creation_date
+             FROM note
+             WHERE archived = 1
+             ORDER BY creation_date DESC;",
+        );
+    }
+
+    // Notes that are only linked by themselves are orphans too.
+    // Links of archived notes are ignored unless archived notes are included
+    pub fn get_orphan_notes(include_archived: bool) -> Vec<Note> {
+        return Database::get_notes_where_query(&format!(
+            "SELECT note_id, note_name, file_name, creation_date
+             FROM note
+             WHERE note_id NOT IN (
+                SELECT note_link_id
+                FROM note_link
+                WHERE note_id != note_link_id
+                AND note_id IN (SELECT note_id FROM note WHERE {0})
+             )
+             AND {0}
+             ORDER BY creation_date DESC;",
+            Database::get_archived_condition_of(include_archived)
+        ));
+    }
+
+    // Notes that only link to themselves are dead ends too.
+    // Links to archived notes are ignored unless archived notes are included
+    pub fn get_dead_end_notes(include_archived: bool) -> Vec<Note> {
+        return Database::get_notes_where_query(&format!(
+            "SELECT note_id, note_name, file_name, creation_date
+             FROM note
+             WHERE note_id NOT IN (
+                SELECT note_id
+                FROM note_link
+                WHERE note_id != note_link_id
+                AND note_link_id IN (SELECT note_id FROM note WHERE {0})
+             )
+             AND {0}
+             ORDER BY creation_date DESC;",
+            Database::get_archived_condition_of(include_archived)
+        ));
+    }
+
+    fn get_archived_condition_of(include_archived: bool) -> &'static str {
+        if include_archived {
+            return "1 = 1";
+        } else {
+            return "archived = 0";
+        }
+    }
+
+    fn get_notes_where_query(query: &str) -> Vec<Note> {
+        let conn = Database::get_connection();
+
+        let mut select_statement = match conn.prepare(query) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
 
-        let rows = Database::get_rows_of_prepared_query(select_statement, &amount.to_string());
-        return rows;
+        let rows = match select_statement.query_map([], Database::get_note_from_row) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        // Convert rows to vector
+        let mut row_vector = Vec::new();
+        for row in rows {
+            row_vector.push(row.unwrap());
+        }
+        return row_vector;
+    }
+
+    // The number of unarchived notes that can be chosen as random notes (see
+    // `get_random_candidate_at`). Without a tag name all unarchived notes are counted
+    pub fn get_random_candidate_count(tag_name: Option<&str>) -> usize {
+        let conn = Database::get_connection();
+
+        return conn
+            .query_row(
+                "SELECT COUNT(*)
+                 FROM note
+                 WHERE archived = 0
+                 AND (:tag_name IS NULL OR note_id IN (
+                    SELECT note_id
+                    FROM note_tagging
+                    WHERE tag_name = :tag_name
+                 ));",
+                named_params! {
+                    ":tag_name": tag_name
+                },
+                |row| row.get::<usize, i64>(0),
+            )
+            .map(|m| m as usize)
+            .unwrap_or(0);
+    }
+
+    // The candidates are sorted by their id, so that the same position always returns the
+    // same note
+    pub fn get_random_candidate_at(position: usize, tag_name: Option<&str>) -> Option<String> {
+        let conn = Database::get_connection();
+
+        return conn
+            .query_row(
+                "SELECT note_id
+                 FROM note
+                 WHERE archived = 0
+                 AND (:tag_name IS NULL OR note_id IN (
+                    SELECT note_id
+                    FROM note_tagging
+                    WHERE tag_name = :tag_name
+                 ))
+                 ORDER BY note_id
+                 LIMIT 1 OFFSET :position;",
+                named_params! {
+                    ":tag_name": tag_name,
+                    ":position": position as i64,
+                },
+                |row| row.get(0),
+            )
+            .ok();
     }
 
     pub fn get_note_id_where(note_property: NoteProperty, value: &str) -> Option<String> {
@@ -160,16 +415,14 @@ impl Database {
         let query = format!(
             "SELECT note_id
              FROM note
-             WHERE {} = :value",
-            note_property.to_db_string()
+             WHERE {}",
+            Database::get_condition_of(&note_property, "= :value")
         );
 
         let query_result = conn
             .query_row(
                 &query,
-                named_params! {
-                    ":value": value
-                },
+                Database::get_parameters_of(&note_property, &value).as_slice(),
                 |row| row.get(0),
             )
             .ok();
@@ -177,6 +430,43 @@ impl Database {
         return query_result;
     }
 
+    // Used if no note has exactly the given name
+    pub fn get_note_ids_with_name_or_alias_like(name: &str) -> Vec<String> {
+        let conn = Database::get_connection();
+
+        let mut select_statement = match conn.prepare(
+            "SELECT note_id
+             FROM note
+             WHERE note_name = :name COLLATE NOCASE
+             UNION
+             SELECT note_id
+             FROM note_alias
+             WHERE alias_name = :name COLLATE NOCASE",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows =
+            match select_statement.query_map(named_params! { ":name": name }, |row| row.get(0)) {
+                Ok(query_result) => query_result,
+                Err(error) => {
+                    Database::report_error(&error.to_string());
+                    return Vec::new();
+                }
+            };
+
+        // Convert rows to string vector
+        let mut row_vector = Vec::new();
+        for row in rows {
+            row_vector.push(row.unwrap());
+        }
+        return row_vector;
+    }
+
     pub fn get_note_ids_where_property_is_like(
         note_property: NoteProperty,
         value: &str,
@@ -185,20 +475,63 @@ impl Database {
         let query = format!(
             "SELECT note_id
              FROM note
-             WHERE {} LIKE ?;",
-            note_property.to_db_string()
+             WHERE {};",
+            Database::get_condition_of(&note_property, "LIKE :value")
         );
 
-        let select_statement = match conn.prepare(&query) {
+        let mut select_statement = match conn.prepare(&query) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
 
-        let rows = Database::get_rows_of_prepared_query(select_statement, value);
-        return rows;
+        let rows = match select_statement.query_map(
+            Database::get_parameters_of(&note_property, &value).as_slice(),
+            |row| row.get(0),
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        // Convert rows to string vector
+        let mut row_vector = Vec::new();
+        for row in rows {
+            row_vector.push(row.unwrap());
+        }
+        return row_vector;
+    }
+
+    // Custom properties of the yaml header are stored in their own table. The key of the
+    // property is bound to ':property_key' (see `get_parameters_of`)
+    fn get_condition_of(note_property: &NoteProperty, comparison: &str) -> String {
+        match note_property {
+            NoteProperty::Custom(_) => format!(
+                "note_id IN (
+                    SELECT note_id
+                    FROM note_property
+                    WHERE property_key = :property_key AND {} {})",
+                note_property.to_db_string(),
+                comparison
+            ),
+            _ => format!("{} {}", note_property.to_db_string(), comparison),
+        }
+    }
+
+    // The compared value is bound to ':value'
+    fn get_parameters_of<'a>(
+        note_property: &'a NoteProperty,
+        value: &'a dyn ToSql,
+    ) -> Vec<(&'static str, &'a dyn ToSql)> {
+        let mut parameters: Vec<(&str, &dyn ToSql)> = vec![(":value", value)];
+        if let NoteProperty::Custom(property_key) = note_property {
+            parameters.push((":property_key", property_key));
+        }
+        return parameters;
     }
 
     pub fn get_note_where_id(note_id: &str) -> Option<Note> {
@@ -212,111 +545,877 @@ impl Database {
                 named_params! {
                     ":note_id": note_id
                 },
-                |row| {
-                    Ok(Note {
-                        note_id: row.get(0).unwrap(),
-                        note_name: row.get(1).unwrap(),
-                        file_name: row.get(2).unwrap(),
-                        creation_date_time: Local
-                            .datetime_from_str(
-                                &row.get::<usize, String>(3).unwrap(),
-                                "%Y-%m-%d %H:%M:%S",
-                            )
-                            .ok(),
-                    })
+                Database::get_note_from_row,
+            )
+            .ok();
+
+        return query_result;
+    }
+
+    pub fn get_note_where_file_name(file_name: &str) -> Option<Note> {
+        let conn = Database::get_connection();
+
+        let query_result = conn
+            .query_row(
+                "SELECT note_id, note_name, file_name, creation_date
+             FROM note
+             WHERE file_name = :file_name;",
+                named_params! {
+                    ":file_name": file_name
                 },
+                Database::get_note_from_row,
             )
             .ok();
 
-        return query_result;
+        return query_result;
+    }
+
+    fn get_note_from_row(row: &Row) -> Result<Note, Error> {
+        return Ok(Note {
+            note_id: row.get(0)?,
+            note_name: row.get(1)?,
+            file_name: row.get(2)?,
+            creation_date_time: Local
+                .datetime_from_str(&row.get::<usize, String>(3)?, "%Y-%m-%d %H:%M:%S")
+                .ok(),
+        });
+    }
+
+    pub fn get_note_count() -> usize {
+        let conn = Database::get_connection();
+
+        return conn
+            .query_row("SELECT COUNT(*) FROM note WHERE archived = 0;", [], |row| {
+                row.get::<usize, i64>(0)
+            })
+            .map(|m| m as usize)
+            .unwrap_or(0);
+    }
+
+    // Archived notes are included
+    pub fn get_total_note_count() -> usize {
+        return Database::get_count_of("SELECT COUNT(*) FROM note;");
+    }
+
+    pub fn get_archived_note_count() -> usize {
+        return Database::get_count_of("SELECT COUNT(*) FROM note WHERE archived = 1;");
+    }
+
+    pub fn get_tag_count() -> usize {
+        return Database::get_count_of("SELECT COUNT(*) FROM tag;");
+    }
+
+    // Returns the number of incoming and outgoing links of every given note name.
+    // All notes are queried at once, because it's done whenever the note list is drawn
+    pub fn get_link_counts_of_note_names(note_names: &[String]) -> HashMap<String, (usize, usize)> {
+        let mut link_counts = HashMap::new();
+        if note_names.is_empty() {
+            return link_counts;
+        }
+
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT note_name,
+                (SELECT COUNT(*)
+                 FROM note_link
+                 WHERE note_link.note_link_id = note.note_id
+                 AND note_link.note_id != note_link.note_link_id),
+                (SELECT COUNT(*)
+                 FROM note_link
+                 WHERE note_link.note_id = note.note_id
+                 AND note_link.note_id != note_link.note_link_id)
+             FROM note
+             WHERE note_name IN ({});",
+            vec!["?"; note_names.len()].join(", ")
+        );
+
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return link_counts;
+            }
+        };
+        let rows = match select_statement.query_map(params_from_iter(note_names), |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        }) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return link_counts;
+            }
+        };
+
+        for (note_name, incoming_link_count, outgoing_link_count) in rows.flatten() {
+            link_counts.insert(note_name, (incoming_link_count, outgoing_link_count));
+        }
+        return link_counts;
+    }
+
+    // Returns the label of every given note name that has one. All notes are queried at once,
+    // because it's done whenever the note list is drawn
+    pub fn get_labels_of_note_names(note_names: &[String]) -> HashMap<String, NoteLabel> {
+        let mut labels = HashMap::new();
+        if note_names.is_empty() {
+            return labels;
+        }
+
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT note.note_name, note_label.label_name
+             FROM note
+             INNER JOIN note_label ON note.note_id = note_label.note_id
+             WHERE note.note_name IN ({});",
+            vec!["?"; note_names.len()].join(", ")
+        );
+
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return labels;
+            }
+        };
+        let rows = match select_statement.query_map(params_from_iter(note_names), |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        }) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return labels;
+            }
+        };
+
+        for (note_name, label_name) in rows.flatten() {
+            let label_name: String = label_name;
+            if let Some(label) = NoteLabel::from_name(&label_name) {
+                labels.insert(note_name, label);
+            }
+        }
+        return labels;
+    }
+
+    // Returns the note of every given note name that exists. All notes are queried at once,
+    // because it's done whenever a window of the note list is loaded
+    pub fn get_notes_with_names(note_names: &[String]) -> HashMap<String, Note> {
+        let mut notes = HashMap::new();
+        if note_names.is_empty() {
+            return notes;
+        }
+
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT note_id, note_name, file_name, creation_date
+             FROM note
+             WHERE note_name IN ({});",
+            vec!["?"; note_names.len()].join(", ")
+        );
+
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return notes;
+            }
+        };
+        let rows = match select_statement
+            .query_map(params_from_iter(note_names), Database::get_note_from_row)
+        {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return notes;
+            }
+        };
+
+        for note in rows.flatten() {
+            notes.insert(note.note_name.clone(), note);
+        }
+        return notes;
+    }
+
+    // Returns the tags of every given note name that has some. All notes are queried at once,
+    // because it's done whenever a window of the note list is loaded
+    pub fn get_tags_of_note_names(note_names: &[String]) -> HashMap<String, Vec<String>> {
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        if note_names.is_empty() {
+            return tags;
+        }
+
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT note.note_name, note_tagging.tag_name
+             FROM note
+             INNER JOIN note_tagging ON note.note_id = note_tagging.note_id
+             WHERE note.note_name IN ({});",
+            vec!["?"; note_names.len()].join(", ")
+        );
+
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return tags;
+            }
+        };
+        let rows = match select_statement.query_map(params_from_iter(note_names), |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        }) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return tags;
+            }
+        };
+
+        for (note_name, tag_name) in rows.flatten() {
+            let tag_name: String = tag_name;
+            tags.entry(note_name).or_default().push(tag_name);
+        }
+        return tags;
+    }
+
+    pub fn get_label_of(note_id: &str) -> Option<NoteLabel> {
+        let conn = Database::get_connection();
+
+        let label_name: String = conn
+            .query_row(
+                "SELECT label_name
+                 FROM note_label
+                 WHERE note_id = :note_id;",
+                named_params! {
+                    ":note_id": note_id
+                },
+                |row| row.get(0),
+            )
+            .ok()?;
+        return NoteLabel::from_name(&label_name);
+    }
+
+    // Returns the note id and the label of every note with a label
+    pub fn get_all_note_labels() -> Vec<(String, NoteLabel)> {
+        let conn = Database::get_connection();
+        let mut select_statement = match conn.prepare(
+            "SELECT note_id, label_name
+             FROM note_label;",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = match select_statement.query_map([], |row| {
+            let label_name: String = row.get(1)?;
+            Ok((row.get(0)?, label_name))
+        }) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        return rows
+            .flatten()
+            .filter_map(|(note_id, label_name)| {
+                NoteLabel::from_name(&label_name).map(|m| (note_id, m))
+            })
+            .collect();
+    }
+
+    pub fn get_notes_with_label(label: NoteLabel) -> Vec<Note> {
+        let conn = Database::get_connection();
+        let mut select_statement = match conn.prepare(
+            "SELECT note.note_id, note.note_name, note.file_name, note.creation_date
+             FROM note
+             INNER JOIN note_label ON note.note_id = note_label.note_id
+             WHERE note_label.label_name = :label_name
+             ORDER BY note.creation_date DESC;",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = match select_statement.query_map(
+            named_params! {
+                ":label_name": label.to_name()
+            },
+            Database::get_note_from_row,
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        return rows.flatten().collect();
+    }
+
+    // Removes the label of the note if no label is given
+    pub fn set_label_of(note_id: &str, label: Option<NoteLabel>) {
+        let conn = Database::get_connection();
+
+        let result = match label {
+            Some(label) => conn.execute(
+                "INSERT OR REPLACE INTO note_label (note_id, label_name)
+                 VALUES (:note_id, :label_name)",
+                named_params! {
+                    ":note_id": note_id,
+                    ":label_name": label.to_name(),
+                },
+            ),
+            None => conn.execute(
+                "DELETE FROM note_label
+                 WHERE note_id = :note_id",
+                named_params! {
+                    ":note_id": note_id
+                },
+            ),
+        };
+        if let Err(error) = result {
+            Database::report_error(&format!("set-label-of: {}", &error.to_string()));
+        }
+    }
+
+    // Counts the opening of the note for the frecency sort order
+    pub fn add_usage_of(note_id: &str) {
+        let conn = Database::get_connection();
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let result = conn
+            .execute(
+                "UPDATE note_usage
+                 SET open_count = open_count + 1, last_opened = :last_opened
+                 WHERE note_id = :note_id",
+                named_params! {
+                    ":note_id": note_id,
+                    ":last_opened": timestamp,
+                },
+            )
+            .and_then(|updated_row_count| match updated_row_count {
+                0 => Database::set_usage_of(note_id, 1, &timestamp),
+                _ => Ok(updated_row_count),
+            });
+        if let Err(error) = result {
+            Database::report_error(&format!("add-usage-of: {}", &error.to_string()));
+        }
+    }
+
+    pub fn set_usage_of(
+        note_id: &str,
+        open_count: usize,
+        last_opened: &str,
+    ) -> Result<usize, Error> {
+        let conn = Database::get_connection();
+
+        return conn.execute(
+            "INSERT OR REPLACE INTO note_usage (note_id, open_count, last_opened)
+             VALUES (:note_id, :open_count, :last_opened)",
+            named_params! {
+                ":note_id": note_id,
+                ":open_count": open_count as i64,
+                ":last_opened": last_opened,
+            },
+        );
+    }
+
+    // Returns the note id, the number of openings and the time of the last opening of every
+    // note that was opened
+    pub fn get_all_note_usages() -> Vec<(String, usize, String)> {
+        let conn = Database::get_connection();
+        let mut select_statement = match conn.prepare(
+            "SELECT note_id, open_count, last_opened
+             FROM note_usage;",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = match select_statement.query_map([], |row| {
+            Ok((row.get(0)?, row.get::<usize, i64>(1)? as usize, row.get(2)?))
+        }) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        return rows.flatten().collect();
+    }
+
+    // Returns the frecency score of every note by its id
+    pub fn get_frecency_scores() -> HashMap<String, f64> {
+        let conn = Database::get_connection();
+        let mut select_statement =
+            match conn.prepare(&format!("SELECT note_id, {} FROM note;", FRECENCY_SCORE)) {
+                Ok(query_result) => query_result,
+                Err(error) => {
+                    Database::report_error(&error.to_string());
+                    return HashMap::new();
+                }
+            };
+
+        let rows = match select_statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?))) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return HashMap::new();
+            }
+        };
+        return rows.flatten().collect();
+    }
+
+    pub fn get_link_count() -> usize {
+        return Database::get_count_of("SELECT COUNT(*) FROM note_link;");
+    }
+
+    // Uses the same conditions as get_orphan_notes
+    pub fn get_orphan_count() -> usize {
+        return Database::get_count_of(
+            "SELECT COUNT(*)
+             FROM note
+             WHERE note_id NOT IN (
+                SELECT note_link_id
+                FROM note_link
+                WHERE note_id != note_link_id
+                AND note_id IN (SELECT note_id FROM note WHERE archived = 0)
+             )
+             AND archived = 0;",
+        );
+    }
+
+    // The length is the number of characters of the whole note including the yaml header
+    pub fn get_average_note_length() -> f64 {
+        let conn = Database::get_connection();
+
+        return conn
+            .query_row(
+                "SELECT COALESCE(AVG(LENGTH(content)), 0) FROM note_content;",
+                [],
+                |row| row.get::<usize, f64>(0),
+            )
+            .unwrap_or(0.0);
+    }
+
+    pub fn get_db_file_size() -> u64 {
+        let db_dir = &*DB_DIR_PATH.lock().unwrap();
+        return fs::metadata(Path::new(db_dir).join("data.db"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+    }
+
+    fn get_count_of(query: &str) -> usize {
+        let conn = Database::get_connection();
+
+        return conn
+            .query_row(query, [], |row| row.get::<usize, i64>(0))
+            .map(|m| m as usize)
+            .unwrap_or(0);
+    }
+
+    pub fn get_notes_page(
+        offset: usize,
+        limit: usize,
+        sort_order: SortOrder,
+        pinned_note_ids: &[String],
+    ) -> Vec<Note> {
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT note_id, note_name, file_name, creation_date
+             FROM note
+             WHERE archived = 0
+             ORDER BY {}
+             LIMIT :limit OFFSET :offset;",
+            Database::get_order_by_clause_of(sort_order)
+        );
+
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = match select_statement.query_map(
+            named_params! {
+                ":limit": limit as i64,
+                ":offset": offset as i64,
+                ":pinned_note_ids": Database::to_json_array(pinned_note_ids),
+            },
+            Database::get_note_from_row,
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        // Convert rows to vector
+        let mut row_vector = Vec::new();
+        for row in rows {
+            row_vector.push(row.unwrap());
+        }
+        return row_vector;
+    }
+
+    // Returns the index of the note in the list of all notes sorted by the given sort order
+    pub fn get_note_position(
+        note_name: &str,
+        sort_order: SortOrder,
+        pinned_note_ids: &[String],
+    ) -> Option<usize> {
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT position
+             FROM (
+                SELECT note_name, ROW_NUMBER() OVER (ORDER BY {}) - 1 AS position
+                FROM note
+                WHERE archived = 0
+             )
+             WHERE note_name = :note_name;",
+            Database::get_order_by_clause_of(sort_order)
+        );
+
+        return conn
+            .query_row(
+                &query,
+                named_params! {
+                    ":note_name": note_name,
+                    ":pinned_note_ids": Database::to_json_array(pinned_note_ids),
+                },
+                |row| row.get::<usize, i64>(0),
+            )
+            .ok()
+            .map(|m| m as usize);
+    }
+
+    pub fn get_note_names_like(
+        pattern: &str,
+        sort_order: SortOrder,
+        pinned_note_ids: &[String],
+    ) -> Vec<String> {
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT note_name
+             FROM note
+             WHERE note_name LIKE :pattern ESCAPE '\\'
+             AND archived = 0
+             ORDER BY {};",
+            Database::get_order_by_clause_of(sort_order)
+        );
+
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = match select_statement.query_map(
+            named_params! {
+                ":pattern": pattern,
+                ":pinned_note_ids": Database::to_json_array(pinned_note_ids),
+            },
+            |row| row.get(0),
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        // Convert rows to string vector
+        let mut row_vector = Vec::new();
+        for row in rows {
+            row_vector.push(row.unwrap());
+        }
+        return row_vector;
+    }
+
+    // Sorts the given note names like the pages of the note list. Names of notes
+    // that aren't in the database are left out
+    pub fn get_sorted_note_names(
+        note_names: &[String],
+        sort_order: SortOrder,
+        pinned_note_ids: &[String],
+    ) -> Vec<String> {
+        let conn = Database::get_connection();
+        let query = format!(
+            "SELECT note_name
+             FROM note
+             WHERE note_name IN (SELECT value FROM json_each(:note_names))
+             ORDER BY {};",
+            Database::get_order_by_clause_of(sort_order)
+        );
+
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = match select_statement.query_map(
+            named_params! {
+                ":note_names": Database::to_json_array(note_names),
+                ":pinned_note_ids": Database::to_json_array(pinned_note_ids),
+            },
+            |row| row.get(0),
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        return rows.flatten().collect();
+    }
+
+    // Notes without a modification date yet are sorted by their creation date.
+    // Pinned notes are always sorted to the top. Their ids are bound to ':pinned_note_ids'
+    // as a json array (see `to_json_array`)
+    fn get_order_by_clause_of(sort_order: SortOrder) -> String {
+        let order_by_clause = match sort_order {
+            SortOrder::CreationDateDescending => "creation_date DESC, note_id DESC".to_string(),
+            SortOrder::CreationDateAscending => "creation_date ASC, note_id ASC".to_string(),
+            SortOrder::ModificationDateDescending => {
+                "COALESCE(modification_date, creation_date) DESC, note_id DESC".to_string()
+            }
+            SortOrder::ModificationDateAscending => {
+                "COALESCE(modification_date, creation_date) ASC, note_id ASC".to_string()
+            }
+            SortOrder::NameAscending => "note_name COLLATE NOCASE ASC".to_string(),
+            SortOrder::NameDescending => "note_name COLLATE NOCASE DESC".to_string(),
+            SortOrder::Frecency => {
+                format!("{} DESC, creation_date DESC, note_id DESC", FRECENCY_SCORE)
+            }
+        };
+        return format!(
+            "note_id IN (SELECT value FROM json_each(:pinned_note_ids)) DESC, {}",
+            order_by_clause
+        );
+    }
+
+    fn to_json_array(values: &[String]) -> String {
+        return serde_json::to_string(values).unwrap_or_else(|_| "[]".to_string());
+    }
+
+    pub fn get_all_note_links() -> Vec<NoteLink> {
+        let conn = Database::get_connection();
+
+        let mut select_statement = match conn.prepare(
+            "SELECT note_id, note_link_id
+             FROM note_link",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let mut rows = match select_statement.query([]) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        // Convert rows to string vector
+        let mut row_vector = Vec::new();
+        while let Ok(Some(row)) = rows.next() {
+            row_vector.push(NoteLink::new(row.get(0).unwrap(), row.get(1).unwrap()));
+        }
+        return row_vector;
+    }
+
+    pub fn get_outgoing_links(note_id: &str) -> Vec<String> {
+        let conn = Database::get_connection();
+
+        let select_statement = match conn.prepare(
+            "SELECT note_link_id
+             FROM note_link
+             WHERE note_id = ?;",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = Database::get_rows_of_prepared_query(select_statement, note_id);
+        return rows;
+    }
+
+    pub fn get_incoming_links(note_id: &str) -> Vec<String> {
+        let conn = Database::get_connection();
+
+        let select_statement = match conn.prepare(
+            "SELECT note_id
+             FROM note_link
+             WHERE note_link_id = ?;",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = Database::get_rows_of_prepared_query(select_statement, note_id);
+        return rows;
+    }
+
+    pub fn get_tags_of_note(note_id: &str) -> Vec<String> {
+        let conn = Database::get_connection();
+
+        let select_statement = match conn.prepare(
+            "SELECT tag_name
+             FROM note_tagging
+             WHERE note_id = ?;",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = Database::get_rows_of_prepared_query(select_statement, note_id);
+        return rows;
     }
 
-    pub fn get_all_recent_note_ids(count: i32) -> Vec<String> {
+    pub fn get_note_ids_with_tag(tag_name: &str) -> Vec<String> {
         let conn = Database::get_connection();
 
         let select_statement = match conn.prepare(
             "SELECT note_id
-             FROM note
-             ORDER BY creation_date DESC
-             LIMIT ?;",
+             FROM note_tagging
+             WHERE tag_name = ?;",
         ) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
 
-        let rows = Database::get_rows_of_prepared_query(select_statement, &count.to_string());
+        let rows = Database::get_rows_of_prepared_query(select_statement, tag_name);
         return rows;
     }
 
-    pub fn get_all_note_links() -> Vec<NoteLink> {
+    pub fn get_notes_with_tag(tag_name: &str) -> Vec<Note> {
         let conn = Database::get_connection();
 
+        // Notes with nested tags (e.g. 'tag/nested-tag') also belong to the parent tag
         let mut select_statement = match conn.prepare(
-            "SELECT note_id, note_link_id
-             FROM note_link",
+            "SELECT note.note_id, note.note_name, note.file_name, note.creation_date
+             FROM note
+             INNER JOIN note_tagging ON note.note_id = note_tagging.note_id
+             WHERE note_tagging.tag_name = :tag_name
+                OR substr(note_tagging.tag_name, 1, length(:tag_name) + 1) = :tag_name || '/'
+             GROUP BY note.note_id
+             ORDER BY note.creation_date DESC;",
         ) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
 
-        let mut rows = match select_statement.query([]) {
+        let rows = match select_statement.query_map(
+            named_params! {
+                ":tag_name": tag_name
+            },
+            Database::get_note_from_row,
+        ) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
 
-        // Convert rows to string vector
+        // Convert rows to vector
         let mut row_vector = Vec::new();
-        while let Ok(Some(row)) = rows.next() {
-            row_vector.push(NoteLink::new(row.get(0).unwrap(), row.get(1).unwrap()));
+        for row in rows {
+            row_vector.push(row.unwrap());
         }
         return row_vector;
     }
 
-    pub fn get_tags_of_note(note_id: &str) -> Vec<String> {
+    // Returns the note id and the tag name of every tag of every note
+    pub fn get_all_note_taggings() -> Vec<(String, String)> {
         let conn = Database::get_connection();
 
-        let select_statement = match conn.prepare(
-            "SELECT tag_name
-             FROM note_tagging
-             WHERE note_id = ?;",
+        let mut select_statement = match conn.prepare(
+            "SELECT note_id, tag_name
+             FROM note_tagging;",
         ) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
 
-        let rows = Database::get_rows_of_prepared_query(select_statement, note_id);
-        return rows;
+        let rows = match select_statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?))) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        return rows.flatten().collect();
     }
 
-    pub fn get_note_ids_with_tag(tag_name: &str) -> Vec<String> {
+    pub fn get_tag_counts() -> Vec<(String, usize)> {
         let conn = Database::get_connection();
 
-        let select_statement = match conn.prepare(
-            "SELECT note_id
+        let mut select_statement = match conn.prepare(
+            "SELECT tag_name, COUNT(note_id)
              FROM note_tagging
-             WHERE tag_name = ?;",
+             GROUP BY tag_name
+             ORDER BY tag_name;",
         ) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
 
-        let rows = Database::get_rows_of_prepared_query(select_statement, tag_name);
-        return rows;
+        let rows = match select_statement.query_map([], |row| {
+            Ok((row.get_unwrap(0), row.get_unwrap::<usize, i64>(1) as usize))
+        }) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        // Convert rows to vector
+        let mut row_vector = Vec::new();
+        for row in rows {
+            row_vector.push(row.unwrap());
+        }
+        return row_vector;
     }
 
     pub fn get_note_ids_with_tag_like(tag_name: &str) -> Vec<NoteTagging> {
@@ -330,7 +1429,7 @@ impl Database {
         ) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
@@ -341,7 +1440,7 @@ impl Database {
         }) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
@@ -359,7 +1458,7 @@ impl Database {
         let rows = match statement.query_map(params![parameter], |row| row.get(0)) {
             Ok(query_result) => query_result,
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return Vec::new();
             }
         };
@@ -372,26 +1471,236 @@ impl Database {
         return row_vector;
     }
 
-    pub fn update_note_name_where(new_note_name: &str, note_property: NoteProperty, value: &str) {
+    pub fn search_content(search_query: &SearchQuery) -> Vec<String> {
+        if search_query.is_empty() {
+            return Vec::new();
+        }
+
+        // The results are ranked by relevance if words are searched. Otherwise
+        // the newest notes come first
+        let mut query;
+        let mut parameters = Vec::new();
+        match search_query.get_required_fts_query() {
+            Some(fts_query) => {
+                query = "SELECT note_id FROM note_content WHERE note_content MATCH ?".to_string();
+                parameters.push(fts_query);
+            }
+            None => query = "SELECT note_id FROM note WHERE 1".to_string(),
+        }
+        if let Some(fts_query) = search_query.get_excluded_fts_query() {
+            query += " AND note_id NOT IN
+                (SELECT note_id FROM note_content WHERE note_content MATCH ?)";
+            parameters.push(fts_query);
+        }
+
+        // Notes with nested tags (e.g. 'tag/nested-tag') also belong to the parent tag
+        let tag_condition = "(SELECT note_id FROM note_tagging
+            WHERE tag_name = ? OR substr(tag_name, 1, length(?) + 1) = ? || '/')";
+        for tag_name in search_query.get_required_tags() {
+            query += &format!(" AND note_id IN {}", tag_condition);
+            parameters.extend(vec![tag_name.to_string(); 3]);
+        }
+        for tag_name in search_query.get_excluded_tags() {
+            query += &format!(" AND note_id NOT IN {}", tag_condition);
+            parameters.extend(vec![tag_name.to_string(); 3]);
+        }
+
+        query += match search_query.get_required_fts_query() {
+            Some(_) => " ORDER BY rank;",
+            None => " ORDER BY creation_date DESC;",
+        };
+
         let conn = Database::get_connection();
+        let mut select_statement = match conn.prepare(&query) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        let rows = match select_statement.query_map(params_from_iter(parameters), |row| row.get(0))
+        {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        return rows.flatten().collect();
+    }
 
-        let query = format!(
+    pub fn update_modification_date(note_id: &str, modification_date_time: DateTime<Local>) {
+        let conn = Database::get_connection();
+
+        if let Err(error) = conn.execute(
             "UPDATE note
-             SET note_name = :new_note_name
-             WHERE {} = :value",
-            note_property.to_db_string()
-        );
+             SET modification_date = :modification_timestamp
+             WHERE note_id = :note_id",
+            named_params! {
+                ":note_id": note_id,
+                ":modification_timestamp": modification_date_time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            },
+        ) {
+            Database::report_error(&format!("update-modification-date: {}", &error.to_string()));
+        }
+    }
+
+    // Notes whose modification date isn't known yet are only counted as created
+    pub fn get_note_activity(since: &str, until: &str) -> Vec<NoteActivity> {
+        let conn = Database::get_connection();
+
+        let mut select_statement = match conn.prepare(
+            "SELECT day, SUM(created), SUM(modified)
+             FROM (
+                 SELECT date(creation_date) AS day, 1 AS created, 0 AS modified
+                 FROM note
+                 UNION ALL
+                 SELECT date(modification_date) AS day, 0 AS created, 1 AS modified
+                 FROM note
+                 WHERE modification_date IS NOT NULL
+             )
+             WHERE day >= :since AND day <= :until
+             GROUP BY day
+             ORDER BY day;",
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+
+        let rows = match select_statement.query_map(
+            named_params! {
+                ":since": since,
+                ":until": until,
+            },
+            |row| {
+                Ok(NoteActivity {
+                    date: row.get(0)?,
+                    created: row.get::<usize, i64>(1)? as usize,
+                    modified: row.get::<usize, i64>(2)? as usize,
+                })
+            },
+        ) {
+            Ok(query_result) => query_result,
+            Err(error) => {
+                Database::report_error(&error.to_string());
+                return Vec::new();
+            }
+        };
+        return rows.flatten().collect();
+    }
 
+    pub fn update_note_content(note_id: &str, content: &str) {
+        let conn = Database::get_connection();
+
+        Database::delete_note_content(note_id);
         match conn.execute(
-            &query,
+            "INSERT INTO note_content (note_id, content)
+             VALUES (:note_id, :content)",
+            named_params! {
+                ":note_id": note_id,
+                ":content": content,
+            },
+        ) {
+            Ok(_) => {}
+            Err(error) => {
+                Database::report_error(&format!("update-note-content: {}", &error.to_string()));
+                return;
+            }
+        };
+    }
+
+    pub fn update_note_properties(note_id: &str, properties: &[(String, String)]) {
+        let conn = Database::get_connection();
+
+        Database::delete_note_properties(note_id);
+        for (property_key, property_value) in properties {
+            match conn.execute(
+                "INSERT INTO note_property (note_id, property_key, property_value)
+                 VALUES (:note_id, :property_key, :property_value)",
+                named_params! {
+                    ":note_id": note_id,
+                    ":property_key": property_key,
+                    ":property_value": property_value,
+                },
+            ) {
+                Ok(_) => {}
+                Err(error) => {
+                    Database::report_error(&format!(
+                        "update-note-properties: {}",
+                        &error.to_string()
+                    ));
+                    return;
+                }
+            };
+        }
+    }
+
+    pub fn update_aliases_of(note_id: &str, aliases: &[String]) {
+        let conn = Database::get_connection();
+
+        Database::delete_aliases_of(note_id);
+        for alias_name in aliases {
+            if let Err(error) = conn.execute(
+                "INSERT OR IGNORE INTO note_alias (note_id, alias_name)
+                 VALUES (:note_id, :alias_name)",
+                named_params! {
+                    ":note_id": note_id,
+                    ":alias_name": alias_name,
+                },
+            ) {
+                Database::report_error(&format!("update-aliases-of: {}", &error.to_string()));
+                return;
+            }
+        }
+    }
+
+    pub fn update_archived_state_of(note_id: &str, archived: bool) {
+        let conn = Database::get_connection();
+
+        if let Err(error) = conn.execute(
+            "UPDATE note
+             SET archived = :archived
+             WHERE note_id = :note_id",
             named_params! {
-                ":new_note_name": new_note_name,
-                ":value": value
+                ":archived": archived,
+                ":note_id": note_id,
             },
         ) {
+            Database::report_error(&format!("update-archived-state-of: {}", &error.to_string()));
+        }
+    }
+
+    pub fn is_archived(note_id: &str) -> bool {
+        let conn = Database::get_connection();
+
+        return conn
+            .query_row(
+                "SELECT archived FROM note WHERE note_id = ?;",
+                [note_id],
+                |row| row.get::<usize, bool>(0),
+            )
+            .unwrap_or(false);
+    }
+
+    pub fn update_note_name_where(new_note_name: &str, note_property: NoteProperty, value: &str) {
+        let conn = Database::get_connection();
+
+        let query = format!(
+            "UPDATE note
+             SET note_name = :new_note_name
+             WHERE {}",
+            Database::get_condition_of(&note_property, "= :value")
+        );
+
+        let mut parameters = Database::get_parameters_of(&note_property, &value);
+        parameters.push((":new_note_name", &new_note_name));
+        match conn.execute(&query, parameters.as_slice()) {
             Ok(_) => {}
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 return;
             }
         };
@@ -418,7 +1727,7 @@ impl Database {
                     if error == Error::QueryReturnedNoRows {
                         None
                     } else {
-                        Message::error(&format!("insert-tag: {}", &error.to_string()));
+                        Database::report_error(&format!("insert-tag: {}", &error.to_string()));
                         return;
                     }
                 }
@@ -436,7 +1745,7 @@ impl Database {
                 ) {
                     Ok(_) => {}
                     Err(error) => {
-                        Message::error(&format!("insert-tag: {}", &error.to_string()));
+                        Database::report_error(&format!("insert-tag: {}", &error.to_string()));
                         return;
                     }
                 };
@@ -459,7 +1768,7 @@ impl Database {
                     if error == Error::QueryReturnedNoRows {
                         None
                     } else {
-                        Message::error(&format!("insert-tag: {}", &error.to_string()));
+                        Database::report_error(&format!("insert-tag: {}", &error.to_string()));
                         return;
                     }
                 }
@@ -478,7 +1787,10 @@ impl Database {
                 ) {
                     Ok(_) => {}
                     Err(error) => {
-                        Message::error(&format!("insert-note-tagging: {}", &error.to_string()));
+                        Database::report_error(&format!(
+                            "insert-note-tagging: {}",
+                            &error.to_string()
+                        ));
                         return;
                     }
                 };
@@ -486,22 +1798,19 @@ impl Database {
         }
     }
 
-    pub fn insert_note_link_for_note(note_id: &str, note_link_id: &str) -> Result<(), String> {
+    pub fn insert_note_link_for_note(note_id: &str, note_link_id: &str) -> Result<(), BrainError> {
         let conn = Database::get_connection();
 
         if Database::get_note_where_id(note_id).is_none() {
-            return Err(format!(
-                "insert-note-link: the note with the id '{}' wasn't found",
-                note_id
-            ));
+            return Err(BrainError::NoteNotFound(note_id.to_string()));
         }
 
+        // The linked note is checked too, a link to a missing note can't be stored
         if Database::get_note_where_id(note_link_id).is_none() {
-            return Err(format!("insert-note-link: the note with the id '{}' wasn't found. it cannot be linked from note '{}'",
-                        note_link_id, note_id));
+            return Err(BrainError::NoteNotFound(note_link_id.to_string()));
         }
 
-        return insert_note_link(&conn, note_id, note_link_id);
+        return insert_note_link(&conn, note_id, note_link_id).map_err(BrainError::Other);
 
         fn insert_note_link(
             conn: &Connection,
@@ -562,7 +1871,76 @@ impl Database {
         ) {
             Ok(_) => {}
             Err(error) => {
-                Message::error(&format!("delete-note: {}", &error.to_string()));
+                Database::report_error(&format!("delete-note: {}", &error.to_string()));
+                return;
+            }
+        };
+        Database::delete_note_content(note_id);
+        Database::delete_note_properties(note_id);
+        Database::delete_aliases_of(note_id);
+        Database::set_label_of(note_id, None);
+        Database::delete_usage_of(note_id);
+    }
+
+    fn delete_usage_of(note_id: &str) {
+        let conn = Database::get_connection();
+
+        if let Err(error) = conn.execute(
+            "DELETE FROM note_usage
+             WHERE note_id = :note_id",
+            named_params! {
+                ":note_id": note_id
+            },
+        ) {
+            Database::report_error(&format!("delete-usage-of: {}", &error.to_string()));
+        }
+    }
+
+    fn delete_aliases_of(note_id: &str) {
+        let conn = Database::get_connection();
+
+        if let Err(error) = conn.execute(
+            "DELETE FROM note_alias
+             WHERE note_id = :note_id",
+            named_params! {
+                ":note_id": note_id
+            },
+        ) {
+            Database::report_error(&format!("delete-aliases-of: {}", &error.to_string()));
+        }
+    }
+
+    fn delete_note_properties(note_id: &str) {
+        let conn = Database::get_connection();
+
+        match conn.execute(
+            "DELETE FROM note_property
+             WHERE note_id = :note_id",
+            named_params! {
+                ":note_id": note_id
+            },
+        ) {
+            Ok(_) => {}
+            Err(error) => {
+                Database::report_error(&format!("delete-note-properties: {}", &error.to_string()));
+                return;
+            }
+        };
+    }
+
+    fn delete_note_content(note_id: &str) {
+        let conn = Database::get_connection();
+
+        match conn.execute(
+            "DELETE FROM note_content
+             WHERE note_id = :note_id",
+            named_params! {
+                ":note_id": note_id
+            },
+        ) {
+            Ok(_) => {}
+            Err(error) => {
+                Database::report_error(&format!("delete-note-content: {}", &error.to_string()));
                 return;
             }
         };
@@ -580,7 +1958,7 @@ impl Database {
         ) {
             Ok(_) => {}
             Err(error) => {
-                Message::error(&format!("delete-tag: {}", &error.to_string()));
+                Database::report_error(&format!("delete-tag: {}", &error.to_string()));
                 return;
             }
         };
@@ -599,7 +1977,25 @@ impl Database {
         ) {
             Ok(_) => {}
             Err(error) => {
-                Message::error(&format!("delete-note-tagging: {}", &error.to_string()));
+                Database::report_error(&format!("delete-note-tagging: {}", &error.to_string()));
+                return;
+            }
+        };
+    }
+
+    pub fn delete_outgoing_links(note_id: &str) {
+        let conn = Database::get_connection();
+
+        match conn.execute(
+            "DELETE FROM note_link
+             WHERE note_id = :note_id",
+            named_params! {
+                ":note_id": note_id
+            },
+        ) {
+            Ok(_) => {}
+            Err(error) => {
+                Database::report_error(&format!("delete-outgoing-links: {}", &error.to_string()));
                 return;
             }
         };
@@ -617,26 +2013,37 @@ impl Database {
         ) {
             Ok(_) => {}
             Err(error) => {
-                Message::error(&format!("delete-note-link: {}", &error.to_string()));
+                Database::report_error(&format!("delete-note-link: {}", &error.to_string()));
                 return;
             }
         };
     }
 
-    fn get_connection() -> Connection {
+    // All queries of a thread share the same connection
+    fn get_connection() -> Rc<Connection> {
+        if let Some(conn) = DB_CONNECTION.with(|m| m.borrow().clone()) {
+            return conn;
+        }
+
         let db_dir = &*DB_DIR_PATH.lock().unwrap();
         if db_dir.is_empty() {
-            Message::error("the path of the database file has not been set!");
+            Database::report_error("the path of the database file has not been set!");
             panic!();
         }
 
         let conn = match Connection::open(Path::new(db_dir).join("data.db")) {
-            Ok(connection) => connection,
+            Ok(connection) => Rc::new(connection),
             Err(error) => {
-                Message::error(&error.to_string());
+                Database::report_error(&error.to_string());
                 panic!();
             }
         };
+        DB_CONNECTION.with(|m| *m.borrow_mut() = Some(Rc::clone(&conn)));
         return conn;
     }
+
+    // The connection is opened again by the next query
+    fn close_connection() {
+        DB_CONNECTION.with(|m| *m.borrow_mut() = None);
+    }
 }
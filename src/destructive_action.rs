@@ -0,0 +1,25 @@
+// Actions that change or remove notes in a way that is hard to undo. Which of them have to be
+// confirmed before they are executed is set with 'confirm' in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DestructiveAction {
+    Delete,
+    BulkRename,
+    TagRename,
+    TagMerge,
+    NoteMerge,
+    MoveNote,
+}
+
+impl DestructiveAction {
+    pub fn from_config_string(value: &str) -> Option<DestructiveAction> {
+        match value {
+            "delete" => Some(DestructiveAction::Delete),
+            "bulk-rename" => Some(DestructiveAction::BulkRename),
+            "tag-rename" => Some(DestructiveAction::TagRename),
+            "tag-merge" => Some(DestructiveAction::TagMerge),
+            "note-merge" => Some(DestructiveAction::NoteMerge),
+            "move-note" => Some(DestructiveAction::MoveNote),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+// Number of consecutive words that are compared when looking for similar notes
+const SHINGLE_SIZE: usize = 3;
+
+// Notes with identical or similar content
+pub struct DuplicateCluster {
+    pub note_ids: Vec<String>,
+    // The lowest similarity (between 0 and 1) of the notes that put a note into the cluster.
+    // None if all notes are identical
+    pub similarity: Option<f64>,
+}
+
+pub struct DuplicateFinder;
+impl DuplicateFinder {
+    // Notes are identical if their content only differs in whitespace. With a similarity
+    // threshold, notes that share at least this fraction of their word sequences are
+    // clustered too. The clusters and their notes keep the order of the given notes
+    pub fn find_clusters(
+        notes: &[(String, String)],
+        similarity_threshold: Option<f64>,
+    ) -> Vec<DuplicateCluster> {
+        let normalized_contents: Vec<String> = notes
+            .iter()
+            .map(|m| m.1.split_whitespace().collect::<Vec<&str>>().join(" "))
+            .collect();
+
+        // Every note starts in its own cluster
+        let mut parents: Vec<usize> = (0..notes.len()).collect();
+        let mut lowest_similarities: HashMap<usize, f64> = HashMap::new();
+
+        let mut first_index_of_content: HashMap<&str, usize> = HashMap::new();
+        for (index, content) in normalized_contents.iter().enumerate() {
+            if content.is_empty() {
+                continue;
+            }
+            match first_index_of_content.get(content.as_str()) {
+                Some(first_index) => DuplicateFinder::join(&mut parents, *first_index, index),
+                None => {
+                    first_index_of_content.insert(content, index);
+                }
+            }
+        }
+
+        if let Some(similarity_threshold) = similarity_threshold {
+            let shingle_sets: Vec<HashSet<u64>> = normalized_contents
+                .iter()
+                .map(|m| DuplicateFinder::get_shingles_of(m))
+                .collect();
+            for first_index in 0..notes.len() {
+                for second_index in (first_index + 1)..notes.len() {
+                    let similarity = DuplicateFinder::get_similarity(
+                        &shingle_sets[first_index],
+                        &shingle_sets[second_index],
+                    );
+                    if similarity < similarity_threshold
+                        || normalized_contents[first_index] == normalized_contents[second_index]
+                    {
+                        continue;
+                    }
+                    DuplicateFinder::join(&mut parents, first_index, second_index);
+                    let root = DuplicateFinder::find_root(&mut parents, first_index);
+                    let lowest_similarity = lowest_similarities.entry(root).or_insert(1.0);
+                    *lowest_similarity = lowest_similarity.min(similarity);
+                }
+            }
+        }
+
+        let mut clusters: Vec<(usize, Vec<usize>)> = Vec::new();
+        let mut cluster_index_of_root: HashMap<usize, usize> = HashMap::new();
+        for index in 0..notes.len() {
+            let root = DuplicateFinder::find_root(&mut parents, index);
+            match cluster_index_of_root.get(&root) {
+                Some(cluster_index) => clusters[*cluster_index].1.push(index),
+                None => {
+                    cluster_index_of_root.insert(root, clusters.len());
+                    clusters.push((root, vec![index]));
+                }
+            }
+        }
+
+        // The roots can change while joining, so the similarities are looked up by
+        // the current root of every cluster
+        let mut root_similarities: HashMap<usize, f64> = HashMap::new();
+        for (old_root, similarity) in lowest_similarities {
+            let root = DuplicateFinder::find_root(&mut parents, old_root);
+            let root_similarity = root_similarities.entry(root).or_insert(1.0);
+            *root_similarity = root_similarity.min(similarity);
+        }
+
+        return clusters
+            .into_iter()
+            .filter(|m| m.1.len() > 1)
+            .map(|(root, indices)| DuplicateCluster {
+                note_ids: indices.iter().map(|m| notes[*m].0.clone()).collect(),
+                similarity: root_similarities.get(&root).copied(),
+            })
+            .collect();
+    }
+
+    fn find_root(parents: &mut [usize], index: usize) -> usize {
+        let mut root = index;
+        while parents[root] != root {
+            root = parents[root];
+        }
+        // Shorten the path for the next lookups
+        let mut current = index;
+        while parents[current] != root {
+            let next = parents[current];
+            parents[current] = root;
+            current = next;
+        }
+        return root;
+    }
+
+    fn join(parents: &mut [usize], first_index: usize, second_index: usize) {
+        let first_root = DuplicateFinder::find_root(parents, first_index);
+        let second_root = DuplicateFinder::find_root(parents, second_index);
+        if first_root != second_root {
+            parents[second_root.max(first_root)] = second_root.min(first_root);
+        }
+    }
+
+    // The hashes of all sequences of SHINGLE_SIZE consecutive words. Shorter contents
+    // are one sequence
+    fn get_shingles_of(content: &str) -> HashSet<u64> {
+        if content.is_empty() {
+            return HashSet::new();
+        }
+        let words: Vec<String> = content.split(' ').map(|m| m.to_lowercase()).collect();
+        return words
+            .windows(SHINGLE_SIZE.min(words.len()))
+            .map(|m| {
+                let mut hasher = DefaultHasher::new();
+                m.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+    }
+
+    // The jaccard index: the number of shared shingles divided by the number of all shingles
+    fn get_similarity(first_shingles: &HashSet<u64>, second_shingles: &HashSet<u64>) -> f64 {
+        if first_shingles.is_empty() || second_shingles.is_empty() {
+            return 0.0;
+        }
+        let shared_count = first_shingles.intersection(second_shingles).count();
+        let total_count = first_shingles.len() + second_shingles.len() - shared_count;
+        return shared_count as f64 / total_count as f64;
+    }
+}
@@ -1,6 +1,7 @@
-use std::fs::File;
-use std::io::{Error, Read};
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Read, Write};
 use std::path::Path;
+use std::process;
 
 pub struct FileUtility;
 
@@ -19,4 +20,75 @@ impl FileUtility {
 
         return Ok(file_content);
     }
+
+    // The content is written to a temporary file next to the file first, which then replaces
+    // the file. A crash or a full disk while writing can't leave a half written file that way.
+    // The temporary file doesn't end with '.md', so it's never mistaken for a note
+    pub fn write_atomically<P: AsRef<Path>, C: AsRef<[u8]>>(
+        path: P,
+        content: C,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        let file_name = match path.file_name().and_then(|m| m.to_str()) {
+            Some(value) => value,
+            None => return Err(Error::new(ErrorKind::InvalidInput, "invalid file name")),
+        };
+        let temporary_file_path =
+            path.with_file_name(format!(".{}.{}.tmp", file_name, process::id()));
+
+        let write_result = FileUtility::write_and_sync(&temporary_file_path, content.as_ref())
+            .and_then(|_| {
+                // The permissions of the replaced file are kept
+                if let Ok(metadata) = fs::metadata(path) {
+                    fs::set_permissions(&temporary_file_path, metadata.permissions())?;
+                }
+                return fs::rename(&temporary_file_path, path);
+            });
+        if let Err(error) = write_result {
+            let _ = fs::remove_file(&temporary_file_path);
+            return Err(error);
+        }
+
+        // The rename itself is only on the disk after the directory is synced too.
+        // Not every platform can open directories, so this is skipped if it fails
+        if let Some(directory) = path.parent().filter(|m| !m.as_os_str().is_empty()) {
+            if let Ok(directory) = File::open(directory) {
+                let _ = directory.sync_all();
+            }
+        }
+        return Ok(());
+    }
+
+    fn write_and_sync(path: &Path, content: &[u8]) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+        file.write_all(content)?;
+        return file.sync_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    // The temporary file is a link to '/dev/full', so writing it fails like on a full disk
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn failed_write_keeps_original_file() {
+        let directory = env::temp_dir().join(format!("brn-write-test-{}", process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let file_path = directory.join("note.md");
+        fs::write(&file_path, "original content").unwrap();
+        let temporary_file_path = directory.join(format!(".note.md.{}.tmp", process::id()));
+        std::os::unix::fs::symlink("/dev/full", &temporary_file_path).unwrap();
+
+        let write_result = FileUtility::write_atomically(&file_path, "new content");
+        let file_content = fs::read(&file_path).unwrap();
+        let is_temporary_file_left = fs::symlink_metadata(&temporary_file_path).is_ok();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(write_result.is_err());
+        assert_eq!(file_content, b"original content");
+        assert!(!is_temporary_file_left);
+    }
 }
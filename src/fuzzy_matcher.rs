@@ -0,0 +1,72 @@
+use std::cmp::Reverse;
+
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub matched_indices: Vec<usize>,
+}
+
+pub struct FuzzyMatcher;
+impl FuzzyMatcher {
+    // Consecutive matches and matches at the start of words are rated higher,
+    // gaps between matches are rated lower
+    pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+        let pattern_chars = FuzzyMatcher::to_lowercase_chars(pattern);
+        let text_chars = FuzzyMatcher::to_lowercase_chars(text);
+
+        let mut score = 0;
+        let mut matched_indices = Vec::new();
+        let mut pattern_index = 0;
+        let mut previous_match_index: Option<usize> = None;
+
+        for (text_index, text_char) in text_chars.iter().enumerate() {
+            if pattern_index >= pattern_chars.len() {
+                break;
+            }
+            if *text_char != pattern_chars[pattern_index] {
+                continue;
+            }
+
+            score += 1;
+            match previous_match_index {
+                Some(previous_index) if previous_index + 1 == text_index => score += 5,
+                Some(previous_index) => score -= (text_index - previous_index - 1).min(3) as i64,
+                None => score -= text_index.min(3) as i64,
+            }
+            if text_index == 0 || !text_chars[text_index - 1].is_alphanumeric() {
+                score += 3;
+            }
+
+            matched_indices.push(text_index);
+            previous_match_index = Some(text_index);
+            pattern_index += 1;
+        }
+
+        if pattern_index < pattern_chars.len() {
+            return None;
+        }
+
+        return Some(FuzzyMatch {
+            score,
+            matched_indices,
+        });
+    }
+
+    pub fn sort_by_score(pattern: &str, items: &[String]) -> Vec<String> {
+        let mut scored_items: Vec<(i64, &String)> = items
+            .iter()
+            .filter_map(|m| FuzzyMatcher::fuzzy_match(pattern, m).map(|v| (v.score, m)))
+            .collect();
+        scored_items.sort_by_key(|m| Reverse(m.0));
+
+        return scored_items.into_iter().map(|m| m.1.clone()).collect();
+    }
+
+    fn to_lowercase_chars(text: &str) -> Vec<char> {
+        // Only use the first char of the lowercase version so that the indices
+        // still match the characters of the original text
+        return text
+            .chars()
+            .map(|c| c.to_lowercase().next().unwrap_or(c))
+            .collect();
+    }
+}
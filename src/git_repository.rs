@@ -0,0 +1,72 @@
+use std::path::Path;
+use std::process::Command;
+
+// The notes directory can be versioned with git. Git is called as a program, so it has to be
+// installed and the configuration of the user (e.g. the author of commits) is used
+pub struct GitRepository;
+impl GitRepository {
+    // Returns None if the directory isn't part of a git repository
+    pub fn get_changed_files(dir: &Path) -> Result<Option<Vec<String>>, String> {
+        let output = match Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["status", "--porcelain", "--untracked-files=all", "--", "."])
+            .output()
+        {
+            Ok(value) => value,
+            Err(error) => return Err(format!("couldn't run git: {}", error)),
+        };
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        // Every line looks like 'XY path', where XY is the state of the file
+        let changed_files = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|m| m.len() > 3)
+            .map(|m| m[3..].to_string())
+            .collect();
+        return Ok(Some(changed_files));
+    }
+
+    // Commits all changes of the directory, including new and removed files.
+    // The message lists the changed files, so the commit can be understood without the diff
+    pub fn commit_all(dir: &Path) -> Result<usize, String> {
+        let changed_files = match GitRepository::get_changed_files(dir)? {
+            Some(value) => value,
+            None => {
+                return Err(format!(
+                    "'{}' isn't part of a git repository",
+                    dir.to_string_lossy()
+                ))
+            }
+        };
+        if changed_files.is_empty() {
+            return Ok(0);
+        }
+
+        let subject = match changed_files.len() {
+            1 => "Update 1 file".to_string(),
+            file_count => format!("Update {} files", file_count),
+        };
+        let message = format!("{}\n\n{}", subject, changed_files.join("\n"));
+        GitRepository::run_git_command(dir, &["add", "--all", "."])?;
+        GitRepository::run_git_command(dir, &["commit", "--quiet", "-m", &message, "--", "."])?;
+        return Ok(changed_files.len());
+    }
+
+    fn run_git_command(dir: &Path, args: &[&str]) -> Result<(), String> {
+        let output = match Command::new("git").arg("-C").arg(dir).args(args).output() {
+            Ok(value) => value,
+            Err(error) => return Err(format!("couldn't run git: {}", error)),
+        };
+        if !output.status.success() {
+            return Err(format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        return Ok(());
+    }
+}
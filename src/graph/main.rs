@@ -6,7 +6,7 @@ use crate::settings::Settings;
 
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
@@ -21,8 +21,8 @@ lazy_static! {
 
 pub struct Graph;
 impl Graph {
-    pub fn generate(settings: &mut Settings) -> Result<(), String> {
-        let json_string = Graph::get_json_of_notes().unwrap();
+    pub fn generate(settings: &mut Settings, include_archived: bool) -> Result<(), String> {
+        let json_string = Graph::get_json_of_notes(include_archived).unwrap();
 
         let zettelkasten_dir = &settings.zettelkasten_dir;
         let graph_generator_file_path = PathBuf::from(zettelkasten_dir).join("graph.js");
@@ -56,13 +56,19 @@ impl Graph {
         return Ok(());
     }
 
-    fn get_json_of_notes() -> Result<String, String> {
+    fn get_json_of_notes(include_archived: bool) -> Result<String, String> {
         let mut graph_vector = Vec::new();
         let mut number_of_neighbors: HashMap<String, usize> = HashMap::new();
+        let excluded_note_ids = Graph::get_excluded_note_ids(include_archived);
 
         // Edges
         let all_note_links = Database::get_all_note_links();
         for note_link in all_note_links {
+            if excluded_note_ids.contains(&note_link.source_note_id)
+                || excluded_note_ids.contains(&note_link.target_note_id)
+            {
+                continue;
+            }
             let edge_id = format!(
                 "{}->{}",
                 &note_link.source_note_id, &note_link.target_note_id
@@ -86,6 +92,9 @@ impl Graph {
         // Nodes
         let all_note_ids = Database::get_all_note_ids();
         for note_id in all_note_ids {
+            if excluded_note_ids.contains(&note_id) {
+                continue;
+            }
             if let Some(note) = Database::get_note_where_id(&note_id) {
                 let node_weight = match number_of_neighbors.get(&note_id) {
                     Some(result) => result,
@@ -135,6 +144,203 @@ impl Graph {
         }
     }
 
+    // The links of archived notes are kept in the database, so they have to be filtered out
+    pub fn get_excluded_note_ids(include_archived: bool) -> HashSet<String> {
+        if include_archived {
+            return HashSet::new();
+        }
+        return Database::get_archived_notes()
+            .into_iter()
+            .map(|m| m.note_id)
+            .collect();
+    }
+
+    // Returns the ids of the notes that are reachable within the given number of links
+    // (starting with the root note) and the links between them
+    pub fn get_neighborhood_of(
+        root_note_id: &str,
+        depth: usize,
+        excluded_note_ids: &HashSet<String>,
+    ) -> (Vec<String>, Vec<(String, String)>) {
+        return Graph::get_neighborhood_of_notes(
+            &[root_note_id.to_string()],
+            depth,
+            false,
+            excluded_note_ids,
+        );
+    }
+
+    // Returns the ids of the notes with the tag (including nested tags), followed by the ids of
+    // the notes that link to or are linked from them within the given number of links
+    pub fn get_topic_neighborhood_of(
+        tag_name: &str,
+        depth: usize,
+        excluded_note_ids: &HashSet<String>,
+    ) -> (Vec<String>, Vec<(String, String)>) {
+        let tagged_note_ids: Vec<String> = Database::get_notes_with_tag(tag_name)
+            .into_iter()
+            .map(|m| m.note_id)
+            .filter(|m| !excluded_note_ids.contains(m))
+            .collect();
+        return Graph::get_neighborhood_of_notes(&tagged_note_ids, depth, true, excluded_note_ids);
+    }
+
+    fn get_neighborhood_of_notes(
+        root_note_ids: &[String],
+        depth: usize,
+        follow_incoming_links: bool,
+        excluded_note_ids: &HashSet<String>,
+    ) -> (Vec<String>, Vec<(String, String)>) {
+        let mut note_ids = Vec::new();
+        let mut note_links = Vec::new();
+
+        // Breadth-first search to only get the notes within the given depth
+        let mut visited_note_ids = HashSet::new();
+        let mut visited_note_links = HashSet::new();
+        let mut note_queue = VecDeque::new();
+        for root_note_id in root_note_ids {
+            if visited_note_ids.insert(root_note_id.clone()) {
+                note_ids.push(root_note_id.clone());
+                note_queue.push_back((root_note_id.clone(), 0));
+            }
+        }
+
+        while let Some((note_id, note_depth)) = note_queue.pop_front() {
+            if note_depth >= depth {
+                continue;
+            }
+            let mut neighbor_links: Vec<(String, String)> = Database::get_outgoing_links(&note_id)
+                .into_iter()
+                .map(|m| (note_id.clone(), m))
+                .collect();
+            if follow_incoming_links {
+                neighbor_links.extend(
+                    Database::get_incoming_links(&note_id)
+                        .into_iter()
+                        .map(|m| (m, note_id.clone())),
+                );
+            }
+
+            for (source_note_id, target_note_id) in neighbor_links {
+                let neighbor_note_id = if source_note_id == note_id {
+                    target_note_id.clone()
+                } else {
+                    source_note_id.clone()
+                };
+                if excluded_note_ids.contains(&neighbor_note_id) {
+                    continue;
+                }
+                // Links between two root notes are found from both of them
+                if visited_note_links.insert((source_note_id.clone(), target_note_id.clone())) {
+                    note_links.push((source_note_id, target_note_id));
+                }
+                if visited_note_ids.insert(neighbor_note_id.clone()) {
+                    note_ids.push(neighbor_note_id.clone());
+                    note_queue.push_back((neighbor_note_id, note_depth + 1));
+                }
+            }
+        }
+        return (note_ids, note_links);
+    }
+
+    // Without a root note the whole zettelkasten is exported
+    pub fn get_dot_of_notes(
+        root_note_id: Option<&str>,
+        depth: usize,
+        include_archived: bool,
+    ) -> Result<String, String> {
+        let excluded_note_ids = Graph::get_excluded_note_ids(include_archived);
+        let note_ids: Vec<String>;
+        let note_links: Vec<(String, String)>;
+
+        if let Some(root_note_id) = root_note_id {
+            if Database::get_note_where_id(root_note_id).is_none() {
+                return Err(format!(
+                    "export-graph: the note id '{}' does not exist!",
+                    root_note_id
+                ));
+            }
+            let neighborhood = Graph::get_neighborhood_of(root_note_id, depth, &excluded_note_ids);
+            note_ids = neighborhood.0;
+            note_links = neighborhood.1;
+        } else {
+            note_ids = Database::get_all_note_ids()
+                .into_iter()
+                .filter(|m| !excluded_note_ids.contains(m))
+                .collect();
+            note_links = Database::get_all_note_links()
+                .into_iter()
+                .filter(|m| {
+                    !excluded_note_ids.contains(&m.source_note_id)
+                        && !excluded_note_ids.contains(&m.target_note_id)
+                })
+                .map(|m| (m.source_note_id, m.target_note_id))
+                .collect();
+        }
+
+        return Graph::get_dot_of(&note_ids, &note_links, &HashSet::new());
+    }
+
+    // The notes with the tag are drawn in bold to tell them apart from their neighbors
+    pub fn get_dot_of_topic(
+        tag_name: &str,
+        depth: usize,
+        include_archived: bool,
+    ) -> Result<String, String> {
+        let excluded_note_ids = Graph::get_excluded_note_ids(include_archived);
+        let (note_ids, note_links) =
+            Graph::get_topic_neighborhood_of(tag_name, depth, &excluded_note_ids);
+        let tagged_note_ids: HashSet<String> = Database::get_notes_with_tag(tag_name)
+            .into_iter()
+            .map(|m| m.note_id)
+            .collect();
+        return Graph::get_dot_of(&note_ids, &note_links, &tagged_note_ids);
+    }
+
+    fn get_dot_of(
+        note_ids: &[String],
+        note_links: &[(String, String)],
+        highlighted_note_ids: &HashSet<String>,
+    ) -> Result<String, String> {
+        let mut dot_builder = Builder::default();
+        dot_builder.append("digraph zettelkasten {\n");
+
+        // Notes without links are shown as well
+        for note_id in note_ids {
+            let note_name = match Database::get_note_where_id(note_id) {
+                Some(note) => note.note_name,
+                None => note_id.clone(),
+            };
+            let style = match highlighted_note_ids.contains(note_id) {
+                true => ", style=bold",
+                false => "",
+            };
+            dot_builder.append(format!(
+                "    \"{}\" [label=\"{}\"{}];\n",
+                Graph::escape_dot_string(note_id),
+                Graph::escape_dot_string(&note_name),
+                style
+            ));
+        }
+        for (source_note_id, target_note_id) in note_links {
+            dot_builder.append(format!(
+                "    \"{}\" -> \"{}\";\n",
+                Graph::escape_dot_string(source_note_id),
+                Graph::escape_dot_string(target_note_id)
+            ));
+        }
+
+        dot_builder.append("}\n");
+        return match dot_builder.string() {
+            Ok(value) => Ok(value),
+            Err(error) => Err(format!("export-graph: {}", error)),
+        };
+    }
+
+    fn escape_dot_string(text: &str) -> String {
+        return text.replace('\\', "\\\\").replace('"', "\\\"");
+    }
+
     pub fn show(settings: &mut Settings) -> Result<(), String> {
         let zettelkasten_dir = &settings.zettelkasten_dir;
         let browser = match env::var("BROWSER") {
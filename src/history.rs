@@ -10,6 +10,12 @@ pub struct History {
     history_file_path: PathBuf,
 }
 
+impl Default for History {
+    fn default() -> History {
+        return History::new();
+    }
+}
+
 impl History {
     pub fn new() -> History {
         return History {
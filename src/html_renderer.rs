@@ -0,0 +1,238 @@
+use crate::brn_tui::markdown_renderer::{
+    ColumnAlignment, InlineElement, MarkdownLine, MarkdownRenderer,
+};
+use crate::note_utility::NoteUtility;
+
+// The blocks of html that span several lines of the note
+#[derive(PartialEq)]
+enum Block {
+    None,
+    Paragraph,
+    List { is_ordered: bool },
+    Blockquote,
+    Code,
+    Table,
+}
+
+// Renders markdown notes to html with the parser of the preview, so that the export shows
+// the notes the same way. The targets of links are resolved by the caller
+pub struct HtmlRenderer;
+impl HtmlRenderer {
+    // get_note_link returns the href and the text of a link of brn. get_file_href returns
+    // the href of a relative link or image. Links without a href are only shown as text
+    pub fn render<F, G>(content: &str, mut get_note_link: F, mut get_file_href: G) -> String
+    where
+        F: FnMut(&str) -> (Option<String>, String),
+        G: FnMut(&str) -> Option<String>,
+    {
+        let mut html = String::new();
+        let mut current_block = Block::None;
+        let mut table_alignments = Vec::new();
+
+        let mut lines = MarkdownRenderer::parse_lines(content)
+            .into_iter()
+            .peekable();
+        while let Some(line) = lines.next() {
+            match line {
+                MarkdownLine::YamlHeader(_) => (),
+                MarkdownLine::CodeFence { language, .. } => {
+                    HtmlRenderer::close_block(&mut html, &mut current_block);
+                    if let Some(language) = language {
+                        match language.as_str() {
+                            "" => html.push_str("<pre><code>"),
+                            _ => html.push_str(&format!(
+                                "<pre><code class=\"language-{}\">",
+                                HtmlRenderer::escape(&language)
+                            )),
+                        }
+                        current_block = Block::Code;
+                    }
+                }
+                MarkdownLine::Code(line) => {
+                    html.push_str(&HtmlRenderer::escape(line));
+                    html.push('\n');
+                }
+                MarkdownLine::Heading { level, text } => {
+                    HtmlRenderer::close_block(&mut html, &mut current_block);
+                    html.push_str(&format!(
+                        "<h{0}>{1}</h{0}>\n",
+                        level,
+                        HtmlRenderer::render_inline(text, &mut get_note_link, &mut get_file_href)
+                    ));
+                }
+                MarkdownLine::HorizontalRule => {
+                    HtmlRenderer::close_block(&mut html, &mut current_block);
+                    html.push_str("<hr>\n");
+                }
+                MarkdownLine::ListItem { marker, text, .. } => {
+                    let list_block = Block::List {
+                        is_ordered: marker.starts_with(|c: char| c.is_ascii_digit()),
+                    };
+                    if current_block != list_block {
+                        HtmlRenderer::close_block(&mut html, &mut current_block);
+                        html.push_str(match list_block {
+                            Block::List { is_ordered: true } => "<ol>\n",
+                            _ => "<ul>\n",
+                        });
+                        current_block = list_block;
+                    }
+                    html.push_str(&format!(
+                        "<li>{}</li>\n",
+                        HtmlRenderer::render_inline(text, &mut get_note_link, &mut get_file_href)
+                    ));
+                }
+                MarkdownLine::Blockquote(text) => {
+                    if current_block == Block::Blockquote {
+                        html.push('\n');
+                    } else {
+                        HtmlRenderer::close_block(&mut html, &mut current_block);
+                        html.push_str("<blockquote>");
+                        current_block = Block::Blockquote;
+                    }
+                    html.push_str(&HtmlRenderer::render_inline(
+                        text,
+                        &mut get_note_link,
+                        &mut get_file_href,
+                    ));
+                }
+                // The alignments of the columns are only known after the header row
+                MarkdownLine::TableRow(cells) => {
+                    let cell_tag;
+                    if current_block == Block::Table {
+                        cell_tag = "td";
+                    } else {
+                        HtmlRenderer::close_block(&mut html, &mut current_block);
+                        html.push_str("<table>\n<thead>\n");
+                        current_block = Block::Table;
+                        cell_tag = "th";
+                        if let Some(MarkdownLine::TableDelimiter(alignments)) = lines.peek() {
+                            table_alignments = alignments.clone();
+                        }
+                    }
+
+                    html.push_str("<tr>");
+                    for (column_index, cell) in cells.iter().enumerate() {
+                        let style = match table_alignments.get(column_index) {
+                            Some(ColumnAlignment::Center) => " style=\"text-align: center\"",
+                            Some(ColumnAlignment::Right) => " style=\"text-align: right\"",
+                            _ => "",
+                        };
+                        html.push_str(&format!(
+                            "<{0}{1}>{2}</{0}>",
+                            cell_tag,
+                            style,
+                            HtmlRenderer::render_inline(
+                                &cell.replace("\\|", "|"),
+                                &mut get_note_link,
+                                &mut get_file_href
+                            )
+                        ));
+                    }
+                    html.push_str("</tr>\n");
+                }
+                MarkdownLine::TableDelimiter(_) => html.push_str("</thead>\n<tbody>\n"),
+                // Consecutive lines of text belong to the same paragraph
+                MarkdownLine::Text(text) => {
+                    if text.trim().is_empty() {
+                        HtmlRenderer::close_block(&mut html, &mut current_block);
+                        continue;
+                    }
+                    if current_block == Block::Paragraph {
+                        html.push('\n');
+                    } else {
+                        HtmlRenderer::close_block(&mut html, &mut current_block);
+                        html.push_str("<p>");
+                        current_block = Block::Paragraph;
+                    }
+                    html.push_str(&HtmlRenderer::render_inline(
+                        text.trim(),
+                        &mut get_note_link,
+                        &mut get_file_href,
+                    ));
+                }
+            }
+        }
+        HtmlRenderer::close_block(&mut html, &mut current_block);
+        return html;
+    }
+
+    fn render_inline<F, G>(text: &str, get_note_link: &mut F, get_file_href: &mut G) -> String
+    where
+        F: FnMut(&str) -> (Option<String>, String),
+        G: FnMut(&str) -> Option<String>,
+    {
+        let mut html = String::new();
+        for element in MarkdownRenderer::parse_inline(text) {
+            match element {
+                InlineElement::Text(text) => html.push_str(&HtmlRenderer::escape(text)),
+                InlineElement::Image { text, path } => {
+                    let src = match NoteUtility::is_relative_file_link(path) {
+                        true => get_file_href(path),
+                        false => Some(path.to_string()),
+                    };
+                    match src {
+                        Some(src) => html.push_str(&format!(
+                            "<img src=\"{}\" alt=\"{}\">",
+                            HtmlRenderer::escape(&src),
+                            HtmlRenderer::escape(text)
+                        )),
+                        None => html.push_str(&HtmlRenderer::escape(text)),
+                    }
+                }
+                InlineElement::Link { text, target, .. } => {
+                    let href = match NoteUtility::is_relative_file_link(target) {
+                        true => get_file_href(target),
+                        false => Some(target.to_string()),
+                    };
+                    html.push_str(&HtmlRenderer::get_link_html(href, text));
+                }
+                InlineElement::NoteLink { link_text, .. } => {
+                    let (href, text) = get_note_link(link_text);
+                    html.push_str(&HtmlRenderer::get_link_html(href, &text));
+                }
+                InlineElement::Bold(text) => {
+                    html.push_str(&format!("<strong>{}</strong>", HtmlRenderer::escape(text)))
+                }
+                InlineElement::Italic(text) => {
+                    html.push_str(&format!("<em>{}</em>", HtmlRenderer::escape(text)))
+                }
+                InlineElement::Code(text) => {
+                    html.push_str(&format!("<code>{}</code>", HtmlRenderer::escape(text)))
+                }
+            }
+        }
+        return html;
+    }
+
+    fn get_link_html(href: Option<String>, text: &str) -> String {
+        return match href {
+            Some(href) => format!(
+                "<a href=\"{}\">{}</a>",
+                HtmlRenderer::escape(&href),
+                HtmlRenderer::escape(text)
+            ),
+            None => HtmlRenderer::escape(text),
+        };
+    }
+
+    fn close_block(html: &mut String, current_block: &mut Block) {
+        match current_block {
+            Block::None => return,
+            Block::Paragraph => html.push_str("</p>\n"),
+            Block::List { is_ordered: true } => html.push_str("</ol>\n"),
+            Block::List { is_ordered: false } => html.push_str("</ul>\n"),
+            Block::Blockquote => html.push_str("</blockquote>\n"),
+            Block::Code => html.push_str("</code></pre>\n"),
+            Block::Table => html.push_str("</tbody>\n</table>\n"),
+        }
+        *current_block = Block::None;
+    }
+
+    pub fn escape(text: &str) -> String {
+        return text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;");
+    }
+}
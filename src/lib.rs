@@ -0,0 +1,74 @@
+//! The core of brn, which can be used to build other frontends for a zettelkasten.
+//!
+//! A zettelkasten is opened with [`Settings::open`](settings::Settings::open), which also
+//! prepares the database. Problems with its config are collected in
+//! [`Settings::warnings`](settings::Settings::warnings). Afterwards the notes are accessed with:
+//!
+//! - [`NoteUtility`](note_utility::NoteUtility): creating, renaming, removing, tagging and
+//!   linking notes and reading their content. These functions keep the note files and the
+//!   database in sync
+//! - [`Database`](database::Database): queries of notes, links and tags that only read the
+//!   database, e.g. the incoming links or the tags of a note
+//!
+//! Notes are returned as [`Note`](note::Note) and identified by their id. Opening the
+//! zettelkasten or a notebook, looking up notes by name, reading their metadata, adding,
+//! opening and changing notes (e.g. removing, renaming, merging, moving or tagging them) fails
+//! with a [`BrainError`](brain_error::BrainError). The queries of
+//! [`Database`](database::Database) don't fail, they return no result instead and their
+//! errors are collected until they're taken with
+//! [`Database::take_errors`](database::Database::take_errors).
+
+pub mod brain_error;
+pub mod database;
+pub mod destructive_action;
+pub mod duplicate_finder;
+pub mod moved_note;
+pub mod note;
+pub mod note_activity;
+pub mod note_label;
+pub mod note_link;
+pub mod note_metadata;
+pub mod note_property;
+pub mod note_statistics;
+pub mod note_tagging;
+pub mod note_tasks;
+pub mod note_type;
+pub mod note_utility;
+pub mod notebook;
+pub mod removed_note;
+pub mod search_query;
+pub mod settings;
+pub mod sort_order;
+pub mod tag_order;
+pub mod zettelkasten_statistics;
+
+// The frontends of the brn binary and the modules they are built from
+mod brn_tui;
+mod collection_tool;
+mod directory;
+mod file_utility;
+mod fuzzy_matcher;
+mod git_repository;
+mod graph;
+mod history;
+mod html_renderer;
+mod message;
+mod name_overflow;
+mod note_export;
+mod note_id_generator;
+mod note_list_output;
+mod random_generator;
+mod render_mode;
+
+// The parts of the frontends that the brn binary needs, they aren't meant to be used by other
+// programs
+#[doc(hidden)]
+pub mod frontend {
+    pub use crate::brn_tui::main::BrnTui;
+    pub use crate::directory::Directory;
+    pub use crate::graph::main::Graph;
+    pub use crate::message::Message;
+    pub use crate::note_export::{NoteExport, DEFAULT_HTML_STYLESHEET};
+    pub use crate::note_list_output::{ListColumn, ListFormat, NoteListOutput};
+    pub use crate::random_generator::RandomGenerator;
+}
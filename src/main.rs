@@ -1,36 +1,33 @@
-mod brn_tui;
-mod collection_tool;
-mod database;
-mod directory;
-mod file_utility;
-mod graph;
-mod history;
-mod message;
-mod note;
-mod note_link;
-mod note_metadata;
-mod note_property;
-mod note_tagging;
-mod note_type;
-mod note_utility;
-mod settings;
-
-use brn_tui::main::BrnTui;
-use database::Database;
-use directory::Directory;
-use graph::main::Graph;
-use message::Message;
-use note_property::NoteProperty;
-use note_type::NoteType;
-use note_utility::NoteUtility;
-use settings::Settings;
+use brn::brain_error::BrainError;
+use brn::database::Database;
+use brn::destructive_action::DestructiveAction;
+use brn::duplicate_finder::DuplicateCluster;
+use brn::frontend::{
+    BrnTui, Directory, Graph, ListColumn, ListFormat, Message, NoteExport, NoteListOutput,
+    RandomGenerator, DEFAULT_HTML_STYLESHEET,
+};
+use brn::note::Note;
+use brn::note_activity::NoteActivity;
+use brn::note_tagging::NoteTagging;
+use brn::note_type::NoteType;
+use brn::note_utility::NoteUtility;
+use brn::notebook::{Notebook, DEFAULT_NOTEBOOK_NAME};
+use brn::settings::Settings;
+use brn::sort_order::SortOrder;
+use brn::tag_order::TagOrder;
+use brn::zettelkasten_statistics::ZettelkastenStatistics;
 
+use chrono::{DateTime, Local, NaiveDate};
 use clap::{crate_name, crate_version, App, AppSettings, Arg, ArgMatches, SubCommand};
+use colored::*;
 use include_dir::{include_dir, Dir};
+use indoc::indoc;
 use std::env;
 use std::ffi::OsStr;
 use std::fs;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
 
 fn main() {
     let matches = App::new("Brain")
@@ -43,6 +40,13 @@ fn main() {
             .short("d")
             .long("dir")
             .takes_value(true)
+            .conflicts_with("notebook")
+        )
+        .arg(Arg::with_name("notebook")
+            .help("Use the zettelkasten directory of a notebook configured in 'brn/notebooks.yml' in the config directory")
+            .short("n")
+            .long("notebook")
+            .takes_value(true)
         )
         .arg(Arg::with_name("no-backlinking")
             .help("Don't use automatic backlinking of notes")
@@ -64,12 +68,54 @@ fn main() {
                 .takes_value(true)
                 .default_value("100")
             )
+            .arg(Arg::with_name("property")
+                .help("Only lists the notes with the given property in their YAML header (e.g. 'status=draft')")
+                .short("p")
+                .long("property")
+                .value_name("key=value")
+                .takes_value(true)
+            )
+            .arg(Arg::with_name("archived")
+                .help("Lists the archived notes instead")
+                .short("a")
+                .long("archived")
+            )
+            .arg(Arg::with_name("filter")
+                .help("Only lists the notes whose name matches the given text like in the search of the TUI")
+                .short("f")
+                .long("filter")
+                .takes_value(true)
+            )
+            .arg(Arg::with_name("sort")
+                .help("The sort order of the notes")
+                .short("s")
+                .long("sort")
+                .takes_value(true)
+                .possible_values(&["creation-date-desc", "creation-date", "modification-date-desc", "modification-date", "name", "name-desc", "frecency"])
+                .default_value("creation-date-desc")
+            )
+            .arg(Arg::with_name("columns")
+                .help("The columns that are printed for every note")
+                .long("columns")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&["id", "name", "tags", "date"])
+                .default_value("id,name")
+            )
+            .arg(Arg::with_name("format")
+                .help("The output format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["plain", "tsv", "json"])
+                .default_value("plain")
+            )
         )
         .subcommand(SubCommand::with_name("open")
-            .about("Opens the specified note")
+            .about("Opens the specified notes. Several notes are opened in the editor at once")
             .arg(Arg::with_name("name")
-                .help("The name or ID of the note")
+                .help("The names or IDs of the notes")
                 .required(true)
+                .multiple(true)
             )
         )
         .subcommand(SubCommand::with_name("search")
@@ -78,9 +124,154 @@ fn main() {
                 .help("The search string that is used to find notes")
                 .required(true)
             )
+            .arg(Arg::with_name("content")
+                .help("Searches the content of the notes instead of their names and tags")
+                .short("c")
+                .long("content")
+            )
         )
         .subcommand(SubCommand::with_name("random")
-            .about("Opens a random note")
+            .about("Opens a random note that isn't archived")
+            .arg(Arg::with_name("tag")
+                .help("Only chooses notes with the given tag")
+                .short("t")
+                .long("tag")
+                .takes_value(true)
+            )
+        )
+        .subcommand(SubCommand::with_name("tag")
+            .about("Adds a tag to a note. New tags that are similar to existing ones have to be confirmed")
+            .setting(AppSettings::SubcommandsNegateReqs)
+            .arg(Arg::with_name("name")
+                .help("The name or ID of the note")
+                .required(true)
+            )
+            .arg(Arg::with_name("tag")
+                .help("The name of the tag")
+                .required(true)
+            )
+            .subcommand(SubCommand::with_name("rename")
+                .about("Renames a tag in all notes including the tags nested in it")
+                .arg(Arg::with_name("old-tag")
+                    .help("The current name of the tag")
+                    .required(true)
+                )
+                .arg(Arg::with_name("new-tag")
+                    .help("The new name of the tag")
+                    .required(true)
+                )
+                .arg(Arg::with_name("dry-run")
+                    .help("Only shows the notes that would be changed")
+                    .short("d")
+                    .long("dry-run")
+                )
+                .arg(Arg::with_name("yes")
+                    .help("Doesn't ask for confirmation, even if it's enabled with 'confirm' in the config")
+                    .short("y")
+                    .long("yes")
+                )
+            )
+            .subcommand(SubCommand::with_name("merge")
+                .about("Replaces several tags with one tag in all notes")
+                .arg(Arg::with_name("tags")
+                    .help("The tags that are merged")
+                    .required(true)
+                    .multiple(true)
+                )
+                .arg(Arg::with_name("into")
+                    .help("The tag that replaces the merged tags")
+                    .required(true)
+                )
+                .arg(Arg::with_name("dry-run")
+                    .help("Only shows the notes that would be changed")
+                    .short("d")
+                    .long("dry-run")
+                )
+                .arg(Arg::with_name("yes")
+                    .help("Doesn't ask for confirmation, even if it's enabled with 'confirm' in the config")
+                    .short("y")
+                    .long("yes")
+                )
+            )
+        )
+        .subcommand(SubCommand::with_name("tags")
+            .about("Lists all tags with the number of notes using them")
+            .arg(Arg::with_name("sort")
+                .help("Sorts the tags by their name or by the number of notes using them")
+                .short("s")
+                .long("sort")
+                .takes_value(true)
+                .possible_values(&["name", "count"])
+                .default_value("name")
+            )
+            .arg(Arg::with_name("roll-up")
+                .help("Counts the notes with nested tags (e.g. 'tag/nested-tag') for their parent tags too")
+                .short("r")
+                .long("roll-up")
+            )
+        )
+        .subcommand(SubCommand::with_name("stats")
+            .about("Shows the word count, character count and reading time of a note")
+            .arg(Arg::with_name("name")
+                .help("The name or ID of the note")
+                .required(true)
+            )
+        )
+        .subcommand(SubCommand::with_name("info")
+            .about("Shows the number of notes, tags, links and orphans, the average note length and the database size")
+            .arg(Arg::with_name("json")
+                .help("Prints the numbers as json")
+                .long("json")
+            )
+        )
+        .subcommand(SubCommand::with_name("activity")
+            .about("Shows the number of notes created and modified per day")
+            .arg(Arg::with_name("since")
+                .help("Only shows the days from this date on (YYYY-MM-DD)")
+                .long("since")
+                .takes_value(true)
+            )
+            .arg(Arg::with_name("until")
+                .help("Only shows the days up to this date (YYYY-MM-DD)")
+                .long("until")
+                .takes_value(true)
+            )
+            .arg(Arg::with_name("json")
+                .help("Prints the days as json, e.g. to draw a heatmap")
+                .long("json")
+            )
+        )
+        .subcommand(SubCommand::with_name("orphans")
+            .about("Lists all notes that aren't linked by any other note")
+            .arg(Arg::with_name("archived")
+                .help("Includes archived notes and their links")
+                .short("a")
+                .long("archived")
+            )
+        )
+        .subcommand(SubCommand::with_name("deadends")
+            .about("Lists all notes that don't link to any other note")
+            .arg(Arg::with_name("archived")
+                .help("Includes archived notes and their links")
+                .short("a")
+                .long("archived")
+            )
+        )
+        .subcommand(SubCommand::with_name("check")
+            .about("Lists all links that don't lead to an existing note. Exits with an error if there are any")
+            .arg(Arg::with_name("fix")
+                .help("Replaces the broken links with their plain text after asking for confirmation")
+                .long("fix")
+            )
+        )
+        .subcommand(SubCommand::with_name("dedupe")
+            .about("Lists groups of notes with identical content. Doesn't change any note")
+            .arg(Arg::with_name("similarity")
+                .help("Also lists notes that share at least this percentage of their text (1-100)")
+                .short("s")
+                .long("similarity")
+                .takes_value(true)
+            )
         )
         .subcommand(SubCommand::with_name("history")
             .about("Shows a history of recently visited notes")
@@ -106,13 +297,116 @@ fn main() {
                 .short("j")
                 .long("journal")
             )
+            .arg(Arg::with_name("template")
+                .help("The name of the template in the templates directory that the note is created from")
+                .long("template")
+                .value_name("name")
+                .takes_value(true)
+            )
+            .arg(Arg::with_name("parent")
+                .help("The name or ID of the note the new note is a child of. With the hierarchical id scheme the id of the new note is derived from it")
+                .long("parent")
+                .value_name("note")
+                .takes_value(true)
+            )
         )
         .subcommand(SubCommand::with_name("rm")
-            .about("Removes a note from the zettelkasten")
+            .about("Removes a note from the zettelkasten. Its file is moved into .zettelkasten/trash, which only keeps the last removed note")
             .arg(Arg::with_name("name")
                 .help("The name or ID of the note to remove")
                 .required(true)
             )
+            .arg(Arg::with_name("yes")
+                .help("Doesn't ask for confirmation, even if it's enabled with 'confirm' in the config")
+                .short("y")
+                .long("yes")
+            )
+        )
+        .subcommand(SubCommand::with_name("rename")
+            .about("Renames all notes whose name matches a pattern and updates the links to them")
+            .arg(Arg::with_name("pattern")
+                .help("The regular expression the note names have to match")
+                .short("p")
+                .long("pattern")
+                .takes_value(true)
+                .required(true)
+            )
+            .arg(Arg::with_name("replace")
+                .help("The replacement of the matched text. Groups of the pattern can be used with $1, $2, ...")
+                .short("r")
+                .long("replace")
+                .takes_value(true)
+                .required(true)
+            )
+            .arg(Arg::with_name("dry-run")
+                .help("Only shows the old and the new names")
+                .short("d")
+                .long("dry-run")
+            )
+            .arg(Arg::with_name("yes")
+                .help("Doesn't ask for confirmation, even if it's enabled with 'confirm' in the config")
+                .short("y")
+                .long("yes")
+            )
+        )
+        .subcommand(SubCommand::with_name("archive")
+            .about("Hides a note from the note list without deleting it")
+            .arg(Arg::with_name("name")
+                .help("The name or ID of the note to archive")
+                .required(true)
+            )
+        )
+        .subcommand(SubCommand::with_name("unarchive")
+            .about("Restores an archived note")
+            .arg(Arg::with_name("name")
+                .help("The name or ID of the note to restore")
+                .required(true)
+            )
+        )
+        .subcommand(SubCommand::with_name("capture")
+            .about("Appends a timestamped entry to the inbox note")
+            .arg(Arg::with_name("text")
+                .help("The text of the entry")
+                .required(true)
+            )
+        )
+        .subcommand(SubCommand::with_name("move")
+            .about("Moves a note into another notebook")
+            .arg(Arg::with_name("name")
+                .help("The name or ID of the note to move")
+                .required(true)
+            )
+            .arg(Arg::with_name("to")
+                .help("The name of the notebook the note is moved into")
+                .long("to")
+                .takes_value(true)
+                .required(true)
+            )
+            .arg(Arg::with_name("unlink")
+                .help("Replaces the links between the moved note and the notes of this notebook with their text")
+                .long("unlink")
+            )
+            .arg(Arg::with_name("yes")
+                .help("Doesn't ask for confirmation, even if it's enabled with 'confirm' in the config")
+                .short("y")
+                .long("yes")
+            )
+        )
+        .subcommand(SubCommand::with_name("merge")
+            .about("Appends the content of a note to another note, changes the links to it and removes it")
+            .arg(Arg::with_name("from")
+                .help("The name or ID of the note that is merged and removed")
+                .required(true)
+            )
+            .arg(Arg::with_name("into")
+                .help("The name or ID of the note that the content is appended to")
+                .required(true)
+            )
+            .arg(Arg::with_name("yes")
+                .help("Doesn't ask for confirmation, even if it's enabled with 'confirm' in the config")
+                .short("y")
+                .long("yes")
+            )
         )
         .subcommand(SubCommand::with_name("update-db")
             .about("Updates the database entries for all notes in the zettelkasten directory")
@@ -131,20 +425,192 @@ fn main() {
                 .required(true)
             )
         )
+        .subcommand(SubCommand::with_name("export")
+            .about("Exports all notes with their metadata")
+            .arg(Arg::with_name("format")
+                .help("The format of the export")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["json", "obsidian", "html"])
+                .default_value("json")
+            )
+            .arg(Arg::with_name("output")
+                .help("The file the json export (default is stdout) or the directory the html export is written to")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .required_if("format", "html")
+            )
+            .arg(Arg::with_name("target")
+                .help("The directory the Obsidian vault is written to or the name or ID of the note that is exported to html")
+                .required_if("format", "obsidian")
+            )
+            .arg(Arg::with_name("all")
+                .help("Exports all notes to html")
+                .long("all")
+                .conflicts_with("target")
+            )
+            .arg(Arg::with_name("css")
+                .help("The stylesheet that is embedded into the html files instead of the default one")
+                .long("css")
+                .takes_value(true)
+            )
+        )
+        .subcommand(SubCommand::with_name("bundle")
+            .about("Writes a note and the notes it links to into a single markdown file that can be read without brn")
+            .arg(Arg::with_name("note")
+                .help("The name or ID of the note the bundle starts from")
+                .required(true)
+            )
+            .arg(Arg::with_name("depth")
+                .help("The maximum number of links between the note and the bundled notes (default: 1)")
+                .long("depth")
+                .takes_value(true)
+            )
+            .arg(Arg::with_name("output")
+                .help("The markdown file the bundle is written to. Attachments are copied into the directory '<file>_attachments' next to it")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .required(true)
+            )
+            .arg(Arg::with_name("archived")
+                .help("Includes archived notes")
+                .short("a")
+                .long("archived")
+            )
+        )
+        .subcommand(SubCommand::with_name("import")
+            .about("Imports the notes of an export created with 'brn export' or of a folder of markdown files like an Obsidian vault")
+            .arg(Arg::with_name("format")
+                .help("The format of the import")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["json", "obsidian"])
+                .default_value("json")
+            )
+            .arg(Arg::with_name("file")
+                .help("The exported json file or the directory of the markdown files")
+                .required(true)
+            )
+        )
         .subcommand(SubCommand::with_name("graph")
             .about("Shows a graph virtualization of the zettelkasten in the browser.")
+            .arg(Arg::with_name("format")
+                .help("Prints the graph in the specified format instead of showing it in the browser")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["dot"])
+            )
+            .arg(Arg::with_name("root")
+                .help("The name or ID of the note the exported graph starts from")
+                .long("root")
+                .takes_value(true)
+                .requires("format")
+            )
+            .arg(Arg::with_name("depth")
+                .help("The maximum number of links between the root note and the exported notes (default: 1)")
+                .long("depth")
+                .takes_value(true)
+                .requires("root")
+            )
+            .arg(Arg::with_name("archived")
+                .help("Includes archived notes and their links")
+                .short("a")
+                .long("archived")
+            )
+        )
+        .subcommand(SubCommand::with_name("topic")
+            .about("Lists the notes with a tag and the notes that link to or are linked from them")
+            .arg(Arg::with_name("tag")
+                .help("The tag of the topic. Notes with nested tags (e.g. 'tag/nested-tag') are included")
+                .required(true)
+            )
+            .arg(Arg::with_name("depth")
+                .help("The maximum number of links between the notes with the tag and the listed notes (default: 1)")
+                .long("depth")
+                .takes_value(true)
+            )
+            .arg(Arg::with_name("graph")
+                .help("Prints the notes and the links between them in the DOT format instead")
+                .long("graph")
+            )
+            .arg(Arg::with_name("archived")
+                .help("Includes archived notes and their links")
+                .short("a")
+                .long("archived")
+            )
         )
         .get_matches();
 
-    let notes_dir = matches
-        .value_of_os("directory")
-        .unwrap_or(OsStr::new("./"))
-        .to_os_string();
+    let mut notebooks = match Notebook::load_all() {
+        Ok(value) => value,
+        Err(error) => {
+            Message::warning(&("loading notebooks: ".to_string() + &error));
+            Vec::new()
+        }
+    };
+
+    let notebook_name;
+    let notes_dir;
+    if let Some(selected_notebook_name) = matches.value_of("notebook") {
+        match notebooks.iter().find(|m| m.name == selected_notebook_name) {
+            Some(notebook) => notes_dir = notebook.notes_dir.clone(),
+            None => {
+                Message::error(&format!(
+                    "the notebook '{}' doesn't exist",
+                    selected_notebook_name
+                ));
+                if let Some(config_file_path) = Notebook::get_config_file_path() {
+                    Message::hint(&format!(
+                        "notebooks are configured in '{}'",
+                        config_file_path.to_string_lossy()
+                    ));
+                }
+                return;
+            }
+        }
+        notebook_name = selected_notebook_name.to_string();
+    } else {
+        notes_dir = matches
+            .value_of_os("directory")
+            .unwrap_or(OsStr::new("./"))
+            .to_os_string();
+
+        // The directory could belong to a configured notebook
+        notebook_name = match notebooks
+            .iter()
+            .find(|m| Notebook::is_same_dir(&m.notes_dir, &notes_dir))
+        {
+            Some(notebook) => notebook.name.clone(),
+            None => {
+                notebooks.insert(
+                    0,
+                    Notebook {
+                        name: DEFAULT_NOTEBOOK_NAME.to_string(),
+                        notes_dir: notes_dir.clone(),
+                    },
+                );
+                DEFAULT_NOTEBOOK_NAME.to_string()
+            }
+        };
+    }
+
     let notes_dir_path = Path::new(&notes_dir);
     let zettelkasten_dir = notes_dir_path.join(".zettelkasten").into_os_string();
     let mut settings = Settings::init(notes_dir, zettelkasten_dir);
+    settings.notebook_name = notebook_name;
+    settings.notebooks = notebooks;
+    for warning in settings.warnings.drain(..) {
+        Message::warning(&warning);
+    }
 
     Database::set_db_path(&settings.zettelkasten_dir);
+    if Directory::is_zettelkasten_dir(&settings.notes_dir, true) {
+        for warning in NoteUtility::migrate_db(&settings, Message::info) {
+            Message::warning(&warning);
+        }
+    }
 
     if matches.is_present("no-backlinking") {
         settings.backlinking_enabled = false;
@@ -159,9 +625,32 @@ fn main() {
         ("open", Some(open_matches)) => exec_open_command(&open_matches, &mut settings),
         ("search", Some(search_matches)) => exec_search_command(&search_matches, &mut settings),
         ("random", Some(random_matches)) => exec_random_command(&random_matches, &mut settings),
+        ("tag", Some(tag_matches)) => exec_tag_command(&tag_matches, &mut settings),
+        ("tags", Some(tags_matches)) => exec_tags_command(&tags_matches, &mut settings),
+        ("stats", Some(stats_matches)) => exec_stats_command(&stats_matches, &mut settings),
+        ("info", Some(info_matches)) => exec_info_command(&info_matches, &mut settings),
+        ("activity", Some(activity_matches)) => {
+            exec_activity_command(&activity_matches, &mut settings)
+        }
+        ("orphans", Some(orphans_matches)) => exec_orphans_command(&orphans_matches, &mut settings),
+        ("deadends", Some(deadends_matches)) => {
+            exec_deadends_command(&deadends_matches, &mut settings)
+        }
+        ("check", Some(check_matches)) => exec_check_command(&check_matches, &mut settings),
+        ("dedupe", Some(dedupe_matches)) => exec_dedupe_command(&dedupe_matches, &mut settings),
         ("history", Some(history_matches)) => exec_history_command(&history_matches, &mut settings),
         ("add", Some(add_matches)) => exec_add_command(&add_matches, &mut settings),
         ("rm", Some(remove_matches)) => exec_rm_command(&remove_matches, &mut settings),
+        ("rename", Some(rename_matches)) => exec_rename_command(&rename_matches, &mut settings),
+        ("archive", Some(archive_matches)) => {
+            exec_archive_command(&archive_matches, &mut settings, true)
+        }
+        ("unarchive", Some(unarchive_matches)) => {
+            exec_archive_command(&unarchive_matches, &mut settings, false)
+        }
+        ("capture", Some(capture_matches)) => exec_capture_command(&capture_matches, &mut settings),
+        ("move", Some(move_matches)) => exec_move_command(&move_matches, &mut settings),
+        ("merge", Some(merge_matches)) => exec_merge_command(&merge_matches, &mut settings),
         ("update-db", Some(update_db_matches)) => {
             exec_update_db_command(&update_db_matches, &mut settings)
         }
@@ -172,8 +661,25 @@ fn main() {
             exec_get_file_name_command(&get_file_name_matches, &mut settings)
         }
         ("graph", Some(graph_matches)) => exec_graph_command(&graph_matches, &mut settings),
+        ("topic", Some(topic_matches)) => exec_topic_command(&topic_matches, &mut settings),
+        ("export", Some(export_matches)) => exec_export_command(&export_matches, &mut settings),
+        ("bundle", Some(bundle_matches)) => exec_bundle_command(&bundle_matches, &mut settings),
+        ("import", Some(import_matches)) => exec_import_command(&import_matches, &mut settings),
         _ => (),
     }
+    print_database_errors();
+}
+
+// The queries of the database don't fail, so their errors are shown once the command is done
+fn print_database_errors() {
+    for error in Database::take_errors() {
+        Message::error(&error);
+    }
+}
+
+fn exit_with_error() -> ! {
+    print_database_errors();
+    process::exit(1);
 }
 
 fn exec_init_command(_matches: &ArgMatches, settings: &mut Settings) {
@@ -260,45 +766,94 @@ fn exec_tui_command(_matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, true) {
         return;
     }
-    settings.print_to_stdout = false;
-    BrnTui::init(settings);
+    if let Err(error) = BrnTui::init(settings) {
+        Message::error(&format!("tui: {}", error));
+    }
 }
 
 fn exec_list_command(matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
         return;
     }
-    NoteUtility::list(
-        matches
-            .value_of("count")
-            .unwrap_or("100")
-            .parse()
-            .unwrap_or(100),
+    let property = match matches.value_of("property") {
+        Some(property) => match property.split_once('=') {
+            Some((property_key, property_value)) => {
+                Some((property_key.trim(), property_value.trim()))
+            }
+            None => {
+                Message::error(&format!(
+                    "the property '{}' has to be in the format 'key=value'",
+                    property
+                ));
+                return;
+            }
+        },
+        None => None,
+    };
+    // The possible values are checked by clap
+    let sort_order = matches
+        .value_of("sort")
+        .and_then(SortOrder::from_config_string)
+        .unwrap_or(SortOrder::CreationDateDescending);
+    let columns: Vec<ListColumn> = matches
+        .values_of("columns")
+        .unwrap_or_default()
+        .filter_map(ListColumn::from_string)
+        .collect();
+    let format = matches
+        .value_of("format")
+        .and_then(ListFormat::from_string)
+        .unwrap_or(ListFormat::Plain);
+    let count = matches
+        .value_of("count")
+        .unwrap_or("100")
+        .parse()
+        .unwrap_or(100);
+
+    let mut notes = NoteUtility::get_note_list(
+        matches.is_present("archived"),
+        property,
+        matches.value_of("filter"),
+        sort_order,
+        settings,
     );
+    notes.truncate(count);
+    if let Err(error) = NoteListOutput::print(&notes, &columns, format, &settings.date_format) {
+        Message::error(&format!("list: {}", error));
+        exit_with_error();
+    }
 }
 
 fn exec_open_command(matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
         return;
     }
-    let note_name = matches.value_of("name").unwrap_or_default();
+    let note_names: Vec<&str> = matches.values_of("name").unwrap_or_default().collect();
+
+    // Scripts have to be able to tell if the note couldn't be opened
+    let mut note_ids = Vec::new();
+    for note_name in note_names {
+        match NoteUtility::get_note_id_of_name_or_id(note_name) {
+            Ok(value) => note_ids.push(value),
+            Err(error) => {
+                Message::error(&error.to_string());
+                exit_with_error();
+            }
+        };
+    }
 
-    let result;
-    match Database::get_note_id_where(NoteProperty::NoteName, note_name) {
-        Some(note_id) => {
-            result = NoteUtility::open(&note_id, settings);
+    if let [note_id] = note_ids.as_slice() {
+        if !open_note_until_valid(note_id, settings) {
+            exit_with_error();
         }
-        None => {
-            // Maybe the note id was given instead of the name
-            let note_id = note_name;
-            result = NoteUtility::open(&note_id, settings);
+        return;
+    }
+    match NoteUtility::open_many(&note_ids, settings) {
+        Ok(messages) => messages.iter().for_each(|m| Message::warning(m)),
+        Err(error) => {
+            Message::error(&error.to_string());
+            exit_with_error();
         }
-    };
-
-    match result {
-        Ok(None) => (),
-        Ok(Some(message)) => Message::warning(&message),
-        Err(error) => Message::error(&error),
     }
 }
 
@@ -308,100 +863,982 @@ fn exec_search_command(matches: &ArgMatches, settings: &mut Settings) {
     }
     let search_string = matches.value_of("search-string").unwrap_or_default();
 
-    let search_results = NoteUtility::search(search_string);
-    NoteUtility::print_search_results(search_results)
+    if matches.is_present("content") {
+        match NoteUtility::search_content(search_string) {
+            Ok(search_results) => print_note_list(search_results),
+            Err(error) => Message::error(&format!("invalid search: {}", error)),
+        }
+    } else {
+        let search_results = NoteUtility::search(search_string);
+        print_search_results(search_results)
+    }
 }
 
-fn exec_random_command(_matches: &ArgMatches, settings: &mut Settings) {
-    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
-        return;
+fn print_note_list(note_list: Vec<Note>) {
+    for note in note_list {
+        println!("{} {}", note.note_id.yellow(), note.note_name);
     }
+}
+
+fn print_search_results(search_results: Vec<NoteTagging>) {
+    for search_result in search_results {
+        if let Some(note) = Database::get_note_where_id(&search_result.note_id) {
+            let tag_name = &search_result.tag_name.as_ref();
 
-    NoteUtility::open_random_note(settings);
+            if tag_name.is_some() {
+                let tag_name = tag_name.unwrap();
+                println!(
+                    "{} {}\t\t{}{}",
+                    note.note_id.yellow(),
+                    note.note_name,
+                    "#".bright_yellow(),
+                    tag_name.bright_yellow()
+                );
+            } else {
+                println!("{} {}", note.note_id.yellow(), note.note_name);
+            }
+        }
+    }
 }
 
-fn exec_history_command(_matches: &ArgMatches, settings: &mut Settings) {
+fn exec_random_command(matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
         return;
     }
 
-    let note_history = NoteUtility::get_note_history(settings);
-    NoteUtility::print_note_list(note_history);
+    let tag_name = matches.value_of("tag");
+    let random_note_ids =
+        NoteUtility::get_random_note_ids(1, tag_name, &mut RandomGenerator::new());
+    let note = match random_note_ids
+        .first()
+        .and_then(|m| Database::get_note_where_id(m))
+    {
+        Some(value) => value,
+        None => {
+            match tag_name {
+                Some(tag_name) => Message::error(&format!(
+                    "open_random_note: couldn't find a random note with the tag '{}'",
+                    tag_name
+                )),
+                None => Message::error("open_random_note: couldn't find a random note"),
+            }
+            return;
+        }
+    };
+    Message::info(&format!(
+        "opened note:  {} {} ",
+        note.note_id.yellow(),
+        note.note_name
+    ));
+    open_note_until_valid(&note.note_id, settings);
 }
 
-fn exec_add_command(matches: &ArgMatches, settings: &mut Settings) {
+fn exec_tag_command(matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
         return;
     }
+    match matches.subcommand() {
+        ("rename", Some(rename_matches)) => {
+            let old_tag_name = rename_matches.value_of("old-tag").unwrap();
+            let new_tag_name = rename_matches.value_of("new-tag").unwrap();
+            exec_tag_rename(
+                &[old_tag_name],
+                new_tag_name,
+                DestructiveAction::TagRename,
+                rename_matches,
+                settings,
+            );
+            return;
+        }
+        ("merge", Some(merge_matches)) => {
+            let tag_names: Vec<&str> = merge_matches.values_of("tags").unwrap().collect();
+            let new_tag_name = merge_matches.value_of("into").unwrap();
+            exec_tag_rename(
+                &tag_names,
+                new_tag_name,
+                DestructiveAction::TagMerge,
+                merge_matches,
+                settings,
+            );
+            return;
+        }
+        _ => (),
+    }
 
-    let note_name = matches.value_of("name").unwrap_or_default();
+    // Maybe the note id was given instead of the name
+    let note_id = match NoteUtility::get_note_id_of_name_or_id(matches.value_of("name").unwrap()) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            return;
+        }
+    };
+    let tag_name = matches.value_of("tag").unwrap();
+    if !show_new_tag_dialog_for(tag_name, settings) {
+        return;
+    }
 
-    let note_type;
-    if matches.is_present("quote") {
-        note_type = NoteType::Quote;
-    } else if matches.is_present("journal") {
-        note_type = NoteType::Journal;
-    } else {
-        note_type = NoteType::Topic;
+    match NoteUtility::add_tag(&note_id, tag_name, settings) {
+        Ok(()) => Message::info(&format!(
+            "added #{} to {}",
+            tag_name.trim().trim_start_matches('#'),
+            note_id
+        )),
+        Err(error) => Message::error(&error.to_string()),
     }
+}
 
-    match NoteUtility::add(note_name, note_type, settings) {
-        Ok(None) => (),
-        Ok(Some(note_id)) => match NoteUtility::open(&note_id, settings) {
-            Ok(None) => (),
-            Ok(Some(message)) => Message::warning(&message),
-            Err(error) => Message::error(&error),
-        },
-        Err(error) => Message::error(&error),
+// Renaming and merging tags are confirmed separately
+fn exec_tag_rename(
+    old_tag_names: &[&str],
+    new_tag_name: &str,
+    destructive_action: DestructiveAction,
+    matches: &ArgMatches,
+    settings: &mut Settings,
+) {
+    let dry_run = matches.is_present("dry-run");
+
+    // The changed notes are shown before the change is confirmed
+    if !dry_run && !matches.is_present("yes") && settings.needs_confirmation(destructive_action) {
+        match NoteUtility::rename_tags(old_tag_names, new_tag_name, true, settings) {
+            Ok(changed_notes) => {
+                let changed_note_count = changed_notes.len();
+                print_note_list(changed_notes);
+                if changed_note_count > 0
+                    && !show_confirmation_dialog(
+                        &format!(
+                            "Do you want to replace the tags in the {} notes?",
+                            changed_note_count
+                        ),
+                        settings,
+                    )
+                {
+                    return;
+                }
+            }
+            Err(error) => {
+                Message::error(&error.to_string());
+                exit_with_error();
+            }
+        }
+    }
+
+    match NoteUtility::rename_tags(old_tag_names, new_tag_name, dry_run, settings) {
+        Ok(changed_notes) if dry_run => {
+            let changed_note_count = changed_notes.len();
+            print_note_list(changed_notes);
+            Message::info(&format!("{} notes would be changed", changed_note_count));
+        }
+        Ok(changed_notes) => Message::info(&format!(
+            "replaced the tags in {} notes",
+            changed_notes.len()
+        )),
+        Err(error) => {
+            Message::error(&error.to_string());
+            exit_with_error();
+        }
     }
 }
 
-fn exec_rm_command(matches: &ArgMatches, settings: &mut Settings) {
-    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
-        return;
+// New tags that are similar to existing ones are often typos, so they have to be confirmed.
+// Returns false if the tag shouldn't be added
+fn show_new_tag_dialog_for(tag_name: &str, settings: &Settings) -> bool {
+    if !settings.show_interactive_dialogs || NoteUtility::is_existing_tag(tag_name) {
+        return true;
+    }
+    let tag_suggestions = NoteUtility::get_tag_suggestions(tag_name);
+    if tag_suggestions.is_empty() {
+        return true;
     }
 
-    let note_name = matches.value_of("name").unwrap_or_default();
+    Message::warning(&format!(
+        "the tag '#{}' doesn't exist yet. Similar tags: {}",
+        tag_name.trim().trim_start_matches('#'),
+        tag_suggestions
+            .iter()
+            .map(|(tag_name, note_count)| format!("#{} ({})", tag_name, note_count))
+            .collect::<Vec<String>>()
+            .join(", ")
+    ));
+    print!("Do you want to add the new tag anyway? [y/N] ");
+    io::stdout().flush().unwrap();
 
-    if let Err(error) = NoteUtility::remove(note_name, &settings.notes_dir) {
-        Message::error(&error);
+    let mut add_new_tag = String::new();
+    if let Err(error) = io::stdin().read_line(&mut add_new_tag) {
+        Message::error(&format!(
+            "show_new_tag_dialog: couldn't read user input: {}",
+            error
+        ));
+        return false;
     }
+    return add_new_tag.trim().to_lowercase() == "y";
 }
 
-fn exec_update_db_command(_matches: &ArgMatches, settings: &mut Settings) {
-    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
-        return;
+// Returns true if the question was answered with yes or if dialogs aren't shown
+fn show_confirmation_dialog(question: &str, settings: &Settings) -> bool {
+    if !settings.show_interactive_dialogs {
+        return true;
     }
 
-    if let Err(error) = NoteUtility::update_db_for_all_notes_in_project_folder(settings) {
-        Message::error(&error);
+    print!("{} [y/N] ", question);
+    io::stdout().flush().unwrap();
+
+    let mut answer = String::new();
+    if let Err(error) = io::stdin().read_line(&mut answer) {
+        Message::error(&format!(
+            "show_confirmation_dialog: couldn't read user input: {}",
+            error
+        ));
+        return false;
     }
+    return answer.trim().to_lowercase() == "y";
 }
 
-fn exec_get_name_command(matches: &ArgMatches, settings: &mut Settings) {
+fn exec_tags_command(matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
         return;
     }
 
-    let note_id = matches.value_of("id").unwrap_or_default();
-    NoteUtility::print_note_name_of(note_id);
+    let tag_order = matches
+        .value_of("sort")
+        .and_then(TagOrder::from_string)
+        .unwrap_or(TagOrder::Name);
+    print_tags(tag_order, matches.is_present("roll-up"));
 }
 
-fn exec_get_file_name_command(matches: &ArgMatches, settings: &mut Settings) {
+fn print_tags(tag_order: TagOrder, roll_up_nested_tags: bool) {
+    for (tag_name, note_count) in NoteUtility::get_tag_counts(tag_order, roll_up_nested_tags) {
+        println!(
+            "{} {}{}",
+            note_count.to_string().yellow(),
+            "#".bright_yellow(),
+            tag_name.bright_yellow()
+        );
+    }
+}
+
+fn exec_stats_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+    let note_name = matches.value_of("name").unwrap_or_default();
+
+    // Maybe the note id was given instead of the name
+    let note_id = match NoteUtility::get_note_id_of_name_or_id(note_name) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            return;
+        }
+    };
+    print_stats_of(&note_id, settings);
+}
+
+fn print_stats_of(note_id: &str, settings: &mut Settings) {
+    let note = match Database::get_note_where_id(note_id) {
+        Some(value) => value,
+        None => {
+            Message::error(&format!("the note id {} doesn't exist", note_id.yellow()));
+            return;
+        }
+    };
+    let note_statistics = match NoteUtility::get_statistics_of(note_id, settings) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&format!(
+                "couldn't read content of note '{} {}': {}",
+                note.note_id.yellow(),
+                note.note_name,
+                error
+            ));
+            return;
+        }
+    };
+    let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+    let modification_date = fs::metadata(&note_file_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::<Local>::from);
+
+    println!("{} {}", note.note_id.yellow(), note.note_name);
+    if let Some(creation_date) = note.creation_date_time {
+        println!(
+            "created:      {}",
+            creation_date.format(&settings.date_format)
+        );
+    }
+    if let Some(modification_date) = modification_date {
+        println!(
+            "modified:     {}",
+            modification_date.format(&settings.date_format)
+        );
+    }
+    println!("words:        {}", note_statistics.word_count);
+    println!("characters:   {}", note_statistics.character_count);
+    println!(
+        "reading time: {} min",
+        note_statistics.reading_time_in_minutes
+    );
+}
+
+fn exec_info_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    print_zettelkasten_info(matches.is_present("json"));
+}
+
+// The json output is meant for scripts, so the database size is given in bytes
+fn print_zettelkasten_info(as_json: bool) {
+    let statistics = ZettelkastenStatistics::from_database();
+    if as_json {
+        match serde_json::to_string(&statistics) {
+            Ok(statistics_json) => println!("{}", statistics_json),
+            Err(error) => Message::error(&format!(
+                "print-zettelkasten-info: couldn't serialize the statistics: {}",
+                error
+            )),
+        }
+        return;
+    }
+
+    println!("notes:               {}", statistics.note_count);
+    println!("archived notes:      {}", statistics.archived_note_count);
+    println!("tags:                {}", statistics.tag_count);
+    println!("links:               {}", statistics.link_count);
+    println!("orphans:             {}", statistics.orphan_count);
+    println!(
+        "average note length: {} characters",
+        statistics.average_note_length
+    );
+    println!(
+        "database size:       {:.1} KiB",
+        statistics.database_size as f64 / 1024.0
+    );
+}
+
+fn exec_activity_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        exit_with_error();
+    }
+
+    let since = get_date_argument_of(matches, "since");
+    let until = get_date_argument_of(matches, "until");
+    print_note_activity(since, until, matches.is_present("json"));
+}
+
+// Archived notes are counted too
+fn print_note_activity(since: Option<NaiveDate>, until: Option<NaiveDate>, as_json: bool) {
+    let note_activity = NoteActivity::from_database(since, until);
+    if as_json {
+        match serde_json::to_string(&note_activity) {
+            Ok(note_activity_json) => println!("{}", note_activity_json),
+            Err(error) => Message::error(&format!(
+                "print-note-activity: couldn't serialize the activity: {}",
+                error
+            )),
+        }
+        return;
+    }
+
+    println!("date        created  modified");
+    for day in note_activity {
+        println!("{}  {:>7}  {:>8}", day.date, day.created, day.modified);
+    }
+}
+
+fn get_date_argument_of(matches: &ArgMatches, argument_name: &str) -> Option<NaiveDate> {
+    let value = matches.value_of(argument_name)?;
+    match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => return Some(date),
+        Err(_) => {
+            Message::error(&format!(
+                "invalid date '{}' for --{}, expected YYYY-MM-DD",
+                value, argument_name
+            ));
+            exit_with_error();
+        }
+    }
+}
+
+fn exec_orphans_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    print_note_list(Database::get_orphan_notes(matches.is_present("archived")));
+}
+
+fn exec_deadends_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    print_note_list(Database::get_dead_end_notes(matches.is_present("archived")));
+}
+
+fn exec_check_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        exit_with_error();
+    }
+
+    let broken_links = match NoteUtility::get_broken_links(settings) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            exit_with_error();
+        }
+    };
+    let broken_link_count = print_broken_links(&broken_links);
+    if broken_link_count == 0 {
+        Message::info("no broken links found");
+        return;
+    }
+    if !matches.is_present("fix") || !show_unlink_dialog_for(broken_link_count, settings) {
+        exit_with_error();
+    }
+
+    for (note, link_texts) in &broken_links {
+        match NoteUtility::unlink(note, link_texts, settings) {
+            Ok(warnings) => warnings.iter().for_each(|m| Message::warning(m)),
+            Err(error) => {
+                Message::error(&error.to_string());
+                exit_with_error();
+            }
+        }
+    }
+    Message::info(&format!("removed {} broken links", broken_link_count));
+}
+
+// Returns the number of broken links
+fn print_broken_links(broken_links: &[(Note, Vec<String>)]) -> usize {
+    let mut broken_link_count = 0;
+    for (note, link_texts) in broken_links {
+        for link_text in link_texts {
+            println!(
+                "{} {}: {}",
+                note.note_id.yellow(),
+                note.note_name,
+                format!("[[{}]]", link_text).red()
+            );
+            broken_link_count += 1;
+        }
+    }
+    return broken_link_count;
+}
+
+fn show_unlink_dialog_for(broken_link_count: usize, settings: &Settings) -> bool {
+    return show_confirmation_dialog(
+        &format!(
+            "Do you want to remove the {} broken links?",
+            broken_link_count
+        ),
+        settings,
+    );
+}
+
+fn exec_dedupe_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        exit_with_error();
+    }
+
+    let similarity_threshold = match matches.value_of("similarity") {
+        Some(value) => match value.parse::<u8>() {
+            Ok(percentage) if (1..=100).contains(&percentage) => Some(percentage as f64 / 100.0),
+            _ => {
+                Message::error(&format!(
+                    "invalid similarity '{}', expected a percentage between 1 and 100",
+                    value
+                ));
+                exit_with_error();
+            }
+        },
+        None => None,
+    };
+    let clusters = match NoteUtility::get_duplicate_clusters(similarity_threshold, settings) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            exit_with_error();
+        }
+    };
+    if clusters.is_empty() {
+        Message::info("no duplicate notes found");
+        return;
+    }
+    print_duplicate_clusters(&clusters);
+}
+
+fn print_duplicate_clusters(clusters: &[DuplicateCluster]) {
+    for (cluster_index, cluster) in clusters.iter().enumerate() {
+        if cluster_index > 0 {
+            println!();
+        }
+        match cluster.similarity {
+            Some(similarity) => println!("similar ({:.0}%):", similarity * 100.0),
+            None => println!("identical:"),
+        }
+        for note_id in &cluster.note_ids {
+            let note_name = Database::get_note_where_id(note_id)
+                .map(|m| m.note_name)
+                .unwrap_or_default();
+            println!("{} {}", note_id.yellow(), note_name);
+        }
+    }
+}
+
+fn exec_history_command(_matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    let note_history = NoteUtility::get_note_history(settings);
+    print_note_list(note_history);
+}
+
+fn exec_add_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    let note_name = matches.value_of("name").unwrap_or_default();
+
+    let note_type;
+    if matches.is_present("quote") {
+        note_type = NoteType::Quote;
+    } else if matches.is_present("journal") {
+        note_type = NoteType::Journal;
+    } else {
+        note_type = NoteType::Topic;
+    }
+
+    let template_name = matches.value_of("template");
+
+    // Maybe the note id was given instead of the name
+    let parent_note_id = match matches.value_of("parent") {
+        Some(parent_note_name) => match NoteUtility::get_note_id_of_name_or_id(parent_note_name) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                Message::error(&error.to_string());
+                return;
+            }
+        },
+        None => None,
+    };
+
+    match NoteUtility::add(
+        note_name,
+        note_type,
+        template_name,
+        parent_note_id.as_deref(),
+        settings,
+    ) {
+        Ok(note_id) => {
+            if let Some(note) = Database::get_note_where_id(&note_id) {
+                Message::info(&format!(
+                    "created note: {} {}",
+                    note.note_id.yellow(),
+                    note.note_name
+                ));
+            }
+            open_note_until_valid(&note_id, settings);
+        }
+        Err(error) => Message::error(&error.to_string()),
+    }
+}
+
+// The note is opened again as long as its yaml header lacks a name or tags and the user wants
+// to fix it. Returns false if the note couldn't be opened or still doesn't have a name
+fn open_note_until_valid(note_id: &str, settings: &mut Settings) -> bool {
+    loop {
+        let (is_opened, needs_fixing) = match NoteUtility::open(note_id, settings) {
+            Ok(warnings) => {
+                warnings.iter().for_each(|m| Message::warning(m));
+                let has_no_tags = Database::get_tags_of_note(note_id).is_empty();
+                if has_no_tags {
+                    Message::example(indoc! {r##"
+                        ---
+
+                        tags: [ first-tag, #second-tag, third-tag ]
+
+                        ---
+                    "##});
+                }
+                (true, has_no_tags)
+            }
+            Err(error @ BrainError::Parse { .. }) => {
+                Message::error(&error.to_string());
+                Message::example(indoc! {r#"
+                    ---
+
+                    name: "note name"
+
+                    ---
+                "#});
+                (false, true)
+            }
+            Err(error) => {
+                Message::error(&error.to_string());
+                return false;
+            }
+        };
+        if !needs_fixing || !show_open_again_dialog(settings) {
+            return is_opened;
+        }
+    }
+}
+
+// Without an answer (e.g. at the end of the input) the note isn't opened again
+fn show_open_again_dialog(settings: &Settings) -> bool {
+    if !settings.show_interactive_dialogs {
+        return false;
+    }
+
+    print!("Do you want to open the file again? [Y/n] ");
+    io::stdout().flush().unwrap();
+
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(0) => return false,
+        Ok(_) => return answer.trim().to_lowercase() != "n",
+        Err(error) => {
+            Message::error(&format!(
+                "show_open_again_dialog: couldn't read user input: {}",
+                error
+            ));
+            return false;
+        }
+    }
+}
+
+fn exec_rm_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    let note_name = matches.value_of("name").unwrap_or_default();
+
+    // Notes that don't exist are reported by the removal
+    let note_id = match NoteUtility::get_note_id_of_name_or_id(note_name) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            return;
+        }
+    };
+    let note = Database::get_note_where_id(&note_id);
+    if let Some(note) = note {
+        if settings.needs_confirmation(DestructiveAction::Delete)
+            && !matches.is_present("yes")
+            && !show_confirmation_dialog(
+                &format!("Do you want to delete '{}'?", note.note_name),
+                settings,
+            )
+        {
+            return;
+        }
+    }
+
+    match NoteUtility::remove(&note_id, settings) {
+        Ok(removed_note) => {
+            removed_note
+                .warnings
+                .iter()
+                .for_each(|m| Message::warning(m));
+            if !removed_note.linking_note_ids.is_empty() {
+                Message::warning(&format!(
+                    "the removed note '{}' is still linked in the following notes: {}",
+                    note_id,
+                    removed_note.linking_note_ids.join(", ")
+                ));
+            }
+        }
+        Err(error) => Message::error(&error.to_string()),
+    }
+}
+
+fn exec_rename_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+    let pattern = matches.value_of("pattern").unwrap();
+    let replacement = matches.value_of("replace").unwrap();
+    let dry_run = matches.is_present("dry-run");
+
+    // The renamings are shown before they are confirmed
+    let is_confirmation_needed = !dry_run
+        && !matches.is_present("yes")
+        && settings.needs_confirmation(DestructiveAction::BulkRename);
+    if is_confirmation_needed {
+        match NoteUtility::rename_matching_notes(pattern, replacement, true, settings) {
+            Ok(renamings) => {
+                print_renamings(&renamings);
+                if !renamings.is_empty()
+                    && !show_confirmation_dialog(
+                        &format!("Do you want to rename the {} notes?", renamings.len()),
+                        settings,
+                    )
+                {
+                    return;
+                }
+            }
+            Err(error) => {
+                Message::error(&error.to_string());
+                exit_with_error();
+            }
+        }
+    }
+
+    match NoteUtility::rename_matching_notes(pattern, replacement, dry_run, settings) {
+        Ok(renamings) if dry_run => {
+            print_renamings(&renamings);
+            Message::info(&format!("{} notes would be renamed", renamings.len()));
+        }
+        Ok(renamings) => {
+            if !is_confirmation_needed {
+                print_renamings(&renamings);
+            }
+            Message::info(&format!("renamed {} notes", renamings.len()));
+        }
+        Err(error) => {
+            Message::error(&error.to_string());
+            exit_with_error();
+        }
+    }
+}
+
+fn print_renamings(renamings: &[(Note, String)]) {
+    for (note, new_note_name) in renamings {
+        println!(
+            "{} {} -> {}",
+            note.note_id.yellow(),
+            note.note_name,
+            new_note_name
+        );
+    }
+}
+
+fn exec_archive_command(matches: &ArgMatches, settings: &mut Settings, archived: bool) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+    let note_name = matches.value_of("name").unwrap_or_default();
+
+    // Maybe the note id was given instead of the name
+    let note_id = match NoteUtility::get_note_id_of_name_or_id(note_name) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            return;
+        }
+    };
+    if let Err(error) = NoteUtility::set_archived(&note_id, archived, settings) {
+        Message::error(&error.to_string());
+    }
+}
+
+fn exec_capture_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+    let text = matches.value_of("text").unwrap_or_default();
+
+    match NoteUtility::capture(text, settings) {
+        Ok((_, warnings)) => {
+            for warning in warnings {
+                Message::warning(&warning);
+            }
+        }
+        Err(error) => Message::error(&error.to_string()),
+    }
+}
+
+fn exec_move_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+    let note_name = matches.value_of("name").unwrap_or_default();
+    let notebook_name = matches.value_of("to").unwrap_or_default();
+    let unlink_broken_links = matches.is_present("unlink");
+
+    // Maybe the note id was given instead of the name
+    let note_id = match NoteUtility::get_note_id_of_name_or_id(note_name) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            return;
+        }
+    };
+    if settings.needs_confirmation(DestructiveAction::MoveNote)
+        && !matches.is_present("yes")
+        && !show_confirmation_dialog(
+            &format!(
+                "Do you want to move '{}' into the notebook '{}'?",
+                note_name, notebook_name
+            ),
+            settings,
+        )
+    {
+        return;
+    }
+
+    match NoteUtility::move_to_notebook(&note_id, notebook_name, unlink_broken_links, settings) {
+        Ok(moved_note) => {
+            for warning in &moved_note.warnings {
+                Message::warning(warning);
+            }
+            for (note_name, link_text) in &moved_note.broken_links {
+                match unlink_broken_links {
+                    true => Message::info(&format!(
+                        "move: unlinked '[[{}]]' in '{}'",
+                        link_text, note_name
+                    )),
+                    false => Message::warning(&format!(
+                        "move: the link '[[{}]]' in '{}' is broken now",
+                        link_text, note_name
+                    )),
+                }
+            }
+            Message::info(&format!(
+                "moved note {} into the notebook '{}'",
+                moved_note.note_id, notebook_name
+            ));
+        }
+        Err(error) => Message::error(&error.to_string()),
+    }
+}
+
+fn exec_merge_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    // Maybe the note ids were given instead of the names
+    let mut notes = Vec::new();
+    for argument_name in &["from", "into"] {
+        let note_name = matches.value_of(argument_name).unwrap_or_default();
+        match NoteUtility::get_note_id_of_name_or_id(note_name)
+            .map(|m| Database::get_note_where_id(&m))
+        {
+            Ok(Some(note)) => notes.push(note),
+            Ok(None) => {
+                Message::error(&format!("the note '{}' does not exist!", note_name));
+                return;
+            }
+            Err(error) => {
+                Message::error(&error.to_string());
+                return;
+            }
+        }
+    }
+    let (from_note, into_note) = (&notes[0], &notes[1]);
+
+    if settings.needs_confirmation(DestructiveAction::NoteMerge)
+        && !matches.is_present("yes")
+        && !show_confirmation_dialog(
+            &format!(
+                "Do you want to merge '{}' into '{}' and delete '{}'?",
+                from_note.note_name, into_note.note_name, from_note.note_name
+            ),
+            settings,
+        )
+    {
+        return;
+    }
+
+    match NoteUtility::merge(&from_note.note_id, &into_note.note_id, settings) {
+        Ok((relinked_note_count, warnings)) => {
+            warnings.iter().for_each(|m| Message::warning(m));
+            Message::info(&format!(
+                "merged '{}' into '{}' and changed the links in {} notes",
+                from_note.note_name, into_note.note_name, relinked_note_count
+            ));
+        }
+        Err(error) => Message::error(&error.to_string()),
+    }
+}
+
+fn exec_update_db_command(_matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    match NoteUtility::update_db_for_all_notes_in_project_folder(settings, |m| println!("{}", m)) {
+        Ok(warnings) => warnings.iter().for_each(|m| Message::warning(m)),
+        Err(error) => Message::error(&error.to_string()),
+    }
+}
+
+fn exec_get_name_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    let note_id = matches.value_of("id").unwrap_or_default();
+    print_note_name_of(note_id);
+}
+
+fn print_note_name_of(note_id: &str) {
+    if let Some(note) = Database::get_note_where_id(note_id) {
+        println!("{}", note.note_name);
+    } else {
+        Message::error(&format!("the note id {} doesn't exist", note_id.yellow()));
+    }
+}
+
+fn exec_get_file_name_command(matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
         return;
     }
 
     let note_id = matches.value_of("id").unwrap_or_default();
-    NoteUtility::print_file_name_of(note_id);
+    print_file_name_of(note_id);
 }
 
-fn exec_graph_command(_matches: &ArgMatches, settings: &mut Settings) {
+fn print_file_name_of(note_id: &str) {
+    if let Some(note) = Database::get_note_where_id(note_id) {
+        println!("{}", note.file_name);
+    } else {
+        Message::error(&format!("the note id {} doesn't exist", note_id.yellow()));
+    }
+}
+
+fn exec_graph_command(matches: &ArgMatches, settings: &mut Settings) {
     if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
         return;
     }
 
-    if let Err(error) = Graph::generate(settings) {
+    let include_archived = matches.is_present("archived");
+    if matches.is_present("format") {
+        // Maybe the note id was given instead of the name
+        let root_note_id = match matches
+            .value_of("root")
+            .map(NoteUtility::get_note_id_of_name_or_id)
+            .transpose()
+        {
+            Ok(value) => value,
+            Err(error) => {
+                Message::error(&error.to_string());
+                return;
+            }
+        };
+        let depth = match matches.value_of("depth").unwrap_or("1").parse::<usize>() {
+            Ok(value) => value,
+            Err(_) => {
+                Message::error("the depth has to be a positive number");
+                return;
+            }
+        };
+
+        match Graph::get_dot_of_notes(root_note_id.as_deref(), depth, include_archived) {
+            Ok(dot_string) => print!("{}", dot_string),
+            Err(error) => Message::error(&error),
+        }
+        return;
+    }
+
+    if let Err(error) = Graph::generate(settings, include_archived) {
         Message::error(&error);
     } else {
         if let Err(error) = Graph::show(settings) {
@@ -409,3 +1846,209 @@ fn exec_graph_command(_matches: &ArgMatches, settings: &mut Settings) {
         }
     }
 }
+
+fn exec_topic_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    let tag_name = matches.value_of("tag").unwrap().trim_start_matches('#');
+    let depth = match matches.value_of("depth").unwrap_or("1").parse::<usize>() {
+        Ok(value) => value,
+        Err(_) => {
+            Message::error("the depth has to be a positive number");
+            return;
+        }
+    };
+    let include_archived = matches.is_present("archived");
+    if Database::get_notes_with_tag(tag_name).is_empty() {
+        Message::error(&format!(
+            "topic: there are no notes with the tag '{}'",
+            tag_name
+        ));
+        return;
+    }
+
+    if matches.is_present("graph") {
+        match Graph::get_dot_of_topic(tag_name, depth, include_archived) {
+            Ok(dot_string) => print!("{}", dot_string),
+            Err(error) => Message::error(&error),
+        }
+        return;
+    }
+
+    // The notes with the tag come first, then their neighbors ordered by their distance
+    let excluded_note_ids = Graph::get_excluded_note_ids(include_archived);
+    let (note_ids, _) = Graph::get_topic_neighborhood_of(tag_name, depth, &excluded_note_ids);
+    let notes: Vec<Note> = note_ids
+        .iter()
+        .filter_map(|m| Database::get_note_where_id(m))
+        .collect();
+    if let Err(error) = NoteListOutput::print(
+        &notes,
+        &[ListColumn::Id, ListColumn::Name],
+        ListFormat::Plain,
+        &settings.date_format,
+    ) {
+        Message::error(&format!("topic: {}", error));
+        exit_with_error();
+    }
+}
+
+fn exec_export_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    if matches.value_of("format") == Some("html") {
+        exec_html_export_command(matches, settings);
+        return;
+    }
+    if matches.value_of("format") == Some("obsidian") {
+        let output_dir = Path::new(matches.value_of_os("target").unwrap());
+        match NoteExport::export_obsidian(output_dir, settings) {
+            Ok(note_count) => println!(
+                "Exported {} notes to '{}'",
+                note_count,
+                output_dir.to_string_lossy()
+            ),
+            Err(error) => Message::error(&error),
+        }
+        return;
+    }
+
+    let result = match matches.value_of_os("output") {
+        Some(output_path) => match fs::File::create(output_path) {
+            Ok(file) => NoteExport::export_json(&mut io::BufWriter::new(file), settings),
+            Err(error) => Err(format!(
+                "couldn't create '{}': {}",
+                output_path.to_string_lossy(),
+                error
+            )),
+        },
+        None => NoteExport::export_json(&mut io::stdout().lock(), settings),
+    };
+    if let Err(error) = result {
+        Message::error(&error);
+    }
+}
+
+fn exec_html_export_command(matches: &ArgMatches, settings: &mut Settings) {
+    let notes = if matches.is_present("all") {
+        Database::get_all_notes()
+    } else {
+        let name = match matches.value_of("target") {
+            Some(value) => value,
+            None => {
+                Message::error("export: the name or ID of a note or --all is required");
+                return;
+            }
+        };
+        // Maybe the note id was given instead of the name
+        let note = match NoteUtility::get_note_id_of_name_or_id(name) {
+            Ok(note_id) => Database::get_note_where_id(&note_id),
+            Err(error) => {
+                Message::error(&error.to_string());
+                return;
+            }
+        };
+        match note {
+            Some(value) => vec![value],
+            None => {
+                Message::error(&format!("export: the note '{}' does not exist!", name));
+                return;
+            }
+        }
+    };
+
+    let stylesheet = match matches.value_of_os("css") {
+        Some(css_path) => match fs::read_to_string(css_path) {
+            Ok(value) => value,
+            Err(error) => {
+                Message::error(&format!(
+                    "export: couldn't read '{}': {}",
+                    css_path.to_string_lossy(),
+                    error
+                ));
+                return;
+            }
+        },
+        None => DEFAULT_HTML_STYLESHEET.to_string(),
+    };
+
+    let output_dir = Path::new(matches.value_of_os("output").unwrap());
+    match NoteExport::export_html(&notes, output_dir, &stylesheet, settings) {
+        Ok(note_count) => println!(
+            "Exported {} notes to '{}'",
+            note_count,
+            output_dir.to_string_lossy()
+        ),
+        Err(error) => Message::error(&error),
+    }
+}
+
+fn exec_bundle_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+
+    // Maybe the note id was given instead of the name
+    let note_id = match NoteUtility::get_note_id_of_name_or_id(matches.value_of("note").unwrap()) {
+        Ok(value) => value,
+        Err(error) => {
+            Message::error(&error.to_string());
+            return;
+        }
+    };
+    let depth = match matches.value_of("depth").unwrap_or("1").parse::<usize>() {
+        Ok(value) => value,
+        Err(_) => {
+            Message::error("the depth has to be a positive number");
+            return;
+        }
+    };
+    let output_path = Path::new(matches.value_of_os("output").unwrap_or_default());
+
+    match NoteExport::bundle(
+        &note_id,
+        depth,
+        matches.is_present("archived"),
+        output_path,
+        settings,
+    ) {
+        Ok(note_count) => Message::info(&format!(
+            "bundled {} notes into '{}'",
+            note_count,
+            output_path.to_string_lossy()
+        )),
+        Err(error) => Message::error(&error),
+    }
+}
+
+fn exec_import_command(matches: &ArgMatches, settings: &mut Settings) {
+    if !Directory::is_zettelkasten_dir(&settings.notes_dir, false) {
+        return;
+    }
+    let file_path = matches.value_of_os("file").unwrap_or_default();
+
+    if matches.value_of("format") == Some("obsidian") {
+        match NoteExport::import_obsidian(Path::new(file_path), settings) {
+            Ok((note_count, unresolved_links)) => {
+                for (note_name, link_text) in &unresolved_links {
+                    Message::warning(&format!(
+                        "import: couldn't resolve the link '[[{}]]' in '{}'",
+                        link_text, note_name
+                    ));
+                }
+                Message::info(&format!("imported {} notes", note_count));
+            }
+            Err(error) => Message::error(&error),
+        }
+        return;
+    }
+
+    match NoteExport::import_json(file_path, settings) {
+        Ok(note_count) => Message::info(&format!("imported {} notes", note_count)),
+        Err(error) => Message::error(&error),
+    }
+}
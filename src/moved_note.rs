@@ -0,0 +1,10 @@
+// The result of moving a note into another notebook
+#[derive(Debug)]
+pub struct MovedNote {
+    // The id of the note in the other notebook, which differs if the id was already used there
+    pub note_id: String,
+    // The links that broke as (note name, link text)
+    pub broken_links: Vec<(String, String)>,
+    // Problems with the links and the yaml header of the moved note
+    pub warnings: Vec<String>,
+}
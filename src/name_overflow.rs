@@ -0,0 +1,18 @@
+// How note names that are too long for the note list of the TUI are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameOverflow {
+    Ellipsis,
+    Wrap,
+    Scroll,
+}
+
+impl NameOverflow {
+    pub fn from_config_string(value: &str) -> Option<NameOverflow> {
+        match value {
+            "ellipsis" => Some(NameOverflow::Ellipsis),
+            "wrap" => Some(NameOverflow::Wrap),
+            "scroll" => Some(NameOverflow::Scroll),
+            _ => None,
+        }
+    }
+}
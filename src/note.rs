@@ -1,5 +1,6 @@
 use chrono::{DateTime, Local};
 
+#[derive(Debug, Clone)]
 pub struct Note {
     pub note_id: String,
     pub note_name: String,
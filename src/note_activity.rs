@@ -0,0 +1,22 @@
+use crate::database::Database;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+// The number of notes created and modified on a day. Days without any activity are
+// left out, so that programs drawing a heatmap from it decide how to show the gaps
+#[derive(Debug, Serialize)]
+pub struct NoteActivity {
+    pub date: String,
+    pub created: usize,
+    pub modified: usize,
+}
+
+impl NoteActivity {
+    // Both bounds are inclusive
+    pub fn from_database(since: Option<NaiveDate>, until: Option<NaiveDate>) -> Vec<NoteActivity> {
+        let since = since.map_or(String::from("0000-01-01"), |m| m.to_string());
+        let until = until.map_or(String::from("9999-12-31"), |m| m.to_string());
+        return Database::get_note_activity(&since, &until);
+    }
+}
@@ -0,0 +1,1014 @@
+use crate::database::Database;
+use crate::file_utility::FileUtility;
+use crate::graph::main::Graph;
+use crate::html_renderer::HtmlRenderer;
+use crate::message::Message;
+use crate::note::Note;
+use crate::note_utility::NoteUtility;
+use crate::settings::Settings;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+use indoc::{formatdoc, indoc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+use yaml_rust::{Yaml, YamlLoader};
+
+// Has to be increased whenever the format of the export changes
+const EXPORT_SCHEMA_VERSION: u32 = 1;
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const OBSIDIAN_ATTACHMENTS_DIR_NAME: &str = "attachments";
+const HTML_ATTACHMENTS_DIR_NAME: &str = "attachments";
+// Embedded into every html file, unless another stylesheet is given
+pub const DEFAULT_HTML_STYLESHEET: &str = indoc! {"
+    body {
+        max-width: 45em;
+        margin: 2em auto;
+        padding: 0 1em;
+        font-family: sans-serif;
+        line-height: 1.5;
+        color: #222;
+    }
+    a { color: #2a6db0; }
+    pre, code { background: #f4f4f4; border-radius: 3px; }
+    pre { padding: 0.5em 1em; overflow-x: auto; }
+    blockquote { margin-left: 0; padding-left: 1em; border-left: 3px solid #ccc; color: #555; }
+    img { max-width: 100%; }
+    hr { border: none; border-top: 1px solid #ccc; }
+    table { border-collapse: collapse; }
+    th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; }
+"};
+// These characters aren't allowed in note names
+const ILLEGAL_NOTE_NAME_CHARS: &str = "!?$%§&/={}+*#|~^@";
+
+lazy_static! {
+    static ref ATTACHMENT_LINK_VALIDATOR: Regex = Regex::new(
+        r#"(?x)
+        (!?\[[^\]\n]*\]\()    # $1 = link text (images start with '!')
+        ([^)\s]+)              # $2 = path of the attachment
+        ((?:\s+"[^"]*")?\))    # $3 = optional title
+    "#
+    )
+    .unwrap();
+    static ref FRONTMATTER_VALIDATOR: Regex = Regex::new(
+        r"(?xs)
+        ^---[\ \t]*\n
+        (.*?)               # $1 = yaml text
+        \n?---[\ \t]*(?:\n|$)
+        (.*)                # $2 = body of the note
+        $
+    "
+    )
+    .unwrap();
+    static ref WIKILINK_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        \[\[
+            ([^\[\]\n]+?)      # $1 = link target with optional heading and display text
+        \]\]
+    "
+    )
+    .unwrap();
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExportedNote {
+    pub id: String,
+    pub name: String,
+    pub file_name: String,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+    pub tags: Vec<String>,
+    pub links: Vec<String>,
+    pub content: String,
+}
+
+// A markdown file of an imported vault. The links are changed after all files were read,
+// as they can point to files that come later
+struct VaultFile {
+    note_id: String,
+    note_name: String,
+    creation_date_time: DateTime<Local>,
+    tags: Vec<String>,
+    aliases: Vec<String>,
+    body: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportFile {
+    schema_version: u32,
+    notes: Vec<ExportedNote>,
+}
+
+pub struct NoteExport;
+
+impl NoteExport {
+    // The notes are written one after another, so only one note is kept in memory
+    pub fn export_json<W: Write>(writer: &mut W, settings: &Settings) -> Result<(), String> {
+        let notes = Database::get_all_notes();
+
+        let mut write_all = |text: &str| -> Result<(), String> {
+            return writer
+                .write_all(text.as_bytes())
+                .map_err(|m| format!("export: couldn't write export: {}", m));
+        };
+
+        write_all(&format!(
+            "{{\"schema_version\":{},\"notes\":[",
+            EXPORT_SCHEMA_VERSION
+        ))?;
+        for (index, note) in notes.iter().enumerate() {
+            let exported_note = match NoteExport::get_exported_note(note, settings) {
+                Ok(value) => value,
+                Err(error) => {
+                    Message::warning(&error);
+                    continue;
+                }
+            };
+            let note_json = match serde_json::to_string(&exported_note) {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(format!(
+                        "export: couldn't convert note '{}' to json: {}",
+                        note.note_id, error
+                    ));
+                }
+            };
+
+            if index > 0 {
+                write_all(",")?;
+            }
+            write_all(&note_json)?;
+        }
+        write_all("]}\n")?;
+
+        return Ok(());
+    }
+
+    fn get_exported_note(note: &Note, settings: &Settings) -> Result<ExportedNote, String> {
+        let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "export: skipped note '{} {}': couldn't read note file: {}",
+                    note.note_id, note.note_name, error
+                ));
+            }
+        };
+        let modified = fs::metadata(&note_file_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|m| {
+                DateTime::<Local>::from(m)
+                    .format(TIMESTAMP_FORMAT)
+                    .to_string()
+            });
+
+        return Ok(ExportedNote {
+            id: note.note_id.clone(),
+            name: note.note_name.clone(),
+            file_name: note.file_name.clone(),
+            created: note
+                .creation_date_time
+                .map(|m| m.format(TIMESTAMP_FORMAT).to_string()),
+            modified,
+            tags: Database::get_tags_of_note(&note.note_id),
+            links: Database::get_outgoing_links(&note.note_id),
+            content,
+        });
+    }
+
+    // Writes the note and all notes within the given number of links into one markdown file.
+    // Links between the bundled notes point to the section of the linked note and attachments
+    // are copied into a directory next to the file, so that the bundle can be read without brn.
+    // Returns the number of bundled notes
+    pub fn bundle(
+        root_note_id: &str,
+        depth: usize,
+        include_archived: bool,
+        output_path: &Path,
+        settings: &Settings,
+    ) -> Result<usize, String> {
+        if Database::get_note_where_id(root_note_id).is_none() {
+            return Err(format!(
+                "bundle: the note id '{}' does not exist!",
+                root_note_id
+            ));
+        }
+        if NoteExport::is_in_notes_dir(output_path, settings) {
+            return Err(
+                "bundle: the bundle can't be written into the notes directory as it would become a note"
+                    .to_string(),
+            );
+        }
+
+        let excluded_note_ids = Graph::get_excluded_note_ids(include_archived);
+        let (note_ids, _) = Graph::get_neighborhood_of(root_note_id, depth, &excluded_note_ids);
+        let notes: Vec<Note> = note_ids
+            .iter()
+            .filter_map(|m| Database::get_note_where_id(m))
+            .collect();
+
+        let attachments_dir_name = format!(
+            "{}_attachments",
+            output_path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+        );
+        let attachments_dir = output_path.with_file_name(&attachments_dir_name);
+        let mut copied_attachments: HashMap<PathBuf, String> = HashMap::new();
+
+        let mut bundle_content = String::new();
+        for note in &notes {
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+            let content = match FileUtility::get_content_from_file(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(format!(
+                        "bundle: couldn't read note file '{}': {}",
+                        note_file_path.to_string_lossy(),
+                        error
+                    ));
+                }
+            };
+
+            // Links to notes outside of the bundle can't be followed, so only their name is kept
+            let note_body = NoteUtility::replace_links_in(
+                &NoteUtility::remove_yaml_header(&content),
+                |link_text| match NoteUtility::get_linked_note(link_text) {
+                    Ok(Some(linked_note)) if note_ids.contains(&linked_note.note_id) => format!(
+                        "[{}](#{})",
+                        linked_note.note_name,
+                        NoteExport::get_anchor_of(&linked_note.note_id)
+                    ),
+                    Ok(Some(linked_note)) => linked_note.note_name,
+                    _ => link_text.to_string(),
+                },
+            );
+            let note_body = NoteExport::copy_attachments_of(
+                &note_body,
+                &attachments_dir,
+                &attachments_dir_name,
+                &mut copied_attachments,
+                settings,
+            )
+            .map_err(|m| format!("bundle: {}", m))?;
+
+            if !bundle_content.is_empty() {
+                bundle_content.push_str("\n---\n\n");
+            }
+            bundle_content.push_str(&format!(
+                "<a id=\"{}\"></a>\n\n",
+                NoteExport::get_anchor_of(&note.note_id)
+            ));
+            let note_body = note_body.trim();
+            if !note_body.starts_with("# ") {
+                bundle_content.push_str(&format!("# {}\n\n", note.note_name));
+            }
+            bundle_content.push_str(note_body);
+            bundle_content.push('\n');
+        }
+
+        if let Err(error) = fs::write(output_path, bundle_content) {
+            return Err(format!(
+                "bundle: couldn't write '{}': {}",
+                output_path.to_string_lossy(),
+                error
+            ));
+        }
+        return Ok(notes.len());
+    }
+
+    // Writes every note into a markdown file named after the note, so that the directory can
+    // be opened as an Obsidian vault. Links are changed to links by name, the tags are kept in
+    // the frontmatter and attachments are copied into the 'attachments' directory of the vault.
+    // Returns the number of exported notes
+    pub fn export_obsidian(output_dir: &Path, settings: &Settings) -> Result<usize, String> {
+        NoteExport::create_output_dir(output_dir, settings)
+            .map_err(|m| format!("export: {}", m))?;
+
+        // The titles are determined first, as they are needed for the links between the notes
+        let notes = Database::get_all_notes();
+        let mut used_titles = HashSet::new();
+        let mut titles: HashMap<String, String> = HashMap::new();
+        for note in &notes {
+            let title = NoteExport::get_obsidian_title_of(note, &used_titles);
+            used_titles.insert(title.to_lowercase());
+            titles.insert(note.note_id.clone(), title);
+        }
+
+        let attachments_dir = output_dir.join(OBSIDIAN_ATTACHMENTS_DIR_NAME);
+        let mut copied_attachments: HashMap<PathBuf, String> = HashMap::new();
+        let mut exported_note_count = 0;
+        for note in &notes {
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+            let content = match FileUtility::get_content_from_file(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    Message::warning(&format!(
+                        "export: skipped note '{} {}': couldn't read note file: {}",
+                        note.note_id, note.note_name, error
+                    ));
+                    continue;
+                }
+            };
+
+            // Broken links are kept, as Obsidian shows them as notes that don't exist yet
+            let note_body = NoteUtility::replace_links_in(
+                &NoteUtility::remove_yaml_header(&content),
+                |link_text| match NoteUtility::get_linked_note(link_text) {
+                    Ok(Some(linked_note)) => format!("[[{}]]", titles[&linked_note.note_id]),
+                    _ => format!("[[{}]]", link_text),
+                },
+            );
+            let note_body = NoteExport::copy_attachments_of(
+                &note_body,
+                &attachments_dir,
+                OBSIDIAN_ATTACHMENTS_DIR_NAME,
+                &mut copied_attachments,
+                settings,
+            )
+            .map_err(|m| format!("export: {}", m))?;
+
+            let exported_file_path = output_dir.join(format!("{}.md", titles[&note.note_id]));
+            let exported_content =
+                NoteExport::get_obsidian_frontmatter_of(note) + note_body.trim_start();
+            if let Err(error) = fs::write(&exported_file_path, exported_content) {
+                return Err(format!(
+                    "export: couldn't write '{}': {}",
+                    exported_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+            exported_note_count += 1;
+        }
+        return Ok(exported_note_count);
+    }
+
+    // Writes every given note into a standalone html file named after its id. Links between
+    // the exported notes point to their files, links to other notes only show the note name.
+    // Attachments are copied into the 'attachments' directory. Returns the number of exported notes
+    pub fn export_html(
+        notes: &[Note],
+        output_dir: &Path,
+        stylesheet: &str,
+        settings: &Settings,
+    ) -> Result<usize, String> {
+        NoteExport::create_output_dir(output_dir, settings)
+            .map_err(|m| format!("export: {}", m))?;
+
+        let exported_note_ids: HashSet<&str> = notes.iter().map(|m| m.note_id.as_str()).collect();
+        let notes_dir = PathBuf::from(&settings.notes_dir);
+        let attachments_dir = output_dir.join(HTML_ATTACHMENTS_DIR_NAME);
+        let mut copied_attachments: HashMap<PathBuf, String> = HashMap::new();
+        let mut exported_note_count = 0;
+        for note in notes {
+            let note_file_path = notes_dir.join(&note.file_name);
+            let content = match FileUtility::get_content_from_file(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    Message::warning(&format!(
+                        "export: skipped note '{} {}': couldn't read note file: {}",
+                        note.note_id, note.note_name, error
+                    ));
+                    continue;
+                }
+            };
+
+            let mut copy_error = None;
+            let get_note_link = |link_text: &str| match NoteUtility::get_linked_note(link_text) {
+                Ok(Some(linked_note))
+                    if exported_note_ids.contains(linked_note.note_id.as_str()) =>
+                {
+                    (
+                        Some(NoteExport::get_html_file_name_of(&linked_note.note_id)),
+                        linked_note.note_name,
+                    )
+                }
+                Ok(Some(linked_note)) => (None, linked_note.note_name),
+                _ => (None, link_text.to_string()),
+            };
+            let get_file_href = |link_target: &str| {
+                if let Some(linked_note) =
+                    NoteUtility::get_note_of_file_link(link_target, &notes_dir)
+                {
+                    return match exported_note_ids.contains(linked_note.note_id.as_str()) {
+                        true => Some(NoteExport::get_html_file_name_of(&linked_note.note_id)),
+                        false => None,
+                    };
+                }
+                let source_path = NoteUtility::get_path_of_file_link(link_target, &notes_dir);
+                if !source_path.is_file() {
+                    return None;
+                }
+                match NoteExport::copy_attachment(
+                    &source_path,
+                    &attachments_dir,
+                    &mut copied_attachments,
+                ) {
+                    Ok(file_name) => Some(format!("{}/{}", HTML_ATTACHMENTS_DIR_NAME, file_name)),
+                    Err(error) => {
+                        copy_error = Some(error);
+                        None
+                    }
+                }
+            };
+
+            // Notes without a title get their name as heading, like in bundles
+            let note_body = NoteUtility::remove_yaml_header(&content);
+            let mut body_html = String::new();
+            if !note_body.trim_start().starts_with("# ") {
+                body_html.push_str(&format!(
+                    "<h1>{}</h1>\n",
+                    HtmlRenderer::escape(&note.note_name)
+                ));
+            }
+            body_html.push_str(&HtmlRenderer::render(
+                &note_body,
+                get_note_link,
+                get_file_href,
+            ));
+            if let Some(error) = copy_error {
+                return Err(format!("export: {}", error));
+            }
+
+            let exported_file_path =
+                output_dir.join(NoteExport::get_html_file_name_of(&note.note_id));
+            let exported_content = formatdoc! {"
+                <!DOCTYPE html>
+                <html>
+                <head>
+                <meta charset=\"utf-8\">
+                <title>{}</title>
+                <style>
+                {}
+                </style>
+                </head>
+                <body>
+                {}</body>
+                </html>
+                ",
+                HtmlRenderer::escape(&note.note_name),
+                stylesheet.trim_end(),
+                body_html,
+            };
+            if let Err(error) = fs::write(&exported_file_path, exported_content) {
+                return Err(format!(
+                    "export: couldn't write '{}': {}",
+                    exported_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+            exported_note_count += 1;
+        }
+        return Ok(exported_note_count);
+    }
+
+    fn get_html_file_name_of(note_id: &str) -> String {
+        return format!("{}.html", note_id);
+    }
+
+    // Characters that aren't allowed in file names or that have a special meaning in the links
+    // of Obsidian are replaced. Titles that are already used get a number appended, ignoring
+    // the case as not all file systems are case sensitive
+    fn get_obsidian_title_of(note: &Note, used_titles: &HashSet<String>) -> String {
+        let mut title: String = note
+            .note_name
+            .trim()
+            .chars()
+            .map(|c| {
+                if "\\/:*?\"<>|#^[]".contains(c) || c.is_control() {
+                    return '-';
+                }
+                c
+            })
+            .collect();
+        title = title.trim_start_matches('.').trim().to_string();
+        if title.is_empty() {
+            title = note.note_id.clone();
+        }
+
+        let mut new_title = title.clone();
+        let mut counter = 2;
+        while used_titles.contains(&new_title.to_lowercase()) {
+            new_title = format!("{} ({})", title, counter);
+            counter += 1;
+        }
+        return new_title;
+    }
+
+    // Obsidian expects the tags without the leading '#'
+    fn get_obsidian_frontmatter_of(note: &Note) -> String {
+        let tags: Vec<String> = Database::get_tags_of_note(&note.note_id)
+            .iter()
+            .map(|m| NoteUtility::to_yaml_string(m.trim_start_matches('#')))
+            .collect();
+
+        let mut frontmatter = String::from("---\n");
+        frontmatter.push_str(&format!(
+            "id: {}\n",
+            NoteUtility::to_yaml_string(&note.note_id)
+        ));
+        if let Some(creation_date_time) = note.creation_date_time {
+            frontmatter.push_str(&format!(
+                "created: {}\n",
+                creation_date_time.format(TIMESTAMP_FORMAT)
+            ));
+        }
+        frontmatter.push_str(&format!("tags: [ {} ]\n", tags.join(", ")));
+        frontmatter.push_str("---\n\n");
+        return frontmatter;
+    }
+
+    fn get_anchor_of(note_id: &str) -> String {
+        return format!("note-{}", note_id);
+    }
+
+    // The output directory doesn't have to exist yet, so its first existing parent is checked
+    fn create_output_dir(output_dir: &Path, settings: &Settings) -> Result<(), String> {
+        let existing_dir = output_dir
+            .ancestors()
+            .map(|m| {
+                if m.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    m
+                }
+            })
+            .find(|m| m.exists());
+        if let (Some(Ok(existing_dir)), Ok(notes_dir)) = (
+            existing_dir.map(fs::canonicalize),
+            fs::canonicalize(&settings.notes_dir),
+        ) {
+            if existing_dir.starts_with(&notes_dir) {
+                return Err("the export can't be written into the notes directory".to_string());
+            }
+        }
+        if let Err(error) = fs::create_dir_all(output_dir) {
+            return Err(format!(
+                "couldn't create '{}': {}",
+                output_dir.to_string_lossy(),
+                error
+            ));
+        }
+        return Ok(());
+    }
+
+    fn is_in_notes_dir(output_path: &Path, settings: &Settings) -> bool {
+        let output_dir = match output_path.parent() {
+            Some(value) if value.as_os_str().is_empty() => Path::new("."),
+            Some(value) => value,
+            None => return false,
+        };
+        return match (
+            fs::canonicalize(output_dir),
+            fs::canonicalize(&settings.notes_dir),
+        ) {
+            (Ok(output_dir), Ok(notes_dir)) => output_dir == notes_dir,
+            _ => false,
+        };
+    }
+
+    // Attachments are files in the notes directory that are linked or embedded as images.
+    // Files with the same name get a number prepended
+    fn copy_attachments_of(
+        note_body: &str,
+        attachments_dir: &Path,
+        attachments_dir_name: &str,
+        copied_attachments: &mut HashMap<PathBuf, String>,
+        settings: &Settings,
+    ) -> Result<String, String> {
+        let mut new_note_body = String::new();
+        let mut last_match_end = 0;
+        for attachment_match in ATTACHMENT_LINK_VALIDATOR.captures_iter(note_body) {
+            let attachment_path = attachment_match.get(2).unwrap().as_str();
+            let source_path = PathBuf::from(&settings.notes_dir).join(attachment_path);
+            if attachment_path.contains("://") || !source_path.is_file() {
+                continue;
+            }
+
+            let copied_file_name =
+                NoteExport::copy_attachment(&source_path, attachments_dir, copied_attachments)?;
+
+            let whole_match = attachment_match.get(0).unwrap();
+            new_note_body.push_str(&note_body[last_match_end..whole_match.start()]);
+            new_note_body.push_str(&format!(
+                "{}{}/{}{}",
+                &attachment_match[1], attachments_dir_name, copied_file_name, &attachment_match[3]
+            ));
+            last_match_end = whole_match.end();
+        }
+        new_note_body.push_str(&note_body[last_match_end..]);
+        return Ok(new_note_body);
+    }
+
+    // Every file is only copied once. Returns the name of the copy in the attachments directory
+    fn copy_attachment(
+        source_path: &Path,
+        attachments_dir: &Path,
+        copied_attachments: &mut HashMap<PathBuf, String>,
+    ) -> Result<String, String> {
+        if let Some(copied_file_name) = copied_attachments.get(source_path) {
+            return Ok(copied_file_name.clone());
+        }
+
+        let file_name = source_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mut new_file_name = file_name.clone();
+        let mut counter = 2;
+        while copied_attachments.values().any(|m| *m == new_file_name) {
+            new_file_name = format!("{}-{}", counter, file_name);
+            counter += 1;
+        }
+
+        let copy_result = fs::create_dir_all(attachments_dir)
+            .and_then(|_| fs::copy(source_path, attachments_dir.join(&new_file_name)));
+        if let Err(error) = copy_result {
+            return Err(format!(
+                "couldn't copy attachment '{}': {}",
+                source_path.to_string_lossy(),
+                error
+            ));
+        }
+        copied_attachments.insert(source_path.to_path_buf(), new_file_name.clone());
+        return Ok(new_file_name);
+    }
+
+    // Notes whose id or name already exists get a new one.
+    // Returns the number of imported notes
+    pub fn import_json<P: AsRef<Path>>(
+        file_path: P,
+        settings: &mut Settings,
+    ) -> Result<usize, String> {
+        let file = match File::open(&file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "import: couldn't open '{}': {}",
+                    file_path.as_ref().to_string_lossy(),
+                    error
+                ));
+            }
+        };
+        let export_file: ExportFile = match serde_json::from_reader(BufReader::new(file)) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "import: '{}' is not a valid export: {}",
+                    file_path.as_ref().to_string_lossy(),
+                    error
+                ));
+            }
+        };
+        if export_file.schema_version > EXPORT_SCHEMA_VERSION {
+            return Err(format!(
+                "import: the schema version {} of the export isn't supported (latest supported version: {})",
+                export_file.schema_version, EXPORT_SCHEMA_VERSION
+            ));
+        }
+
+        let mut used_note_ids: HashSet<String> = Database::get_all_note_ids().into_iter().collect();
+        let mut used_note_names: HashSet<String> = Database::get_all_notes()
+            .into_iter()
+            .map(|m| m.note_name)
+            .collect();
+
+        // New ids are assigned first, so that links between the imported notes can be changed
+        let mut new_note_ids: HashMap<String, String> = HashMap::new();
+        for exported_note in &export_file.notes {
+            if used_note_ids.contains(&exported_note.id) {
+                let prefix: String = exported_note.id.chars().take(1).collect();
+                let new_note_id = NoteExport::get_unused_note_id(&prefix, &used_note_ids);
+                new_note_ids.insert(exported_note.id.clone(), new_note_id.clone());
+                used_note_ids.insert(new_note_id);
+            } else {
+                used_note_ids.insert(exported_note.id.clone());
+            }
+        }
+
+        let mut imported_notes = Vec::new();
+        for exported_note in export_file.notes {
+            let note_id = new_note_ids
+                .get(&exported_note.id)
+                .cloned()
+                .unwrap_or_else(|| exported_note.id.clone());
+            let note_name = NoteExport::get_unused_note_name(&exported_note.name, &used_note_names);
+            used_note_names.insert(note_name.clone());
+
+            let mut content = exported_note.content.clone();
+            for (old_note_id, new_note_id) in &new_note_ids {
+                content = content.replace(
+                    &format!("[[{}]]", old_note_id),
+                    &format!("[[{}]]", new_note_id),
+                );
+            }
+            if note_id != exported_note.id {
+                content = match NoteUtility::replace_yaml_header_entry(&content, "id", &note_id) {
+                    Some(value) => value,
+                    None => {
+                        Message::warning(&format!(
+                            "import: skipped note '{} {}': the note doesn't have a yaml header",
+                            exported_note.id, exported_note.name
+                        ));
+                        continue;
+                    }
+                };
+            }
+            if note_name != exported_note.name {
+                content = NoteUtility::replace_yaml_header_entry(&content, "name", &note_name)
+                    .unwrap_or(content);
+            }
+
+            let creation_date_time = exported_note
+                .created
+                .as_ref()
+                .and_then(|m| Local.datetime_from_str(m, TIMESTAMP_FORMAT).ok())
+                .unwrap_or_else(Local::now);
+            let file_name =
+                NoteUtility::get_unused_file_name(&exported_note.file_name, &settings.notes_dir);
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&file_name);
+            if let Err(error) = fs::write(&note_file_path, content.as_bytes()) {
+                Message::error(&format!(
+                    "import: couldn't create note file '{}': {}",
+                    note_file_path.to_string_lossy(),
+                    error
+                ));
+                continue;
+            }
+
+            imported_notes.push(Note::new(note_id, note_name, file_name, creation_date_time));
+        }
+
+        for warning in NoteUtility::insert_notes_into_db(&imported_notes, settings) {
+            Message::warning(&warning);
+        }
+        return Ok(imported_notes.len());
+    }
+
+    // Imports the markdown files of the directory and its subdirectories, e.g. an Obsidian vault.
+    // The name of a note is the name of its file and `[[wikilinks]]` are resolved by file name.
+    // Returns the number of imported notes and the links that couldn't be resolved as
+    // (note name, link text)
+    pub fn import_obsidian(
+        vault_dir: &Path,
+        settings: &mut Settings,
+    ) -> Result<(usize, Vec<(String, String)>), String> {
+        let mut file_paths = Vec::new();
+        if let Err(error) = NoteExport::find_markdown_files_in(vault_dir, &mut file_paths) {
+            return Err(format!(
+                "import: couldn't read '{}': {}",
+                vault_dir.to_string_lossy(),
+                error
+            ));
+        }
+        file_paths.sort();
+
+        let mut used_note_ids: HashSet<String> = Database::get_all_note_ids().into_iter().collect();
+        let mut used_note_names: HashSet<String> = Database::get_all_notes()
+            .into_iter()
+            .map(|m| m.note_name)
+            .collect();
+
+        // Links can contain the file name or the path inside of the vault, so both are mapped
+        let mut note_ids_of_links: HashMap<String, String> = HashMap::new();
+        let mut vault_files = Vec::new();
+        for file_path in &file_paths {
+            let content = match FileUtility::get_content_from_file(file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    Message::warning(&format!(
+                        "import: skipped '{}': {}",
+                        file_path.to_string_lossy(),
+                        error
+                    ));
+                    continue;
+                }
+            };
+            let file_stem = file_path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let relative_path = file_path
+                .strip_prefix(vault_dir)
+                .unwrap_or(file_path)
+                .with_extension("")
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let note_id = NoteExport::get_unused_note_id("T", &used_note_ids);
+            used_note_ids.insert(note_id.clone());
+            let note_name: String = file_stem
+                .chars()
+                .map(|c| {
+                    if ILLEGAL_NOTE_NAME_CHARS.contains(c) {
+                        '-'
+                    } else {
+                        c
+                    }
+                })
+                .collect();
+            let note_name = NoteExport::get_unused_note_name(note_name.trim(), &used_note_names);
+            used_note_names.insert(note_name.clone());
+
+            note_ids_of_links
+                .entry(file_stem.to_lowercase())
+                .or_insert_with(|| note_id.clone());
+            note_ids_of_links.insert(relative_path.to_lowercase(), note_id.clone());
+
+            let (frontmatter, body) = match FRONTMATTER_VALIDATOR.captures(&content) {
+                Some(value) => (
+                    YamlLoader::load_from_str(&value[1])
+                        .ok()
+                        .and_then(|m| m.into_iter().next())
+                        .unwrap_or(Yaml::Null),
+                    value[2].to_string(),
+                ),
+                None => (Yaml::Null, content.clone()),
+            };
+            let creation_date_time = ["created", "date"]
+                .iter()
+                .filter_map(|m| frontmatter[*m].as_str())
+                .find_map(NoteExport::parse_date_time)
+                .or_else(|| {
+                    fs::metadata(file_path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .map(DateTime::<Local>::from)
+                })
+                .unwrap_or_else(Local::now);
+
+            vault_files.push(VaultFile {
+                note_id,
+                note_name,
+                creation_date_time,
+                tags: NoteExport::get_yaml_strings_of(&frontmatter["tags"])
+                    .iter()
+                    .map(|m| m.trim_start_matches('#').to_string())
+                    .collect(),
+                aliases: NoteExport::get_yaml_strings_of(&frontmatter["aliases"]),
+                body,
+            });
+        }
+
+        let mut unresolved_links = Vec::new();
+        let mut imported_notes = Vec::new();
+        for vault_file in vault_files {
+            // Headings and display texts of links are dropped, as brn links only contain the id
+            let body = WIKILINK_VALIDATOR.replace_all(&vault_file.body, |m: &regex::Captures| {
+                let link_target = m[1].split(['|', '#']).next().unwrap_or("");
+                let link_target = link_target.trim().trim_end_matches(".md").to_lowercase();
+                if let Some(note_id) = note_ids_of_links.get(&link_target) {
+                    return format!("[[{}]]", note_id);
+                }
+                if let Ok(Some(note)) = NoteUtility::get_linked_note(&link_target) {
+                    return format!("[[{}]]", note.note_id);
+                }
+                unresolved_links.push((vault_file.note_name.clone(), m[1].to_string()));
+                return m[0].to_string();
+            });
+            let body = body.trim();
+
+            let mut content = String::from("---\n\n");
+            content.push_str(&format!("id: {}\n", vault_file.note_id));
+            content.push_str(&format!(
+                "name: {}\n",
+                NoteUtility::to_yaml_string(&vault_file.note_name)
+            ));
+            content.push_str(&format!(
+                "date: {}\n",
+                vault_file.creation_date_time.format(TIMESTAMP_FORMAT)
+            ));
+            content.push_str(&format!(
+                "tags: [ {} ]\n",
+                NoteExport::to_yaml_list_items(&vault_file.tags)
+            ));
+            if !vault_file.aliases.is_empty() {
+                content.push_str(&format!(
+                    "aliases: [ {} ]\n",
+                    NoteExport::to_yaml_list_items(&vault_file.aliases)
+                ));
+            }
+            content.push_str("backlinks: [ ]\n\n---\n\n");
+            if !body.starts_with("# ") {
+                content.push_str(&format!("# {}\n\n", vault_file.note_name));
+            }
+            content.push_str(body);
+            content.push('\n');
+
+            let file_name = NoteUtility::get_unused_file_name(
+                &vault_file
+                    .creation_date_time
+                    .format("%Y-%m-%d-%H%M%S")
+                    .to_string(),
+                &settings.notes_dir,
+            );
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&file_name);
+            if let Err(error) = fs::write(&note_file_path, content.as_bytes()) {
+                Message::error(&format!(
+                    "import: couldn't create note file '{}': {}",
+                    note_file_path.to_string_lossy(),
+                    error
+                ));
+                continue;
+            }
+            imported_notes.push(Note::new(
+                vault_file.note_id,
+                vault_file.note_name,
+                file_name,
+                vault_file.creation_date_time,
+            ));
+        }
+
+        for warning in NoteUtility::insert_notes_into_db(&imported_notes, settings) {
+            Message::warning(&warning);
+        }
+        return Ok((imported_notes.len(), unresolved_links));
+    }
+
+    // Hidden directories like '.obsidian' only contain settings of the vault
+    fn find_markdown_files_in(dir: &Path, file_paths: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_hidden = path
+                .file_name()
+                .is_some_and(|m| m.to_string_lossy().starts_with('.'));
+            if is_hidden {
+                continue;
+            }
+            if path.is_dir() {
+                NoteExport::find_markdown_files_in(&path, file_paths)?;
+            } else if path.extension().is_some_and(|m| m == "md") {
+                file_paths.push(path);
+            }
+        }
+        return Ok(());
+    }
+
+    // Lists can also be written as a single string separated by commas or spaces
+    fn get_yaml_strings_of(value: &Yaml) -> Vec<String> {
+        let texts: Vec<String> = match value {
+            Yaml::Array(items) => items
+                .iter()
+                .filter_map(|m| m.as_str().map(|m| m.to_string()))
+                .collect(),
+            Yaml::String(text) => text
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(|m| m.to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+        return texts
+            .into_iter()
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect();
+    }
+
+    fn to_yaml_list_items(values: &[String]) -> String {
+        return values
+            .iter()
+            .map(|m| NoteUtility::to_yaml_string(m))
+            .collect::<Vec<String>>()
+            .join(", ");
+    }
+
+    fn parse_date_time(text: &str) -> Option<DateTime<Local>> {
+        if let Ok(date_time) = Local.datetime_from_str(text, TIMESTAMP_FORMAT) {
+            return Some(date_time);
+        }
+        return NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .and_then(|m| Local.from_local_datetime(&m.and_hms(0, 0, 0)).single());
+    }
+
+    // Note ids consist of the note type and the creation timestamp
+    fn get_unused_note_id(prefix: &str, used_note_ids: &HashSet<String>) -> String {
+        let mut date_time = Local::now();
+        loop {
+            let note_id = format!("{}{}", prefix, date_time.format("%Y%m%d%H%M%S"));
+            if !used_note_ids.contains(&note_id) {
+                return note_id;
+            }
+            date_time = date_time + Duration::seconds(1);
+        }
+    }
+
+    fn get_unused_note_name(note_name: &str, used_note_names: &HashSet<String>) -> String {
+        let mut new_note_name = note_name.to_string();
+        let mut counter = 2;
+        while used_note_names.contains(&new_note_name) {
+            new_note_name = format!("{} ({})", note_name, counter);
+            counter += 1;
+        }
+        return new_note_name;
+    }
+}
@@ -0,0 +1,127 @@
+use crate::note_type::NoteType;
+use crate::random_generator::RandomGenerator;
+
+use chrono::{DateTime, Duration, Local};
+
+const RANDOM_ID_LENGTH: usize = 6;
+const RANDOM_ID_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdScheme {
+    Timestamp,
+    Hierarchical,
+    Random,
+}
+
+impl IdScheme {
+    pub fn from_config_string(value: &str) -> Option<IdScheme> {
+        match value {
+            "timestamp" => Some(IdScheme::Timestamp),
+            "hierarchical" => Some(IdScheme::Hierarchical),
+            "random" => Some(IdScheme::Random),
+            _ => None,
+        }
+    }
+
+    pub fn get_generator(&self) -> Box<dyn NoteIdGenerator> {
+        match self {
+            IdScheme::Timestamp => Box::new(TimestampIdGenerator),
+            IdScheme::Hierarchical => Box::new(HierarchicalIdGenerator),
+            IdScheme::Random => Box::new(RandomIdGenerator {
+                random_generator: RandomGenerator::new(),
+            }),
+        }
+    }
+}
+
+pub struct NoteIdRequest<'a> {
+    pub note_type: NoteType,
+    pub creation_date_time: DateTime<Local>,
+    pub parent_note_id: Option<&'a str>,
+    pub existing_note_ids: &'a [String],
+}
+
+// Generators only suggest ids. The caller checks if an id is already used and asks
+// again with an increased attempt, so collisions are handled the same way for every scheme
+pub trait NoteIdGenerator {
+    fn generate_id(&mut self, request: &NoteIdRequest, attempt: usize) -> String;
+}
+
+// The note type followed by the creation time, e.g. 'T20220101120000'.
+// Notes created in the same second get the id of the next free second
+pub struct TimestampIdGenerator;
+impl NoteIdGenerator for TimestampIdGenerator {
+    fn generate_id(&mut self, request: &NoteIdRequest, attempt: usize) -> String {
+        let date_time = request.creation_date_time + Duration::seconds(attempt as i64);
+        return format!(
+            "{}{}",
+            get_note_type_identifier(request.note_type),
+            date_time.format("%Y%m%d%H%M%S")
+        );
+    }
+}
+
+// Ids like in the zettelkasten of Niklas Luhmann, e.g. '1', '1a', '1a2'.
+// Numbers and letters alternate, so a child of '1a' is '1a1' and a child of '1a1' is '1a1a'
+pub struct HierarchicalIdGenerator;
+impl NoteIdGenerator for HierarchicalIdGenerator {
+    fn generate_id(&mut self, request: &NoteIdRequest, attempt: usize) -> String {
+        let parent_note_id = match request.parent_note_id {
+            Some(value) => value,
+            None => {
+                let last_top_level_number = request
+                    .existing_note_ids
+                    .iter()
+                    .filter_map(|m| m.parse::<usize>().ok())
+                    .max()
+                    .unwrap_or(0);
+                return (last_top_level_number + 1 + attempt).to_string();
+            }
+        };
+
+        let child_number = attempt + 1;
+        if parent_note_id.ends_with(|c: char| c.is_ascii_digit()) {
+            return format!("{}{}", parent_note_id, get_letters_of(child_number));
+        }
+        return format!("{}{}", parent_note_id, child_number);
+    }
+}
+
+// The note type followed by random letters and digits, e.g. 'T4k2x9a'
+pub struct RandomIdGenerator {
+    random_generator: RandomGenerator,
+}
+impl NoteIdGenerator for RandomIdGenerator {
+    fn generate_id(&mut self, request: &NoteIdRequest, _attempt: usize) -> String {
+        let random_part: String = (0..RANDOM_ID_LENGTH)
+            .map(|_| {
+                RANDOM_ID_CHARS[self.random_generator.next_index(RANDOM_ID_CHARS.len())] as char
+            })
+            .collect();
+        return format!(
+            "{}{}",
+            get_note_type_identifier(request.note_type),
+            random_part
+        );
+    }
+}
+
+fn get_note_type_identifier(note_type: NoteType) -> &'static str {
+    match note_type {
+        NoteType::Topic => "T",
+        NoteType::Quote => "Q",
+        NoteType::Journal => "J",
+    }
+}
+
+// 1 -> 'a', 26 -> 'z', 27 -> 'aa'
+fn get_letters_of(number: usize) -> String {
+    let mut letters = Vec::new();
+    let mut number = number;
+    while number > 0 {
+        number -= 1;
+        letters.push((b'a' + (number % 26) as u8) as char);
+        number /= 26;
+    }
+    return letters.into_iter().rev().collect();
+}
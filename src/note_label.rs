@@ -0,0 +1,31 @@
+// Labels mark notes for a workflow state (e.g. notes that still have to be read). Unlike tags
+// they are only stored in the database and a note has at most one label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteLabel {
+    Important,
+    ToRead,
+    Stub,
+}
+
+impl NoteLabel {
+    pub fn all() -> Vec<NoteLabel> {
+        return vec![NoteLabel::Important, NoteLabel::ToRead, NoteLabel::Stub];
+    }
+
+    pub fn from_name(value: &str) -> Option<NoteLabel> {
+        match value {
+            "important" => Some(NoteLabel::Important),
+            "to-read" => Some(NoteLabel::ToRead),
+            "stub" => Some(NoteLabel::Stub),
+            _ => None,
+        }
+    }
+
+    pub fn to_name(&self) -> String {
+        match self {
+            NoteLabel::Important => "important".to_string(),
+            NoteLabel::ToRead => "to-read".to_string(),
+            NoteLabel::Stub => "stub".to_string(),
+        }
+    }
+}
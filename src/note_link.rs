@@ -1,3 +1,4 @@
+#[derive(Debug, Clone)]
 pub struct NoteLink {
     pub source_note_id: String,
     pub target_note_id: String,
@@ -0,0 +1,137 @@
+use crate::database::Database;
+use crate::note::Note;
+
+use colored::*;
+use serde_json::{Map, Value};
+use std::io::{self, ErrorKind, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    Plain,
+    Tsv,
+    Json,
+}
+
+impl ListFormat {
+    pub fn from_string(value: &str) -> Option<ListFormat> {
+        match value {
+            "plain" => Some(ListFormat::Plain),
+            "tsv" => Some(ListFormat::Tsv),
+            "json" => Some(ListFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListColumn {
+    Id,
+    Name,
+    Tags,
+    Date,
+}
+
+impl ListColumn {
+    pub fn from_string(value: &str) -> Option<ListColumn> {
+        match value {
+            "id" => Some(ListColumn::Id),
+            "name" => Some(ListColumn::Name),
+            "tags" => Some(ListColumn::Tags),
+            "date" => Some(ListColumn::Date),
+            _ => None,
+        }
+    }
+
+    fn to_key_string(self) -> &'static str {
+        match self {
+            ListColumn::Id => "id",
+            ListColumn::Name => "name",
+            ListColumn::Tags => "tags",
+            ListColumn::Date => "date",
+        }
+    }
+}
+
+// Prints note lists in a format that can be read by other programs.
+// Only the plain format is colored, as it's meant to be read by humans.
+// Dates are written in the `date-format` of the settings, like in the TUI
+pub struct NoteListOutput;
+impl NoteListOutput {
+    pub fn print(
+        notes: &[Note],
+        columns: &[ListColumn],
+        format: ListFormat,
+        date_format: &str,
+    ) -> Result<(), String> {
+        let lines = if format == ListFormat::Json {
+            let note_list: Vec<Value> = notes
+                .iter()
+                .map(|m| NoteListOutput::get_json_of(m, columns, date_format))
+                .collect();
+            match serde_json::to_string(&note_list) {
+                Ok(note_list_json) => vec![note_list_json],
+                Err(error) => return Err(format!("couldn't serialize the note list: {}", error)),
+            }
+        } else {
+            let separator = if format == ListFormat::Tsv { "\t" } else { " " };
+            notes
+                .iter()
+                .map(|note| {
+                    columns
+                        .iter()
+                        .map(|m| {
+                            let value = NoteListOutput::get_text_of(note, *m, date_format);
+                            if format == ListFormat::Plain && *m == ListColumn::Id {
+                                return value.yellow().to_string();
+                            }
+                            value
+                        })
+                        .collect::<Vec<String>>()
+                        .join(separator)
+                })
+                .collect()
+        };
+
+        // The output is often piped into programs like `head` that stop reading early
+        let mut stdout = io::stdout().lock();
+        for line in lines {
+            match writeln!(stdout, "{}", line) {
+                Ok(()) => (),
+                Err(error) if error.kind() == ErrorKind::BrokenPipe => return Ok(()),
+                Err(error) => return Err(format!("couldn't print the note list: {}", error)),
+            }
+        }
+        return Ok(());
+    }
+
+    // Tab characters in note names would break the columns of the tsv format
+    fn get_text_of(note: &Note, column: ListColumn, date_format: &str) -> String {
+        match column {
+            ListColumn::Id => note.note_id.clone(),
+            ListColumn::Name => note.note_name.replace('\t', " "),
+            ListColumn::Tags => Database::get_tags_of_note(&note.note_id).join(","),
+            ListColumn::Date => NoteListOutput::get_date_of(note, date_format),
+        }
+    }
+
+    fn get_json_of(note: &Note, columns: &[ListColumn], date_format: &str) -> Value {
+        let mut note_object = Map::new();
+        for column in columns {
+            let value = match column {
+                ListColumn::Id => Value::from(note.note_id.clone()),
+                ListColumn::Name => Value::from(note.note_name.clone()),
+                ListColumn::Tags => Value::from(Database::get_tags_of_note(&note.note_id)),
+                ListColumn::Date => Value::from(NoteListOutput::get_date_of(note, date_format)),
+            };
+            note_object.insert(column.to_key_string().to_string(), value);
+        }
+        return Value::Object(note_object);
+    }
+
+    fn get_date_of(note: &Note, date_format: &str) -> String {
+        return note
+            .creation_date_time
+            .map(|m| m.format(date_format).to_string())
+            .unwrap_or_default();
+    }
+}
@@ -1,3 +1,4 @@
+use crate::brain_error::BrainError;
 use crate::file_utility::FileUtility;
 use crate::note::Note;
 use crate::note_property::NoteProperty;
@@ -100,16 +101,98 @@ impl NoteMetadata {
         note: &Note,
         property: NoteProperty,
         settings: &Settings,
-    ) -> Result<Option<String>, String> {
+    ) -> Result<Option<String>, BrainError> {
+        let notes_dir = &settings.notes_dir;
+        let absolute_note_file_path = PathBuf::from(notes_dir).join(&note.file_name);
+        return NoteMetadata::get_property_of_file(&absolute_note_file_path, property).map_err(
+            |error| BrainError::Parse {
+                note_id: note.note_id.clone(),
+                message: error,
+            },
+        );
+    }
+
+    pub fn get_tags_of(
+        note: &Note,
+        settings: &Settings,
+    ) -> Result<Option<Vec<String>>, BrainError> {
         let notes_dir = &settings.notes_dir;
         let absolute_note_file_path = PathBuf::from(notes_dir).join(&note.file_name);
-        return NoteMetadata::get_property_of_file(&absolute_note_file_path, property);
+        return NoteMetadata::get_tags_of_file(&absolute_note_file_path).map_err(|error| {
+            BrainError::Parse {
+                note_id: note.note_id.clone(),
+                message: error,
+            }
+        });
     }
 
-    pub fn get_tags_of(note: &Note, settings: &Settings) -> Result<Option<Vec<String>>, String> {
+    pub fn get_custom_properties_of(
+        note: &Note,
+        settings: &Settings,
+    ) -> Result<Vec<(String, String)>, BrainError> {
         let notes_dir = &settings.notes_dir;
         let absolute_note_file_path = PathBuf::from(notes_dir).join(&note.file_name);
-        return NoteMetadata::get_tags_of_file(&absolute_note_file_path);
+        return NoteMetadata::get_custom_properties_of_file(&absolute_note_file_path).map_err(
+            |error| BrainError::Parse {
+                note_id: note.note_id.clone(),
+                message: error,
+            },
+        );
+    }
+
+    // Returns all properties of the yaml header except the ones that are stored elsewhere.
+    // Every item of a list is returned as its own property
+    pub fn get_custom_properties_of_file<P: AsRef<Path>>(
+        file_path: P,
+    ) -> Result<Vec<(String, String)>, String> {
+        let note_metadata = match NoteMetadata::get_metadata_of_file(file_path) {
+            Ok(value) => value,
+            Err(error) => return Err(error),
+        };
+        let reserved_keys = [
+            NoteProperty::NoteId.to_metadata_identifier(),
+            NoteProperty::NoteName.to_metadata_identifier(),
+            NoteProperty::CreationDate.to_metadata_identifier(),
+            "tags".to_string(),
+            "backlinks".to_string(),
+        ];
+
+        fn to_property_value(value: &Yaml) -> Option<String> {
+            match value {
+                Yaml::String(text) => Some(text.clone()),
+                Yaml::Real(number) => Some(number.clone()),
+                Yaml::Integer(number) => Some(number.to_string()),
+                Yaml::Boolean(boolean) => Some(boolean.to_string()),
+                _ => None,
+            }
+        }
+
+        let mut properties = Vec::new();
+        if let Some(note_metadata) = note_metadata.as_hash() {
+            for (key, value) in note_metadata {
+                let key = match key.as_str() {
+                    Some(value) => value,
+                    None => continue,
+                };
+                if reserved_keys.iter().any(|m| m == key) {
+                    continue;
+                }
+
+                match value {
+                    Yaml::Array(items) => {
+                        for item in items.iter().filter_map(to_property_value) {
+                            properties.push((key.to_string(), item));
+                        }
+                    }
+                    _ => {
+                        if let Some(property_value) = to_property_value(value) {
+                            properties.push((key.to_string(), property_value));
+                        }
+                    }
+                }
+            }
+        }
+        return Ok(properties);
     }
 
     pub fn get_property_of_file<P: AsRef<Path>>(
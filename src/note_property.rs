@@ -1,11 +1,12 @@
 use std::fmt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum NoteProperty {
     NoteId,
     NoteName,
     CreationDate,
     // FileName,
+    Custom(String),
 }
 
 impl NoteProperty {
@@ -15,6 +16,7 @@ impl NoteProperty {
             NoteProperty::NoteName => "note_name".to_string(),
             NoteProperty::CreationDate => "creation_date".to_string(),
             // NoteProperty::FileName => "file_name".to_string(),
+            NoteProperty::Custom(_) => "property_value".to_string(),
         }
     }
 
@@ -24,6 +26,7 @@ impl NoteProperty {
             NoteProperty::NoteName => "name".to_string(),
             NoteProperty::CreationDate => "date".to_string(),
             // NoteProperty::FileName => "".to_string(),
+            NoteProperty::Custom(key) => key.clone(),
         }
     }
 }
@@ -0,0 +1,87 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref YAML_HEADER_VALIDATOR: Regex = Regex::new(
+        r"(?xs)
+        ^\s*
+        ---[\ \t]*\n
+        .*?
+        \n---[\ \t]*(\n|$)
+    "
+    )
+    .unwrap();
+    static ref CODE_FENCE_VALIDATOR: Regex = Regex::new(r"^\s*(```|~~~)").unwrap();
+    static ref HORIZONTAL_RULE_VALIDATOR: Regex = Regex::new(r"^\s*([-*_]\s*){3,}$").unwrap();
+    static ref LINE_PREFIX_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        ^\s*
+        (
+            \#{1,6}\s+                          # heading
+            | (>\s?)+                           # blockquote
+            | ([-*+]|\d+[.)])\s+(\[[\ xX]\]\s+)?  # list item with optional checkbox
+        )
+    "
+    )
+    .unwrap();
+    static ref LINK_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        !?\[([^\]]*)\]\([^)]*\)     # $1 = text of markdown links and images
+        | \[\[([^\]]*)\]\]          # $2 = text of note links
+    "
+    )
+    .unwrap();
+    static ref EMPHASIS_VALIDATOR: Regex = Regex::new(r"\*+|~~|`+").unwrap();
+}
+
+const WORDS_PER_MINUTE: usize = 200;
+
+pub struct NoteStatistics {
+    pub word_count: usize,
+    pub character_count: usize,
+    pub reading_time_in_minutes: usize,
+}
+
+impl NoteStatistics {
+    pub fn from_content(note_content: &str) -> Self {
+        let plain_text = NoteStatistics::get_plain_text(note_content);
+        let words: Vec<&str> = plain_text.split_whitespace().collect();
+
+        let word_count = words.len();
+        let character_count = words.iter().map(|m| m.chars().count()).sum();
+        let reading_time_in_minutes = word_count.div_ceil(WORDS_PER_MINUTE);
+
+        return NoteStatistics {
+            word_count,
+            character_count,
+            reading_time_in_minutes,
+        };
+    }
+
+    // Removes the yaml header and the markdown syntax so that only the written text remains.
+    // The text inside of code blocks is kept as it is
+    pub fn get_plain_text(note_content: &str) -> String {
+        let note_body = YAML_HEADER_VALIDATOR.replace(note_content, "");
+        let mut plain_text = String::new();
+        let mut is_in_code_block = false;
+
+        for line in note_body.lines() {
+            if CODE_FENCE_VALIDATOR.is_match(line) {
+                is_in_code_block = !is_in_code_block;
+                continue;
+            }
+
+            if is_in_code_block {
+                plain_text.push_str(line);
+            } else if !HORIZONTAL_RULE_VALIDATOR.is_match(line) {
+                let line = LINE_PREFIX_VALIDATOR.replace(line, "");
+                let line = LINK_VALIDATOR.replace_all(&line, "$1$2");
+                let line = EMPHASIS_VALIDATOR.replace_all(&line, "");
+                plain_text.push_str(&line);
+            }
+            plain_text.push('\n');
+        }
+
+        return plain_text;
+    }
+}
@@ -1,6 +1,6 @@
 use std::hash::{Hash, Hasher};
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct NoteTagging {
     pub note_id: String,
     pub tag_name: Option<String>,
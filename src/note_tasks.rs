@@ -0,0 +1,88 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref CODE_FENCE_VALIDATOR: Regex = Regex::new(r"^\s*(```|~~~)").unwrap();
+    static ref TASK_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        ^
+        ([\ \t]*([-*+]|\d+[.)])\s+\[)   # $1 = list marker and opening bracket
+        ([\ xX])                        # $3 = state of the checkbox
+        (\](\s|$))                      # $4 = closing bracket
+    "
+    )
+    .unwrap();
+}
+
+// The tasks of a note are list items with a checkbox ('- [ ]' or '- [x]')
+pub struct NoteTasks {
+    pub task_count: usize,
+    pub done_task_count: usize,
+}
+
+impl NoteTasks {
+    pub fn from_content(note_content: &str) -> Self {
+        let lines: Vec<&str> = note_content.lines().collect();
+        let task_line_indices = NoteTasks::get_task_line_indices(note_content);
+        let done_task_count = task_line_indices
+            .iter()
+            .filter(|m| NoteTasks::get_state_of(lines[**m]) == Some(true))
+            .count();
+
+        return NoteTasks {
+            task_count: task_line_indices.len(),
+            done_task_count,
+        };
+    }
+
+    // The indices of the lines that are tasks. Checkboxes inside of code blocks are ignored
+    pub fn get_task_line_indices(note_content: &str) -> Vec<usize> {
+        let mut task_line_indices = Vec::new();
+        let mut is_in_code_block = false;
+
+        for (index, line) in note_content.lines().enumerate() {
+            if CODE_FENCE_VALIDATOR.is_match(line) {
+                is_in_code_block = !is_in_code_block;
+            } else if !is_in_code_block && NoteTasks::get_state_of(line).is_some() {
+                task_line_indices.push(index);
+            }
+        }
+
+        return task_line_indices;
+    }
+
+    // Whether the task of the line is done. None if the line isn't a task
+    pub fn get_state_of(line: &str) -> Option<bool> {
+        return TASK_VALIDATOR
+            .captures(line)
+            .map(|m| !m[3].trim().is_empty());
+    }
+
+    // Splits the text of a list item into the state of its checkbox and the remaining text.
+    // None if the list item doesn't start with a checkbox
+    pub fn split_checkbox(list_item_text: &str) -> Option<(bool, &str)> {
+        let is_done = match list_item_text.get(..3)? {
+            "[ ]" => false,
+            "[x]" | "[X]" => true,
+            _ => return None,
+        };
+        let text = &list_item_text[3..];
+        if !text.is_empty() && !text.starts_with(char::is_whitespace) {
+            return None;
+        }
+        return Some((is_done, text.trim_start()));
+    }
+
+    // Checks an open task and unchecks a done one. None if the line isn't a task
+    pub fn toggle_task_of(line: &str) -> Option<String> {
+        let is_done = NoteTasks::get_state_of(line)?;
+        let new_state = if is_done { " " } else { "x" };
+        return Some(
+            TASK_VALIDATOR
+                .replace(line, |m: &regex::Captures| {
+                    format!("{}{}{}", &m[1], new_state, &m[4])
+                })
+                .to_string(),
+        );
+    }
+}
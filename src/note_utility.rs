@@ -1,36 +1,67 @@
+use crate::brain_error::BrainError;
 use crate::collection_tool::CollectionTool;
 use crate::database::Database;
+use crate::directory::Directory;
+use crate::duplicate_finder::{DuplicateCluster, DuplicateFinder};
 use crate::file_utility::FileUtility;
-use crate::message::Message;
+use crate::fuzzy_matcher::FuzzyMatcher;
+use crate::moved_note::MovedNote;
 use crate::note::Note;
+use crate::note_id_generator::{IdScheme, NoteIdRequest};
+use crate::note_label::NoteLabel;
 use crate::note_metadata::NoteMetadata;
 use crate::note_property::NoteProperty;
+use crate::note_statistics::NoteStatistics;
 use crate::note_tagging::NoteTagging;
+use crate::note_tasks::NoteTasks;
 use crate::note_type::NoteType;
+use crate::random_generator::RandomGenerator;
+use crate::removed_note::RemovedNote;
+use crate::search_query::SearchQuery;
 use crate::settings::Settings;
+use crate::sort_order::SortOrder;
+use crate::tag_order::TagOrder;
 
 use chrono::prelude::*;
-use colored::*;
-use indoc::{formatdoc, indoc};
 use lazy_static::lazy_static;
-use regex::Regex;
-use std::collections::HashSet;
+use regex::{Captures, NoExpand, Regex};
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::ffi::{OsStr, OsString};
-use std::fs::{self, File, OpenOptions};
-use std::io::{self, Error, Write};
+use std::fs::{self, File};
+use std::io::{self, Error, ErrorKind, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+const DB_SCHEMA_VERSION: i32 = 5;
+const MAX_NOTE_ID_ATTEMPTS: usize = 1000;
+const TRASH_DIR_NAME: &str = "trash";
+// The name of the inbox note if no inbox note is configured
+const INBOX_NOTE_NAME: &str = "Inbox";
+// The inbox note only collects entries, so it doesn't use the sections of the note template
+const INBOX_NOTE_TEMPLATE: &str = "---\n\nid: <note-id>\nname: <note-name>\ndate: <creation-date>\ntags: [ ]\nbacklinks: [ ]\n\n---\n\n# <note-name>\n\n";
+
 lazy_static! {
     static ref NOTE_LINK_VALIDATOR: Regex = Regex::new(
         r"(?x)
         \[\[
-            ([a-zA-Z0-9]+?)     # $1 = Link text
+            ([^\[\]\n]+?)      # $1 = Link text (note id or note name)
         \]\]
     "
     )
     .unwrap();
+    // Markdown links to files, e.g. '[text](other-note.md)'. Images aren't file links
+    static ref FILE_LINK_VALIDATOR: Regex = Regex::new(
+        r#"(?x)
+        (?:^|[^!])
+        \[[^\]\n]*\]
+        \(
+            ([^)\s]+)         # $1 = Link target (path of the file)
+            (?:\s+"[^"]*")?
+        \)
+    "#
+    )
+    .unwrap();
     static ref NOTE_FORMAT_VALIDATOR: Regex = Regex::new(
         r##"(?xs)
         (                   # $1 = yaml header
@@ -92,34 +123,95 @@ lazy_static! {
     .unwrap();
     static ref TAG_NAME_VALIDATOR: Regex = Regex::new(
         r##"(?x)
-        ^[^!?$%§&/=\{\}+*\|~^@]*$
+        ^[^!?$%§&=\{\}+*\|~^@]*$
     "##
     )
     .unwrap();
+    static ref NOTE_CONTENT_TAG_VALIDATOR: Regex = Regex::new(
+        r"(?x)
+        (?:^|\s)
+        \#
+        ([\p{L}\p{N}_][\p{L}\p{N}_/-]*)   # $1 = tag name (nested tags are separated by '/')
+    "
+    )
+    .unwrap();
+    static ref CODE_FENCE_VALIDATOR: Regex = Regex::new(r"^\s*(```|~~~)").unwrap();
+    static ref INLINE_CODE_VALIDATOR: Regex = Regex::new(r"`[^`]*`").unwrap();
     static ref WHITESPACE_VALIDATOR: Regex = Regex::new(r"^\s*$").unwrap();
+    static ref HEADING_VALIDATOR: Regex = Regex::new(r"(?m)^\#{1,6}([ \t][^\n]*)?$").unwrap();
+    static ref NOTE_HEADER_NAME_VALIDATOR: Regex = Regex::new(
+        r"(?xm)
+        ^name:[\ \t]*[^\n]*
+        (\n[\ \t]+[^\n]*\S[^\n]*)*      # the value can be split into multiple lines
+    "
+    )
+    .unwrap();
 }
 
 pub struct NoteUtility;
 impl NoteUtility {
-    pub fn list(count: i32) {
-        let note_ids = Database::get_all_recent_note_ids(count);
+    // The name filter works like the search of the TUI, so it uses fuzzy matching if it's enabled
+    pub fn get_note_list(
+        archived: bool,
+        property: Option<(&str, &str)>,
+        name_filter: Option<&str>,
+        sort_order: SortOrder,
+        settings: &Settings,
+    ) -> Vec<Note> {
+        let notes = if let Some((property_key, property_value)) = property {
+            Database::get_note_ids_where_property_is_like(
+                NoteProperty::Custom(property_key.to_string()),
+                property_value,
+            )
+            .iter()
+            .filter_map(|m| Database::get_note_where_id(m))
+            .collect()
+        } else if archived {
+            Database::get_archived_notes()
+        } else {
+            Database::get_unarchived_notes()
+        };
 
-        for note_id in note_ids {
-            let note = Database::get_note_where_id(&note_id).unwrap();
-            println!("{} {}", note_id.yellow(), note.note_name);
-        }
+        let name_filter = name_filter.unwrap_or_default().to_lowercase();
+        let mut notes_by_name: HashMap<String, Note> = notes
+            .into_iter()
+            .filter(|m| {
+                if settings.fuzzy_matching_enabled {
+                    FuzzyMatcher::fuzzy_match(&name_filter, &m.note_name).is_some()
+                } else {
+                    m.note_name.to_lowercase().contains(&name_filter)
+                }
+            })
+            .map(|m| (m.note_name.clone(), m))
+            .collect();
+
+        let mut note_names: Vec<String> = notes_by_name.keys().cloned().collect();
+        NoteUtility::sort_note_names(&mut note_names, sort_order, settings);
+        return note_names
+            .iter()
+            .filter_map(|m| notes_by_name.remove(m))
+            .collect();
     }
 
-    pub fn get(count: i32) -> Vec<String> {
-        let note_ids = Database::get_all_recent_note_ids(count);
-        let mut note_list = Vec::new();
-
-        for note_id in note_ids {
-            let note = Database::get_note_where_id(&note_id).unwrap();
-            note_list.push(note.note_name);
-        }
-
-        return note_list;
+    // Pinned notes stay at the top regardless of the sort order. The notes are sorted by the
+    // database, so that the lists are in the same order as the pages of the default note list
+    pub fn sort_note_names(
+        note_names: &mut Vec<String>,
+        sort_order: SortOrder,
+        settings: &Settings,
+    ) {
+        let mut sorted_note_names =
+            Database::get_sorted_note_names(note_names, sort_order, &settings.pinned_note_ids);
+
+        // Names that aren't in the database anymore are kept at the end
+        let known_note_names: HashSet<&String> = sorted_note_names.iter().collect();
+        let unknown_note_names: Vec<String> = note_names
+            .iter()
+            .filter(|m| !known_note_names.contains(m))
+            .cloned()
+            .collect();
+        sorted_note_names.extend(unknown_note_names);
+        *note_names = sorted_note_names;
     }
 
     pub fn search(complete_search_string: &str) -> Vec<NoteTagging> {
@@ -234,426 +326,2565 @@ impl NoteUtility {
         }
     }
 
-    pub fn print_search_results(search_results: Vec<NoteTagging>) {
-        for search_result in search_results {
-            if let Some(note) = Database::get_note_where_id(&search_result.note_id) {
-                let tag_name = &search_result.tag_name.as_ref();
-
-                if tag_name.is_some() {
-                    let tag_name = tag_name.unwrap();
-                    println!(
-                        "{} {}\t\t{}{}",
-                        note.note_id.yellow(),
-                        note.note_name,
-                        "#".bright_yellow(),
-                        tag_name.bright_yellow()
-                    );
-                } else {
-                    println!("{} {}", note.note_id.yellow(), note.note_name);
-                }
-            }
-        }
+    // See SearchQuery for the syntax of the search string
+    pub fn search_content(search_string: &str) -> Result<Vec<Note>, BrainError> {
+        let search_query = SearchQuery::parse(search_string).map_err(BrainError::Other)?;
+        return Ok(Database::search_content(&search_query)
+            .iter()
+            .filter_map(|m| Database::get_note_where_id(m))
+            .collect());
     }
 
+    // The id of a child note is derived from the id of its parent if the id scheme is hierarchical
     pub fn add(
         note_name: &str,
         note_type: NoteType,
+        template_name: Option<&str>,
+        parent_note_id: Option<&str>,
         settings: &mut Settings,
-    ) -> Result<Option<String>, String> {
-        let template_path = Path::new(&settings.zettelkasten_dir).join("note-template.md");
-        if !template_path.exists() {
+    ) -> Result<String, BrainError> {
+        let template_path = match NoteUtility::get_template_path(template_name, settings) {
+            Ok(value) => value,
+            Err(error) => return Err(BrainError::Other(format!("add_note: {}", error))),
+        };
+
+        if let Err(error) = NoteUtility::validate_new_note_name(note_name) {
+            return Err(BrainError::Other(format!("add_note: {}", error)));
+        }
+
+        let note_id =
+            match NoteUtility::get_new_note_id(note_type, parent_note_id, settings.id_scheme) {
+                Ok(value) => value,
+                Err(error) => return Err(BrainError::Other(format!("add_note: {}", error))),
+            };
+
+        let note = match NoteUtility::create_note_from_template(
+            &note_id,
+            note_name,
+            &settings.notes_dir,
+            template_path.as_os_str(),
+        ) {
+            Ok(value) => value,
+            Err(error) => return Err(BrainError::Other(format!("add_note: {}", error))),
+        };
+
+        Database::insert_note(&note);
+        NoteUtility::update_content_index_of(&note, settings)?;
+        NoteUtility::update_properties_of(&note, settings)?;
+        if let Some(parent_note_id) = parent_note_id {
+            if let Err(error) = NoteUtility::connect_to_parent(&note, parent_note_id, settings) {
+                return Err(BrainError::Other(format!(
+                    "add_note: the note '{}' was created but couldn't be connected to its parent: {}",
+                    note.note_id, error
+                )));
+            }
+        }
+        return Ok(note.note_id);
+    }
+
+    // Without a template name the configured default template is used.
+    // If there is none either, the note template of the zettelkasten directory is used
+    fn get_template_path(
+        template_name: Option<&str>,
+        settings: &Settings,
+    ) -> Result<PathBuf, String> {
+        let template_name = match template_name {
+            Some(value) => Some(value),
+            None => settings.default_template.as_deref(),
+        };
+
+        let template_path = match template_name {
+            Some(template_name) => {
+                let mut template_path = Path::new(&settings.templates_dir).join(template_name);
+                if template_path.extension().is_none() {
+                    template_path.set_extension("md");
+                }
+                if !template_path.is_file() {
+                    return Err(format!(
+                        "the template '{}' couldn't be found at '{}'",
+                        template_name,
+                        template_path.to_string_lossy()
+                    ));
+                }
+                template_path
+            }
+            None => {
+                let template_path = Path::new(&settings.zettelkasten_dir).join("note-template.md");
+                if !template_path.exists() {
+                    return Err(format!(
+                        "the note template couldn't be found at '{}'",
+                        template_path.to_string_lossy()
+                    ));
+                }
+                template_path
+            }
+        };
+        return Ok(template_path);
+    }
+
+    pub fn get_template_names(settings: &Settings) -> Vec<String> {
+        let dir_entries = match fs::read_dir(&settings.templates_dir) {
+            Ok(value) => value,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut template_names: Vec<String> = dir_entries
+            .filter_map(|m| m.ok())
+            .map(|m| m.path())
+            .filter(|m| m.is_file() && m.extension().is_some_and(|v| v == "md"))
+            .filter_map(|m| m.file_stem().map(|v| v.to_string_lossy().to_string()))
+            .collect();
+        template_names.sort();
+        return template_names;
+    }
+
+    pub fn validate_new_note_name(note_name: &str) -> Result<(), String> {
+        NoteUtility::validate_characters_of_note_name(note_name)?;
+
+        if Database::get_note_id_where(NoteProperty::NoteName, note_name).is_some() {
             return Err(format!(
-                "add_note: the note template couldn't be found at '{}'",
-                template_path.to_string_lossy()
+                "a note with the name '{}' already exists",
+                note_name
             ));
         }
 
+        return Ok(());
+    }
+
+    fn validate_characters_of_note_name(note_name: &str) -> Result<(), String> {
+        if WHITESPACE_VALIDATOR.is_match(note_name) {
+            return Err("the note name can't be empty".to_string());
+        }
+
         if !NOTE_NAME_VALIDATOR.is_match(note_name) {
             return Err(format!(
-                "add_note: the note name '{}' contains illegal characters",
+                "the note name '{}' contains illegal characters",
                 note_name
             ));
         }
+        return Ok(());
+    }
 
-        if let Some(note) = NoteUtility::create_note_from_template(
-            note_name,
-            note_type,
-            &settings.notes_dir,
-            template_path.as_os_str(),
-        ) {
-            if settings.print_to_stdout {
-                Message::info(&format!(
-                    "created note: {} {}",
-                    note.note_id.yellow(),
-                    note.note_name
-                ));
+    // The parent is stored in the yaml header, so that siblings can be created in every id scheme.
+    // Parent and child link to each other and the child inherits the configured properties
+    fn connect_to_parent(
+        note: &Note,
+        parent_note_id: &str,
+        settings: &mut Settings,
+    ) -> Result<(), String> {
+        let parent_note = match Database::get_note_where_id(parent_note_id) {
+            Some(value) => value,
+            None => return Err(format!("the note id '{}' doesn't exist", parent_note_id)),
+        };
+        let parent_file_path = PathBuf::from(&settings.notes_dir).join(&parent_note.file_name);
+        let parent_content = match FileUtility::get_content_from_file(&parent_file_path) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("couldn't read the parent note file: {}", error)),
+        };
+
+        let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let mut note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("couldn't read note file: {}", error)),
+        };
+        note_content =
+            match NoteUtility::replace_yaml_header_entry(&note_content, "parent", parent_note_id) {
+                Some(value) => value,
+                None => return Err("the note doesn't have a yaml header".to_string()),
+            };
+        for key in settings
+            .inherited_properties
+            .iter()
+            .filter(|m| *m != "tags")
+        {
+            if let Some(yaml_value) = NoteUtility::get_yaml_header_entry(&parent_content, key) {
+                note_content = NoteUtility::replace_yaml_header_entry_with_yaml(
+                    &note_content,
+                    key,
+                    &yaml_value,
+                )
+                .unwrap_or(note_content);
+            }
+        }
+        if let Err(error) = FileUtility::write_atomically(&note_file_path, note_content) {
+            return Err(format!("couldn't write note file: {}", error));
+        }
+        NoteUtility::update_properties_of(note, settings)?;
+
+        // Tags are also stored in the database, so they are added one by one
+        if settings.inherited_properties.iter().any(|m| m == "tags") {
+            let parent_tags = match NoteMetadata::get_tags_of(&parent_note, settings) {
+                Ok(value) => value.unwrap_or_default(),
+                Err(error) => return Err(error.to_string()),
+            };
+            for tag_name in parent_tags {
+                NoteUtility::add_tag(&note.note_id, &tag_name, settings)?;
+            }
+        }
+
+        NoteUtility::add_link(&note.note_id, parent_note_id, settings)?;
+        NoteUtility::add_link(parent_note_id, &note.note_id, settings)?;
+        NoteUtility::update_content_index_of(note, settings)?;
+        return Ok(());
+    }
+
+    // The parent is taken from the yaml header. Hierarchical ids contain the id of the parent,
+    // e.g. '1a' for the note '1a2', so notes that were created without a parent still have one
+    pub fn get_parent_note_id(note_id: &str, settings: &Settings) -> Option<String> {
+        let note = Database::get_note_where_id(note_id)?;
+        if let Ok(properties) = NoteMetadata::get_custom_properties_of(&note, settings) {
+            if let Some((_, parent_note_id)) = properties.into_iter().find(|m| m.0 == "parent") {
+                return Some(parent_note_id);
             }
+        }
+        if settings.id_scheme != IdScheme::Hierarchical {
+            return None;
+        }
 
-            Database::insert_note(&note);
-            return Ok(Some(note.note_id));
+        let last_char = note_id.chars().last()?;
+        let parent_note_id =
+            note_id.trim_end_matches(|c: char| c.is_ascii_digit() == last_char.is_ascii_digit());
+        if parent_note_id.is_empty() {
+            return None;
         }
+        return Database::get_note_where_id(parent_note_id).map(|m| m.note_id);
+    }
 
-        return Ok(None);
+    // Generated ids that are already used are generated again
+    fn get_new_note_id(
+        note_type: NoteType,
+        parent_note_id: Option<&str>,
+        id_scheme: IdScheme,
+    ) -> Result<String, String> {
+        let existing_note_ids = Database::get_all_note_ids();
+        let request = NoteIdRequest {
+            note_type,
+            creation_date_time: Local::now(),
+            parent_note_id,
+            existing_note_ids: &existing_note_ids,
+        };
+
+        let mut id_generator = id_scheme.get_generator();
+        for attempt in 0..MAX_NOTE_ID_ATTEMPTS {
+            let note_id = id_generator.generate_id(&request, attempt);
+            if !existing_note_ids.contains(&note_id) {
+                return Ok(note_id);
+            }
+        }
+        return Err(format!(
+            "couldn't find an unused note id after {} attempts",
+            MAX_NOTE_ID_ATTEMPTS
+        ));
     }
 
     fn create_note_from_template(
+        note_id: &str,
         note_name: &str,
-        note_type: NoteType,
         notes_dir: &OsStr,
         template_path: &OsStr,
-    ) -> Option<Note> {
+    ) -> Result<Note, String> {
+        let template_content = match FileUtility::get_content_from_file(&template_path) {
+            Ok(file_content) => file_content,
+            Err(error) => return Err(format!("couldn't read template file: {}", error)),
+        };
+        return NoteUtility::create_note_from_template_content(
+            note_id,
+            note_name,
+            notes_dir,
+            &template_content,
+        );
+    }
+
+    fn create_note_from_template_content(
+        note_id: &str,
+        note_name: &str,
+        notes_dir: &OsStr,
+        template_content: &str,
+    ) -> Result<Note, String> {
         let creation_date_time = Local::now();
         let creation_timestamp = creation_date_time.format("%Y-%m-%d %H:%M:%S").to_string();
         let creation_file_timestamp = creation_date_time.format("%Y-%m-%d-%H%M%S").to_string();
+        let creation_date = creation_date_time.format("%Y-%m-%d").to_string();
 
-        let note_type_identifier = match note_type {
-            NoteType::Topic => "T",
-            NoteType::Quote => "Q",
-            NoteType::Journal => "J",
-        };
-        let note_id = format!(
-            "{}{}",
-            note_type_identifier,
-            creation_date_time.format("%Y%m%d%H%M%S").to_string()
-        );
-
-        let file_name = format!("{}.md", &creation_file_timestamp);
+        // Notes created in the same second would overwrite each other
+        let file_name = NoteUtility::get_unused_file_name(&creation_file_timestamp, notes_dir);
         let file_path = Path::new(notes_dir).join(&file_name);
 
-        let note_content = match FileUtility::get_content_from_file(&template_path) {
-            Ok(file_content) => file_content,
-            Err(error) => {
-                Message::error(&format!(
-                    "create_note_from_template: couldn't read template file: {}",
-                    error
-                ));
-                return None;
-            }
-        };
-        let note_content = note_content
-            .replace("<note-id>", &note_id)
+        let note_content = template_content
+            .replace("<note-id>", note_id)
             .replace("<note-name>", &note_name)
-            .replace("<creation-date>", &creation_timestamp);
+            .replace("<creation-date>", &creation_timestamp)
+            .replace("{{id}}", note_id)
+            .replace("{{title}}", &note_name)
+            .replace("{{date}}", &creation_date);
 
         let mut new_note = match File::create(&file_path) {
             Ok(created_file) => created_file,
-            Err(error) => {
-                Message::error(&format!(
-                    "create_note_from_template: couldn't create file: {}",
-                    error
-                ));
-                return None;
-            }
+            Err(error) => return Err(format!("couldn't create file: {}", error)),
         };
 
-        if let Err(error) = new_note.write(note_content.as_bytes()) {
-            Message::warning(&format!(
-                "create_note_from_template: couldn't apply template to created note: {}",
-                error
+        // The empty file would otherwise be left behind without a note in the database
+        if let Err(error) = new_note.write_all(note_content.as_bytes()) {
+            let _ = fs::remove_file(&file_path);
+            return Err(format!(
+                "couldn't apply template to created note '{}': {}",
+                file_name, error
             ));
         };
 
-        return Some(Note::new(
-            note_id,
+        return Ok(Note::new(
+            note_id.to_string(),
             note_name.to_string(),
             file_name,
             creation_date_time,
         ));
     }
 
-    pub fn remove(note_name: &str, notes_dir: &OsStr) -> Result<(), String> {
-        let note_id = match Database::get_note_id_where(NoteProperty::NoteName, note_name) {
-            Some(value) => value,
-            None => {
-                // try if the 'note name' is already the note id
-                // (if not then the next step would cause an error which is on purpose)
-                note_name.to_string()
-            }
+    // Appends the text as a timestamped list item to the end of the inbox note.
+    // Returns the id of the inbox note and the warnings about its links
+    pub fn capture(text: &str, settings: &Settings) -> Result<(String, Vec<String>), BrainError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(BrainError::Other(
+                "capture: the captured text can't be empty".to_string(),
+            ));
+        }
+
+        let note_id = match NoteUtility::get_inbox_note_id(settings) {
+            Ok(value) => value,
+            Err(error) => return Err(BrainError::Other(format!("capture: {}", error))),
         };
         let note = match Database::get_note_where_id(&note_id) {
             Some(value) => value,
-            None => {
-                return Err(format!("remove_note: note couldn't be removed: the note id or note name '{}' does not exist!", note_id));
+            None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+        };
+        let note_file_path = Path::new(&settings.notes_dir).join(&note.file_name);
+        let mut note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                })
             }
         };
 
-        let note_file_path = Path::new(notes_dir).join(note.file_name);
+        if !note_content.is_empty() && !note_content.ends_with('\n') {
+            note_content.push('\n');
+        }
+        // Following lines are indented, so that they belong to the same list item
+        note_content.push_str(&format!(
+            "- {} {}\n",
+            Local::now().format(&settings.date_format),
+            text.lines().collect::<Vec<&str>>().join("\n  ")
+        ));
+        if let Err(error) = FileUtility::write_atomically(&note_file_path, note_content) {
+            return Err(BrainError::Io {
+                path: note_file_path,
+                source: error,
+            });
+        }
 
-        NoteUtility::delete_tags_of_note(&note_id);
-        Database::delete_all_links_with_note(&note_id);
-        Database::delete_note(&note_id);
+        let warnings = NoteUtility::update_db_entries_of(&note, settings);
+        return Ok((note.note_id, warnings));
+    }
 
-        match fs::remove_file(&note_file_path) {
-            Ok(_) => {}
-            Err(error) => {
-                return Err(format!(
-                    "remove_note: note file '{}' couldn't be removed: {}",
-                    note_file_path.to_string_lossy(),
-                    error
-                ));
+    // The inbox note is created if it doesn't exist yet
+    fn get_inbox_note_id(settings: &Settings) -> Result<String, String> {
+        let note_id = match &settings.inbox_note_id {
+            Some(note_id) if Database::get_note_where_id(note_id).is_some() => {
+                return Ok(note_id.clone())
             }
+            Some(note_id) => note_id.clone(),
+            None => match Database::get_note_id_where(NoteProperty::NoteName, INBOX_NOTE_NAME) {
+                Some(note_id) => return Ok(note_id),
+                None => NoteUtility::get_new_note_id(NoteType::Topic, None, settings.id_scheme)?,
+            },
         };
+        if Database::get_note_id_where(NoteProperty::NoteName, INBOX_NOTE_NAME).is_some() {
+            return Err(format!(
+                "the inbox note '{}' doesn't exist and its name '{}' is already used by another note",
+                note_id, INBOX_NOTE_NAME
+            ));
+        }
 
-        return Ok(());
+        let note = match NoteUtility::create_note_from_template_content(
+            &note_id,
+            INBOX_NOTE_NAME,
+            &settings.notes_dir,
+            INBOX_NOTE_TEMPLATE,
+        ) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("the inbox note couldn't be created: {}", error)),
+        };
+        Database::insert_note(&note);
+        NoteUtility::update_content_index_of(&note, settings)?;
+        NoteUtility::update_properties_of(&note, settings)?;
+        return Ok(note.note_id);
     }
 
-    fn delete_tags_of_note(note_id: &str) {
-        let note_tags = Database::get_tags_of_note(note_id);
-
-        for note_tag in note_tags {
-            Database::delete_note_tagging(note_id, &note_tag);
+    // A counter is appended to the file name if the file already exists
+    pub fn get_unused_file_name(file_name: &str, notes_dir: &OsStr) -> String {
+        let file_stem = file_name.trim_end_matches(".md");
+        let mut new_file_name = format!("{}.md", file_stem);
+        let mut counter = 1;
+        while Path::new(notes_dir).join(&new_file_name).exists() {
+            new_file_name = format!("{}-{}.md", file_stem, counter);
+            counter += 1;
+        }
+        return new_file_name;
+    }
 
-            let number_of_notes_with_current_tag = Database::get_note_ids_with_tag(&note_tag).len();
-            if number_of_notes_with_current_tag == 0 {
-                Database::delete_tag(&note_tag);
+    pub fn remove(note_name: &str, settings: &Settings) -> Result<RemovedNote, BrainError> {
+        // If the note doesn't exist, the next step causes an error which is on purpose
+        let note_id = match NoteUtility::get_note_id_of_name_or_id(note_name) {
+            Ok(value) => value,
+            Err(_) => return Err(BrainError::NoteNotFound(note_name.to_string())),
+        };
+        let note = match Database::get_note_where_id(&note_id) {
+            Some(value) => value,
+            None => {
+                return Err(BrainError::NoteNotFound(note_id.to_string()));
             }
+        };
+
+        // The note stays in the database if its file couldn't be moved into the trash
+        let note_file_path = Path::new(&settings.notes_dir).join(&note.file_name);
+        if let Err(error) = NoteUtility::move_to_trash(&note_file_path, &note.file_name, settings) {
+            return Err(BrainError::Io {
+                path: note_file_path,
+                source: error,
+            });
         }
+        let linking_note_ids = Database::get_incoming_links(&note_id);
+        let warnings = NoteUtility::delete_from_db(&note, settings);
+
+        // Links inside of the content of other notes are left untouched
+        return Ok(RemovedNote {
+            linking_note_ids: linking_note_ids
+                .into_iter()
+                .filter(|m| *m != note_id)
+                .collect(),
+            warnings,
+        });
     }
 
-    pub fn update_db_for_all_notes_in_project_folder(
-        settings: &mut Settings,
-    ) -> Result<(), String> {
-        let cleared_successfully = Database::clear();
-        if !cleared_successfully {
-            return Err(format!("update-db: Database couldn't be cleared!"));
+    // The removed note shouldn't appear as backlink in the notes it linked to anymore.
+    // Returns the errors of the backlinks that couldn't be removed
+    fn delete_from_db(note: &Note, settings: &Settings) -> Vec<String> {
+        let mut errors = Vec::new();
+        for linked_note_id in Database::get_outgoing_links(&note.note_id) {
+            if let Some(linked_note) = Database::get_note_where_id(&linked_note_id) {
+                if let Err(error) =
+                    NoteUtility::remove_backlink_from(&linked_note, &note.note_id, settings)
+                {
+                    errors.push(error);
+                }
+            }
         }
 
-        println!("(1/4) Create Database...");
-        Database::init();
-        println!("(1/4) Create Database: Done");
+        NoteUtility::delete_tags_of_note(&note.note_id);
+        Database::delete_all_links_with_note(&note.note_id);
+        Database::delete_note(&note.note_id);
+        return errors;
+    }
 
-        println!("(2/4) Get all notes in directory...");
-        let note_metadata_list = match NoteUtility::get_all_note_metadata(settings) {
-            Ok(result) => result,
-            Err(error) => return Err(error),
+    // Appends the body of one note to another note and removes it. Links to the merged note
+    // are changed to link to the other note. Links between the two notes would link the note
+    // to itself afterwards, so they are replaced with their text.
+    // Returns the number of other notes whose links were changed and the warnings of the links
+    // and the yaml header of the changed notes
+    pub fn merge(
+        from_note_id: &str,
+        into_note_id: &str,
+        settings: &Settings,
+    ) -> Result<(usize, Vec<String>), BrainError> {
+        if from_note_id == into_note_id {
+            return Err(BrainError::Other(
+                "merge: a note can't be merged into itself".to_string(),
+            ));
+        }
+        let from_note = match Database::get_note_where_id(from_note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(from_note_id.to_string())),
+        };
+        let into_note = match Database::get_note_where_id(into_note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(into_note_id.to_string())),
         };
-        println!("(2/4) Get all notes in directory: Done");
-
-        settings.show_interactive_dialogs = false;
-
-        // First insert all notes before inserting tags and links
-        // as they depend on notes
-        let mut counter = 0;
-        let mut former_percentage = 0;
-        println!("(3/4) Update notes...");
-        for note_metadata in &note_metadata_list {
-            Database::insert_note(&note_metadata);
 
-            counter += 1;
-            let completion_percentage =
-                ((counter as f32) / (note_metadata_list.len() as f32) * 100.) as usize;
-            if completion_percentage - former_percentage >= 5 {
-                println!("(3/4) Update notes: {}%", completion_percentage);
-                former_percentage = completion_percentage;
+        let from_note_file_path = PathBuf::from(&settings.notes_dir).join(&from_note.file_name);
+        let into_note_file_path = PathBuf::from(&settings.notes_dir).join(&into_note.file_name);
+        let from_note_content = match FileUtility::get_content_from_file(&from_note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: from_note_file_path,
+                    source: error,
+                })
             }
-        }
-        println!("(3/4) Update notes: Done");
-
-        counter = 0;
-        former_percentage = 0;
-        println!("(4/4) Update note links and tags...");
-        for note_metadata in &note_metadata_list {
-            NoteUtility::check_links_in_note(&note_metadata, settings);
+        };
+        let into_note_content = match FileUtility::get_content_from_file(&into_note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: into_note_file_path,
+                    source: error,
+                })
+            }
+        };
+        let from_note_body = match NOTE_FORMAT_VALIDATOR.captures(&from_note_content) {
+            Some(value) => value.get(3).unwrap().as_str(),
+            None => {
+                return Err(BrainError::Parse {
+                    note_id: from_note.note_id.clone(),
+                    message: "it does not have the correct format".to_string(),
+                })
+            }
+        };
+        let into_note_format_match = match NOTE_FORMAT_VALIDATOR.captures(&into_note_content) {
+            Some(value) => value,
+            None => {
+                return Err(BrainError::Parse {
+                    note_id: into_note.note_id.clone(),
+                    message: "it does not have the correct format".to_string(),
+                })
+            }
+        };
+        let into_note_header = into_note_format_match.get(1).unwrap().as_str();
+        let into_note_body = into_note_format_match.get(3).unwrap().as_str();
 
-            counter += 1;
-            match NoteUtility::check_metadata_of(&note_metadata, settings) {
-                Ok(None) => (),
-                Ok(Some(message)) => Message::warning(&message),
-                Err(error) => Message::error(&format!("check_yaml_header_of: {}", error)),
-            };
+        let new_into_note_body = NoteUtility::replace_links_in(into_note_body, |link_text| {
+            if NoteUtility::is_link_to(link_text, from_note_id) {
+                return link_text.to_string();
+            }
+            return format!("[[{}]]", link_text);
+        });
+        let new_from_note_body = NoteUtility::replace_links_in(from_note_body, |link_text| {
+            if NoteUtility::is_link_to(link_text, from_note_id)
+                || NoteUtility::is_link_to(link_text, into_note_id)
+            {
+                return link_text.to_string();
+            }
+            return format!("[[{}]]", link_text);
+        });
+        let mut new_into_note_content = format!(
+            "{}{}\n\n---\n\n{}\n",
+            into_note_header,
+            new_into_note_body.trim_end(),
+            new_from_note_body.trim()
+        );
 
-            let completion_percentage =
-                ((counter as f32) / (note_metadata_list.len() as f32) * 100.) as usize;
-            if completion_percentage - former_percentage >= 5 {
-                println!(
-                    "(4/4) Update note links and tags: {}%",
-                    completion_percentage
-                );
-                former_percentage = completion_percentage;
+        // The tags of the yaml header of both notes are kept
+        let mut tags = NoteMetadata::get_tags_of(&into_note, settings)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let tag_count = tags.len();
+        for tag in NoteMetadata::get_tags_of(&from_note, settings)
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+        {
+            let tag_name = tag.trim_start_matches('#');
+            if !tags.iter().any(|m| m.trim_start_matches('#') == tag_name) {
+                tags.push(tag_name.to_string());
             }
         }
-        println!("(4/4) Update note links and tags: Done");
+        if tags.len() > tag_count {
+            let yaml_tags = format!(
+                "[ {} ]",
+                tags.iter()
+                    .map(|m| NoteUtility::to_yaml_string(m))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+            new_into_note_content = NoteUtility::replace_yaml_header_entry_with_yaml(
+                &new_into_note_content,
+                "tags",
+                &yaml_tags,
+            )
+            .unwrap_or(new_into_note_content);
+        }
+        if let Err(error) =
+            FileUtility::write_atomically(&into_note_file_path, new_into_note_content)
+        {
+            return Err(BrainError::Io {
+                path: into_note_file_path,
+                source: error,
+            });
+        }
+
+        // Links with the id of the merged note get the id of the other note, all other links
+        // get its name
+        let mut relinked_note_count = 0;
+        let mut failed_notes = Vec::new();
+        let mut warnings = Vec::new();
+        for linking_note in NoteUtility::get_linking_notes(from_note_id) {
+            if linking_note.note_id == from_note_id || linking_note.note_id == into_note_id {
+                continue;
+            }
+            let linking_note_file_path =
+                PathBuf::from(&settings.notes_dir).join(&linking_note.file_name);
+            let linking_note_content =
+                match FileUtility::get_content_from_file(&linking_note_file_path) {
+                    Ok(value) => value,
+                    Err(_) => {
+                        failed_notes.push(linking_note.note_id);
+                        continue;
+                    }
+                };
+            let linking_note_format_match =
+                match NOTE_FORMAT_VALIDATOR.captures(&linking_note_content) {
+                    Some(value) => value,
+                    None => {
+                        failed_notes.push(linking_note.note_id);
+                        continue;
+                    }
+                };
+            let linking_note_header = linking_note_format_match.get(1).unwrap().as_str();
+            let linking_note_body = linking_note_format_match.get(3).unwrap().as_str();
+            let new_linking_note_body = NoteUtility::replace_links_in(linking_note_body, |m| {
+                if m == from_note_id {
+                    return format!("[[{}]]", into_note_id);
+                }
+                if NoteUtility::is_link_to(m, from_note_id) {
+                    return format!("[[{}]]", into_note.note_name);
+                }
+                return format!("[[{}]]", m);
+            });
+            if FileUtility::write_atomically(
+                &linking_note_file_path,
+                format!("{}{}", linking_note_header, new_linking_note_body),
+            )
+            .is_err()
+            {
+                failed_notes.push(linking_note.note_id);
+                continue;
+            }
+            warnings.extend(NoteUtility::update_db_entries_of(&linking_note, settings));
+            relinked_note_count += 1;
+        }
+
+        warnings.extend(NoteUtility::delete_from_db(&from_note, settings));
+        if let Err(error) =
+            NoteUtility::move_to_trash(&from_note_file_path, &from_note.file_name, settings)
+        {
+            return Err(BrainError::Io {
+                path: from_note_file_path,
+                source: error,
+            });
+        }
+
+        warnings.extend(NoteUtility::update_db_entries_of(&into_note, settings));
+        match NoteUtility::check_metadata_of(&into_note, settings) {
+            Ok(warning) => warnings.extend(warning),
+            Err(error) => return Err(BrainError::Other(format!("merge: {}", error))),
+        }
+
+        if !failed_notes.is_empty() {
+            return Err(BrainError::Other(format!(
+                "merge: the notes were merged but the links in the following notes couldn't be updated: {}",
+                failed_notes.join(", ")
+            )));
+        }
+        return Ok((relinked_note_count, warnings));
+    }
+
+    // Ambiguous links don't link to any note
+    fn is_link_to(link_text: &str, note_id: &str) -> bool {
+        return match NoteUtility::get_linked_note(link_text) {
+            Ok(Some(note)) => note.note_id == note_id,
+            _ => false,
+        };
+    }
+
+    // brn has no links between notebooks, so the links between the moved note and the notes
+    // of this notebook break. If `unlink_broken_links` is set, they are replaced with their text.
+    // The id is kept if it isn't used in the other notebook yet. The note is only removed from
+    // this notebook after it was added to the other one.
+    pub fn move_to_notebook(
+        note_id: &str,
+        notebook_name: &str,
+        unlink_broken_links: bool,
+        settings: &Settings,
+    ) -> Result<MovedNote, BrainError> {
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+        };
+        if notebook_name == settings.notebook_name {
+            return Err(BrainError::Other(format!(
+                "move_note: the note is already in the notebook '{}'",
+                notebook_name
+            )));
+        }
+        let target_notes_dir = match settings.notebooks.iter().find(|m| m.name == notebook_name) {
+            Some(notebook) => notebook.notes_dir.clone(),
+            None => {
+                return Err(BrainError::Other(format!(
+                    "move_note: the notebook '{}' doesn't exist",
+                    notebook_name
+                )))
+            }
+        };
+        if !Directory::is_zettelkasten_dir(&target_notes_dir, true) {
+            return Err(BrainError::Other(format!(
+                "move_note: the directory '{}' of the notebook '{}' is not a zettelkasten directory",
+                target_notes_dir.to_string_lossy(),
+                notebook_name
+            )));
+        }
+
+        let note_file_path = Path::new(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                })
+            }
+        };
+        let label = Database::get_label_of(&note.note_id);
+
+        // The links of other notes can contain the name or an alias of the moved note as well
+        let mut incoming_links = Vec::new();
+        for linking_note in NoteUtility::get_linking_notes(&note.note_id) {
+            if linking_note.note_id == note.note_id {
+                continue;
+            }
+            let linking_note_file_path =
+                Path::new(&settings.notes_dir).join(&linking_note.file_name);
+            let linking_note_content =
+                match FileUtility::get_content_from_file(&linking_note_file_path) {
+                    Ok(value) => value,
+                    Err(_) => continue,
+                };
+            let link_texts: Vec<String> = NoteUtility::get_link_texts(&linking_note_content)
+                .into_iter()
+                .filter(|m| {
+                    matches!(NoteUtility::get_linked_note(m),
+                        Ok(Some(linked_note)) if linked_note.note_id == note.note_id)
+                })
+                .collect();
+            incoming_links.push((linking_note, link_texts));
+        }
+
+        let target_zettelkasten_dir = Path::new(&target_notes_dir)
+            .join(".zettelkasten")
+            .into_os_string();
+        let mut target_settings = Settings::init(target_notes_dir, target_zettelkasten_dir);
+        target_settings.notebook_name = notebook_name.to_string();
+        target_settings.backlinking_enabled = settings.backlinking_enabled;
+
+        // The database of this notebook is opened again when the guard is dropped
+        let target_database = Database::open_temporarily(&target_settings.zettelkasten_dir);
+        let migration_warnings = NoteUtility::migrate_db(&target_settings, |_| ());
+        let added_note = NoteUtility::add_moved_note(
+            &note,
+            &note_content,
+            label,
+            unlink_broken_links,
+            &mut target_settings,
+        );
+        drop(target_database);
+        let (moved_note, outgoing_broken_link_texts, mut warnings) = match added_note {
+            Ok(value) => value,
+            Err(error) => return Err(BrainError::Other(format!("move_note: {}", error))),
+        };
+        warnings.extend(migration_warnings);
+
+        warnings.extend(NoteUtility::delete_from_db(&note, settings));
+        if let Err(error) = fs::remove_file(&note_file_path) {
+            return Err(BrainError::Other(format!(
+                "move_note: the note was added to the notebook '{}', but its file '{}' couldn't be removed: {}",
+                notebook_name,
+                note_file_path.to_string_lossy(),
+                error
+            )));
+        }
+
+        let mut broken_links: Vec<(String, String)> = outgoing_broken_link_texts
+            .into_iter()
+            .map(|m| (moved_note.note_name.clone(), m))
+            .collect();
+        for (linking_note, link_texts) in incoming_links {
+            if unlink_broken_links {
+                match NoteUtility::unlink(&linking_note, &link_texts, settings) {
+                    Ok(unlink_warnings) => warnings.extend(unlink_warnings),
+                    Err(error) => warnings.push(format!("move_note: {}", error)),
+                }
+            }
+            for link_text in link_texts {
+                broken_links.push((linking_note.note_name.clone(), link_text));
+            }
+        }
+        return Ok(MovedNote {
+            note_id: moved_note.note_id,
+            broken_links,
+            warnings,
+        });
+    }
+
+    // The database of the other notebook has to be opened already. The backlinks of the note
+    // lead to notes of the previous notebook, so they are removed.
+    // Returns the added note, the texts of its broken links and the warnings of adding it
+    fn add_moved_note(
+        note: &Note,
+        note_content: &str,
+        label: Option<NoteLabel>,
+        unlink_broken_links: bool,
+        settings: &mut Settings,
+    ) -> Result<(Note, Vec<String>, Vec<String>), String> {
+        if Database::get_note_id_where(NoteProperty::NoteName, &note.note_name).is_some() {
+            return Err(format!(
+                "a note with the name '{}' already exists in the notebook '{}'",
+                note.note_name, settings.notebook_name
+            ));
+        }
+
+        let mut note_content = note_content.to_string();
+        let mut note_id = note.note_id.clone();
+        if Database::get_note_where_id(&note_id).is_some() {
+            let note_type = match note_id.chars().next() {
+                Some('Q') => NoteType::Quote,
+                Some('J') => NoteType::Journal,
+                _ => NoteType::Topic,
+            };
+            note_id = NoteUtility::get_new_note_id(note_type, None, settings.id_scheme)?;
+            note_content =
+                match NoteUtility::replace_yaml_header_entry(&note_content, "id", &note_id) {
+                    Some(value) => value,
+                    None => return Err("the note doesn't have a yaml header".to_string()),
+                };
+        }
+        if NoteUtility::get_yaml_header_entry(&note_content, "backlinks").is_some() {
+            note_content =
+                NoteUtility::replace_yaml_header_entry_with_yaml(&note_content, "backlinks", "[ ]")
+                    .unwrap_or(note_content);
+        }
+
+        let file_name = NoteUtility::get_unused_file_name(&note.file_name, &settings.notes_dir);
+        let note_file_path = Path::new(&settings.notes_dir).join(&file_name);
+        if let Err(error) = FileUtility::write_atomically(&note_file_path, &note_content) {
+            return Err(format!(
+                "couldn't create note file '{}': {}",
+                note_file_path.to_string_lossy(),
+                error
+            ));
+        }
+
+        let moved_note = Note::new(
+            note_id,
+            note.note_name.clone(),
+            file_name,
+            note.creation_date_time.unwrap_or_else(Local::now),
+        );
+        let mut warnings =
+            NoteUtility::insert_notes_into_db(std::slice::from_ref(&moved_note), settings);
+        Database::set_label_of(&moved_note.note_id, label);
+
+        // The note is removed from the notebook again, so that it isn't in both notebooks
+        let broken_link_texts = NoteUtility::get_broken_link_texts(&note_content);
+        if unlink_broken_links && !broken_link_texts.is_empty() {
+            match NoteUtility::unlink(&moved_note, &broken_link_texts, settings) {
+                Ok(unlink_warnings) => warnings.extend(unlink_warnings),
+                Err(error) => {
+                    NoteUtility::delete_from_db(&moved_note, settings);
+                    let _ = fs::remove_file(&note_file_path);
+                    return Err(error.to_string());
+                }
+            }
+        }
+        return Ok((moved_note, broken_link_texts, warnings));
+    }
+
+    // The trash only keeps the last removed note, as it's only used to undo the removal
+    fn move_to_trash(
+        note_file_path: &Path,
+        file_name: &str,
+        settings: &Settings,
+    ) -> io::Result<()> {
+        let trash_dir = Path::new(&settings.zettelkasten_dir).join(TRASH_DIR_NAME);
+        if trash_dir.is_dir() {
+            fs::remove_dir_all(&trash_dir)?;
+        }
+        fs::create_dir_all(&trash_dir)?;
+        return fs::rename(note_file_path, trash_dir.join(file_name));
+    }
+
+    // Moves the note file back into the notes directory. The database has to be
+    // updated afterwards with `sync_changed_files()`
+    pub fn restore_from_trash(file_name: &str, settings: &Settings) -> Result<(), BrainError> {
+        let trash_file_path = Path::new(&settings.zettelkasten_dir)
+            .join(TRASH_DIR_NAME)
+            .join(file_name);
+        let note_file_path = Path::new(&settings.notes_dir).join(file_name);
+        if !trash_file_path.is_file() {
+            return Err(BrainError::Other(format!(
+                "restore_note: the note file '{}' isn't in the trash anymore",
+                file_name
+            )));
+        }
+        if note_file_path.exists() {
+            return Err(BrainError::Other(format!(
+                "restore_note: the note file '{}' already exists",
+                file_name
+            )));
+        }
+        if let Err(error) = fs::rename(&trash_file_path, &note_file_path) {
+            return Err(BrainError::Io {
+                path: trash_file_path,
+                source: error,
+            });
+        }
+        return Ok(());
+    }
+
+    pub fn rename(
+        note_id: &str,
+        new_note_name: &str,
+        settings: &Settings,
+    ) -> Result<(), BrainError> {
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => {
+                return Err(BrainError::NoteNotFound(note_id.to_string()));
+            }
+        };
+        if note.note_name == new_note_name {
+            return Ok(());
+        }
+        if let Err(error) = NoteUtility::validate_new_note_name(new_note_name) {
+            return Err(BrainError::Other(format!("rename_note: {}", error)));
+        }
+
+        // The file name is based on the creation date, so only the content has to be changed
+        let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&absolute_note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: absolute_note_file_path,
+                    source: error,
+                });
+            }
+        };
+
+        let note_format_match = match NOTE_FORMAT_VALIDATOR.captures(&note_content) {
+            Some(value) => value,
+            None => {
+                return Err(BrainError::Parse {
+                    note_id: note.note_id.clone(),
+                    message: "it does not have the correct format".to_string(),
+                });
+            }
+        };
+        let yaml_header = note_format_match.get(1).unwrap().as_str();
+        let note_body = note_format_match.get(3).unwrap().as_str();
+
+        let new_name_entry = format!("name: {}", NoteUtility::to_yaml_string(new_note_name));
+        let new_yaml_header =
+            NOTE_HEADER_NAME_VALIDATOR.replace(yaml_header, NoExpand(&new_name_entry));
+
+        // Also rename the title of the note if it is the same as the note name
+        let title_validator = Regex::new(&format!(
+            r"(?m)^\#[ \t]+{}[ \t]*$",
+            regex::escape(&note.note_name)
+        ))
+        .unwrap();
+        let new_title = format!("# {}", new_note_name);
+        let new_note_body = title_validator.replace(note_body, NoExpand(&new_title));
+
+        // Links by the name are matched like when following them, so the case of the name
+        // doesn't matter. Links by the id or an alias stay valid, so they are kept. This has
+        // to happen before the name is changed in the database
+        let rename_link = |link_text: &str| -> String {
+            if link_text.to_lowercase() == note.note_name.to_lowercase()
+                && NoteUtility::is_link_to(link_text, note_id)
+            {
+                return format!("[[{}]]", new_note_name);
+            }
+            return format!("[[{}]]", link_text);
+        };
+        let new_note_body = NoteUtility::replace_links_in(&new_note_body, rename_link);
+        let mut failed_notes = Vec::new();
+        let mut changed_linking_notes = Vec::new();
+        for linking_note in NoteUtility::get_linking_notes(note_id) {
+            if linking_note.note_id == note_id {
+                continue;
+            }
+            let linking_note_file_path =
+                PathBuf::from(&settings.notes_dir).join(&linking_note.file_name);
+            let linking_note_content =
+                match FileUtility::get_content_from_file(&linking_note_file_path) {
+                    Ok(value) => value,
+                    Err(_) => {
+                        failed_notes.push(linking_note.note_id);
+                        continue;
+                    }
+                };
+            let linking_note_format_match =
+                match NOTE_FORMAT_VALIDATOR.captures(&linking_note_content) {
+                    Some(value) => value,
+                    None => {
+                        failed_notes.push(linking_note.note_id);
+                        continue;
+                    }
+                };
+            let linking_note_header = linking_note_format_match.get(1).unwrap().as_str();
+            let linking_note_body = linking_note_format_match.get(3).unwrap().as_str();
+            let new_linking_note_body =
+                NoteUtility::replace_links_in(linking_note_body, rename_link);
+            if new_linking_note_body != linking_note_body {
+                let new_linking_note_content =
+                    format!("{}{}", linking_note_header, new_linking_note_body);
+                changed_linking_notes.push((linking_note, new_linking_note_content));
+            }
+        }
+
+        let new_note_content = new_yaml_header.to_string() + &new_note_body;
+        if let Err(error) = NoteUtility::replace_content_of_file(
+            &absolute_note_file_path,
+            new_note_content.as_bytes(),
+        ) {
+            return Err(BrainError::Io {
+                path: absolute_note_file_path,
+                source: error,
+            });
+        }
+        Database::update_note_name_where(new_note_name, NoteProperty::NoteId, note_id);
+        Database::update_note_content(note_id, &new_note_content);
+        NoteUtility::update_modification_date_of(note_id, &absolute_note_file_path);
+
+        // The links keep linking to the same note, so only the content of the notes changes
+        for (linking_note, new_linking_note_content) in changed_linking_notes {
+            let linking_note_file_path =
+                PathBuf::from(&settings.notes_dir).join(&linking_note.file_name);
+            if NoteUtility::replace_content_of_file(
+                &linking_note_file_path,
+                new_linking_note_content.as_bytes(),
+            )
+            .is_err()
+            {
+                failed_notes.push(linking_note.note_id);
+            } else {
+                Database::update_note_content(&linking_note.note_id, &new_linking_note_content);
+                NoteUtility::update_modification_date_of(
+                    &linking_note.note_id,
+                    &linking_note_file_path,
+                );
+            }
+        }
+
+        if !failed_notes.is_empty() {
+            return Err(BrainError::Other(format!(
+                "rename_note: the note was renamed but the links in the following notes couldn't be updated: {}",
+                failed_notes.join(", ")
+            )));
+        }
+
+        return Ok(());
+    }
+
+    // Renames every note whose name matches the pattern. The replacement can contain the groups
+    // of the pattern (e.g. '$1'). All new names are checked before the first note is renamed.
+    // Returns the renamed notes with their new names
+    pub fn rename_matching_notes(
+        pattern: &str,
+        replacement: &str,
+        dry_run: bool,
+        settings: &Settings,
+    ) -> Result<Vec<(Note, String)>, BrainError> {
+        let name_validator = match Regex::new(pattern) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Other(format!(
+                    "rename: invalid pattern '{}': {}",
+                    pattern, error
+                )))
+            }
+        };
+
+        let mut renamings: Vec<(Note, String)> = Vec::new();
+        let mut kept_note_names: Vec<String> = Vec::new();
+        for note in Database::get_all_notes() {
+            let new_note_name = name_validator
+                .replace_all(&note.note_name, replacement)
+                .trim()
+                .to_string();
+            if new_note_name == note.note_name {
+                kept_note_names.push(note.note_name);
+                continue;
+            }
+            if let Err(error) = NoteUtility::validate_characters_of_note_name(&new_note_name) {
+                return Err(BrainError::Other(format!(
+                    "rename: '{}': {}",
+                    note.note_name, error
+                )));
+            }
+            renamings.push((note, new_note_name));
+        }
+
+        // Names of notes that are renamed become free, as long as they aren't taken again
+        let mut new_note_names: HashMap<&str, &str> = kept_note_names
+            .iter()
+            .map(|m| (m.as_str(), m.as_str()))
+            .collect();
+        for (note, new_note_name) in renamings.iter() {
+            if let Some(other_note_name) = new_note_names.insert(new_note_name, &note.note_name) {
+                return Err(BrainError::Other(format!(
+                    "rename: '{}' and '{}' would both be named '{}', no note was renamed",
+                    other_note_name, note.note_name, new_note_name
+                )));
+            }
+        }
+        if dry_run {
+            return Ok(renamings);
+        }
+
+        // A note can only take the name of another renamed note after that one was renamed
+        let mut pending_renamings: Vec<&(Note, String)> = renamings.iter().collect();
+        while !pending_renamings.is_empty() {
+            let (ready_renamings, blocked_renamings): (Vec<_>, Vec<_>) = pending_renamings
+                .into_iter()
+                .partition(|m| Database::get_note_id_where(NoteProperty::NoteName, &m.1).is_none());
+            if ready_renamings.is_empty() {
+                let blocked_note_names: Vec<&str> = blocked_renamings
+                    .iter()
+                    .map(|m| m.0.note_name.as_str())
+                    .collect();
+                return Err(BrainError::Other(format!(
+                    "rename: the notes {} would swap their names, which isn't supported",
+                    blocked_note_names.join(", ")
+                )));
+            }
+            for (note, new_note_name) in ready_renamings {
+                NoteUtility::rename(&note.note_id, new_note_name, settings)?;
+            }
+            pending_renamings = blocked_renamings;
+        }
+        return Ok(renamings);
+    }
+
+    // Replaces the value of an entry in the yaml header. Returns None if there is no yaml header
+    pub fn replace_yaml_header_entry(note_content: &str, key: &str, value: &str) -> Option<String> {
+        return NoteUtility::replace_yaml_header_entry_with_yaml(
+            note_content,
+            key,
+            &NoteUtility::to_yaml_string(value),
+        );
+    }
+
+    // Returns the yaml value of the entry as it is written in the yaml header
+    fn get_yaml_header_entry(note_content: &str, key: &str) -> Option<String> {
+        let note_format_match = NOTE_FORMAT_VALIDATOR.captures(note_content)?;
+        let yaml_header = note_format_match.get(1).unwrap().as_str();
+        let entry_validator = Regex::new(&format!(
+            r"(?m)^{}:[ \t]*([^\n]*(\n[ \t]+[^\n]*\S[^\n]*)*)",
+            regex::escape(key)
+        ))
+        .unwrap();
+        let yaml_value = entry_validator
+            .captures(yaml_header)?
+            .get(1)?
+            .as_str()
+            .trim();
+        if yaml_value.is_empty() {
+            return None;
+        }
+        return Some(yaml_value.to_string());
+    }
+
+    // The yaml value is inserted as it is, e.g. to insert lists
+    fn replace_yaml_header_entry_with_yaml(
+        note_content: &str,
+        key: &str,
+        yaml_value: &str,
+    ) -> Option<String> {
+        let note_format_match = NOTE_FORMAT_VALIDATOR.captures(note_content)?;
+        let yaml_header = note_format_match.get(1).unwrap().as_str();
+        let note_body = note_format_match.get(3).unwrap().as_str();
+
+        // The value can be split into multiple lines
+        let entry_validator = Regex::new(&format!(
+            r"(?m)^{}:[ \t]*[^\n]*(\n[ \t]+[^\n]*\S[^\n]*)*",
+            regex::escape(key)
+        ))
+        .unwrap();
+        let new_entry = format!("{}: {}", key, yaml_value);
+        if entry_validator.is_match(yaml_header) {
+            let new_yaml_header = entry_validator.replace(yaml_header, NoExpand(&new_entry));
+            return Some(new_yaml_header.to_string() + note_body);
+        }
+
+        // Missing entries are added at the end of the yaml header
+        let header_end_validator = Regex::new(r"(?s)^(.*\S)(\s*---[ \t]*\n?)$").unwrap();
+        let new_yaml_header = header_end_validator
+            .replace(yaml_header, |caps: &regex::Captures| {
+                format!("{}\n{}{}", &caps[1], new_entry, &caps[2])
+            });
+        return Some(new_yaml_header.to_string() + note_body);
+    }
+
+    // Adds the tag to the yaml header of the note and to the database
+    pub fn add_tag(
+        note_id: &str,
+        tag_name: &str,
+        settings: &mut Settings,
+    ) -> Result<(), BrainError> {
+        let tag_name = tag_name.trim().trim_start_matches('#');
+        if tag_name.is_empty() || !TAG_NAME_VALIDATOR.is_match(tag_name) {
+            return Err(BrainError::Other(format!(
+                "add-tag: the tag name '{}' contains illegal characters",
+                tag_name
+            )));
+        }
+
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+        };
+        let mut tags = match NoteMetadata::get_tags_of(&note, settings) {
+            Ok(value) => value.unwrap_or_default(),
+            Err(error) => return Err(BrainError::Other(format!("add-tag: {}", error))),
+        };
+        if tags.iter().any(|m| m.trim_start_matches('#') == tag_name) {
+            return Ok(());
+        }
+        tags.push(tag_name.to_string());
+
+        let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                })
+            }
+        };
+        let yaml_tags = format!(
+            "[ {} ]",
+            tags.iter()
+                .map(|m| NoteUtility::to_yaml_string(m))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+        let new_note_content = match NoteUtility::replace_yaml_header_entry_with_yaml(
+            &note_content,
+            "tags",
+            &yaml_tags,
+        ) {
+            Some(value) => value,
+            None => {
+                return Err(BrainError::Parse {
+                    note_id: note.note_id.clone(),
+                    message: "it doesn't have a yaml header".to_string(),
+                })
+            }
+        };
+        if let Err(error) = FileUtility::write_atomically(&note_file_path, new_note_content) {
+            return Err(BrainError::Io {
+                path: note_file_path,
+                source: error,
+            });
+        }
+
+        Database::insert_tag_for_note(tag_name, note_id);
+        return Ok(());
+    }
+
+    // Renames the tags in the yaml header and in the body of every note using them.
+    // Nested tags are renamed too, e.g. '#ml/basics' becomes '#machine-learning/basics'.
+    // Returns the notes that were changed or would be changed in a dry run
+    pub fn rename_tags(
+        old_tag_names: &[&str],
+        new_tag_name: &str,
+        dry_run: bool,
+        settings: &mut Settings,
+    ) -> Result<Vec<Note>, BrainError> {
+        let new_tag_name = new_tag_name.trim().trim_start_matches('#');
+        if new_tag_name.is_empty() || !TAG_NAME_VALIDATOR.is_match(new_tag_name) {
+            return Err(BrainError::Other(format!(
+                "rename-tags: the tag name '{}' contains illegal characters",
+                new_tag_name
+            )));
+        }
+        let old_tag_names: Vec<&str> = old_tag_names
+            .iter()
+            .map(|m| m.trim().trim_start_matches('#'))
+            .filter(|m| *m != new_tag_name)
+            .collect();
+
+        let mut note_ids = Vec::new();
+        for (tag_name, _) in Database::get_tag_counts() {
+            if NoteUtility::get_renamed_tag(&tag_name, &old_tag_names, new_tag_name).is_some() {
+                note_ids.extend(Database::get_note_ids_with_tag(&tag_name));
+            }
+        }
+        note_ids.sort();
+        note_ids.dedup();
+
+        let mut changed_notes = Vec::new();
+        for note_id in note_ids {
+            let note = match Database::get_note_where_id(&note_id) {
+                Some(value) => value,
+                None => continue,
+            };
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+            let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(BrainError::Io {
+                        path: note_file_path,
+                        source: error,
+                    })
+                }
+            };
+
+            let new_note_content = NoteUtility::rename_tags_in_content(
+                &note,
+                &note_content,
+                &old_tag_names,
+                new_tag_name,
+                settings,
+            )?;
+            if new_note_content == note_content {
+                continue;
+            }
+            if !dry_run {
+                if let Err(error) = FileUtility::write_atomically(&note_file_path, new_note_content)
+                {
+                    return Err(BrainError::Io {
+                        path: note_file_path,
+                        source: error,
+                    });
+                }
+            }
+            changed_notes.push(note);
+        }
+
+        // The tags of the changed notes are read again, so that no tag is stored twice
+        if !dry_run {
+            let file_names: Vec<String> =
+                changed_notes.iter().map(|m| m.file_name.clone()).collect();
+            NoteUtility::sync_changed_files(&file_names, settings);
+        }
+        return Ok(changed_notes);
+    }
+
+    fn rename_tags_in_content(
+        note: &Note,
+        note_content: &str,
+        old_tag_names: &[&str],
+        new_tag_name: &str,
+        settings: &Settings,
+    ) -> Result<String, BrainError> {
+        let mut new_note_content = note_content.to_string();
+
+        // A note that already has the new tag keeps it only once
+        let header_tags = match NoteMetadata::get_tags_of(note, settings) {
+            Ok(value) => value.unwrap_or_default(),
+            Err(error) => return Err(BrainError::Other(format!("rename-tags: {}", error))),
+        };
+        let mut new_header_tags: Vec<String> = Vec::new();
+        for tag in header_tags.iter() {
+            let prefix = if tag.starts_with('#') { "#" } else { "" };
+            let tag_name = tag.trim_start_matches('#');
+            let new_tag = match NoteUtility::get_renamed_tag(tag_name, old_tag_names, new_tag_name)
+            {
+                Some(renamed_tag) => format!("{}{}", prefix, renamed_tag),
+                None => tag.clone(),
+            };
+            if !new_header_tags
+                .iter()
+                .any(|m| m.trim_start_matches('#') == new_tag.trim_start_matches('#'))
+            {
+                new_header_tags.push(new_tag);
+            }
+        }
+        if new_header_tags != header_tags {
+            let yaml_tags = format!(
+                "[ {} ]",
+                new_header_tags
+                    .iter()
+                    .map(|m| NoteUtility::to_yaml_string(m))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+            if let Some(value) = NoteUtility::replace_yaml_header_entry_with_yaml(
+                &new_note_content,
+                "tags",
+                &yaml_tags,
+            ) {
+                new_note_content = value;
+            }
+        }
+
+        // Tags in the body are found the same way as in `get_all_tags_in_note`
+        let note_format_match = match NOTE_FORMAT_VALIDATOR.captures(&new_note_content) {
+            Some(value) => value,
+            None => return Ok(new_note_content),
+        };
+        let yaml_header = note_format_match.get(1).unwrap().as_str();
+        let note_body = note_format_match.get(3).unwrap().as_str();
+        let mut new_note_body = String::new();
+        let mut is_in_code_block = false;
+        for line in note_body.split_inclusive('\n') {
+            if CODE_FENCE_VALIDATOR.is_match(line) {
+                is_in_code_block = !is_in_code_block;
+            }
+            if is_in_code_block || CODE_FENCE_VALIDATOR.is_match(line) {
+                new_note_body.push_str(line);
+                continue;
+            }
+
+            // Inline code is kept as it is
+            let mut last_end = 0;
+            for inline_code_match in INLINE_CODE_VALIDATOR.find_iter(line) {
+                new_note_body.push_str(&NoteUtility::rename_tags_in_text(
+                    &line[last_end..inline_code_match.start()],
+                    old_tag_names,
+                    new_tag_name,
+                ));
+                new_note_body.push_str(inline_code_match.as_str());
+                last_end = inline_code_match.end();
+            }
+            new_note_body.push_str(&NoteUtility::rename_tags_in_text(
+                &line[last_end..],
+                old_tag_names,
+                new_tag_name,
+            ));
+        }
+
+        return Ok(format!("{}{}", yaml_header, new_note_body));
+    }
+
+    fn rename_tags_in_text(text: &str, old_tag_names: &[&str], new_tag_name: &str) -> String {
+        return NOTE_CONTENT_TAG_VALIDATOR
+            .replace_all(text, |captures: &Captures| {
+                let whole_match = captures.get(0).unwrap().as_str();
+                let tag_name = captures.get(1).unwrap().as_str().trim_end_matches('/');
+                match NoteUtility::get_renamed_tag(tag_name, old_tag_names, new_tag_name) {
+                    Some(renamed_tag) => whole_match.replacen(tag_name, &renamed_tag, 1),
+                    None => whole_match.to_string(),
+                }
+            })
+            .to_string();
+    }
+
+    // Returns None if the tag isn't one of the old tags or nested in one of them
+    fn get_renamed_tag(
+        tag_name: &str,
+        old_tag_names: &[&str],
+        new_tag_name: &str,
+    ) -> Option<String> {
+        for old_tag_name in old_tag_names {
+            if tag_name == *old_tag_name {
+                return Some(new_tag_name.to_string());
+            }
+            if let Some(nested_tag_name) = tag_name
+                .strip_prefix(old_tag_name)
+                .and_then(|m| m.strip_prefix('/'))
+            {
+                return Some(format!("{}/{}", new_tag_name, nested_tag_name));
+            }
+        }
+        return None;
+    }
+
+    // Appends a link to the end of the source note. Returns None if the source note already
+    // links to the target note, otherwise the warnings of the links of the source note
+    pub fn add_link(
+        source_note_id: &str,
+        target_note_id: &str,
+        settings: &Settings,
+    ) -> Result<Option<Vec<String>>, BrainError> {
+        let source_note = match Database::get_note_where_id(source_note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(source_note_id.to_string())),
+        };
+        if Database::get_note_where_id(target_note_id).is_none() {
+            return Err(BrainError::NoteNotFound(target_note_id.to_string()));
+        }
+        if Database::get_outgoing_links(source_note_id)
+            .iter()
+            .any(|m| m == target_note_id)
+        {
+            return Ok(None);
+        }
+
+        let note_file_path = PathBuf::from(&settings.notes_dir).join(&source_note.file_name);
+        let mut note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                })
+            }
+        };
+        if !note_content.is_empty() && !note_content.ends_with('\n') {
+            note_content.push('\n');
+        }
+        note_content.push_str(&format!("[[{}]]\n", target_note_id));
+        if let Err(error) = FileUtility::write_atomically(&note_file_path, note_content) {
+            return Err(BrainError::Io {
+                path: note_file_path,
+                source: error,
+            });
+        }
+
+        let warnings = NoteUtility::check_links_in_note(&source_note, settings);
+        NoteUtility::update_content_index_of(&source_note, settings)?;
+        return Ok(Some(warnings));
+    }
+
+    // Checks or unchecks the task in the given line (starting at 0) of the note file.
+    // Returns whether the task is done now
+    pub fn toggle_task(
+        note_id: &str,
+        line_index: usize,
+        settings: &Settings,
+    ) -> Result<bool, BrainError> {
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+        };
+
+        let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                })
+            }
+        };
+        if !NoteTasks::get_task_line_indices(&note_content).contains(&line_index) {
+            return Err(BrainError::Other(
+                "toggle-task: the line isn't a task".to_string(),
+            ));
+        }
+        // The line endings are kept as they are
+        let mut lines: Vec<&str> = note_content.split_inclusive('\n').collect();
+        let toggled_line = match NoteTasks::toggle_task_of(lines[line_index]) {
+            Some(value) => value,
+            None => {
+                return Err(BrainError::Other(
+                    "toggle-task: the line isn't a task".to_string(),
+                ))
+            }
+        };
+        let is_done = NoteTasks::get_state_of(&toggled_line) == Some(true);
+        lines[line_index] = &toggled_line;
+        if let Err(error) = FileUtility::write_atomically(&note_file_path, lines.concat()) {
+            return Err(BrainError::Io {
+                path: note_file_path,
+                source: error,
+            });
+        }
+
+        NoteUtility::update_content_index_of(&note, settings)?;
+        return Ok(is_done);
+    }
+
+    // Archived notes are hidden from the default note list but their links are kept.
+    // The state is stored in the yaml header so that it survives rebuilding the database
+    pub fn set_archived(
+        note_id: &str,
+        archived: bool,
+        settings: &Settings,
+    ) -> Result<(), BrainError> {
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+        };
+
+        let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                })
+            }
+        };
+        let new_note_content = match NoteUtility::replace_yaml_header_entry_with_yaml(
+            &note_content,
+            "archived",
+            &archived.to_string(),
+        ) {
+            Some(value) => value,
+            None => {
+                return Err(BrainError::Parse {
+                    note_id: note.note_id.clone(),
+                    message: "it doesn't have a yaml header".to_string(),
+                })
+            }
+        };
+        if let Err(error) = FileUtility::write_atomically(&note_file_path, new_note_content) {
+            return Err(BrainError::Io {
+                path: note_file_path,
+                source: error,
+            });
+        }
+
+        NoteUtility::update_properties_of(&note, settings)?;
+        return Ok(());
+    }
+
+    pub fn to_yaml_string(value: &str) -> String {
+        let needs_quotes = value.contains(|c| ":#[]{},&*!|>'\"%@`".contains(c))
+            || value.starts_with(' ')
+            || value.ends_with(' ');
+        if needs_quotes {
+            return format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+        } else {
+            return value.to_string();
+        }
+    }
+
+    fn delete_tags_of_note(note_id: &str) {
+        let note_tags = Database::get_tags_of_note(note_id);
+
+        for note_tag in note_tags {
+            Database::delete_note_tagging(note_id, &note_tag);
+
+            let number_of_notes_with_current_tag = Database::get_note_ids_with_tag(&note_tag).len();
+            if number_of_notes_with_current_tag == 0 {
+                Database::delete_tag(&note_tag);
+            }
+        }
+    }
+
+    // Brings the database of older zettelkasten directories up to date.
+    // Returns the warnings of the links of the notes
+    pub fn migrate_db<F: FnMut(&str)>(settings: &Settings, mut report_progress: F) -> Vec<String> {
+        Database::init();
+        if Database::get_schema_version() >= DB_SCHEMA_VERSION {
+            return Vec::new();
+        }
+
+        // Version 1: Links by name and the content index
+        // Version 2: Custom properties of the yaml header
+        // Version 3: Archived notes
+        // Version 4: Aliases of notes
+        // Version 5: Modification dates of notes
+        let notes = Database::get_all_notes();
+        if !notes.is_empty() {
+            report_progress("updating the database to the latest version...");
+        }
+        let mut warnings = Vec::new();
+        for note in notes {
+            if let Some(note_links) =
+                NoteUtility::get_all_links_in_note(&note, settings, &mut warnings)
+            {
+                for note_link_id in note_links {
+                    let _ = Database::insert_note_link_for_note(&note.note_id, &note_link_id);
+                }
+            }
+            if let Err(error) = NoteUtility::update_content_index_of(&note, settings) {
+                warnings.push(error.to_string());
+            }
+            if let Err(error) = NoteUtility::update_properties_of(&note, settings) {
+                warnings.push(error.to_string());
+            }
+        }
+
+        Database::set_schema_version(DB_SCHEMA_VERSION);
+        return warnings;
+    }
+
+    // Returns the warnings of loading its config and of the migration of its database
+    pub fn open_notebook(
+        notebook_name: &str,
+        settings: &mut Settings,
+    ) -> Result<Vec<String>, BrainError> {
+        settings.switch_notebook(notebook_name)?;
+        Database::set_db_path(&settings.zettelkasten_dir);
+        let mut warnings = std::mem::take(&mut settings.warnings);
+        warnings.extend(NoteUtility::migrate_db(settings, |_| ()));
+        return Ok(warnings);
+    }
+
+    // The progress is reported as text, e.g. "(3/4) Update notes: 50%".
+    // Returns the warnings of the links and the yaml headers of the notes
+    pub fn update_db_for_all_notes_in_project_folder<F: FnMut(&str)>(
+        settings: &mut Settings,
+        mut report_progress: F,
+    ) -> Result<Vec<String>, BrainError> {
+        // Labels and the usage of the notes are only stored in the database, so they have to be kept
+        let note_labels = Database::get_all_note_labels();
+        let note_usages = Database::get_all_note_usages();
+        let cleared_successfully = Database::clear();
+        if !cleared_successfully {
+            return Err(BrainError::Other(
+                "update-db: Database couldn't be cleared!".to_string(),
+            ));
+        }
+
+        report_progress("(1/4) Create Database...");
+        Database::init();
+        report_progress("(1/4) Create Database: Done");
+
+        report_progress("(2/4) Get all notes in directory...");
+        let note_metadata_list = match NoteUtility::get_all_note_metadata(settings) {
+            Ok(result) => result,
+            Err(error) => return Err(BrainError::Other(error)),
+        };
+        report_progress("(2/4) Get all notes in directory: Done");
+
+        // First insert all notes before inserting tags and links
+        // as they depend on notes
+        let mut counter = 0;
+        let mut former_percentage = 0;
+        report_progress("(3/4) Update notes...");
+        for note_metadata in &note_metadata_list {
+            Database::insert_note(&note_metadata);
+
+            counter += 1;
+            let completion_percentage =
+                ((counter as f32) / (note_metadata_list.len() as f32) * 100.) as usize;
+            if completion_percentage - former_percentage >= 5 {
+                report_progress(&format!("(3/4) Update notes: {}%", completion_percentage));
+                former_percentage = completion_percentage;
+            }
+        }
+        report_progress("(3/4) Update notes: Done");
+
+        counter = 0;
+        former_percentage = 0;
+        report_progress("(4/4) Update note links and tags...");
+        let mut warnings = Vec::new();
+        for note_metadata in &note_metadata_list {
+            warnings.extend(NoteUtility::update_db_entries_of(note_metadata, settings));
+
+            counter += 1;
+            match NoteUtility::check_metadata_of(note_metadata, settings) {
+                Ok(None) => (),
+                Ok(Some(message)) => warnings.push(message),
+                Err(error) => warnings.push(format!("check_yaml_header_of: {}", error)),
+            };
+
+            let completion_percentage =
+                ((counter as f32) / (note_metadata_list.len() as f32) * 100.) as usize;
+            if completion_percentage - former_percentage >= 5 {
+                report_progress(&format!(
+                    "(4/4) Update note links and tags: {}%",
+                    completion_percentage
+                ));
+                former_percentage = completion_percentage;
+            }
+        }
+        report_progress("(4/4) Update note links and tags: Done");
+
+        for (note_id, label) in note_labels {
+            if Database::get_note_where_id(&note_id).is_some() {
+                Database::set_label_of(&note_id, Some(label));
+            }
+        }
+        for (note_id, open_count, last_opened) in note_usages {
+            if Database::get_note_where_id(&note_id).is_some() {
+                let _ = Database::set_usage_of(&note_id, open_count, &last_opened);
+            }
+        }
+        Database::set_schema_version(DB_SCHEMA_VERSION);
+        return Ok(warnings);
+    }
+
+    // Brings the database up to date with note files that were created, changed or
+    // deleted outside of brn. Returns the warnings instead of printing them
+    pub fn sync_changed_files(file_names: &[String], settings: &mut Settings) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut changed_notes = Vec::new();
+
+        // First update all notes before updating tags and links
+        // as they depend on notes
+        for file_name in file_names {
+            let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(file_name);
+            let existing_note = Database::get_note_where_file_name(file_name);
+
+            if !absolute_note_file_path.is_file() {
+                if let Some(note) = existing_note {
+                    NoteUtility::delete_tags_of_note(&note.note_id);
+                    Database::delete_all_links_with_note(&note.note_id);
+                    Database::delete_note(&note.note_id);
+                }
+                continue;
+            }
+
+            match existing_note {
+                Some(note) => changed_notes.push(note),
+                None => match NoteMetadata::get_basic_data_of_file(&absolute_note_file_path) {
+                    Ok(note) => {
+                        Database::insert_note(&note);
+                        changed_notes.push(note);
+                    }
+                    Err(error) => warnings.push(format!("'{}': {}", file_name, error)),
+                },
+            }
+        }
+
+        for note in changed_notes.iter() {
+            warnings.extend(NoteUtility::update_db_entries_of(note, settings));
+            match NoteUtility::check_metadata_of(note, settings) {
+                Ok(None) => (),
+                Ok(Some(message)) => warnings.push(message),
+                Err(error) => warnings.push(error),
+            };
+        }
+
+        return warnings;
+    }
+
+    // Adds notes whose files were created outside of brn to the database.
+    // Returns the warnings about the links and the yaml headers of the notes
+    pub fn insert_notes_into_db(notes: &[Note], settings: &mut Settings) -> Vec<String> {
+        let mut warnings = Vec::new();
+        // First insert all notes before inserting tags and links
+        // as they depend on notes
+        for note in notes {
+            Database::insert_note(note);
+        }
+
+        for note in notes {
+            warnings.extend(NoteUtility::update_db_entries_of(note, settings));
+            match NoteUtility::check_metadata_of(note, settings) {
+                Ok(None) => (),
+                Ok(Some(message)) => warnings.push(message),
+                Err(error) => warnings.push(format!("check_yaml_header_of: {}", error)),
+            };
+        }
+        return warnings;
+    }
+
+    fn get_all_note_metadata(settings: &mut Settings) -> Result<Vec<Note>, String> {
+        let directory_entries = fs::read_dir(&settings.notes_dir).unwrap();
+        let mut note_metadata_list: Vec<Note> = Vec::new();
+
+        for entry in directory_entries {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                continue;
+            }
+
+            let file_name = match path.file_name().unwrap().to_str() {
+                Some(value) => value,
+                None => {
+                    return Err(format!(
+                        "update-db: The file name '{}' contains illegal characters!",
+                        path.to_string_lossy()
+                    ))
+                }
+            };
+
+            let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&file_name);
+            let _ = match NoteMetadata::get_basic_data_of_file(&absolute_note_file_path) {
+                Ok(note_metadata) => {
+                    note_metadata_list.push(note_metadata);
+                }
+                Err(error) => return Err(error),
+            };
+        }
+
+        return Ok(note_metadata_list);
+    }
+
+    pub fn get_content_of_note(
+        note_id: &str,
+        settings: &mut Settings,
+    ) -> Result<String, BrainError> {
+        let absolute_file_path = NoteUtility::get_absolute_path_of_note(note_id, settings)?;
+
+        let note_content = match FileUtility::get_content_from_file(&absolute_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: PathBuf::from(absolute_file_path),
+                    source: error,
+                });
+            }
+        };
+        return Ok(note_content);
+    }
+
+    pub fn get_statistics_of(
+        note_id: &str,
+        settings: &mut Settings,
+    ) -> Result<NoteStatistics, BrainError> {
+        let note_content = NoteUtility::get_content_of_note(note_id, settings)?;
+        return Ok(NoteStatistics::from_content(&note_content));
+    }
+
+    // Archived notes are never chosen
+    pub fn get_random_note_ids(
+        amount: usize,
+        tag_name: Option<&str>,
+        random_generator: &mut RandomGenerator,
+    ) -> Vec<String> {
+        // Only the chosen notes are loaded from the database. The candidates are always
+        // in the same order, so the seed alone decides the result
+        let tag_name = tag_name.map(|m| m.trim_start_matches('#'));
+        let candidate_count = Database::get_random_candidate_count(tag_name);
+        let mut chosen_positions = HashSet::new();
+        let mut note_ids = Vec::new();
+        while note_ids.len() < amount.min(candidate_count) {
+            let position = random_generator.next_index(candidate_count);
+            if !chosen_positions.insert(position) {
+                continue;
+            }
+            match Database::get_random_candidate_at(position, tag_name) {
+                Some(note_id) => note_ids.push(note_id),
+                // The notes were changed in the meantime
+                None => break,
+            }
+        }
+        return note_ids;
+    }
+
+    // Returns the warnings of the links and the yaml header of the note after editing it
+    pub fn open(note_id: &str, settings: &mut Settings) -> Result<Vec<String>, BrainError> {
+        return NoteUtility::open_at_line(note_id, None, settings);
+    }
+
+    pub fn open_at_line(
+        note_id: &str,
+        line_number: Option<usize>,
+        settings: &mut Settings,
+    ) -> Result<Vec<String>, BrainError> {
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+        };
+        let editor = NoteUtility::get_editor(settings);
+        let absolute_file_path = NoteUtility::get_absolute_path_of_note(note_id, settings)?;
+
+        let editor_args = NoteUtility::get_editor_args(&absolute_file_path, line_number, settings);
+        if let Err(error) = Command::new(&editor).args(&editor_args).status() {
+            return Err(BrainError::EditorFailed {
+                editor,
+                source: error,
+            });
+        }
+
+        return NoteUtility::update_after_editing(&note, settings);
+    }
+
+    // All notes are given to the editor at once. If the editor arguments can't take several
+    // paths or the editor can't be started, the notes are opened one after another instead.
+    // Returns the warnings of the checks of the notes
+    pub fn open_many(
+        note_ids: &[String],
+        settings: &mut Settings,
+    ) -> Result<Vec<String>, BrainError> {
+        let mut notes = Vec::new();
+        let mut absolute_file_paths = Vec::new();
+        for note_id in note_ids {
+            let note = match Database::get_note_where_id(note_id) {
+                Some(value) => value,
+                None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+            };
+            absolute_file_paths.push(NoteUtility::get_absolute_path_of_note(note_id, settings)?);
+            notes.push(note);
+        }
+
+        let editor = NoteUtility::get_editor(settings);
+        let editor_status =
+            match NoteUtility::get_editor_args_of_many(&absolute_file_paths, settings) {
+                Some(editor_args) => Command::new(&editor).args(&editor_args).status().ok(),
+                None => None,
+            };
+
+        // The notes could have been changed even if the editor failed afterwards
+        let mut warnings = Vec::new();
+        for note in notes.iter() {
+            let result = match editor_status {
+                Some(_) => NoteUtility::update_after_editing(note, settings),
+                None => NoteUtility::open(&note.note_id, settings),
+            };
+            warnings.extend(result?);
+        }
+        if let Some(editor_status) = editor_status {
+            if !editor_status.success() {
+                return Err(BrainError::Other(format!(
+                    "open_notes: the editor '{}' exited with {}",
+                    editor, editor_status
+                )));
+            }
+        }
+        return Ok(warnings);
+    }
+
+    // Files that aren't notes are opened without updating the database
+    pub fn open_file(file_path: &Path, settings: &Settings) -> Result<(), BrainError> {
+        let editor = NoteUtility::get_editor(settings);
+        let editor_args = NoteUtility::get_editor_args(file_path.as_os_str(), None, settings);
+        match Command::new(&editor).args(&editor_args).status() {
+            Ok(_) => return Ok(()),
+            Err(error) => {
+                return Err(BrainError::EditorFailed {
+                    editor,
+                    source: error,
+                })
+            }
+        }
+    }
+
+    // The database is brought up to date with the changes made in the editor
+    fn update_after_editing(
+        note: &Note,
+        settings: &mut Settings,
+    ) -> Result<Vec<String>, BrainError> {
+        if let Err(error) = settings.note_history.add(&note.note_id) {
+            return Err(BrainError::Other(error));
+        }
+        Database::add_usage_of(&note.note_id);
+
+        let mut warnings = NoteUtility::update_db_entries_of(note, settings);
+        match NoteUtility::check_metadata_of(note, settings) {
+            Ok(warning) => {
+                warnings.extend(warning);
+                return Ok(warnings);
+            }
+            Err(error) => {
+                return Err(BrainError::Parse {
+                    note_id: note.note_id.clone(),
+                    message: error,
+                })
+            }
+        }
+    }
+
+    // The configured editor is used before the EDITOR and VISUAL environment variables
+    fn get_editor(settings: &Settings) -> String {
+        if let Some(editor) = &settings.editor {
+            return editor.clone();
+        }
+        for variable_name in ["EDITOR", "VISUAL"] {
+            if let Ok(editor) = env::var(variable_name) {
+                if !editor.trim().is_empty() {
+                    return editor;
+                }
+            }
+        }
+        return "vi".to_string();
+    }
+
+    // Replaces the placeholders '{path}' and '{line}' in the configured editor arguments.
+    // The path is appended if none of the arguments contains it
+    fn get_editor_args(
+        file_path: &OsStr,
+        line_number: Option<usize>,
+        settings: &Settings,
+    ) -> Vec<OsString> {
+        let file_path_string = file_path.to_string_lossy();
+        let line_number_string = line_number.unwrap_or(1).to_string();
+
+        let mut editor_args: Vec<OsString> = Vec::new();
+        let mut contains_path = false;
+        for editor_arg in &settings.editor_args {
+            if editor_arg == "{path}" {
+                // Keeps paths that aren't valid unicode intact
+                editor_args.push(file_path.to_os_string());
+                contains_path = true;
+                continue;
+            }
+            if editor_arg.contains("{path}") {
+                contains_path = true;
+            }
+            editor_args.push(OsString::from(
+                editor_arg
+                    .replace("{path}", &file_path_string)
+                    .replace("{line}", &line_number_string),
+            ));
+        }
+        if !contains_path {
+            editor_args.push(file_path.to_os_string());
+        }
+        return editor_args;
+    }
+
+    // Every '{path}' argument is replaced with all paths and '{line}' with the first line.
+    // Returns None if the path is only part of an argument, as it can't be given several times then
+    fn get_editor_args_of_many(
+        file_paths: &[OsString],
+        settings: &Settings,
+    ) -> Option<Vec<OsString>> {
+        let mut editor_args: Vec<OsString> = Vec::new();
+        let mut contains_path = false;
+        for editor_arg in &settings.editor_args {
+            if editor_arg == "{path}" {
+                editor_args.extend(file_paths.iter().cloned());
+                contains_path = true;
+                continue;
+            }
+            if editor_arg.contains("{path}") {
+                return None;
+            }
+            editor_args.push(OsString::from(editor_arg.replace("{line}", "1")));
+        }
+        if !contains_path {
+            editor_args.extend(file_paths.iter().cloned());
+        }
+        return Some(editor_args);
+    }
+
+    // Returns the number of the first line that contains one of the words of the search query
+    pub fn get_line_of_first_match(
+        note_id: &str,
+        search_query: &str,
+        settings: &mut Settings,
+    ) -> Option<usize> {
+        let note_content = match NoteUtility::get_content_of_note(note_id, settings) {
+            Ok(value) => value,
+            Err(_) => return None,
+        };
+        let search_words = NoteUtility::get_search_words_of(search_query);
+
+        return note_content
+            .lines()
+            .position(|line| {
+                let line = line.to_lowercase();
+                search_words.iter().any(|m| line.contains(m.as_str()))
+            })
+            .map(|m| m + 1);
+    }
+
+    // The words of a content search in lower case, e.g. to find them in the note content
+    pub fn get_search_words_of(search_query: &str) -> Vec<String> {
+        return match SearchQuery::parse(search_query) {
+            Ok(search_query) => search_query.get_search_words(),
+            Err(_) => Vec::new(),
+        };
+    }
+
+    pub fn get_absolute_path_of_note(
+        note_id: &str,
+        settings: &mut Settings,
+    ) -> Result<OsString, BrainError> {
+        let note = match Database::get_note_where_id(note_id) {
+            Some(value) => value,
+            None => return Err(BrainError::NoteNotFound(note_id.to_string())),
+        };
+
+        let notes_dir = &settings.notes_dir;
+        let relative_file_path = Path::new(notes_dir).join(&note.file_name);
+        let absolute_file_path = match relative_file_path.canonicalize() {
+            Ok(path) => path,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: relative_file_path,
+                    source: error,
+                });
+            }
+        };
+
+        return Ok(absolute_file_path.as_os_str().to_os_string());
+    }
+
+    // The problems with the links are returned instead of printed, because the notes are
+    // also checked while the TUI is shown
+    fn check_links_in_note(note: &Note, settings: &Settings) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(note_links) = NoteUtility::get_all_links_in_note(note, settings, &mut warnings)
+        {
+            if settings.backlinking_enabled {
+                warnings.extend(NoteUtility::create_backlinks_from(
+                    &note_links,
+                    note,
+                    settings,
+                ));
+            }
+
+            // Links that were removed from the note have to be removed from the database too
+            Database::delete_outgoing_links(&note.note_id);
+            for note_link_id in note_links {
+                if let Err(error) =
+                    Database::insert_note_link_for_note(&note.note_id, &note_link_id)
+                {
+                    warnings.push(format!(
+                        "the link of the note '{}' couldn't be stored: {}",
+                        note.note_id, error
+                    ));
+                }
+            }
+        }
+        return warnings;
+    }
+
+    fn update_content_index_of(note: &Note, settings: &Settings) -> Result<(), BrainError> {
+        let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        match FileUtility::get_content_from_file(&absolute_note_file_path) {
+            Ok(note_content) => {
+                Database::update_note_content(&note.note_id, &note_content);
+                NoteUtility::update_modification_date_of(&note.note_id, &absolute_note_file_path);
+                return Ok(());
+            }
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: absolute_note_file_path,
+                    source: error,
+                })
+            }
+        }
+    }
+
+    // The modification time of the file is used, so that changes outside of brn are counted too
+    fn update_modification_date_of(note_id: &str, note_file_path: &Path) {
+        if let Ok(modification_time) = fs::metadata(note_file_path).and_then(|m| m.modified()) {
+            Database::update_modification_date(note_id, DateTime::<Local>::from(modification_time));
+        }
+    }
+
+    fn update_properties_of(note: &Note, settings: &Settings) -> Result<(), BrainError> {
+        match NoteMetadata::get_custom_properties_of(note, settings) {
+            Ok(properties) => {
+                let archived = properties
+                    .iter()
+                    .any(|(key, value)| key == "archived" && value == "true");
+                let aliases: Vec<String> = properties
+                    .iter()
+                    .filter(|(key, _)| key == "aliases")
+                    .map(|(_, value)| value.trim().to_string())
+                    .filter(|m| !m.is_empty())
+                    .collect();
+                Database::update_note_properties(&note.note_id, &properties);
+                Database::update_archived_state_of(&note.note_id, archived);
+                Database::update_aliases_of(&note.note_id, &aliases);
+                return Ok(());
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    // Brings the links, the content index and the properties of the note in the database up to
+    // date after its file was changed. Returns the warnings
+    fn update_db_entries_of(note: &Note, settings: &Settings) -> Vec<String> {
+        let mut warnings = NoteUtility::check_links_in_note(note, settings);
+        if let Err(error) = NoteUtility::update_content_index_of(note, settings) {
+            warnings.push(error.to_string());
+        }
+        if let Err(error) = NoteUtility::update_properties_of(note, settings) {
+            warnings.push(error.to_string());
+        }
+        return warnings;
+    }
 
-        settings.show_interactive_dialogs = true;
-        return Ok(());
+    pub fn remove_yaml_header(note_content: &str) -> String {
+        match NOTE_FORMAT_VALIDATOR.captures(note_content) {
+            Some(note_format_match) => note_format_match.get(3).unwrap().as_str().to_string(),
+            None => note_content.to_string(),
+        }
     }
 
-    fn get_all_note_metadata(settings: &mut Settings) -> Result<Vec<Note>, String> {
-        let directory_entries = fs::read_dir(&settings.notes_dir).unwrap();
-        let mut note_metadata_list: Vec<Note> = Vec::new();
+    // The links of the yaml header (e.g. backlinks) are ignored
+    pub fn get_link_texts(note_content: &str) -> Vec<String> {
+        let note_body = NoteUtility::remove_yaml_header(note_content);
+        let mut link_texts: Vec<String> = Vec::new();
+        for note_link_match in NOTE_LINK_VALIDATOR.captures_iter(&note_body) {
+            let link_text = note_link_match.get(1).unwrap().as_str().trim().to_string();
+            if !link_texts.contains(&link_text) {
+                link_texts.push(link_text);
+            }
+        }
+        return link_texts;
+    }
 
-        for entry in directory_entries {
-            let path = entry.unwrap().path();
-            if path.is_dir() {
-                continue;
+    // Only relative paths are returned, links to websites or to anchors in the same note
+    // aren't links to files
+    pub fn get_file_link_targets(note_content: &str) -> Vec<String> {
+        let note_body = NoteUtility::remove_yaml_header(note_content);
+        let mut link_targets: Vec<String> = Vec::new();
+        for file_link_match in FILE_LINK_VALIDATOR.captures_iter(&note_body) {
+            let link_target = file_link_match.get(1).unwrap().as_str().to_string();
+            if NoteUtility::is_relative_file_link(&link_target)
+                && !link_targets.contains(&link_target)
+            {
+                link_targets.push(link_target);
             }
+        }
+        return link_targets;
+    }
 
-            let file_name = match path.file_name().unwrap().to_str() {
-                Some(value) => value,
-                None => {
-                    return Err(format!(
-                        "update-db: The file name '{}' contains illegal characters!",
-                        path.to_string_lossy()
-                    ))
-                }
-            };
+    pub fn is_relative_file_link(link_target: &str) -> bool {
+        return !link_target.starts_with('#')
+            && !link_target.starts_with('/')
+            && !link_target.contains(':');
+    }
 
-            let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&file_name);
-            let _ = match NoteMetadata::get_basic_data_of_file(&absolute_note_file_path) {
-                Ok(note_metadata) => {
-                    note_metadata_list.push(note_metadata);
-                }
-                Err(error) => return Err(error),
-            };
+    // Notes are stored in the notes directory, so relative paths are resolved against it.
+    // Anchors like in 'note.md#heading' are ignored
+    pub fn get_path_of_file_link(link_target: &str, notes_dir: &Path) -> PathBuf {
+        let file_path = link_target.split('#').next().unwrap_or_default();
+        return notes_dir.join(file_path.replace("%20", " "));
+    }
+
+    // Returns the note if the link target is a note file of the notes directory
+    pub fn get_note_of_file_link(link_target: &str, notes_dir: &Path) -> Option<Note> {
+        let file_path = NoteUtility::get_path_of_file_link(link_target, notes_dir);
+        let file_name = file_path.file_name()?.to_str()?;
+        let directory = file_path.parent()?.canonicalize().ok()?;
+        if directory != notes_dir.canonicalize().ok()? {
+            return None;
         }
+        return Database::get_note_where_file_name(file_name);
+    }
 
-        return Ok(note_metadata_list);
+    // Every link is replaced with the text that is returned for its link text
+    pub fn replace_links_in<F: FnMut(&str) -> String>(
+        note_body: &str,
+        mut replacement: F,
+    ) -> String {
+        return NOTE_LINK_VALIDATOR
+            .replace_all(note_body, |m: &Captures| {
+                replacement(m.get(1).unwrap().as_str().trim())
+            })
+            .to_string();
     }
 
-    pub fn get_content_of_note(note_id: &str, settings: &mut Settings) -> Result<String, String> {
-        let absolute_file_path = match NoteUtility::get_absolute_path_of_note(note_id, settings) {
-            Ok(value) => value,
-            Err(error) => return Err(error),
-        };
+    // Notes can be linked by their id or by their name
+    pub fn get_linked_notes(note_id: &str) -> Vec<Note> {
+        return Database::get_outgoing_links(note_id)
+            .iter()
+            .filter_map(|m| Database::get_note_where_id(m))
+            .collect();
+    }
 
-        let note_content = match FileUtility::get_content_from_file(&absolute_file_path) {
-            Ok(value) => value,
-            Err(error) => {
-                return Err(error.to_string());
-            }
-        };
-        return Ok(note_content);
+    pub fn get_linking_notes(note_id: &str) -> Vec<Note> {
+        return Database::get_incoming_links(note_id)
+            .iter()
+            .filter_map(|m| Database::get_note_where_id(m))
+            .collect();
     }
 
-    pub fn open_random_note(settings: &mut Settings) {
-        let note_id;
-        let random_notes = Database::get_random_note_ids(1);
-        if random_notes.len() >= 1 {
-            note_id = &random_notes[0];
-        } else {
-            Message::error("open_random_note: couldn't find a random note");
-            return;
+    // Links can contain the id, the name or an alias of a note
+    pub fn get_linked_note(link_text: &str) -> Result<Option<Note>, BrainError> {
+        if let Some(note) = Database::get_note_where_id(link_text) {
+            return Ok(Some(note));
         }
+        let note_id = NoteUtility::get_note_id_of_name(link_text)?;
+        return Ok(note_id.and_then(|m| Database::get_note_where_id(&m)));
+    }
 
-        let note = match Database::get_note_where_id(note_id) {
-            Some(value) => value,
-            None => {
-                Message::error(&format!(
-                    "open_random_note: couldn't open note: the note id '{}' does not exist!",
-                    note_id
-                ));
-                return;
-            }
-        };
-        Message::info(&format!(
-            "opened note:  {} {} ",
-            note_id.yellow(),
-            note.note_name
-        ));
+    // An exact match of the name is preferred. Otherwise the case is ignored and the aliases
+    // of the notes are checked, which can lead to several matching notes
+    pub fn get_note_id_of_name(note_name: &str) -> Result<Option<String>, BrainError> {
+        if let Some(note_id) = Database::get_note_id_where(NoteProperty::NoteName, note_name) {
+            return Ok(Some(note_id));
+        }
 
-        if let Err(error) = NoteUtility::open(note_id, settings) {
-            Message::error(&error);
+        let mut note_ids = Database::get_note_ids_with_name_or_alias_like(note_name);
+        if note_ids.len() <= 1 {
+            return Ok(note_ids.pop());
         }
+        let candidates: Vec<String> = note_ids
+            .iter()
+            .filter_map(|m| Database::get_note_where_id(m))
+            .map(|m| format!("'{}' ({})", m.note_name, m.note_id))
+            .collect();
+        return Err(BrainError::Other(format!(
+            "the name '{}' matches several notes: {}",
+            note_name,
+            candidates.join(", ")
+        )));
     }
 
-    pub fn open(note_id: &str, settings: &mut Settings) -> Result<Option<String>, String> {
-        let note = match Database::get_note_where_id(note_id) {
-            Some(value) => value,
-            None => {
-                return Err(format!("the note id '{}' does not exist!", note_id));
-            }
-        };
-        let editor = match env::var("EDITOR") {
-            Ok(value) => value,
-            Err(error) => {
-                return Err(format!(
-                    "couldn't read the EDITOR environment variable: '{}'",
-                    error
-                ));
-            }
-        };
-        let absolute_file_path = match NoteUtility::get_absolute_path_of_note(note_id, settings) {
-            Ok(value) => value,
-            Err(error) => {
-                return Err(format!(
-                    "couldn't get the absolute path of {}: '{}'",
-                    &note.file_name, error
-                ));
-            }
+    // The id is tried before the name and the aliases. If no note matches, the given name is returned
+    pub fn get_note_id_of_name_or_id(note_name: &str) -> Result<String, BrainError> {
+        if Database::get_note_where_id(note_name).is_some() {
+            return Ok(note_name.to_string());
+        }
+        return match NoteUtility::get_note_id_of_name(note_name)? {
+            Some(note_id) => Ok(note_id),
+            None => Ok(note_name.to_string()),
         };
+    }
 
-        // Open the note in the editor specified by the EDITOR environment variable
-        match Command::new(&editor).arg(&absolute_file_path).status() {
-            Ok(_) => {}
-            Err(error) => {
-                return Err(format!(
-                    "couldn't open the note '{}' with '{}': '{}'",
-                    &editor, &note.file_name, error
-                ));
-            }
-        };
+    // Returns every note with the texts of its links that don't lead to an existing note.
+    // Links that match several notes aren't broken, they are only ambiguous
+    pub fn get_broken_links(settings: &Settings) -> Result<Vec<(Note, Vec<String>)>, BrainError> {
+        let mut broken_links = Vec::new();
+        for note in Database::get_all_notes() {
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+            let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(BrainError::Io {
+                        path: note_file_path,
+                        source: error,
+                    })
+                }
+            };
 
-        if let Err(error) = settings.note_history.add(note_id) {
-            return Err(error);
+            let broken_link_texts = NoteUtility::get_broken_link_texts(&note_content);
+            if !broken_link_texts.is_empty() {
+                broken_links.push((note, broken_link_texts));
+            }
         }
+        return Ok(broken_links);
+    }
 
-        NoteUtility::check_links_in_note(&note, settings);
+    // Links that match several notes aren't broken, they are only ambiguous
+    pub fn get_broken_link_texts(note_content: &str) -> Vec<String> {
+        return NoteUtility::get_link_texts(note_content)
+            .into_iter()
+            .filter(|m| matches!(NoteUtility::get_linked_note(m), Ok(None)))
+            .collect();
+    }
 
-        match NoteUtility::check_metadata_of(&note, settings) {
-            Ok(None) => return Ok(None),
-            Ok(Some(message)) => return Ok(Some(message)),
-            Err(error) => return Err("check_yaml_header_of: ".to_string() + &error),
+    // The yaml header and the headings aren't compared, so that notes that only contain
+    // the headings of the note template aren't duplicates
+    pub fn get_duplicate_clusters(
+        similarity_threshold: Option<f64>,
+        settings: &Settings,
+    ) -> Result<Vec<DuplicateCluster>, BrainError> {
+        let mut note_bodies = Vec::new();
+        for note in Database::get_all_notes() {
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+            let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(BrainError::Io {
+                        path: note_file_path,
+                        source: error,
+                    })
+                }
+            };
+            let note_body = match NOTE_FORMAT_VALIDATOR.captures(&note_content) {
+                Some(value) => value.get(3).unwrap().as_str().to_string(),
+                None => note_content.clone(),
+            };
+            let note_body = HEADING_VALIDATOR.replace_all(&note_body, "").to_string();
+            note_bodies.push((note.note_id, note_body));
         }
+        return Ok(DuplicateFinder::find_clusters(
+            &note_bodies,
+            similarity_threshold,
+        ));
     }
 
-    fn get_absolute_path_of_note(
-        note_id: &str,
-        settings: &mut Settings,
-    ) -> Result<OsString, String> {
-        let note = match Database::get_note_where_id(note_id) {
+    // The brackets of the links are removed, so that the link text stays readable.
+    // Returns the warnings of the remaining links
+    pub fn unlink(
+        note: &Note,
+        link_texts: &[String],
+        settings: &Settings,
+    ) -> Result<Vec<String>, BrainError> {
+        let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                })
+            }
+        };
+        let note_format_match = match NOTE_FORMAT_VALIDATOR.captures(&note_content) {
             Some(value) => value,
             None => {
-                return Err(format!("the note id '{}' does not exist!", note_id));
+                return Err(BrainError::Parse {
+                    note_id: note.note_id.clone(),
+                    message: "it does not have the correct format".to_string(),
+                })
             }
         };
 
-        let notes_dir = &settings.notes_dir;
-        let relative_file_path = Path::new(notes_dir).join(&note.file_name);
-        let absolute_file_path = match relative_file_path.canonicalize() {
-            Ok(path) => path,
-            Err(error) => {
-                return Err(error.to_string());
+        let note_header = note_format_match.get(1).unwrap().as_str();
+        let note_body = note_format_match.get(3).unwrap().as_str();
+        let new_note_body = NoteUtility::replace_links_in(note_body, |link_text| {
+            if link_texts.iter().any(|m| m == link_text) {
+                return link_text.to_string();
             }
-        };
+            return format!("[[{}]]", link_text);
+        });
+        if let Err(error) = FileUtility::write_atomically(
+            &note_file_path,
+            format!("{}{}", note_header, new_note_body),
+        ) {
+            return Err(BrainError::Io {
+                path: note_file_path,
+                source: error,
+            });
+        }
 
-        return Ok(absolute_file_path.as_os_str().to_os_string());
+        let warnings = NoteUtility::check_links_in_note(note, settings);
+        NoteUtility::update_content_index_of(note, settings)?;
+        return Ok(warnings);
     }
 
-    fn check_links_in_note(note: &Note, settings: &Settings) {
-        if let Some(note_links) = NoteUtility::get_all_links_in_note(&note, settings) {
-            if settings.backlinking_enabled {
-                NoteUtility::create_backlinks_from(&note_links, &note, settings);
-            }
-            for note_link_id in note_links {
-                if let Err(error) =
-                    Database::insert_note_link_for_note(&note.note_id, &note_link_id)
-                {
-                    Message::warning(&error);
+    // The links with the given link text are changed to the new link text. The links of the
+    // note are checked again in any case, as a broken link is valid as soon as its note exists.
+    // Returns the warnings of the links
+    pub fn relink(
+        note: &Note,
+        link_text: &str,
+        new_link_text: &str,
+        settings: &Settings,
+    ) -> Result<Vec<String>, BrainError> {
+        if link_text != new_link_text {
+            let note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+            let note_content = match FileUtility::get_content_from_file(&note_file_path) {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(BrainError::Io {
+                        path: note_file_path,
+                        source: error,
+                    })
+                }
+            };
+            let note_format_match = match NOTE_FORMAT_VALIDATOR.captures(&note_content) {
+                Some(value) => value,
+                None => {
+                    return Err(BrainError::Parse {
+                        note_id: note.note_id.clone(),
+                        message: "it does not have the correct format".to_string(),
+                    })
+                }
+            };
+
+            let note_header = note_format_match.get(1).unwrap().as_str();
+            let note_body = note_format_match.get(3).unwrap().as_str();
+            let new_note_body = NoteUtility::replace_links_in(note_body, |m| {
+                if m == link_text {
+                    return format!("[[{}]]", new_link_text);
                 }
+                return format!("[[{}]]", m);
+            });
+            if let Err(error) = FileUtility::write_atomically(
+                &note_file_path,
+                format!("{}{}", note_header, new_note_body),
+            ) {
+                return Err(BrainError::Io {
+                    path: note_file_path,
+                    source: error,
+                });
             }
         }
+
+        let warnings = NoteUtility::check_links_in_note(note, settings);
+        NoteUtility::update_content_index_of(note, settings)?;
+        return Ok(warnings);
     }
 
-    fn get_all_links_in_note(note: &Note, settings: &Settings) -> Option<Vec<String>> {
+    // Links that can't be resolved unambiguously are added to the warnings
+    fn get_all_links_in_note(
+        note: &Note,
+        settings: &Settings,
+        warnings: &mut Vec<String>,
+    ) -> Option<Vec<String>> {
         let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
         let note_content = match FileUtility::get_content_from_file(&absolute_note_file_path) {
             Ok(value) => value,
             Err(error) => {
-                Message::error(&format!(
+                warnings.push(format!(
                     "get-all-links-in-note: couldn't read content of note '{} {}': {}",
-                    note.note_id.yellow(),
-                    note.note_name,
-                    error
+                    note.note_id, note.note_name, error
                 ));
                 return None;
             }
@@ -663,38 +2894,108 @@ impl NoteUtility {
             let note_body = note_format_match.get(3).unwrap().as_str();
             let mut note_links = Vec::new();
             for note_link_match in NOTE_LINK_VALIDATOR.captures_iter(note_body) {
-                let linked_note_id = note_link_match.get(1).unwrap().as_str();
-                note_links.push(linked_note_id.to_string());
+                let link_text = note_link_match.get(1).unwrap().as_str().trim();
+
+                // Notes can be linked by their id, their name or one of their aliases
+                let linked_note_id = match NoteUtility::get_linked_note(link_text) {
+                    Ok(Some(linked_note)) => linked_note.note_id,
+                    Ok(None) => link_text.to_string(),
+                    Err(error) => {
+                        warnings.push(format!("{} (in the note '{}')", error, note.note_name));
+                        link_text.to_string()
+                    }
+                };
+                if !note_links.contains(&linked_note_id) {
+                    note_links.push(linked_note_id);
+                }
             }
             return Some(note_links);
         } else {
-            Message::warning(&format!("get-all-links-in-note: couldn't search for links in '{} {}': note does not have the correct format",
-                note.note_id.yellow(),
-                note.note_name));
-            Message::display_correct_note_format();
+            warnings.push(format!(
+                "get-all-links-in-note: couldn't search for links in '{} {}': note does not have the correct format",
+                note.note_id,
+                note.note_name
+            ));
             return None;
         }
     }
 
-    fn create_backlinks_from(note_links: &Vec<String>, source_note: &Note, settings: &Settings) {
+    fn get_all_tags_in_note(
+        note: &Note,
+        settings: &Settings,
+    ) -> Result<Option<Vec<String>>, String> {
+        let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&absolute_note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "get-all-tags-in-note: couldn't read content of note '{} {}': {}",
+                    note.note_id, note.note_name, error
+                ))
+            }
+        };
+
+        let note_body = match NOTE_FORMAT_VALIDATOR.captures(&note_content) {
+            Some(note_format_match) => note_format_match.get(3).unwrap().as_str(),
+            None => return Ok(None),
+        };
+
+        let mut note_tags = Vec::new();
+        let mut is_in_code_block = false;
+        for line in note_body.lines() {
+            if CODE_FENCE_VALIDATOR.is_match(line) {
+                is_in_code_block = !is_in_code_block;
+                continue;
+            }
+            if is_in_code_block {
+                continue;
+            }
+
+            let line_without_code = INLINE_CODE_VALIDATOR.replace_all(line, "");
+            for note_tag_match in NOTE_CONTENT_TAG_VALIDATOR.captures_iter(&line_without_code) {
+                let tag_name = note_tag_match
+                    .get(1)
+                    .unwrap()
+                    .as_str()
+                    .trim_end_matches('/');
+                if !note_tags.iter().any(|m| m == tag_name) {
+                    note_tags.push(tag_name.to_string());
+                }
+            }
+        }
+
+        return Ok(Some(note_tags));
+    }
+
+    // Returns the errors of the backlinks that couldn't be added
+    fn create_backlinks_from(
+        note_links: &Vec<String>,
+        source_note: &Note,
+        settings: &Settings,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
         for linked_note_id in note_links {
             if let Some(linked_note) = Database::get_note_where_id(&linked_note_id) {
-                NoteUtility::add_backlink_to(&linked_note, &source_note.note_id, settings)
+                if let Err(error) =
+                    NoteUtility::add_backlink_to(&linked_note, &source_note.note_id, settings)
+                {
+                    errors.push(error);
+                }
             }
         }
+        return errors;
     }
 
-    fn add_backlink_to(note: &Note, backlink_id: &str, settings: &Settings) {
+    fn add_backlink_to(note: &Note, backlink_id: &str, settings: &Settings) -> Result<(), String> {
         let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
         let note_content = match FileUtility::get_content_from_file(&absolute_note_file_path) {
             Ok(value) => value,
             Err(error) => {
-                Message::error(&format!(
+                return Err(format!(
                     "add_backlink: couldn't read note file '{}': {}",
                     &absolute_note_file_path.to_string_lossy(),
                     error
                 ));
-                return;
             }
         };
 
@@ -704,7 +3005,7 @@ impl NoteUtility {
             let text_after_backlinks = note_content_match.get(5).unwrap().as_str();
 
             if backlink_exists_in_string(backlink_id, backlinks) {
-                return;
+                return Ok(());
             }
 
             let backlinks_string = create_backlinks_string_from(backlinks, backlink_id);
@@ -714,21 +3015,18 @@ impl NoteUtility {
                 &absolute_note_file_path,
                 new_note_content.as_bytes(),
             ) {
-                Message::error(&format!(
+                return Err(format!(
                     "add_backlink: couldn't change contents of note '{} {}': {}",
-                    note.note_id.yellow(),
-                    note.note_name,
-                    error
+                    note.note_id, note.note_name, error
                 ));
             };
         } else {
-            Message::error(&format!(
+            return Err(format!(
                 "couldn't add backlink to '{} {}': note does not have the correct format",
-                note.note_id.yellow(),
-                note.note_name
+                note.note_id, note.note_name
             ));
-            return;
         }
+        return Ok(());
 
         fn create_backlinks_string_from(
             existing_backlinks_list: &str,
@@ -757,46 +3055,63 @@ impl NoteUtility {
         }
     }
 
-    fn replace_content_of_file<P: AsRef<Path>>(
-        path: P,
-        new_file_content: &[u8],
-    ) -> Result<(), Error> {
-        let mut note_file = match OpenOptions::new().write(true).truncate(true).open(path) {
-            Ok(opened_file) => opened_file,
-            Err(error) => return Err(error),
+    fn remove_backlink_from(
+        note: &Note,
+        backlink_id: &str,
+        settings: &Settings,
+    ) -> Result<(), String> {
+        let absolute_note_file_path = PathBuf::from(&settings.notes_dir).join(&note.file_name);
+        let note_content = match FileUtility::get_content_from_file(&absolute_note_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "remove_backlink: couldn't read note file '{}': {}",
+                    &absolute_note_file_path.to_string_lossy(),
+                    error
+                ))
+            }
         };
-        if let Err(error) = note_file.write(new_file_content) {
-            return Err(error);
-        }
 
-        return Ok(());
-    }
-
-    fn show_open_file_dialog_for(note_id: &str, settings: &mut Settings) {
-        if !settings.show_interactive_dialogs {
-            return;
-        }
+        if let Some(note_content_match) = NOTE_CONTENT_BACKLINK_VALIDATOR.captures(&note_content) {
+            let text_before_backlinks = note_content_match.get(1).unwrap().as_str().to_string();
+            let backlinks = note_content_match.get(3).unwrap().as_str();
+            let text_after_backlinks = note_content_match.get(5).unwrap().as_str();
 
-        print!("Do you want to open the file again? [Y/n] ");
-        io::stdout().flush().unwrap();
+            let remaining_backlinks: Vec<String> = NOTE_LINK_VALIDATOR
+                .captures_iter(backlinks)
+                .map(|m| m.get(1).unwrap().as_str())
+                .filter(|m| *m != backlink_id)
+                .map(|m| format!("[[{}]]", m))
+                .collect();
+            let backlinks_string = if remaining_backlinks.is_empty() {
+                "backlinks: [ ]".to_string()
+            } else {
+                format!("backlinks: [ {} ]", remaining_backlinks.join(", "))
+            };
+            let new_note_content = text_before_backlinks + &backlinks_string + text_after_backlinks;
 
-        let mut open_file_again = String::new();
-        match io::stdin().read_line(&mut open_file_again) {
-            Ok(_) => {}
-            Err(error) => {
-                Message::error(&format!(
-                    "show_open_file_dialog: couldn't read user input: {}",
-                    error
+            if let Err(error) = NoteUtility::replace_content_of_file(
+                &absolute_note_file_path,
+                new_note_content.as_bytes(),
+            ) {
+                return Err(format!(
+                    "remove_backlink: couldn't change contents of note '{} {}': {}",
+                    note.note_id, note.note_name, error
                 ));
-                return;
-            }
+            };
         }
+        return Ok(());
+    }
 
-        if !(open_file_again.trim().to_lowercase() == "n") {
-            if let Err(error) = NoteUtility::open(note_id, settings) {
-                Message::error(&error);
-            }
+    // Only existing files are replaced
+    fn replace_content_of_file<P: AsRef<Path>>(
+        path: P,
+        new_file_content: &[u8],
+    ) -> Result<(), Error> {
+        if !path.as_ref().is_file() {
+            return Err(Error::new(ErrorKind::NotFound, "the file doesn't exist"));
         }
+        return FileUtility::write_atomically(path, new_file_content);
     }
 
     pub fn get_note_history(settings: &Settings) -> Vec<Note> {
@@ -811,162 +3126,123 @@ impl NoteUtility {
         return note_list;
     }
 
-    pub fn print_note_list(note_list: Vec<Note>) {
-        for note in note_list {
-            println!("{} {}", note.note_id.yellow(), note.note_name);
-        }
+    // Existing tags that match the partially typed tag name, the most used tags first
+    pub fn get_tag_suggestions(partial_tag_name: &str) -> Vec<(String, usize)> {
+        let partial_tag_name = partial_tag_name.trim().trim_start_matches('#');
+        let mut tag_counts: Vec<(String, usize)> = Database::get_tag_counts()
+            .into_iter()
+            .filter(|(tag_name, _)| FuzzyMatcher::fuzzy_match(partial_tag_name, tag_name).is_some())
+            .collect();
+        tag_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        return tag_counts;
     }
 
-    pub fn print_note_name_of(note_id: &str) {
-        if let Some(note) = Database::get_note_where_id(note_id) {
-            println!("{}", note.note_name);
-        } else {
-            Message::error(&format!("the note id {} doesn't exist", note_id.yellow()));
-        }
+    pub fn is_existing_tag(tag_name: &str) -> bool {
+        let tag_name = tag_name.trim().trim_start_matches('#');
+        return Database::get_tag_counts().iter().any(|m| m.0 == tag_name);
     }
 
-    pub fn print_file_name_of(note_id: &str) {
-        if let Some(note) = Database::get_note_where_id(note_id) {
-            println!("{}", note.file_name);
+    // Notes with nested tags (e.g. 'tag/nested-tag') are shown when filtering by the parent tag,
+    // so they can be counted for the parent tags too. Parent tags that aren't used by any note
+    // directly are listed then as well
+    pub fn get_tag_counts(tag_order: TagOrder, roll_up_nested_tags: bool) -> Vec<(String, usize)> {
+        let mut tag_counts: Vec<(String, usize)> = if roll_up_nested_tags {
+            let mut note_ids_of_tags: HashMap<String, HashSet<String>> = HashMap::new();
+            for (note_id, tag_name) in Database::get_all_note_taggings() {
+                let mut parent_tag_end = Some(tag_name.len());
+                while let Some(end) = parent_tag_end {
+                    note_ids_of_tags
+                        .entry(tag_name[..end].to_string())
+                        .or_default()
+                        .insert(note_id.clone());
+                    parent_tag_end = tag_name[..end].rfind('/');
+                }
+            }
+            note_ids_of_tags
+                .into_iter()
+                .map(|(tag_name, note_ids)| (tag_name, note_ids.len()))
+                .collect()
         } else {
-            Message::error(&format!("the note id {} doesn't exist", note_id.yellow()));
+            Database::get_tag_counts()
+        };
+
+        match tag_order {
+            TagOrder::Name => tag_counts.sort_by(|a, b| a.0.cmp(&b.0)),
+            TagOrder::Count => tag_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0))),
         }
+        return tag_counts;
     }
 
-    fn check_metadata_of(note: &Note, settings: &mut Settings) -> Result<Option<String>, String> {
-        let mut warning_message = String::new();
+    // A note without a name is an error, a note without tags only gets a warning
+    fn check_metadata_of(note: &Note, settings: &Settings) -> Result<Option<String>, String> {
         let note_name = match NoteMetadata::get_property_of(note, NoteProperty::NoteName, settings)
         {
             Ok(value) => value,
-            Err(error) => return Err(error),
+            Err(error) => return Err(error.to_string()),
         };
         let tags = match NoteMetadata::get_tags_of(note, settings) {
             Ok(value) => value,
-            Err(error) => return Err(error),
+            Err(error) => return Err(error.to_string()),
         };
 
-        match NoteUtility::check_metadata_name_of(
-            &note.note_id,
-            note_name,
-            &note.note_name,
-            settings,
-        ) {
-            Err(error) => return Err(error),
-            Ok(Some(warning)) => warning_message = warning,
-            Ok(None) => (),
-        }
-
-        match NoteUtility::check_metadata_tags_of(&note.note_id, tags, settings) {
-            Err(error) => return Err(error),
-            Ok(Some(warning)) => warning_message = warning,
-            Ok(None) => (),
-        }
+        // Tags can also be declared inside of the note content
+        let tags = match (tags, NoteUtility::get_all_tags_in_note(note, settings)?) {
+            (Some(mut tags), Some(content_tags)) => {
+                tags.extend(content_tags);
+                Some(tags)
+            }
+            (None, Some(content_tags)) if !content_tags.is_empty() => Some(content_tags),
+            (tags, _) => tags,
+        };
 
-        if warning_message == "" {
-            return Ok(None);
-        } else {
-            return Ok(Some(warning_message));
-        }
+        NoteUtility::check_metadata_name_of(&note.note_id, note_name, &note.note_name)?;
+        return NoteUtility::check_metadata_tags_of(&note.note_id, tags);
     }
 
     fn check_metadata_name_of(
         note_id: &str,
         note_name: Option<String>,
         original_note_name: &str,
-        settings: &mut Settings,
-    ) -> Result<Option<String>, String> {
+    ) -> Result<(), String> {
         match note_name {
-            Some(new_note_name) => {
-                if WHITESPACE_VALIDATOR.is_match(&new_note_name) {
-                    if settings.print_to_stdout {
-                        show_missing_note_name_warning_for(note_id, settings);
-                    }
-                    return Err(format!("the note '{}' doesn't have a name! please add a value after the 'name' property to the yaml header!",
-                                    note_id));
-                } else if new_note_name != original_note_name {
+            Some(new_note_name) if !WHITESPACE_VALIDATOR.is_match(&new_note_name) => {
+                if new_note_name != original_note_name {
                     Database::update_note_name_where(&new_note_name, NoteProperty::NoteId, note_id);
                 }
+                return Ok(());
             }
-            None => {
-                if settings.print_to_stdout {
-                    show_missing_note_name_warning_for(note_id, settings);
-                }
+            _ => {
                 return Err(format!("the note '{}' doesn't have a name! please add a value after the 'name' property to the yaml header!",
-                                note_id));
+                                note_id))
             }
         }
-
-        return Ok(None);
-
-        fn show_missing_note_name_warning_for(note_id: &str, settings: &mut Settings) {
-            Message::error("this note doesn't have a name! please add a value after the 'name' property to the yaml header!");
-            Message::example(indoc! {r#"
-                ---
-
-                name: "note name"
-
-                ---
-            "#});
-            NoteUtility::show_open_file_dialog_for(note_id, settings);
-        }
     }
 
     fn check_metadata_tags_of(
         note_id: &str,
         tags: Option<Vec<String>>,
-        settings: &mut Settings,
     ) -> Result<Option<String>, String> {
         NoteUtility::delete_tags_of_note(note_id);
 
-        match tags {
-            Some(tags) => {
-                if tags.len() == 0 {
-                    if settings.print_to_stdout {
-                        show_missing_tags_warning_for(note_id, settings);
-                    } else {
-                        return Ok(Some(format!("the note '{}' doesn't have any tags! It will be difficult to find again!", note_id)));
-                    }
-                }
-
-                for tag in tags.iter() {
-                    if TAG_NAME_VALIDATOR.is_match(tag) {
-                        Database::insert_tag_for_note(tag, note_id);
-                    } else {
-                        return Err(format!(
-                            "check_tags: the tag name '{}' contains illegal characters",
-                            tag
-                        ));
-                    }
-                }
-            }
-            None => {
-                if settings.print_to_stdout {
-                    show_missing_tags_warning_for(note_id, settings);
-                } else {
-                    return Ok(Some(format!(
-                        "the note '{}' doesn't have any tags! It will be difficult to find again!",
-                        note_id
-                    )));
-                }
+        let tags = tags.unwrap_or_default();
+        if tags.is_empty() {
+            return Ok(Some(format!(
+                "the note '{}' doesn't have any tags! It will be difficult to find again!",
+                note_id
+            )));
+        }
+        for tag in tags.iter() {
+            // A leading '#' is not part of the tag name
+            let tag = tag.trim_start_matches('#');
+            if TAG_NAME_VALIDATOR.is_match(tag) {
+                Database::insert_tag_for_note(tag, note_id);
+            } else {
+                return Err(format!(
+                    "check_tags: the tag name '{}' contains illegal characters",
+                    tag
+                ));
             }
         }
-
         return Ok(None);
-
-        fn show_missing_tags_warning_for(note_id: &str, settings: &mut Settings) {
-            Message::warning(&formatdoc! {"
-                the note '{}' doesn't have any tags! It will be difficult to find again!
-                please add a few appropriate tags!
-            ", &note_id});
-            Message::example(indoc! {r##"
-                ---
-
-                tags: [ first-tag, #second-tag, third-tag ]
-
-                ---
-            "##});
-
-            NoteUtility::show_open_file_dialog_for(note_id, settings);
-        }
     }
 }
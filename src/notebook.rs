@@ -0,0 +1,95 @@
+use crate::file_utility::FileUtility;
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use yaml_rust::YamlLoader;
+
+pub const DEFAULT_NOTEBOOK_NAME: &str = "default";
+
+pub struct Notebook {
+    pub name: String,
+    pub notes_dir: OsString,
+}
+
+impl Notebook {
+    // The notebooks are configured in 'brn/notebooks.yml' inside of the config directory
+    // of the user (e.g. '~/.config/brn/notebooks.yml') as a mapping of names to directories
+    pub fn load_all() -> Result<Vec<Notebook>, String> {
+        let config_file_path = match Notebook::get_config_file_path() {
+            Some(value) => value,
+            None => return Ok(Vec::new()),
+        };
+        if !config_file_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let config_content = match FileUtility::get_content_from_file(&config_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "couldn't read notebook config '{}': {}",
+                    config_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+        };
+        let config = match YamlLoader::load_from_str(&config_content) {
+            Ok(yaml_vector) => match yaml_vector.into_iter().next() {
+                Some(value) => value,
+                None => return Ok(Vec::new()),
+            },
+            Err(error) => {
+                return Err(format!(
+                    "couldn't parse notebook config '{}': {}",
+                    config_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+        };
+
+        let config_hash = match config.as_hash() {
+            Some(value) => value,
+            None => {
+                return Err(format!(
+                    "the notebook config '{}' has to be a mapping of names to directories",
+                    config_file_path.to_string_lossy()
+                ));
+            }
+        };
+
+        let mut notebooks = Vec::new();
+        for (name, notes_dir) in config_hash {
+            if let (Some(name), Some(notes_dir)) = (name.as_str(), notes_dir.as_str()) {
+                notebooks.push(Notebook {
+                    name: name.to_string(),
+                    notes_dir: Notebook::expand_home_dir(notes_dir),
+                });
+            }
+        }
+        return Ok(notebooks);
+    }
+
+    pub fn get_config_file_path() -> Option<PathBuf> {
+        return dirs::config_dir().map(|m| m.join("brn").join("notebooks.yml"));
+    }
+
+    // Checks if both paths point to the same directory
+    pub fn is_same_dir(first_dir: &OsStr, second_dir: &OsStr) -> bool {
+        match (
+            Path::new(first_dir).canonicalize(),
+            Path::new(second_dir).canonicalize(),
+        ) {
+            (Ok(first_path), Ok(second_path)) => first_path == second_path,
+            _ => first_dir == second_dir,
+        }
+    }
+
+    fn expand_home_dir(dir: &str) -> OsString {
+        if let Some(relative_dir) = dir.strip_prefix("~/") {
+            if let Some(home_dir) = dirs::home_dir() {
+                return home_dir.join(relative_dir).into_os_string();
+            }
+        }
+        return OsString::from(dir);
+    }
+}
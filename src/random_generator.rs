@@ -0,0 +1,45 @@
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Setting this environment variable makes the chosen random notes reproducible
+const SEED_VARIABLE_NAME: &str = "BRN_RANDOM_SEED";
+
+// SplitMix64, which is good enough for picking random notes
+pub struct RandomGenerator {
+    state: u64,
+}
+
+impl Default for RandomGenerator {
+    fn default() -> RandomGenerator {
+        return RandomGenerator::new();
+    }
+}
+
+impl RandomGenerator {
+    pub fn new() -> RandomGenerator {
+        let seed = match env::var(SEED_VARIABLE_NAME)
+            .ok()
+            .and_then(|m| m.parse().ok())
+        {
+            Some(value) => value,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|m| m.as_nanos() as u64)
+                .unwrap_or_default(),
+        };
+        return RandomGenerator { state: seed };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D049BB133111EB);
+        return value ^ (value >> 31);
+    }
+
+    // Returns a random index in 0..length
+    pub fn next_index(&mut self, length: usize) -> usize {
+        return ((self.next_u64() as u128 * length as u128) >> 64) as usize;
+    }
+}
@@ -0,0 +1,8 @@
+// The result of removing a note
+#[derive(Debug)]
+pub struct RemovedNote {
+    // The notes that still link to the removed note, as their content is left untouched
+    pub linking_note_ids: Vec<String>,
+    // The backlinks that couldn't be removed from the notes the removed note linked to
+    pub warnings: Vec<String>,
+}
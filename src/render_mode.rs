@@ -0,0 +1,35 @@
+use crate::settings::Settings;
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    Markdown,
+    Org,
+    Plain,
+}
+
+impl RenderMode {
+    pub fn from_config_string(value: &str) -> Option<RenderMode> {
+        match value {
+            "markdown" => Some(RenderMode::Markdown),
+            "org" => Some(RenderMode::Org),
+            "plain" => Some(RenderMode::Plain),
+            _ => None,
+        }
+    }
+
+    // Files with an extension that isn't configured are shown as plain text
+    pub fn of_file(file_name: &str, settings: &Settings) -> RenderMode {
+        let extension = Path::new(file_name)
+            .extension()
+            .map(|m| m.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        return settings
+            .render_modes
+            .iter()
+            .find(|m| m.0 == extension)
+            .map(|m| m.1)
+            .unwrap_or(RenderMode::Plain);
+    }
+}
@@ -0,0 +1,162 @@
+// A single condition of a content search
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchTerm {
+    // Searched in the content of the notes
+    Word(String),
+    // The words have to appear in this order in the content of the notes
+    Phrase(String),
+    // Nested tags (e.g. 'tag/nested-tag') also belong to the parent tag
+    Tag(String),
+}
+
+// The parsed query of a content search. A note has to match all required terms
+// and none of the excluded terms. The grammar is:
+//
+//   query = term { whitespace term }
+//   term  = [ "-" ] ( word | '"' phrase '"' | "tag:" tag-name )
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery {
+    pub required_terms: Vec<SearchTerm>,
+    pub excluded_terms: Vec<SearchTerm>,
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Result<SearchQuery, String> {
+        let mut search_query = SearchQuery {
+            required_terms: Vec::new(),
+            excluded_terms: Vec::new(),
+        };
+        let mut chars = query.chars().peekable();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let is_excluded = chars.next_if_eq(&'-').is_some();
+            let term = match chars.peek() {
+                Some('"') => {
+                    chars.next();
+                    let mut phrase = String::new();
+                    let mut is_closed = false;
+                    for c in chars.by_ref() {
+                        if c == '"' {
+                            is_closed = true;
+                            break;
+                        }
+                        phrase.push(c);
+                    }
+                    if !is_closed {
+                        return Err(format!("the phrase \"{}\" isn't closed", phrase));
+                    }
+                    if phrase.trim().is_empty() {
+                        return Err("a phrase can't be empty".to_string());
+                    }
+                    if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                        return Err(format!(
+                            "the phrase \"{}\" has to be followed by a space",
+                            phrase
+                        ));
+                    }
+                    SearchTerm::Phrase(phrase.trim().to_string())
+                }
+                Some(c) if !c.is_whitespace() => {
+                    let mut word = String::new();
+                    while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                        word.push(c);
+                    }
+                    if word.contains('"') {
+                        return Err(format!(
+                            "'{}' contains a '\"', phrases have to start with it",
+                            word
+                        ));
+                    }
+                    match word.strip_prefix("tag:") {
+                        Some(tag_name) => {
+                            let tag_name = tag_name.trim_start_matches('#');
+                            if tag_name.is_empty() {
+                                return Err("'tag:' has to be followed by a tag name".to_string());
+                            }
+                            SearchTerm::Tag(tag_name.to_string())
+                        }
+                        None => SearchTerm::Word(word),
+                    }
+                }
+                _ => return Err("'-' has to be followed by a word, a phrase or a tag".to_string()),
+            };
+
+            if is_excluded {
+                search_query.excluded_terms.push(term);
+            } else {
+                search_query.required_terms.push(term);
+            }
+        }
+        return Ok(search_query);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.required_terms.is_empty() && self.excluded_terms.is_empty();
+    }
+
+    // The FTS5 query that matches the notes containing all required words and phrases.
+    // Every word is quoted so that it can't be interpreted as an FTS5 operator
+    pub fn get_required_fts_query(&self) -> Option<String> {
+        let fts_terms = SearchQuery::get_fts_terms_of(&self.required_terms);
+        if fts_terms.is_empty() {
+            return None;
+        }
+        return Some(fts_terms.join(" "));
+    }
+
+    // The FTS5 query that matches the notes containing any of the excluded words and phrases
+    pub fn get_excluded_fts_query(&self) -> Option<String> {
+        let fts_terms = SearchQuery::get_fts_terms_of(&self.excluded_terms);
+        if fts_terms.is_empty() {
+            return None;
+        }
+        return Some(fts_terms.join(" OR "));
+    }
+
+    fn get_fts_terms_of(terms: &[SearchTerm]) -> Vec<String> {
+        return terms
+            .iter()
+            .filter_map(|m| match m {
+                SearchTerm::Word(text) | SearchTerm::Phrase(text) => {
+                    Some(format!("\"{}\"", text.replace('"', "\"\"")))
+                }
+                SearchTerm::Tag(_) => None,
+            })
+            .collect();
+    }
+
+    pub fn get_required_tags(&self) -> Vec<&str> {
+        return SearchQuery::get_tags_of(&self.required_terms);
+    }
+
+    pub fn get_excluded_tags(&self) -> Vec<&str> {
+        return SearchQuery::get_tags_of(&self.excluded_terms);
+    }
+
+    fn get_tags_of(terms: &[SearchTerm]) -> Vec<&str> {
+        return terms
+            .iter()
+            .filter_map(|m| match m {
+                SearchTerm::Tag(tag_name) => Some(tag_name.as_str()),
+                _ => None,
+            })
+            .collect();
+    }
+
+    // The required words and phrases in lower case, e.g. to find them in the note content
+    pub fn get_search_words(&self) -> Vec<String> {
+        return self
+            .required_terms
+            .iter()
+            .filter_map(|m| match m {
+                SearchTerm::Word(text) | SearchTerm::Phrase(text) => Some(text.to_lowercase()),
+                SearchTerm::Tag(_) => None,
+            })
+            .collect();
+    }
+}
@@ -1,34 +1,572 @@
+use crate::brain_error::BrainError;
+use crate::brn_tui::keybindings::Keybindings;
+use crate::brn_tui::panel_layout::{Panel, SplitDirection};
+use crate::brn_tui::theme::Theme;
+use crate::database::Database;
+use crate::destructive_action::DestructiveAction;
+use crate::directory::Directory;
+use crate::file_utility::FileUtility;
 use crate::history::History;
-use crate::message::Message;
+use crate::name_overflow::NameOverflow;
+use crate::note_id_generator::IdScheme;
+use crate::note_utility::NoteUtility;
+use crate::notebook::{Notebook, DEFAULT_NOTEBOOK_NAME};
+use crate::render_mode::RenderMode;
+use crate::sort_order::SortOrder;
 
-use std::ffi::OsString;
+use chrono::format::{Item, StrftimeItems};
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use yaml_rust::{Yaml, YamlLoader};
+
+// ISO 8601
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Width of the note list of the TUI in percent of the terminal width
+pub const DEFAULT_LIST_WIDTH: u16 = 35;
+pub const MIN_LIST_WIDTH: u16 = 15;
+pub const MAX_LIST_WIDTH: u16 = 70;
+
+// Rows of the note list of the TUI only show the note name by default
+pub const DEFAULT_LIST_FORMAT: &str = "{name}";
+
+// Number of rendered note previews the TUI keeps in memory
+pub const DEFAULT_PREVIEW_CACHE_SIZE: usize = 50;
+
+// Number of messages the TUI keeps for the message history
+pub const DEFAULT_MESSAGE_LOG_SIZE: usize = 100;
 
 pub struct Settings {
     pub notes_dir: OsString,
     pub zettelkasten_dir: OsString,
+    pub notebook_name: String,
+    pub notebooks: Vec<Notebook>,
     pub backlinking_enabled: bool,
-    pub print_to_stdout: bool,
     pub show_interactive_dialogs: bool,
+    pub fuzzy_matching_enabled: bool,
+    pub markdown_preview_enabled: bool,
+    pub frontmatter_preview_enabled: bool,
+    pub line_numbers_enabled: bool,
+    pub preview_wrap_enabled: bool,
+    pub syntax_highlighting_enabled: bool,
+    pub render_modes: Vec<(String, RenderMode)>,
+    pub open_new_notes_in_editor: bool,
+    pub editor: Option<String>,
+    pub editor_args: Vec<String>,
+    pub keybindings: Keybindings,
+    pub theme: Theme,
+    pub sort_order: SortOrder,
+    pub id_scheme: IdScheme,
+    pub inherited_properties: Vec<String>,
+    pub date_format: String,
+    pub list_width: u16,
+    pub split_direction: SplitDirection,
+    pub visible_panels: Vec<Panel>,
+    pub list_format: String,
+    pub name_overflow: NameOverflow,
+    pub note_id_column_enabled: bool,
+    pub link_counts_enabled: bool,
+    pub git_integration_enabled: bool,
+    pub preview_cache_size: usize,
+    pub message_log_size: usize,
+    pub restore_session_enabled: bool,
+    pub last_note_id: Option<String>,
+    pub home_note_id: Option<String>,
+    pub inbox_note_id: Option<String>,
+    pub confirmed_actions: Vec<DestructiveAction>,
+    pub last_search_query: String,
+    pub pinned_note_ids: Vec<String>,
+    pub templates_dir: OsString,
+    pub default_template: Option<String>,
     pub note_history: History,
+    // The problems found while loading the config and the state, which the frontends show
+    pub warnings: Vec<String>,
 }
 
 impl Settings {
     pub fn init(notes_dir: OsString, zettelkasten_dir: OsString) -> Self {
+        let templates_dir = PathBuf::from(&zettelkasten_dir)
+            .join("templates")
+            .into_os_string();
         let mut settings = Settings {
             notes_dir: notes_dir,
             zettelkasten_dir: zettelkasten_dir,
+            notebook_name: DEFAULT_NOTEBOOK_NAME.to_string(),
+            notebooks: Vec::new(),
             note_history: History::new(),
             backlinking_enabled: true,
-            print_to_stdout: true,
             show_interactive_dialogs: true,
+            fuzzy_matching_enabled: false,
+            markdown_preview_enabled: true,
+            frontmatter_preview_enabled: false,
+            line_numbers_enabled: false,
+            preview_wrap_enabled: true,
+            syntax_highlighting_enabled: true,
+            render_modes: vec![
+                ("md".to_string(), RenderMode::Markdown),
+                ("markdown".to_string(), RenderMode::Markdown),
+                ("org".to_string(), RenderMode::Org),
+                ("txt".to_string(), RenderMode::Plain),
+            ],
+            open_new_notes_in_editor: true,
+            editor: None,
+            editor_args: Vec::new(),
+            keybindings: Keybindings::default(),
+            theme: Theme::default(),
+            sort_order: SortOrder::CreationDateDescending,
+            id_scheme: IdScheme::Timestamp,
+            inherited_properties: Vec::new(),
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            list_width: DEFAULT_LIST_WIDTH,
+            split_direction: SplitDirection::Horizontal,
+            visible_panels: vec![Panel::List, Panel::Preview, Panel::Backlinks],
+            list_format: DEFAULT_LIST_FORMAT.to_string(),
+            name_overflow: NameOverflow::Ellipsis,
+            note_id_column_enabled: false,
+            link_counts_enabled: false,
+            git_integration_enabled: false,
+            preview_cache_size: DEFAULT_PREVIEW_CACHE_SIZE,
+            message_log_size: DEFAULT_MESSAGE_LOG_SIZE,
+            restore_session_enabled: false,
+            last_note_id: None,
+            home_note_id: None,
+            inbox_note_id: None,
+            confirmed_actions: vec![DestructiveAction::Delete, DestructiveAction::NoteMerge],
+            last_search_query: String::new(),
+            pinned_note_ids: Vec::new(),
+            templates_dir: templates_dir,
+            default_template: None,
+            warnings: Vec::new(),
         };
 
         if let Err(error) = settings
             .note_history
             .init(&settings.zettelkasten_dir.as_os_str())
         {
-            Message::info(&("initializing history: ".to_string() + &error));
+            settings
+                .warnings
+                .push(format!("initializing history: {}", error));
+        }
+
+        if let Err(error) = settings.load_config() {
+            settings.warnings.push(format!("loading config: {}", error));
+        }
+        if let Err(error) = settings.load_state() {
+            settings.warnings.push(format!("loading state: {}", error));
         }
         return settings;
     }
+
+    // Opens the zettelkasten in the directory and brings its database up to date.
+    // This is the starting point for programs that use brn as a library
+    pub fn open(notes_dir: &OsStr) -> Result<Settings, BrainError> {
+        if !Directory::is_zettelkasten_dir(notes_dir, true) {
+            return Err(BrainError::Other(format!(
+                "the directory '{}' is not a zettelkasten directory",
+                notes_dir.to_string_lossy()
+            )));
+        }
+        let zettelkasten_dir = Path::new(notes_dir).join(".zettelkasten").into_os_string();
+        let settings = Settings::init(notes_dir.to_os_string(), zettelkasten_dir);
+        Database::set_db_path(&settings.zettelkasten_dir);
+        // The warnings of the links are shown again as soon as the notes are checked
+        NoteUtility::migrate_db(&settings, |_| ());
+        return Ok(settings);
+    }
+
+    // Loads the settings of another notebook. Settings that were given on the command line are kept
+    pub fn switch_notebook(&mut self, notebook_name: &str) -> Result<(), BrainError> {
+        let notes_dir = match self.notebooks.iter().find(|m| m.name == notebook_name) {
+            Some(notebook) => notebook.notes_dir.clone(),
+            None => {
+                return Err(BrainError::Other(format!(
+                    "the notebook '{}' doesn't exist",
+                    notebook_name
+                )))
+            }
+        };
+        if !Directory::is_zettelkasten_dir(&notes_dir, true) {
+            return Err(BrainError::Other(format!(
+                "the directory '{}' of the notebook '{}' is not a zettelkasten directory",
+                notes_dir.to_string_lossy(),
+                notebook_name
+            )));
+        }
+
+        let zettelkasten_dir = Path::new(&notes_dir).join(".zettelkasten").into_os_string();
+        let mut settings = Settings::init(notes_dir, zettelkasten_dir);
+        settings.backlinking_enabled = self.backlinking_enabled;
+        settings.show_interactive_dialogs = self.show_interactive_dialogs;
+        settings.notebook_name = notebook_name.to_string();
+        settings.notebooks = std::mem::take(&mut self.notebooks);
+        *self = settings;
+        return Ok(());
+    }
+
+    fn load_config(&mut self) -> Result<(), String> {
+        let config_file_path = PathBuf::from(&self.zettelkasten_dir).join("config.yml");
+        if !config_file_path.exists() {
+            return Ok(());
+        }
+
+        let config_content = match FileUtility::get_content_from_file(&config_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "couldn't read config file '{}': {}",
+                    config_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+        };
+        let config = match YamlLoader::load_from_str(&config_content) {
+            Ok(yaml_vector) => match yaml_vector.into_iter().next() {
+                Some(value) => value,
+                None => return Ok(()),
+            },
+            Err(error) => {
+                return Err(format!(
+                    "couldn't parse config file '{}': {}",
+                    config_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+        };
+
+        if let Some(fuzzy_matching_enabled) = config["fuzzy-matching"].as_bool() {
+            self.fuzzy_matching_enabled = fuzzy_matching_enabled;
+        }
+        if let Some(markdown_preview_enabled) = config["markdown-preview"].as_bool() {
+            self.markdown_preview_enabled = markdown_preview_enabled;
+        }
+        if let Some(frontmatter_preview_enabled) = config["show-frontmatter"].as_bool() {
+            self.frontmatter_preview_enabled = frontmatter_preview_enabled;
+        }
+        if let Some(line_numbers_enabled) = config["line-numbers"].as_bool() {
+            self.line_numbers_enabled = line_numbers_enabled;
+        }
+        if let Some(preview_wrap_enabled) = config["wrap-preview"].as_bool() {
+            self.preview_wrap_enabled = preview_wrap_enabled;
+        }
+        if let Some(syntax_highlighting_enabled) = config["syntax-highlighting"].as_bool() {
+            self.syntax_highlighting_enabled = syntax_highlighting_enabled;
+        }
+        match &config["render-modes"] {
+            Yaml::Hash(render_modes) => {
+                for (extension, render_mode) in render_modes {
+                    let extension = match extension.as_str() {
+                        Some(value) => value.trim_start_matches('.').to_lowercase(),
+                        None => continue,
+                    };
+                    let render_mode = match render_mode
+                        .as_str()
+                        .and_then(RenderMode::from_config_string)
+                    {
+                        Some(value) => value,
+                        None => {
+                            self.warnings.push(format!(
+                                "loading config: unknown render mode of '{}', possible values are markdown, org and plain",
+                                extension
+                            ));
+                            continue;
+                        }
+                    };
+                    self.render_modes.retain(|m| m.0 != extension);
+                    self.render_modes.push((extension, render_mode));
+                }
+            }
+            Yaml::BadValue => (),
+            _ => self
+                .warnings
+                .push("loading config: 'render-modes' has to be a mapping".to_string()),
+        }
+        if let Some(list_format) = config["list-format"].as_str() {
+            self.list_format = list_format.to_string();
+        }
+        if let Some(name_overflow) = config["long-names"].as_str() {
+            match NameOverflow::from_config_string(name_overflow) {
+                Some(value) => self.name_overflow = value,
+                None => self.warnings.push(format!(
+                    "loading config: unknown value '{}' of 'long-names'",
+                    name_overflow
+                )),
+            }
+        }
+        if let Some(note_id_column_enabled) = config["show-note-ids"].as_bool() {
+            self.note_id_column_enabled = note_id_column_enabled;
+        }
+        if let Some(link_counts_enabled) = config["show-link-counts"].as_bool() {
+            self.link_counts_enabled = link_counts_enabled;
+        }
+        if let Some(git_integration_enabled) = config["git-integration"].as_bool() {
+            self.git_integration_enabled = git_integration_enabled;
+        }
+        if let Some(preview_cache_size) = config["preview-cache-size"].as_i64() {
+            self.preview_cache_size = preview_cache_size.max(0) as usize;
+        }
+        if let Some(message_log_size) = config["message-log-size"].as_i64() {
+            self.message_log_size = message_log_size.max(0) as usize;
+        }
+        if let Some(open_new_notes_in_editor) = config["open-new-notes"].as_bool() {
+            self.open_new_notes_in_editor = open_new_notes_in_editor;
+        }
+        if let Some(editor) = config["editor"].as_str() {
+            self.editor = Some(editor.to_string());
+        }
+        match &config["editor-args"] {
+            Yaml::String(editor_args) => {
+                self.editor_args = editor_args
+                    .split_whitespace()
+                    .map(|m| m.to_string())
+                    .collect();
+            }
+            Yaml::Array(editor_args) => {
+                self.editor_args = editor_args
+                    .iter()
+                    .filter_map(|m| m.as_str().map(|v| v.to_string()))
+                    .collect();
+            }
+            Yaml::BadValue => (),
+            _ => self.warnings.push(
+                "loading config: 'editor-args' has to be a string or a list of strings".to_string(),
+            ),
+        }
+        if let Some(sort_order) = config["sort-order"].as_str() {
+            match SortOrder::from_config_string(sort_order) {
+                Some(value) => self.sort_order = value,
+                None => self.warnings.push(format!(
+                    "loading config: unknown sort order '{}'",
+                    sort_order
+                )),
+            }
+        }
+        if let Some(id_scheme) = config["id-scheme"].as_str() {
+            match IdScheme::from_config_string(id_scheme) {
+                Some(value) => self.id_scheme = value,
+                None => self
+                    .warnings
+                    .push(format!("loading config: unknown id scheme '{}'", id_scheme)),
+            }
+        }
+        match &config["inherited-properties"] {
+            Yaml::Array(inherited_properties) => {
+                self.inherited_properties = inherited_properties
+                    .iter()
+                    .filter_map(|m| m.as_str().map(|v| v.to_string()))
+                    .collect();
+            }
+            Yaml::BadValue => (),
+            _ => self
+                .warnings
+                .push("loading config: 'inherited-properties' has to be a list".to_string()),
+        }
+        if let Some(split_direction) = config["layout"].as_str() {
+            match SplitDirection::from_config_string(split_direction) {
+                Some(value) => self.split_direction = value,
+                None => self.warnings.push(format!(
+                    "loading config: unknown layout '{}', possible values are horizontal and vertical",
+                    split_direction
+                )),
+            }
+        }
+        match &config["panels"] {
+            Yaml::Array(panels) => {
+                let mut visible_panels = Vec::new();
+                for panel in panels {
+                    match panel.as_str().and_then(Panel::from_config_string) {
+                        Some(value) => visible_panels.push(value),
+                        None => self.warnings.push("loading config: unknown panel in 'panels', possible values are list, preview, backlinks and tags".to_string()),
+                    }
+                }
+                if visible_panels.is_empty() {
+                    self.warnings.push(
+                        "loading config: 'panels' has to contain at least one panel".to_string(),
+                    );
+                } else {
+                    self.visible_panels = visible_panels;
+                }
+            }
+            Yaml::BadValue => (),
+            _ => self
+                .warnings
+                .push("loading config: 'panels' has to be a list".to_string()),
+        }
+        if let Some(restore_session_enabled) = config["restore-session"].as_bool() {
+            self.restore_session_enabled = restore_session_enabled;
+        }
+        if let Some(date_format) = config["date-format"].as_str() {
+            // Formatting a date with an invalid format would panic
+            if StrftimeItems::new(date_format).any(|m| m == Item::Error) {
+                self.warnings.push(format!(
+                    "loading config: invalid date format '{}', using '{}' instead",
+                    date_format, DEFAULT_DATE_FORMAT
+                ));
+            } else {
+                self.date_format = date_format.to_string();
+            }
+        }
+        if let Some(templates_dir) = config["templates-dir"].as_str() {
+            // Relative paths are relative to the '.zettelkasten' directory
+            self.templates_dir = PathBuf::from(&self.zettelkasten_dir)
+                .join(templates_dir)
+                .into_os_string();
+        }
+        if let Some(default_template) = config["default-template"].as_str() {
+            self.default_template = Some(default_template.to_string());
+        }
+        if let Some(home_note_id) = config["home-note"].as_str() {
+            self.home_note_id = Some(home_note_id.to_string());
+        }
+        if let Some(inbox_note_id) = config["inbox-note"].as_str() {
+            self.inbox_note_id = Some(inbox_note_id.to_string());
+        }
+        match &config["confirm"] {
+            Yaml::Array(confirmed_actions) => {
+                self.confirmed_actions.clear();
+                for confirmed_action in confirmed_actions {
+                    match confirmed_action
+                        .as_str()
+                        .and_then(DestructiveAction::from_config_string)
+                    {
+                        Some(value) => self.confirmed_actions.push(value),
+                        None => self.warnings.push("loading config: unknown action in 'confirm', possible values are delete, bulk-rename, tag-rename, tag-merge, note-merge and move-note".to_string()),
+                    }
+                }
+            }
+            Yaml::BadValue => (),
+            _ => self
+                .warnings
+                .push("loading config: 'confirm' has to be a list".to_string()),
+        }
+        if !config["theme"].is_badvalue() {
+            let (theme, warnings) = Theme::from_config(&config["theme"]);
+            self.theme = theme;
+            for warning in warnings {
+                self.warnings.push(format!("loading config: {}", warning));
+            }
+        }
+        if !config["keybindings"].is_badvalue() {
+            let (keybindings, warnings) = Keybindings::from_config(&config["keybindings"]);
+            self.keybindings = keybindings;
+            for warning in warnings {
+                self.warnings.push(format!("loading config: {}", warning));
+            }
+        }
+
+        return Ok(());
+    }
+
+    // The state contains settings that are changed in the TUI instead of the config file
+    fn load_state(&mut self) -> Result<(), String> {
+        let state_file_path = PathBuf::from(&self.zettelkasten_dir).join("state.yml");
+        if !state_file_path.exists() {
+            return Ok(());
+        }
+
+        let state_content = match FileUtility::get_content_from_file(&state_file_path) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(format!(
+                    "couldn't read state file '{}': {}",
+                    state_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+        };
+        let state = match YamlLoader::load_from_str(&state_content) {
+            Ok(yaml_vector) => match yaml_vector.into_iter().next() {
+                Some(value) => value,
+                None => return Ok(()),
+            },
+            Err(error) => {
+                return Err(format!(
+                    "couldn't parse state file '{}': {}",
+                    state_file_path.to_string_lossy(),
+                    error
+                ));
+            }
+        };
+
+        if let Some(list_width) = state["list-width"].as_i64() {
+            self.list_width = Settings::clamp_list_width(list_width);
+        }
+        // The line numbers toggled in the TUI override the configured ones
+        if let Some(line_numbers_enabled) = state["line-numbers"].as_bool() {
+            self.line_numbers_enabled = line_numbers_enabled;
+        }
+        if let Some(pinned_note_ids) = state["pinned-notes"].as_vec() {
+            self.pinned_note_ids = pinned_note_ids
+                .iter()
+                .filter_map(|m| m.as_str().map(|v| v.to_string()))
+                .collect();
+        }
+
+        // The last session overrides the configured sort order
+        if self.restore_session_enabled {
+            if let Some(sort_order) = state["sort-order"]
+                .as_str()
+                .and_then(SortOrder::from_config_string)
+            {
+                self.sort_order = sort_order;
+            }
+            self.last_note_id = state["selected-note"].as_str().map(|m| m.to_string());
+            if let Some(search_query) = state["search"].as_str() {
+                self.last_search_query = search_query.to_string();
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub fn save_state(&self) -> Result<(), BrainError> {
+        let state_file_path = PathBuf::from(&self.zettelkasten_dir).join("state.yml");
+        let mut state_content = format!("list-width: {}\n", self.list_width);
+        state_content.push_str(&format!("line-numbers: {}\n", self.line_numbers_enabled));
+        let quoted_pinned_note_ids: Vec<String> = self
+            .pinned_note_ids
+            .iter()
+            .map(|m| Settings::to_quoted_yaml_string(m))
+            .collect();
+        state_content.push_str(&format!(
+            "pinned-notes: [{}]\n",
+            quoted_pinned_note_ids.join(", ")
+        ));
+        if self.restore_session_enabled {
+            state_content.push_str(&format!(
+                "sort-order: {}\n",
+                self.sort_order.to_config_string()
+            ));
+            if let Some(note_id) = &self.last_note_id {
+                state_content.push_str(&format!(
+                    "selected-note: {}\n",
+                    Settings::to_quoted_yaml_string(note_id)
+                ));
+            }
+            state_content.push_str(&format!(
+                "search: {}\n",
+                Settings::to_quoted_yaml_string(&self.last_search_query)
+            ));
+        }
+
+        if let Err(error) = fs::write(&state_file_path, state_content) {
+            return Err(BrainError::Io {
+                path: state_file_path,
+                source: error,
+            });
+        }
+        return Ok(());
+    }
+
+    fn to_quoted_yaml_string(value: &str) -> String {
+        return format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    }
+
+    pub fn clamp_list_width(list_width: i64) -> u16 {
+        return list_width.clamp(MIN_LIST_WIDTH as i64, MAX_LIST_WIDTH as i64) as u16;
+    }
+
+    pub fn needs_confirmation(&self, action: DestructiveAction) -> bool {
+        return self.confirmed_actions.contains(&action);
+    }
 }
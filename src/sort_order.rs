@@ -0,0 +1,61 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    CreationDateDescending,
+    CreationDateAscending,
+    ModificationDateDescending,
+    ModificationDateAscending,
+    NameAscending,
+    NameDescending,
+    Frecency,
+}
+
+impl SortOrder {
+    pub fn from_config_string(value: &str) -> Option<SortOrder> {
+        match value {
+            "creation-date-desc" => Some(SortOrder::CreationDateDescending),
+            "creation-date" => Some(SortOrder::CreationDateAscending),
+            "modification-date-desc" => Some(SortOrder::ModificationDateDescending),
+            "modification-date" => Some(SortOrder::ModificationDateAscending),
+            "name" => Some(SortOrder::NameAscending),
+            "name-desc" => Some(SortOrder::NameDescending),
+            "frecency" => Some(SortOrder::Frecency),
+            _ => None,
+        }
+    }
+
+    pub fn to_config_string(&self) -> String {
+        match self {
+            SortOrder::CreationDateDescending => "creation-date-desc".to_string(),
+            SortOrder::CreationDateAscending => "creation-date".to_string(),
+            SortOrder::ModificationDateDescending => "modification-date-desc".to_string(),
+            SortOrder::ModificationDateAscending => "modification-date".to_string(),
+            SortOrder::NameAscending => "name".to_string(),
+            SortOrder::NameDescending => "name-desc".to_string(),
+            SortOrder::Frecency => "frecency".to_string(),
+        }
+    }
+
+    pub fn to_display_string(&self) -> String {
+        match self {
+            SortOrder::CreationDateDescending => "newest first".to_string(),
+            SortOrder::CreationDateAscending => "oldest first".to_string(),
+            SortOrder::ModificationDateDescending => "recently modified first".to_string(),
+            SortOrder::ModificationDateAscending => "least recently modified first".to_string(),
+            SortOrder::NameAscending => "name (A-Z)".to_string(),
+            SortOrder::NameDescending => "name (Z-A)".to_string(),
+            SortOrder::Frecency => "frequently and recently opened first".to_string(),
+        }
+    }
+
+    pub fn next(&self) -> SortOrder {
+        match self {
+            SortOrder::CreationDateDescending => SortOrder::CreationDateAscending,
+            SortOrder::CreationDateAscending => SortOrder::ModificationDateDescending,
+            SortOrder::ModificationDateDescending => SortOrder::ModificationDateAscending,
+            SortOrder::ModificationDateAscending => SortOrder::NameAscending,
+            SortOrder::NameAscending => SortOrder::NameDescending,
+            SortOrder::NameDescending => SortOrder::Frecency,
+            SortOrder::Frecency => SortOrder::CreationDateDescending,
+        }
+    }
+}
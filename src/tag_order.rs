@@ -0,0 +1,22 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagOrder {
+    Name,
+    Count,
+}
+
+impl TagOrder {
+    pub fn from_string(value: &str) -> Option<TagOrder> {
+        match value {
+            "name" => Some(TagOrder::Name),
+            "count" => Some(TagOrder::Count),
+            _ => None,
+        }
+    }
+
+    pub fn toggled(&self) -> TagOrder {
+        match self {
+            TagOrder::Name => TagOrder::Count,
+            TagOrder::Count => TagOrder::Name,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::database::Database;
+
+use serde::Serialize;
+
+// Only aggregate queries of the database are used, so that the statistics
+// are fast to compute even for large zettelkasten directories
+#[derive(Debug, Serialize)]
+pub struct ZettelkastenStatistics {
+    pub note_count: usize,
+    pub archived_note_count: usize,
+    pub tag_count: usize,
+    pub link_count: usize,
+    pub orphan_count: usize,
+    pub average_note_length: usize,
+    pub database_size: u64,
+}
+
+impl ZettelkastenStatistics {
+    pub fn from_database() -> Self {
+        return ZettelkastenStatistics {
+            note_count: Database::get_total_note_count(),
+            archived_note_count: Database::get_archived_note_count(),
+            tag_count: Database::get_tag_count(),
+            link_count: Database::get_link_count(),
+            orphan_count: Database::get_orphan_count(),
+            average_note_length: Database::get_average_note_length().round() as usize,
+            database_size: Database::get_db_file_size(),
+        };
+    }
+}